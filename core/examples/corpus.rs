@@ -0,0 +1,29 @@
+//! writes the seed corpus to `<dir>/in`, one file per certificate, and the AFL dictionary to
+//! `<dir>/x509.dict`, e.g. for `cargo afl fuzz -i <dir>/in -x <dir>/x509.dict -o out ...`.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use x509_core::generate::corpus::{afl_dictionary, seed_corpus};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = match env::args().nth(1) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            eprintln!("usage: corpus <dir>");
+            return Ok(());
+        }
+    };
+
+    let seeds = dir.join("in");
+    fs::create_dir_all(&seeds)?;
+    let corpus = seed_corpus().map_err(|e| format!("generating the corpus failed: {:?}", e))?;
+    for entry in corpus.iter() {
+        fs::write(seeds.join(format!("{}.der", entry.name)), &entry.der)?;
+    }
+    fs::write(dir.join("x509.dict"), afl_dictionary())?;
+    println!("wrote {} seeds to {}", corpus.len(), seeds.display());
+
+    Ok(())
+}
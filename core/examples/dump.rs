@@ -0,0 +1,110 @@
+//! prints the certificates in a file with one base64 encoded certificate per line, the format
+//! used by CT log dumps, followed by a count of the extensions that were seen.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+use x509_core::parse::certificate::NameRef;
+use x509_core::CertificateRef;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let file_name = match env::args().nth(1) {
+        Some(file_name) => file_name,
+        None => {
+            eprintln!("usage: dump <file>");
+            return Ok(());
+        }
+    };
+
+    let reader = BufReader::new(File::open(file_name)?);
+    let mut buf = Vec::new();
+    let mut dist: HashMap<String, u32> = HashMap::new();
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let cert = match CertificateRef::from_base64(&line, &mut buf) {
+            Ok(cert) => cert,
+            Err(e) => {
+                writeln!(out, "error parsing certificate: {:?}", e)?;
+                continue;
+            }
+        };
+        let tbs = cert.tbs_cert();
+
+        writeln!(out, "serial: {}", tbs.serial_number().to_big_int())?;
+        writeln!(out, "issuer: {}", DisplayName(tbs.issuer()))?;
+        writeln!(out, "subject: {}", DisplayName(tbs.subject()))?;
+        writeln!(out, "valid from: {}", tbs.validity().not_before()?)?;
+        writeln!(out, "valid to: {}", tbs.validity().not_after()?)?;
+
+        if let Some(extensions) = tbs.extensions() {
+            writeln!(out, "extensions:")?;
+            for ext in extensions {
+                let ext = ext.map_err(x509_core::error::Error::ParseError)?;
+                let oid = ext.extension_id();
+                if ext.critical() {
+                    writeln!(out, "  {} (critical)", oid)?;
+                } else {
+                    writeln!(out, "  {}", oid)?;
+                }
+                *dist.entry(oid.to_string()).or_insert(0) += 1;
+            }
+        }
+        writeln!(out)?;
+    }
+    out.flush()?;
+
+    eprintln!("OIDs from extensions");
+    for (oid, cnt) in dist.iter() {
+        eprintln!("{}: {}", oid, cnt);
+    }
+
+    Ok(())
+}
+
+/// writes a name as `type=value, ...` without collecting the parts first
+struct DisplayName<'a, 'b>(&'b NameRef<'a>);
+
+impl Display for DisplayName<'_, '_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let NameRef::DistinguishedNameRef(dn) = self.0;
+        let mut first = true;
+        // the iterators keep returning the same error, stop at the first one
+        for rdn in dn.iter() {
+            let rdn = match rdn {
+                Ok(rdn) => rdn,
+                Err(_) => return write_malformed(f, first),
+            };
+            for atv in rdn.iter() {
+                let atv = match atv {
+                    Ok(atv) => atv,
+                    Err(_) => return write_malformed(f, first),
+                };
+                if !first {
+                    f.write_str(", ")?;
+                }
+                first = false;
+                write!(f, "{}=", atv.attribute_type())?;
+                match atv.value_as_str() {
+                    Ok(value) => f.write_str(&value)?,
+                    Err(_) => f.write_str("<binary>")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_malformed(f: &mut Formatter, first: bool) -> fmt::Result {
+    if !first {
+        f.write_str(", ")?;
+    }
+    f.write_str("<malformed>")
+}
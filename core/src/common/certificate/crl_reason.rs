@@ -0,0 +1,55 @@
+use crate::parse::error::ParseError;
+use std::convert::TryFrom;
+
+/// CRLReason as defined in RFC 5280 section 5.3.1, used by CRL entries and OCSP responses
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum CRLReason {
+    Unspecified,
+    KeyCompromise,
+    CACompromise,
+    AffiliationChanged,
+    Superseded,
+    CessationOfOperation,
+    CertificateHold,
+    RemoveFromCRL,
+    PrivilegeWithdrawn,
+    AACompromise,
+}
+
+impl TryFrom<i64> for CRLReason {
+    type Error = ParseError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(CRLReason::Unspecified),
+            1 => Ok(CRLReason::KeyCompromise),
+            2 => Ok(CRLReason::CACompromise),
+            3 => Ok(CRLReason::AffiliationChanged),
+            4 => Ok(CRLReason::Superseded),
+            5 => Ok(CRLReason::CessationOfOperation),
+            6 => Ok(CRLReason::CertificateHold),
+            // 7 is not used
+            8 => Ok(CRLReason::RemoveFromCRL),
+            9 => Ok(CRLReason::PrivilegeWithdrawn),
+            10 => Ok(CRLReason::AACompromise),
+            _ => Err(ParseError::MalformedData),
+        }
+    }
+}
+
+impl From<&CRLReason> for i64 {
+    fn from(r: &CRLReason) -> Self {
+        match r {
+            CRLReason::Unspecified => 0,
+            CRLReason::KeyCompromise => 1,
+            CRLReason::CACompromise => 2,
+            CRLReason::AffiliationChanged => 3,
+            CRLReason::Superseded => 4,
+            CRLReason::CessationOfOperation => 5,
+            CRLReason::CertificateHold => 6,
+            CRLReason::RemoveFromCRL => 8,
+            CRLReason::PrivilegeWithdrawn => 9,
+            CRLReason::AACompromise => 10,
+        }
+    }
+}
@@ -1,3 +1,7 @@
+mod crl_reason;
+mod parameter_rule;
 mod version;
 
-pub use version::Version;
\ No newline at end of file
+pub use crl_reason::CRLReason;
+pub use parameter_rule::ParameterRule;
+pub use version::Version;
@@ -0,0 +1,60 @@
+use crate::oids;
+use crate::parse::der::ObjectIdentifierRef;
+
+/// what the parameters of an AlgorithmIdentifier may be, following RFC 3279, RFC 4055,
+/// RFC 5480, RFC 5758 and RFC 8410. shared by the generated
+/// [`AlgorithmIdentifier`](crate::generate::certificate::AlgorithmIdentifier) and the parsed
+/// [`AlgorithmidentifierRef`](crate::parse::certificate::AlgorithmidentifierRef).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParameterRule {
+    /// rsaEncryption and the PKCS#1 v1.5 signature algorithms
+    Null,
+    /// the ECDSA signature algorithms and Ed25519
+    Absent,
+    /// the hash algorithms, RFC 5754 asks for absent parameters but NULL is widely used
+    NullOrAbsent,
+    /// id-ecPublicKey, RFC 5480 forbids implicit and explicit curves
+    NamedCurve,
+    /// RSASSA-PSS-params. they may only be absent in a public key, a signature needs them.
+    RsassaPss,
+    /// an algorithm this crate doesn't know the rules of
+    Any,
+}
+
+impl ParameterRule {
+    pub fn for_algorithm(algorithm: &ObjectIdentifierRef) -> Self {
+        match *algorithm {
+            oids::RSA_ENCRYPTION
+            | oids::MD5_WITH_RSA_ENCRYPTION
+            | oids::SHA1_WITH_RSA_ENCRYPTION
+            | oids::SHA256_WITH_RSA_ENCRYPTION
+            | oids::SHA384_WITH_RSA_ENCRYPTION
+            | oids::SHA512_WITH_RSA_ENCRYPTION => ParameterRule::Null,
+            oids::ECDSA_WITH_SHA1
+            | oids::ECDSA_WITH_SHA256
+            | oids::ECDSA_WITH_SHA384
+            | oids::ECDSA_WITH_SHA512
+            | oids::ED25519 => ParameterRule::Absent,
+            oids::SHA1 | oids::SHA256 | oids::SHA384 | oids::SHA512 => ParameterRule::NullOrAbsent,
+            oids::EC_PUBLIC_KEY => ParameterRule::NamedCurve,
+            oids::RSASSA_PSS => ParameterRule::RsassaPss,
+            _ => ParameterRule::Any,
+        }
+    }
+}
+
+#[test]
+fn test_parameter_rule() {
+    assert_eq!(
+        ParameterRule::for_algorithm(&oids::SHA256_WITH_RSA_ENCRYPTION),
+        ParameterRule::Null
+    );
+    assert_eq!(
+        ParameterRule::for_algorithm(&oids::ECDSA_WITH_SHA256),
+        ParameterRule::Absent
+    );
+    assert_eq!(
+        ParameterRule::for_algorithm(&oids::COMMON_NAME),
+        ParameterRule::Any
+    );
+}
@@ -15,4 +15,108 @@ impl ExplicitTag {
     pub fn get_identifier_octet(&self) -> u8 {
         0xa0 | self.0
     }
-}
\ No newline at end of file
+}
+
+/// whether `b` is part of the PrintableString alphabet: A-Z, a-z, 0-9, space and '()+,-./:=?
+pub(crate) fn is_printable(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b" '()+,-./:=?".contains(&b)
+}
+
+/// how [`read_length`] treats length octets that are valid BER but not DER
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LengthRules {
+    /// only the shortest form: the short form up to 127, no leading zero octets in the long form
+    Der,
+    /// also longer forms than necessary, as some BER encoders write them
+    Ber,
+}
+
+/// the length octets of a TLV with `len` value bytes, in the shortest form as DER requires
+pub fn encode_length(len: usize) -> Vec<u8> {
+    let (octets, octets_len) = length_octets(len);
+    octets[..octets_len].to_vec()
+}
+
+/// the length octets of `len` in a buffer, and how much of the buffer is used
+pub(crate) fn length_octets(len: usize) -> ([u8; 9], usize) {
+    let mut octets = [0u8; 9];
+    if len <= 127 {
+        // short form
+        octets[0] = len as u8;
+        return (octets, 1);
+    }
+
+    // long form, the number of length octets followed by the length without leading zeros
+    let bytes = len.to_be_bytes();
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    let count = bytes.len() - zeros;
+    octets[0] = 0x80 | count as u8;
+    octets[1..=count].copy_from_slice(&bytes[zeros..]);
+
+    (octets, 1 + count)
+}
+
+/// reads the length octets at the start of `data`, returning the rest of `data` and the length.
+/// the indefinite length of BER and lengths that don't fit into a `usize` are rejected under
+/// both rules. that the rest holds `length` bytes is up to the caller.
+pub fn read_length(data: &[u8], rules: LengthRules) -> Result<(&[u8], usize), ParseError> {
+    let (&first, rest) = data.split_first().ok_or(ParseError::InvalidLength)?;
+    if first & 0x80 == 0 {
+        return Ok((rest, first as usize));
+    }
+
+    // 0x80 is the indefinite length, 0xff is reserved
+    let count = (first & 0x7f) as usize;
+    if count == 0 || count > std::mem::size_of::<usize>() {
+        return Err(ParseError::InvalidLength);
+    }
+    let octets = rest.get(..count).ok_or(ParseError::InvalidLength)?;
+    let len = octets
+        .iter()
+        .fold(0, |len, &octet| len << 8 | octet as usize);
+    if rules == LengthRules::Der && (octets[0] == 0 || len <= 127) {
+        return Err(ParseError::InvalidLength);
+    }
+
+    Ok((&rest[count..], len))
+}
+
+#[test]
+fn test_encode_length() {
+    assert_eq!(encode_length(0), [0x00]);
+    assert_eq!(encode_length(127), [0x7f]);
+    assert_eq!(encode_length(128), [0x81, 0x80]);
+    assert_eq!(encode_length(0x1234), [0x82, 0x12, 0x34]);
+    assert_eq!(encode_length(usize::MAX)[1..], usize::MAX.to_be_bytes()[..]);
+}
+
+#[test]
+fn test_read_length() {
+    for len in [0, 5, 127, 128, 255, 256, 0x12345] {
+        let mut data = encode_length(len);
+        data.push(0xaa);
+        for rules in [LengthRules::Der, LengthRules::Ber] {
+            assert_eq!(read_length(&data, rules).unwrap(), (&[0xaa][..], len));
+        }
+    }
+
+    // longer forms than necessary
+    for data in [&[0x81, 0x05][..], &[0x82, 0x00, 0x80]] {
+        assert!(read_length(data, LengthRules::Ber).is_ok());
+        assert!(matches!(
+            read_length(data, LengthRules::Der),
+            Err(ParseError::InvalidLength)
+        ));
+    }
+
+    // empty, indefinite, reserved, too many and missing length octets
+    for data in [
+        &[][..],
+        &[0x80],
+        &[0xff],
+        &[0x89, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        &[0x82, 0x01],
+    ] {
+        assert!(read_length(data, LengthRules::Ber).is_err());
+    }
+}
@@ -1,2 +1,3 @@
 pub mod certificate;
 pub mod der;
+pub mod time;
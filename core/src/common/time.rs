@@ -0,0 +1,331 @@
+//! A small calendar type for certificate times.
+//!
+//! Parsing and generation use [`UtcDateTime`] so that neither path depends on a particular
+//! date/time crate. Conversions to and from `chrono` and `time` are available behind the
+//! `chrono` (default) and `time` features.
+
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A date and time in UTC with nanosecond precision. Always holds a valid calendar date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UtcDateTime {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+}
+
+impl UtcDateTime {
+    /// returns None if any of the components is out of range
+    pub fn from_ymd_hms(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+    ) -> Option<Self> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+
+        Some(Self {
+            year,
+            month: month as u8,
+            day: day as u8,
+            hour: hour as u8,
+            minute: minute as u8,
+            second: second as u8,
+            nanosecond: 0,
+        })
+    }
+
+    /// returns None if `nanosecond` is not below one second
+    pub fn with_nanosecond(self, nanosecond: u32) -> Option<Self> {
+        if nanosecond >= 1_000_000_000 {
+            return None;
+        }
+
+        Some(Self { nanosecond, ..self })
+    }
+
+    pub fn from_unix_timestamp(seconds: i64) -> Option<Self> {
+        let days = seconds.div_euclid(SECONDS_PER_DAY);
+        let secs_of_day = seconds.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days)?;
+
+        Self::from_ymd_hms(
+            year,
+            month,
+            day,
+            (secs_of_day / 3600) as u32,
+            (secs_of_day % 3600 / 60) as u32,
+            (secs_of_day % 60) as u32,
+        )
+    }
+
+    /// the current system time
+    pub fn now() -> Self {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is set before 1970");
+
+        Self::from_unix_timestamp(since_epoch.as_secs() as i64)
+            .and_then(|t| t.with_nanosecond(since_epoch.subsec_nanos()))
+            .expect("system clock is out of range")
+    }
+
+    /// seconds since 1970-01-01T00:00:00Z, ignoring the fractional part
+    pub fn unix_timestamp(&self) -> i64 {
+        let days = days_from_civil(self.year, self.month as u32, self.day as u32);
+        days * SECONDS_PER_DAY
+            + self.hour as i64 * 3600
+            + self.minute as i64 * 60
+            + self.second as i64
+    }
+
+    /// the time from `earlier` to this one, None if `earlier` is later
+    pub fn duration_since(&self, earlier: UtcDateTime) -> Option<Duration> {
+        if *self < earlier {
+            return None;
+        }
+        let seconds = self.unix_timestamp() - earlier.unix_timestamp();
+        let duration =
+            Duration::from_secs(seconds as u64) + Duration::from_nanos(self.nanosecond as u64);
+
+        duration.checked_sub(Duration::from_nanos(earlier.nanosecond as u64))
+    }
+
+    /// returns None if the result can't be represented
+    pub fn checked_add_seconds(self, seconds: i64) -> Option<Self> {
+        let timestamp = self.unix_timestamp().checked_add(seconds)?;
+        Self::from_unix_timestamp(timestamp)?.with_nanosecond(self.nanosecond)
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month as u32
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day as u32
+    }
+
+    pub fn hour(&self) -> u32 {
+        self.hour as u32
+    }
+
+    pub fn minute(&self) -> u32 {
+        self.minute as u32
+    }
+
+    pub fn second(&self) -> u32 {
+        self.second as u32
+    }
+
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+}
+
+/// formats as RFC 3339, e.g. `2021-07-31T16:44:00+00:00`
+impl Display for UtcDateTime {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+        if self.nanosecond != 0 {
+            let mut digits = 9;
+            let mut fraction = self.nanosecond;
+            while fraction.is_multiple_of(10) {
+                fraction /= 10;
+                digits -= 1;
+            }
+            write!(f, ".{:0width$}", fraction, width = digits)?;
+        }
+        write!(f, "+00:00")
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// days_from_civil and civil_from_days follow Howard Hinnant's algorithms
+// (http://howardhinnant.github.io/date_algorithms.html)
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 {
+        year as i64 - 1
+    } else {
+        year as i64
+    };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> Option<(i32, u32, u32)> {
+    let days = days.checked_add(719_468)?;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    if year < i32::MIN as i64 || year > i32::MAX as i64 {
+        return None;
+    }
+
+    Some((year as i32, month, day))
+}
+
+#[cfg(feature = "chrono")]
+impl From<UtcDateTime> for chrono::DateTime<chrono::Utc> {
+    fn from(t: UtcDateTime) -> Self {
+        use chrono::TimeZone;
+
+        let naive = chrono::NaiveDate::from_ymd_opt(t.year, t.month(), t.day())
+            .and_then(|d| d.and_hms_nano_opt(t.hour(), t.minute(), t.second(), t.nanosecond))
+            .expect("UtcDateTime always holds a valid date");
+        chrono::Utc.from_utc_datetime(&naive)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for UtcDateTime {
+    fn from(dt: chrono::DateTime<Tz>) -> Self {
+        use chrono::{Datelike, Timelike};
+
+        let naive = dt.naive_utc();
+        Self {
+            year: naive.year(),
+            month: naive.month() as u8,
+            day: naive.day() as u8,
+            hour: naive.hour() as u8,
+            minute: naive.minute() as u8,
+            // chrono represents leap seconds as nanoseconds >= 1s, fold them into the second
+            second: naive.second() as u8,
+            nanosecond: naive.nanosecond() % 1_000_000_000,
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl std::convert::TryFrom<UtcDateTime> for time::OffsetDateTime {
+    type Error = time::error::ComponentRange;
+
+    fn try_from(t: UtcDateTime) -> Result<Self, Self::Error> {
+        use std::convert::TryInto;
+
+        let date = time::Date::from_calendar_date(t.year, (t.month).try_into()?, t.day)?;
+        let time = time::Time::from_hms_nano(t.hour, t.minute, t.second, t.nanosecond)?;
+        Ok(time::PrimitiveDateTime::new(date, time).assume_utc())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for UtcDateTime {
+    fn from(dt: time::OffsetDateTime) -> Self {
+        let dt = dt.to_offset(time::UtcOffset::UTC);
+        Self {
+            year: dt.year(),
+            month: dt.month() as u8,
+            day: dt.day(),
+            hour: dt.hour(),
+            minute: dt.minute(),
+            second: dt.second(),
+            nanosecond: dt.nanosecond(),
+        }
+    }
+}
+
+#[test]
+fn test_unix_timestamp_round_trip() {
+    let t = UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 44, 40).unwrap();
+    assert_eq!(t.unix_timestamp(), 1_627_749_880);
+    assert_eq!(UtcDateTime::from_unix_timestamp(1_627_749_880), Some(t));
+
+    let t = UtcDateTime::from_ymd_hms(1969, 12, 31, 23, 59, 59).unwrap();
+    assert_eq!(t.unix_timestamp(), -1);
+    assert_eq!(UtcDateTime::from_unix_timestamp(-1), Some(t));
+
+    let leap_day = UtcDateTime::from_ymd_hms(2000, 2, 29, 0, 0, 0).unwrap();
+    assert_eq!(
+        UtcDateTime::from_unix_timestamp(leap_day.unix_timestamp()),
+        Some(leap_day)
+    );
+}
+
+#[test]
+fn test_duration_since() {
+    let earlier = UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 0, 0)
+        .unwrap()
+        .with_nanosecond(500_000_000)
+        .unwrap();
+    let later = UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 0, 2).unwrap();
+    assert_eq!(
+        later.duration_since(earlier),
+        Some(Duration::from_millis(1500))
+    );
+    assert_eq!(earlier.duration_since(later), None);
+    assert_eq!(later.duration_since(later), Some(Duration::from_secs(0)));
+}
+
+#[test]
+fn test_invalid_dates() {
+    assert!(UtcDateTime::from_ymd_hms(2021, 2, 29, 0, 0, 0).is_none());
+    assert!(UtcDateTime::from_ymd_hms(2021, 13, 1, 0, 0, 0).is_none());
+    assert!(UtcDateTime::from_ymd_hms(2021, 4, 31, 0, 0, 0).is_none());
+    assert!(UtcDateTime::from_ymd_hms(2021, 1, 1, 24, 0, 0).is_none());
+}
+
+#[test]
+fn test_display() {
+    let t = UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 44, 0).unwrap();
+    assert_eq!(t.to_string(), "2021-07-31T16:44:00+00:00");
+    let t = t.with_nanosecond(120_000_000).unwrap();
+    assert_eq!(t.to_string(), "2021-07-31T16:44:00.12+00:00");
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_time_conversion() {
+    use std::convert::TryFrom;
+
+    let t = UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 44, 40).unwrap();
+    let offset = time::OffsetDateTime::try_from(t).unwrap();
+    assert_eq!(offset.unix_timestamp(), t.unix_timestamp());
+    assert_eq!(UtcDateTime::from(offset), t);
+}
@@ -0,0 +1,184 @@
+//! Field by field comparison of two certificates, e.g. to make sure a renewed certificate only
+//! differs from the old one where it is expected to.
+
+use crate::parse::{der::ObjectIdentifierRef, parsing::CertificateRef};
+
+/// a field that differs between two certificates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change<'a> {
+    Version,
+    SerialNumber,
+    /// the signature algorithm, in tbsCertificate or in the outer certificate
+    SignatureAlgorithm,
+    Issuer,
+    NotBefore,
+    NotAfter,
+    Subject,
+    SubjectPublicKeyInfo,
+    IssuerUniqueId,
+    SubjectUniqueId,
+    ExtensionAdded(ObjectIdentifierRef<'a>),
+    ExtensionRemoved(ObjectIdentifierRef<'a>),
+    /// the value or the criticality changed
+    ExtensionChanged(ObjectIdentifierRef<'a>),
+    Signature,
+}
+
+/// the result of [`compare`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CertDiff<'a> {
+    changes: Vec<Change<'a>>,
+}
+
+impl<'a> CertDiff<'a> {
+    /// the changes, in the order of the fields in the certificate
+    pub fn changes(&self) -> &[Change<'a>] {
+        &self.changes
+    }
+
+    /// true if both certificates are identical
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn contains(&self, change: &Change) -> bool {
+        self.changes.contains(change)
+    }
+
+    /// true if every change is one of `allowed`, e.g. `&[Change::SerialNumber,
+    /// Change::NotBefore, Change::NotAfter, Change::Signature]` for a plain renewal
+    pub fn is_only(&self, allowed: &[Change]) -> bool {
+        self.changes.iter().all(|change| allowed.contains(change))
+    }
+}
+
+/// compares `old` and `new` field by field. times are compared by the instant they denote, all
+/// other fields by their encoding. extensions are matched up by their id, so reordering them
+/// is not a change.
+pub fn compare<'a>(old: &'a CertificateRef, new: &'a CertificateRef) -> CertDiff<'a> {
+    let mut changes = Vec::new();
+    let (old_tbs, new_tbs) = (old.tbs_cert(), new.tbs_cert());
+
+    let mut check = |changed: bool, change: Change<'a>| {
+        if changed {
+            changes.push(change);
+        }
+    };
+    check(old_tbs.version() != new_tbs.version(), Change::Version);
+    check(
+        old_tbs.serial_number() != new_tbs.serial_number(),
+        Change::SerialNumber,
+    );
+    check(
+        old_tbs.algorithm_identifier() != new_tbs.algorithm_identifier()
+            || old.signature_algorithm() != new.signature_algorithm(),
+        Change::SignatureAlgorithm,
+    );
+    check(old_tbs.issuer() != new_tbs.issuer(), Change::Issuer);
+    check(
+        old_tbs.validity().not_before().ok() != new_tbs.validity().not_before().ok(),
+        Change::NotBefore,
+    );
+    check(
+        old_tbs.validity().not_after().ok() != new_tbs.validity().not_after().ok(),
+        Change::NotAfter,
+    );
+    check(old_tbs.subject() != new_tbs.subject(), Change::Subject);
+    check(
+        old_tbs.subject_public_key_info() != new_tbs.subject_public_key_info(),
+        Change::SubjectPublicKeyInfo,
+    );
+    check(
+        old_tbs.issuer_unique_id() != new_tbs.issuer_unique_id(),
+        Change::IssuerUniqueId,
+    );
+    check(
+        old_tbs.subject_unique_id() != new_tbs.subject_unique_id(),
+        Change::SubjectUniqueId,
+    );
+
+    // the extensions from the first one that fails to parse on are left out, a malformed
+    // extension list shows up as removed or added extensions
+    let old_extensions: Vec<_> = old_tbs
+        .extensions()
+        .into_iter()
+        .flatten()
+        .map_while(Result::ok)
+        .collect();
+    let new_extensions: Vec<_> = new_tbs
+        .extensions()
+        .into_iter()
+        .flatten()
+        .map_while(Result::ok)
+        .collect();
+    for old_ext in &old_extensions {
+        let id = *old_ext.extension_id();
+        match new_extensions.iter().find(|e| *e.extension_id() == id) {
+            Some(new_ext) => check(
+                old_ext.critical() != new_ext.critical() || old_ext.value() != new_ext.value(),
+                Change::ExtensionChanged(id),
+            ),
+            None => check(true, Change::ExtensionRemoved(id)),
+        }
+    }
+    for new_ext in &new_extensions {
+        let id = *new_ext.extension_id();
+        if !old_extensions.iter().any(|e| *e.extension_id() == id) {
+            check(true, Change::ExtensionAdded(id));
+        }
+    }
+
+    check(old.signature() != new.signature(), Change::Signature);
+
+    CertDiff { changes }
+}
+
+#[test]
+fn test_compare() {
+    use crate::oids;
+
+    let leaf = CertificateRef::from_slice(include_bytes!("../../certs/ocsp/leaf.der")).unwrap();
+    let responder =
+        CertificateRef::from_slice(include_bytes!("../../certs/ocsp/responder.der")).unwrap();
+
+    assert!(compare(&leaf, &leaf).is_empty());
+
+    let diff = compare(&leaf, &responder);
+    assert!(diff.contains(&Change::SerialNumber));
+    assert!(diff.contains(&Change::Subject));
+    assert!(diff.contains(&Change::Signature));
+    assert!(!diff.contains(&Change::Issuer));
+    assert!(!diff.contains(&Change::Version));
+    assert!(diff.contains(&Change::ExtensionChanged(oids::EXT_KEY_USAGE)));
+    assert!(!diff.is_only(&[Change::SerialNumber, Change::Signature]));
+}
+
+#[test]
+fn test_compare_malformed_extensions() {
+    let der = include_bytes!("../../certs/ocsp/leaf.der");
+    let leaf = CertificateRef::from_slice(der).unwrap();
+    let first_id = *leaf
+        .tbs_cert()
+        .extensions()
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap()
+        .unwrap()
+        .extension_id();
+
+    // the SEQUENCE in front of the first id-ce OID is the first extension. turned into a SET,
+    // none of the extensions parse.
+    let oid = der
+        .windows(4)
+        .position(|w| w == [0x06, 0x03, 0x55, 0x1d])
+        .unwrap();
+    let mut malformed = der.to_vec();
+    assert_eq!(malformed[oid - 2], 0x30);
+    malformed[oid - 2] = 0x31;
+    let malformed = CertificateRef::from_slice(&malformed).unwrap();
+
+    let diff = compare(&leaf, &malformed);
+    assert!(diff.contains(&Change::ExtensionRemoved(first_id)));
+    assert!(!diff.contains(&Change::Signature));
+}
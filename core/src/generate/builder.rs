@@ -1,34 +1,139 @@
 use super::{
     certificate::{AlgorithmIdentifier, Extensions, Name, SubjectPublicKeyInfo, Validity},
-    der::{wrap_in_explicit_tag, DataType, Integer, ToDer},
+    der::{
+        prepend_header, tlv_len, wrap_in_explicit_tag, write_bytes, write_header, BitString,
+        DataType, Integer, ToDer,
+    },
     error::EncodingError,
+    inputs::GenerationInputs,
 };
 use crate::common::{certificate::Version, der::ExplicitTag};
+use crate::utils::to_hex_colon;
+use std::convert::TryFrom;
+use std::io::Write;
+use std::time::Duration;
 
 #[derive(Builder)]
-#[builder(pattern = "owned")]
+#[builder(pattern = "owned", build_fn(validate = "Self::validate"))]
 pub struct TBSCertificate {
+    /// V1 leaves the version field out. extensions require V3, unique identifiers V2 or V3.
+    #[builder(default = "Version::V3")]
+    pub version: Version,
     pub serial_number: Integer,
     pub signature: AlgorithmIdentifier,
     pub issuer: Name,
     pub validity: Validity,
     pub subject: Name,
     pub subject_public_key_info: SubjectPublicKeyInfo,
+    #[builder(default)]
+    pub issuer_unique_id: Option<BitString>,
+    #[builder(default)]
+    pub subject_unique_id: Option<BitString>,
     pub extensions: Option<Extensions>,
 }
 
+impl TBSCertificate {
+    /// the serial number as colon separated uppercase hex, the way browsers show it
+    pub fn serial_hex(&self) -> String {
+        to_hex_colon(self.serial_number.as_bytes())
+    }
+
+    /// see [`Validity::period`]
+    pub fn validity_period(&self) -> Duration {
+        self.validity.period()
+    }
+
+    /// the DER encodings of the unique identifiers that are present, in field order
+    fn unique_ids(&self) -> Result<Vec<Vec<u8>>, EncodingError> {
+        let ids = [(1, &self.issuer_unique_id), (2, &self.subject_unique_id)];
+        let mut encoded = Vec::new();
+        for (tag_number, id) in ids.iter() {
+            if let Some(id) = id {
+                // [1] and [2] are IMPLICIT, so only the tag of the BIT STRING changes
+                let mut der = id.to_der()?;
+                der[0] = 0x80 | tag_number;
+                encoded.push(der);
+            }
+        }
+
+        Ok(encoded)
+    }
+
+    fn inner_len(&self) -> Result<usize, EncodingError> {
+        let mut len = self.serial_number.encoded_len()?
+            + self.signature.encoded_len()?
+            + self.issuer.encoded_len()?
+            + self.validity.encoded_len()?
+            + self.subject.encoded_len()?
+            + self.subject_public_key_info.encoded_len()?;
+        if self.version != Version::V1 {
+            len += self.version.encoded_len()?;
+        }
+        for id in self.unique_ids()? {
+            len += id.len();
+        }
+        if let Some(extensions) = &self.extensions {
+            len += tlv_len(extensions.encoded_len()?);
+        }
+
+        Ok(len)
+    }
+}
+
+impl TBSCertificateBuilder {
+    /// sets the serial number to the next one of `inputs` and the validity to `period` from the
+    /// current time of `inputs`
+    pub fn issued_now(
+        self,
+        inputs: &mut GenerationInputs,
+        period: Duration,
+    ) -> Result<Self, EncodingError> {
+        let not_before = inputs.now();
+        let not_after = i64::try_from(period.as_secs())
+            .ok()
+            .and_then(|seconds| not_before.checked_add_seconds(seconds))
+            .ok_or(EncodingError::TimeOutOfRange)?;
+
+        Ok(self
+            .serial_number(inputs.next_serial_number()?)
+            .validity(Validity::new(not_before, not_after)))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let version = self.version.as_ref().unwrap_or(&Version::V3);
+        if matches!(self.extensions, Some(Some(_))) && *version != Version::V3 {
+            return Err("extensions require a V3 certificate".to_string());
+        }
+        let has_unique_ids = matches!(self.issuer_unique_id, Some(Some(_)))
+            || matches!(self.subject_unique_id, Some(Some(_)));
+        if has_unique_ids && *version == Version::V1 {
+            return Err("unique identifiers require a V2 or V3 certificate".to_string());
+        }
+
+        Ok(())
+    }
+}
+
 impl ToDer for TBSCertificate {
     fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
         let mut tbs = Vec::new();
-        tbs.extend_from_slice(&Version::V3.to_der()?);
+        // v1 is the DEFAULT, DER leaves it out
+        if self.version != Version::V1 {
+            tbs.extend_from_slice(&self.version.to_der()?);
+        }
         tbs.extend_from_slice(&self.serial_number.to_der()?);
         tbs.extend_from_slice(&self.signature.to_der()?);
         tbs.extend_from_slice(&self.issuer.to_der()?);
         tbs.extend_from_slice(&self.validity.to_der()?);
         tbs.extend_from_slice(&self.subject.to_der()?);
         tbs.extend_from_slice(&self.subject_public_key_info.to_der()?);
+        for id in self.unique_ids()? {
+            tbs.extend_from_slice(&id);
+        }
         if let Some(extensions) = &self.extensions {
-            assert!(extensions.len() >= 1);
+            if extensions.is_empty() {
+                return Err(EncodingError::EmptyExtensions);
+            }
             let extensions_der = extensions.to_der()?;
             tbs.extend_from_slice(&wrap_in_explicit_tag(
                 &extensions_der,
@@ -39,13 +144,78 @@ impl ToDer for TBSCertificate {
         Ok(tbs)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut len = 0;
+        if self.version != Version::V1 {
+            len += self.version.to_der_into(buf)?;
+        }
+        len += self.serial_number.to_der_into(&mut buf[len..])?;
+        len += self.signature.to_der_into(&mut buf[len..])?;
+        len += self.issuer.to_der_into(&mut buf[len..])?;
+        len += self.validity.to_der_into(&mut buf[len..])?;
+        len += self.subject.to_der_into(&mut buf[len..])?;
+        len += self.subject_public_key_info.to_der_into(&mut buf[len..])?;
+        for id in self.unique_ids()? {
+            len += write_bytes(&id, &mut buf[len..])?;
+        }
+        if let Some(extensions) = &self.extensions {
+            if extensions.is_empty() {
+                return Err(EncodingError::EmptyExtensions);
+            }
+            let extensions_len = extensions.to_der_into(&mut buf[len..])?;
+            len += prepend_header(
+                ExplicitTag::try_new(3).unwrap().get_identifier_octet(),
+                extensions_len,
+                &mut buf[len..],
+            )?;
+        }
+
+        Ok(len)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Sequence.constructed()
     }
+
+    fn encoded_len(&self) -> Result<usize, EncodingError> {
+        Ok(tlv_len(self.inner_len()?))
+    }
+
+    fn write_der(&self, out: &mut dyn Write) -> Result<usize, EncodingError> {
+        // check before anything reaches `out`
+        if matches!(&self.extensions, Some(extensions) if extensions.is_empty()) {
+            return Err(EncodingError::EmptyExtensions);
+        }
+        let mut len = write_header(self.get_tag(), self.inner_len()?, out)?;
+        if self.version != Version::V1 {
+            len += self.version.write_der(out)?;
+        }
+        len += self.serial_number.write_der(out)?;
+        len += self.signature.write_der(out)?;
+        len += self.issuer.write_der(out)?;
+        len += self.validity.write_der(out)?;
+        len += self.subject.write_der(out)?;
+        len += self.subject_public_key_info.write_der(out)?;
+        for id in self.unique_ids()? {
+            out.write_all(&id)?;
+            len += id.len();
+        }
+        if let Some(extensions) = &self.extensions {
+            len += write_header(
+                ExplicitTag::try_new(3).unwrap().get_identifier_octet(),
+                extensions.encoded_len()?,
+                out,
+            )?;
+            len += extensions.write_der(out)?;
+        }
+
+        Ok(len)
+    }
 }
 
 #[test]
 fn test_tbs_cert_builder() {
+    use crate::common::time::UtcDateTime;
     use crate::generate::der::Data;
 
     let builder = TBSCertificateBuilder::default();
@@ -59,12 +229,8 @@ fn test_tbs_cert_builder() {
     issuer_dn.push(rdn_cn);
 
     //VALIDITY
-    let not_before = chrono::DateTime::parse_from_rfc3339("2021-07-31T12:33:53-00:00")
-        .unwrap()
-        .with_timezone(&chrono::Utc);
-    let not_after = chrono::DateTime::parse_from_rfc3339("2022-07-31T12:33:53-00:00")
-        .unwrap()
-        .with_timezone(&chrono::Utc);
+    let not_before = UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap();
+    let not_after = UtcDateTime::from_ymd_hms(2022, 7, 31, 12, 33, 53).unwrap();
     let validity = Validity::new(not_before, not_after);
 
     //SUBJECT
@@ -106,7 +272,196 @@ fn test_tbs_cert_builder() {
         .build()
         .unwrap();
     let tbs_bytes = tbs.to_der().unwrap();
+    assert_eq!(tbs.serial_hex(), "0A");
+    assert_eq!(
+        tbs.validity_period(),
+        Duration::from_secs(365 * 24 * 60 * 60)
+    );
 
     let res = crate::parse::parsing::expect_tbs(&tbs_bytes);
     assert!(res.is_ok());
+
+    let mut buf = [0u8; 1024];
+    let len = tbs.to_der_into(&mut buf).unwrap();
+    assert_eq!(&buf[..len], &tbs_bytes[..]);
+    assert!(tbs.to_der_into(&mut buf[..len - 1]).is_err());
+
+    let mut streamed = Vec::new();
+    assert_eq!(tbs.write_der(&mut streamed).unwrap(), tbs_bytes.len());
+    assert_eq!(tbs.encoded_len().unwrap(), tbs_bytes.len());
+    assert_eq!(streamed, tbs_bytes);
+}
+
+#[test]
+fn test_unique_ids() {
+    use crate::common::time::UtcDateTime;
+    use crate::generate::der::{Data, Null, ObjectIdentifier};
+
+    let algorithm = AlgorithmIdentifier::new(
+        ObjectIdentifier::from_str("1.2.840.113549.1.1.1").unwrap(),
+        Data::Null(Null()),
+    );
+    let not_before = UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap();
+    let tbs = TBSCertificateBuilder::default()
+        .serial_number(Integer::from_i64(1))
+        .signature(algorithm.clone())
+        .issuer(Name::DistinguishedName(Default::default()))
+        .validity(Validity::new(not_before, not_before))
+        .subject(Name::DistinguishedName(Default::default()))
+        .subject_public_key_info(SubjectPublicKeyInfo::new(
+            algorithm,
+            BitString::new(vec![20; 4], 32),
+        ))
+        .issuer_unique_id(Some(BitString::new(vec![0xab], 8)))
+        .subject_unique_id(Some(BitString::try_new(vec![0xcd, 0xe0], 11).unwrap()))
+        .extensions(None)
+        .build()
+        .unwrap();
+    let tbs_bytes = tbs.to_der().unwrap();
+    assert!(tbs_bytes.ends_with(&[0x81, 0x02, 0x00, 0xab, 0x82, 0x03, 0x05, 0xcd, 0xe0]));
+
+    let (_, parsed) = crate::parse::parsing::expect_tbs(&tbs_bytes).unwrap();
+    assert_eq!(parsed.issuer_unique_id().unwrap().data(), (0, &[0xab][..]));
+    assert_eq!(
+        parsed.subject_unique_id().unwrap().data(),
+        (5, &[0xcd, 0xe0][..])
+    );
+
+    let mut buf = [0u8; 256];
+    let len = tbs.to_der_into(&mut buf).unwrap();
+    assert_eq!(&buf[..len], &tbs_bytes[..]);
+    let mut streamed = Vec::new();
+    assert_eq!(tbs.write_der(&mut streamed).unwrap(), tbs_bytes.len());
+    assert_eq!(tbs.encoded_len().unwrap(), tbs_bytes.len());
+    assert_eq!(streamed, tbs_bytes);
+}
+
+#[test]
+fn test_issued_now() {
+    use crate::common::time::UtcDateTime;
+    use crate::generate::der::{Data, Null, ObjectIdentifier};
+
+    let build = |inputs: &mut GenerationInputs| {
+        let algorithm = AlgorithmIdentifier::new(
+            ObjectIdentifier::from_str("1.2.840.113549.1.1.1").unwrap(),
+            Data::Null(Null()),
+        );
+        TBSCertificateBuilder::default()
+            .issued_now(inputs, Duration::from_secs(90 * 24 * 60 * 60))
+            .unwrap()
+            .signature(algorithm.clone())
+            .issuer(Name::DistinguishedName(Default::default()))
+            .subject(Name::DistinguishedName(Default::default()))
+            .subject_public_key_info(SubjectPublicKeyInfo::new(
+                algorithm,
+                BitString::new(vec![20; 4], 32),
+            ))
+            .extensions(None)
+            .build()
+            .unwrap()
+            .to_der()
+            .unwrap()
+    };
+
+    let now = UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap();
+    let first = build(&mut GenerationInputs::deterministic(now));
+    assert_eq!(first, build(&mut GenerationInputs::deterministic(now)));
+    let (_, parsed) = crate::parse::parsing::expect_tbs(&first).unwrap();
+    assert_eq!(parsed.serial_number().as_bytes(), &[1]);
+    assert_eq!(parsed.validity().not_before().unwrap(), now);
+    assert_eq!(
+        parsed.validity().not_after().unwrap(),
+        UtcDateTime::from_ymd_hms(2021, 10, 29, 12, 33, 53).unwrap()
+    );
+
+    // the next certificate gets the next serial number
+    let mut inputs = GenerationInputs::deterministic(now);
+    build(&mut inputs);
+    let second = build(&mut inputs);
+    let (_, second) = crate::parse::parsing::expect_tbs(&second).unwrap();
+    assert_eq!(second.serial_number().as_bytes(), &[2]);
+}
+
+#[test]
+fn test_version() {
+    use crate::common::time::UtcDateTime;
+    use crate::generate::der::{Data, Null, ObjectIdentifier};
+
+    let builder = || {
+        let algorithm = AlgorithmIdentifier::new(
+            ObjectIdentifier::from_str("1.2.840.113549.1.1.1").unwrap(),
+            Data::Null(Null()),
+        );
+        let not_before = UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap();
+        TBSCertificateBuilder::default()
+            .serial_number(Integer::from_i64(1))
+            .signature(algorithm.clone())
+            .issuer(Name::DistinguishedName(Default::default()))
+            .validity(Validity::new(not_before, not_before))
+            .subject(Name::DistinguishedName(Default::default()))
+            .subject_public_key_info(SubjectPublicKeyInfo::new(
+                algorithm,
+                BitString::new(vec![20; 4], 32),
+            ))
+    };
+
+    let tbs = builder()
+        .version(Version::V1)
+        .extensions(None)
+        .build()
+        .unwrap();
+    let tbs_bytes = tbs.to_der().unwrap();
+    // the serial number directly follows the SEQUENCE header
+    assert_eq!(&tbs_bytes[2..5], &[0x02, 0x01, 0x01]);
+    let (_, parsed) = crate::parse::parsing::expect_tbs(&tbs_bytes).unwrap();
+    assert_eq!(parsed.version(), &Version::V1);
+
+    let mut buf = [0u8; 256];
+    let len = tbs.to_der_into(&mut buf).unwrap();
+    assert_eq!(&buf[..len], &tbs_bytes[..]);
+    let mut streamed = Vec::new();
+    assert_eq!(tbs.write_der(&mut streamed).unwrap(), tbs_bytes.len());
+    assert_eq!(tbs.encoded_len().unwrap(), tbs_bytes.len());
+
+    let mut tbs = builder().extensions(None).build().unwrap();
+    tbs.extensions = Some(Extensions::default());
+    assert!(matches!(tbs.to_der(), Err(EncodingError::EmptyExtensions)));
+    assert!(matches!(
+        tbs.to_der_into(&mut buf),
+        Err(EncodingError::EmptyExtensions)
+    ));
+    let mut streamed = Vec::new();
+    assert!(matches!(
+        tbs.write_der(&mut streamed),
+        Err(EncodingError::EmptyExtensions)
+    ));
+    assert!(streamed.is_empty());
+
+    let tbs = builder()
+        .version(Version::V2)
+        .subject_unique_id(Some(BitString::new(vec![0xab], 8)))
+        .extensions(None)
+        .build()
+        .unwrap();
+    let tbs_bytes = tbs.to_der().unwrap();
+    let (_, parsed) = crate::parse::parsing::expect_tbs(&tbs_bytes).unwrap();
+    assert_eq!(parsed.version(), &Version::V2);
+
+    let mut extensions = Extensions::default();
+    extensions.add(super::certificate::Extension::new(
+        ObjectIdentifier::from_str("3.8.7").unwrap(),
+        false.into(),
+        super::der::OctetString::new(vec![3; 2]),
+    ));
+    assert!(builder()
+        .version(Version::V2)
+        .extensions(Some(extensions))
+        .build()
+        .is_err());
+    assert!(builder()
+        .version(Version::V1)
+        .issuer_unique_id(Some(BitString::new(vec![0xab], 8)))
+        .extensions(None)
+        .build()
+        .is_err());
 }
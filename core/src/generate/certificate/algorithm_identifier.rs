@@ -1,36 +1,254 @@
+use crate::common::certificate::ParameterRule;
+use crate::common::der::ExplicitTag;
 use crate::generate::der::Data;
+use crate::oids;
 
 use super::super::{
-    der::{DataType, ObjectIdentifier, ToDer},
+    der::{
+        encode_tlv, wrap_in_explicit_tag, write_bytes, DataType, Integer, Null, ObjectIdentifier,
+        ToDer,
+    },
     error::EncodingError,
 };
 
 #[derive(Clone)]
 pub struct AlgorithmIdentifier {
     algorithm_identifier: ObjectIdentifier,
-    parameters: Data, // any type
+    parameters: AlgorithmParameters,
+}
+
+/// the parameters of an [`AlgorithmIdentifier`]. which of them an algorithm takes is decided
+/// by its [`ParameterRule`].
+#[derive(Clone)]
+pub enum AlgorithmParameters {
+    /// the field is left out, e.g. for ECDSA and Ed25519
+    Absent,
+    /// e.g. for rsaEncryption and the PKCS#1 v1.5 signature algorithms
+    Null,
+    /// the curve of id-ecPublicKey, e.g. [`oids::SECP256R1`]
+    NamedCurve(ObjectIdentifier),
+    /// RSASSA-PSS-params (RFC 4055 section 3.1) with MGF1 over the same hash and the default
+    /// trailer field
+    RsassaPss {
+        hash_algorithm: ObjectIdentifier,
+        salt_length: u32,
+    },
+    /// any other value
+    Other(Data),
+    /// an already encoded value, e.g. explicit curve parameters. it's written as is, like a
+    /// [`Name::Encoded`](super::Name::Encoded).
+    Raw(Vec<u8>),
+}
+
+impl AlgorithmParameters {
+    /// whether `rule` allows these parameters
+    pub fn conforms_to(&self, rule: ParameterRule) -> bool {
+        match rule {
+            ParameterRule::Null => matches!(self, AlgorithmParameters::Null),
+            ParameterRule::Absent => matches!(self, AlgorithmParameters::Absent),
+            ParameterRule::NullOrAbsent => {
+                matches!(
+                    self,
+                    AlgorithmParameters::Null | AlgorithmParameters::Absent
+                )
+            }
+            ParameterRule::NamedCurve => matches!(self, AlgorithmParameters::NamedCurve(_)),
+            ParameterRule::RsassaPss => matches!(
+                self,
+                AlgorithmParameters::RsassaPss { .. } | AlgorithmParameters::Absent
+            ),
+            ParameterRule::Any => true,
+        }
+    }
+
+    fn to_der(&self) -> Result<Vec<u8>, EncodingError> {
+        match self {
+            AlgorithmParameters::Absent => Ok(Vec::new()),
+            AlgorithmParameters::Null => Null().to_der(),
+            AlgorithmParameters::NamedCurve(curve) => curve.to_der(),
+            AlgorithmParameters::RsassaPss {
+                hash_algorithm,
+                salt_length,
+            } => {
+                let hash_algorithm =
+                    AlgorithmIdentifier::new(hash_algorithm.clone(), Data::Null(Null()))
+                        .to_der()?;
+                let mgf1: ObjectIdentifier = oids::MGF1.into();
+                let mut mask_gen_algorithm = mgf1.to_der()?;
+                mask_gen_algorithm.extend_from_slice(&hash_algorithm);
+                let mask_gen_algorithm =
+                    encode_tlv(DataType::Sequence.constructed(), &mask_gen_algorithm);
+                let salt_length = Integer::from_i64(i64::from(*salt_length)).to_der()?;
+
+                let mut params =
+                    wrap_in_explicit_tag(&hash_algorithm, ExplicitTag::try_new(0).unwrap());
+                params.extend_from_slice(&wrap_in_explicit_tag(
+                    &mask_gen_algorithm,
+                    ExplicitTag::try_new(1).unwrap(),
+                ));
+                params.extend_from_slice(&wrap_in_explicit_tag(
+                    &salt_length,
+                    ExplicitTag::try_new(2).unwrap(),
+                ));
+
+                Ok(encode_tlv(DataType::Sequence.constructed(), &params))
+            }
+            AlgorithmParameters::Other(data) => data.to_der(),
+            AlgorithmParameters::Raw(encoded) => Ok(encoded.clone()),
+        }
+    }
+
+    fn to_der_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        match self {
+            AlgorithmParameters::Absent => Ok(0),
+            AlgorithmParameters::Null => Null().to_der_into(buf),
+            AlgorithmParameters::NamedCurve(curve) => curve.to_der_into(buf),
+            AlgorithmParameters::Other(data) => data.to_der_into(buf),
+            AlgorithmParameters::Raw(encoded) => write_bytes(encoded, buf),
+            AlgorithmParameters::RsassaPss { .. } => write_bytes(&self.to_der()?, buf),
+        }
+    }
 }
 
 impl AlgorithmIdentifier {
+    /// `parameters` are not checked, see [`with_parameters`](AlgorithmIdentifier::with_parameters)
     pub fn new(algorithm_identifier: ObjectIdentifier, parameters: Data) -> Self {
+        let parameters = match parameters {
+            Data::Null(_) => AlgorithmParameters::Null,
+            data => AlgorithmParameters::Other(data),
+        };
         Self {
             algorithm_identifier,
             parameters,
         }
     }
-    // todo allow mutating parameters after construction
+
+    /// fails with [`EncodingError::InvalidParameters`] if the [`ParameterRule`] of the algorithm
+    /// doesn't allow `parameters`, e.g. NULL for ECDSA
+    pub fn with_parameters(
+        algorithm_identifier: ObjectIdentifier,
+        parameters: AlgorithmParameters,
+    ) -> Result<Self, EncodingError> {
+        let rule = ParameterRule::for_algorithm(&(&algorithm_identifier).into());
+        if !parameters.conforms_to(rule) {
+            return Err(EncodingError::InvalidParameters);
+        }
+
+        Ok(Self {
+            algorithm_identifier,
+            parameters,
+        })
+    }
+
+    /// id-RSASSA-PSS with the hash function `hash_algorithm`, e.g. [`oids::SHA256`], for both
+    /// the message and MGF1 and a salt of `salt_length` bytes
+    pub fn rsassa_pss(hash_algorithm: ObjectIdentifier, salt_length: u32) -> Self {
+        Self {
+            algorithm_identifier: oids::RSASSA_PSS.into(),
+            parameters: AlgorithmParameters::RsassaPss {
+                hash_algorithm,
+                salt_length,
+            },
+        }
+    }
+
+    /// an algorithm whose parameters must be absent, not NULL, like Ed25519
+    pub fn without_parameters(algorithm_identifier: ObjectIdentifier) -> Self {
+        Self {
+            algorithm_identifier,
+            parameters: AlgorithmParameters::Absent,
+        }
+    }
+
+    pub fn parameters(&self) -> &AlgorithmParameters {
+        &self.parameters
+    }
 }
 
 impl ToDer for AlgorithmIdentifier {
     fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
         let mut algorithm_identifier = self.algorithm_identifier.to_der()?;
-        let params = self.parameters.to_der()?;
-        algorithm_identifier.extend_from_slice(&params);
+        algorithm_identifier.extend_from_slice(&self.parameters.to_der()?);
 
         Ok(algorithm_identifier)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut len = self.algorithm_identifier.to_der_into(buf)?;
+        len += self.parameters.to_der_into(&mut buf[len..])?;
+
+        Ok(len)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Sequence.constructed()
     }
 }
+
+#[test]
+fn test_without_parameters() {
+    let ed25519 = AlgorithmIdentifier::without_parameters(crate::oids::ED25519.into());
+    assert_eq!(
+        ed25519.to_der().unwrap(),
+        [0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70]
+    );
+    let mut buf = [0u8; 7];
+    assert_eq!(ed25519.to_der_into(&mut buf).unwrap(), 7);
+    assert_eq!(buf.to_vec(), ed25519.to_der().unwrap());
+}
+
+#[test]
+fn test_rsassa_pss() {
+    // the encoding openssl uses for -sigopt rsa_padding_mode:pss with SHA-256
+    let pss = AlgorithmIdentifier::rsassa_pss(oids::SHA256.into(), 32);
+    let der = pss.to_der().unwrap();
+    assert_eq!(
+        der,
+        [
+            0x30, 0x41, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0a, 0x30,
+            0x34, 0xa0, 0x0f, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04,
+            0x02, 0x01, 0x05, 0x00, 0xa1, 0x1c, 0x30, 0x1a, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86,
+            0xf7, 0x0d, 0x01, 0x01, 0x08, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65,
+            0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0xa2, 0x03, 0x02, 0x01, 0x20
+        ]
+    );
+    let mut buf = [0u8; 67];
+    assert_eq!(pss.to_der_into(&mut buf).unwrap(), 67);
+    assert_eq!(buf.to_vec(), der);
+}
+
+#[test]
+fn test_with_parameters() {
+    let ecdsa = || oids::ECDSA_WITH_SHA256.into();
+    assert!(matches!(
+        AlgorithmIdentifier::with_parameters(ecdsa(), AlgorithmParameters::Null),
+        Err(EncodingError::InvalidParameters)
+    ));
+    let ecdsa = AlgorithmIdentifier::with_parameters(ecdsa(), AlgorithmParameters::Absent).unwrap();
+    assert_eq!(
+        ecdsa.to_der().unwrap(),
+        [0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02]
+    );
+
+    let curve = AlgorithmParameters::NamedCurve(oids::SECP256R1.into());
+    let ec = AlgorithmIdentifier::with_parameters(oids::EC_PUBLIC_KEY.into(), curve).unwrap();
+    let der = ec.to_der().unwrap();
+    let (_, parsed) = crate::parse::certificate::parse_algorithm_identifier(&der).unwrap();
+    assert!(parsed.check_parameters().is_ok());
+    let mut buf = [0u8; 21];
+    assert_eq!(ec.to_der_into(&mut buf).unwrap(), 21);
+    assert_eq!(buf.to_vec(), der);
+    assert!(AlgorithmIdentifier::with_parameters(
+        oids::EC_PUBLIC_KEY.into(),
+        AlgorithmParameters::Null
+    )
+    .is_err());
+
+    // an unknown algorithm takes anything, here an empty SEQUENCE
+    let raw = AlgorithmParameters::Raw(vec![0x30, 0x00]);
+    let other = AlgorithmIdentifier::with_parameters(oids::COMMON_NAME.into(), raw).unwrap();
+    assert_eq!(
+        other.to_der().unwrap(),
+        [0x30, 0x07, 0x06, 0x03, 0x55, 0x04, 0x03, 0x30, 0x00]
+    );
+}
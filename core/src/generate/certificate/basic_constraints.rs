@@ -0,0 +1,63 @@
+use super::super::{
+    der::{Boolean, DataType, Integer, ToDer},
+    error::EncodingError,
+};
+
+/// the value of the BasicConstraints extension (RFC 5280 section 4.2.1.9)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BasicConstraints {
+    ca: bool,
+    path_len_constraint: Option<u32>,
+}
+
+impl BasicConstraints {
+    /// a CA certificate, optionally limiting the number of intermediates below it
+    pub fn ca(path_len_constraint: Option<u32>) -> Self {
+        Self {
+            ca: true,
+            path_len_constraint,
+        }
+    }
+
+    pub fn end_entity() -> Self {
+        Self {
+            ca: false,
+            path_len_constraint: None,
+        }
+    }
+}
+
+impl ToDer for BasicConstraints {
+    fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
+        let mut res = Vec::new();
+        // cA is DEFAULT FALSE, so it is only written when true
+        if self.ca {
+            res.extend_from_slice(&Boolean::from(true).to_der()?);
+            if let Some(path_len) = self.path_len_constraint {
+                res.extend_from_slice(&Integer::from_i64(path_len as i64).to_der()?);
+            }
+        }
+
+        Ok(res)
+    }
+
+    fn get_tag(&self) -> u8 {
+        DataType::Sequence.constructed()
+    }
+}
+
+#[test]
+fn test_basic_constraints() {
+    use crate::parse::extensions::BasicConstraints as ParsedBasicConstraints;
+
+    let der = BasicConstraints::ca(Some(0)).to_der().unwrap();
+    assert_eq!(der, vec![0x30, 0x06, 0x01, 0x01, 0xff, 0x02, 0x01, 0x00]);
+    let parsed = ParsedBasicConstraints::from_slice(&der).unwrap();
+    assert!(parsed.is_ca());
+    assert_eq!(parsed.path_len_constraint(), Some(0));
+
+    assert_eq!(
+        BasicConstraints::end_entity().to_der().unwrap(),
+        vec![0x30, 0x00]
+    );
+}
@@ -0,0 +1,82 @@
+use std::convert::TryFrom;
+
+use super::super::{
+    der::{Data, PrintableString, ToDer, Utf8String},
+    error::EncodingError,
+};
+
+/// the DirectoryString CHOICE used for most DN attribute values.
+///
+/// RFC 5280 wants new certificates to use UTF8String, except for attributes like countryName
+/// that are PrintableString by definition. [`DirectoryString::new`] picks PrintableString when
+/// the value allows it, which covers both and matches what most CAs issue.
+#[derive(Clone)]
+pub enum DirectoryString {
+    PrintableString(PrintableString),
+    Utf8String(Utf8String),
+}
+
+impl DirectoryString {
+    /// PrintableString if every character is part of its alphabet, UTF8String otherwise
+    pub fn new(s: &str) -> Self {
+        match PrintableString::try_from(s) {
+            Ok(printable) => DirectoryString::PrintableString(printable),
+            Err(_) => DirectoryString::Utf8String(Utf8String::from_str(s)),
+        }
+    }
+
+    /// forces PrintableString, fails if `s` contains other characters
+    pub fn printable(s: &str) -> Result<Self, EncodingError> {
+        Ok(DirectoryString::PrintableString(PrintableString::try_from(
+            s,
+        )?))
+    }
+
+    /// forces UTF8String
+    pub fn utf8(s: &str) -> Self {
+        DirectoryString::Utf8String(Utf8String::from_str(s))
+    }
+}
+
+impl ToDer for DirectoryString {
+    fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
+        match self {
+            DirectoryString::PrintableString(s) => s.encode_inner(),
+            DirectoryString::Utf8String(s) => s.encode_inner(),
+        }
+    }
+
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        match self {
+            DirectoryString::PrintableString(s) => s.encode_inner_into(buf),
+            DirectoryString::Utf8String(s) => s.encode_inner_into(buf),
+        }
+    }
+
+    fn get_tag(&self) -> u8 {
+        match self {
+            DirectoryString::PrintableString(s) => s.get_tag(),
+            DirectoryString::Utf8String(s) => s.get_tag(),
+        }
+    }
+}
+
+impl From<DirectoryString> for Data {
+    fn from(s: DirectoryString) -> Self {
+        match s {
+            DirectoryString::PrintableString(s) => Data::PrintableString(s),
+            DirectoryString::Utf8String(s) => Data::Utf8String(s),
+        }
+    }
+}
+
+#[test]
+fn test_type_selection() {
+    assert_eq!(DirectoryString::new("DE").to_der().unwrap(), b"\x13\x02DE");
+    assert_eq!(
+        DirectoryString::new("M\u{fc}nchen").to_der().unwrap(),
+        b"\x0c\x08M\xc3\xbcnchen"
+    );
+    assert_eq!(DirectoryString::utf8("DE").to_der().unwrap(), b"\x0c\x02DE");
+    assert!(DirectoryString::printable("a@b").is_err());
+}
@@ -0,0 +1,50 @@
+use super::super::{
+    der::{DataType, ObjectIdentifier, ToDer},
+    error::EncodingError,
+};
+use crate::parse::der::ObjectIdentifierRef;
+
+/// the value of the ExtendedKeyUsage extension (RFC 5280 section 4.2.1.12)
+#[derive(Clone, Default)]
+pub struct ExtendedKeyUsage(Vec<ObjectIdentifier>);
+
+impl ExtendedKeyUsage {
+    /// adds a key purpose, e.g. [`oids::KP_SERVER_AUTH`](crate::oids::KP_SERVER_AUTH)
+    pub fn purpose(mut self, purpose: ObjectIdentifierRef) -> Self {
+        self.0.push(purpose.into());
+        self
+    }
+}
+
+impl ToDer for ExtendedKeyUsage {
+    fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
+        let mut res = Vec::new();
+        for purpose in self.0.iter() {
+            res.extend_from_slice(&purpose.to_der()?);
+        }
+
+        Ok(res)
+    }
+
+    fn get_tag(&self) -> u8 {
+        DataType::Sequence.constructed()
+    }
+}
+
+#[test]
+fn test_extended_key_usage() {
+    use crate::oids;
+    use crate::parse::extensions::{ExtendedKeyUsageRef, KeyPurpose};
+
+    let der = ExtendedKeyUsage::default()
+        .purpose(oids::KP_SERVER_AUTH)
+        .purpose(oids::KP_CLIENT_AUTH)
+        .to_der()
+        .unwrap();
+    let parsed = ExtendedKeyUsageRef::from_slice(&der).unwrap();
+    let purposes: Vec<_> = parsed.key_purposes().map(Result::unwrap).collect();
+    assert_eq!(
+        purposes,
+        vec![KeyPurpose::ServerAuth, KeyPurpose::ClientAuth]
+    );
+}
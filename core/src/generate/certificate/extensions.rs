@@ -1,5 +1,10 @@
 use crate::generate::error::EncodingError;
-use super::super::der::{Boolean, DataType, ObjectIdentifier, OctetString, ToDer};
+use crate::oids;
+use super::super::der::{
+    tlv_len, write_header, Boolean, DataType, Null, ObjectIdentifier, OctetString, ToDer,
+};
+use super::{BasicConstraints, ExtendedKeyUsage, GeneralNames, KeyUsage, Name};
+use std::io::Write;
 use std::usize;
 
 pub trait IntoExtension {
@@ -22,6 +27,79 @@ impl Extension {
             value,
         }
     }
+
+    /// wraps the DER encoding of `value` as the extension value
+    pub fn from_value(
+        extension_id: ObjectIdentifier,
+        critical: bool,
+        value: &impl ToDer,
+    ) -> Result<Self, EncodingError> {
+        Ok(Self::new(
+            extension_id,
+            critical.into(),
+            OctetString::from_der(value)?,
+        ))
+    }
+
+    /// overrides the criticality the constructor chose, e.g. a critical keyUsage is only a
+    /// recommendation of RFC 5280 but a requirement of the CA/Browser Forum for CA certificates
+    pub fn with_critical(mut self, critical: bool) -> Self {
+        self.critical = critical.into();
+        self
+    }
+
+    pub fn is_critical(&self) -> bool {
+        self.critical.to_bool()
+    }
+
+    /// not critical. RFC 5280 requires it to be critical if the subject is empty, see
+    /// [`subject_alternative_names_for`](Extension::subject_alternative_names_for).
+    pub fn subject_alternative_names(names: &GeneralNames) -> Result<Self, EncodingError> {
+        Self::from_value(oids::SUBJECT_ALT_NAME.into(), false, names)
+    }
+
+    /// the subject alternative names of a certificate with `subject`, critical if the subject is
+    /// empty as required by RFC 5280
+    pub fn subject_alternative_names_for(
+        subject: &Name,
+        names: &GeneralNames,
+    ) -> Result<Self, EncodingError> {
+        Ok(Self::subject_alternative_names(names)?.with_critical(subject.is_empty()))
+    }
+
+    /// critical, as recommended by RFC 5280
+    pub fn key_usage(key_usage: &KeyUsage) -> Result<Self, EncodingError> {
+        Self::from_value(oids::KEY_USAGE.into(), true, key_usage)
+    }
+
+    /// critical, RFC 5280 requires this for CA certificates
+    pub fn basic_constraints(basic_constraints: &BasicConstraints) -> Result<Self, EncodingError> {
+        Self::from_value(oids::BASIC_CONSTRAINTS.into(), true, basic_constraints)
+    }
+
+    /// not critical
+    pub fn extended_key_usage(
+        extended_key_usage: &ExtendedKeyUsage,
+    ) -> Result<Self, EncodingError> {
+        Self::from_value(oids::EXT_KEY_USAGE.into(), false, extended_key_usage)
+    }
+
+    /// id-pkix-ocsp-nocheck for the certificate of a delegated OCSP responder, so clients don't
+    /// check its revocation status. not critical, as recommended by RFC 6960.
+    pub fn ocsp_no_check() -> Result<Self, EncodingError> {
+        Self::from_value(oids::PKIX_OCSP_NOCHECK.into(), false, &Null())
+    }
+}
+
+impl Extension {
+    fn inner_len(&self) -> Result<usize, EncodingError> {
+        let mut len = self.extension_id.encoded_len()? + self.value.encoded_len()?;
+        if self.critical.to_bool() {
+            len += self.critical.encoded_len()?;
+        }
+
+        Ok(len)
+    }
 }
 
 impl ToDer for Extension {
@@ -36,9 +114,34 @@ impl ToDer for Extension {
         Ok(res)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut len = self.extension_id.to_der_into(buf)?;
+        if self.critical.to_bool() {
+            len += self.critical.to_der_into(&mut buf[len..])?;
+        }
+        len += self.value.to_der_into(&mut buf[len..])?;
+
+        Ok(len)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Sequence.constructed()
     }
+
+    fn encoded_len(&self) -> Result<usize, EncodingError> {
+        Ok(tlv_len(self.inner_len()?))
+    }
+
+    fn write_der(&self, out: &mut dyn Write) -> Result<usize, EncodingError> {
+        let mut len = write_header(self.get_tag(), self.inner_len()?, out)?;
+        len += self.extension_id.write_der(out)?;
+        if self.critical.to_bool() {
+            len += self.critical.write_der(out)?;
+        }
+        len += self.value.write_der(out)?;
+
+        Ok(len)
+    }
 }
 
 #[derive(Default)]
@@ -52,6 +155,14 @@ impl Extensions {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn inner_len(&self) -> Result<usize, EncodingError> {
+        self.0.iter().map(|ext| ext.encoded_len()).sum()
+    }
 }
 
 impl ToDer for Extensions {
@@ -64,7 +175,115 @@ impl ToDer for Extensions {
         Ok(res)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut len = 0;
+        for ext in self.0.iter() {
+            len += ext.to_der_into(&mut buf[len..])?;
+        }
+
+        Ok(len)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Sequence.constructed()
     }
+
+    fn encoded_len(&self) -> Result<usize, EncodingError> {
+        Ok(tlv_len(self.inner_len()?))
+    }
+
+    fn write_der(&self, out: &mut dyn Write) -> Result<usize, EncodingError> {
+        let mut len = write_header(self.get_tag(), self.inner_len()?, out)?;
+        for ext in self.0.iter() {
+            len += ext.write_der(out)?;
+        }
+
+        Ok(len)
+    }
+}
+
+#[test]
+fn test_typed_extensions() {
+    use super::GeneralName;
+    use crate::parse::{
+        certificate::ExtensionsRef,
+        extensions::{BasicConstraints as ParsedBasicConstraints, KeyUsageRef},
+    };
+
+    let mut extensions = Extensions::default();
+    extensions.add(Extension::basic_constraints(&BasicConstraints::ca(None)).unwrap());
+    extensions.add(Extension::key_usage(&KeyUsage::default().key_cert_sign()).unwrap());
+    let names = GeneralNames::from(vec![GeneralName::dns_name("example.com").unwrap()]);
+    extensions.add(Extension::subject_alternative_names(&names).unwrap());
+    let der = extensions.to_der().unwrap();
+
+    let (_, parsed) = ExtensionsRef::parse_untagged(&der).unwrap();
+    let bc = parsed.find(oids::BASIC_CONSTRAINTS).unwrap().unwrap();
+    assert!(bc.critical());
+    assert!(ParsedBasicConstraints::from_slice(bc.value().as_bytes())
+        .unwrap()
+        .is_ca());
+    let ku = parsed.find(oids::KEY_USAGE).unwrap().unwrap();
+    assert!(KeyUsageRef::from_slice(ku.value().as_bytes())
+        .unwrap()
+        .key_cert_sign());
+    let san = parsed.find(oids::SUBJECT_ALT_NAME).unwrap().unwrap();
+    assert!(!san.critical());
+    assert!(!parsed.ocsp_no_check().unwrap());
+}
+
+#[test]
+fn test_ocsp_no_check() {
+    use crate::parse::certificate::ExtensionsRef;
+
+    let mut extensions = Extensions::default();
+    extensions.add(Extension::ocsp_no_check().unwrap());
+    let der = extensions.to_der().unwrap();
+
+    // the same encoding as the one of openssl
+    let expected = include_bytes!("../../../../certs/ocsp/responder-nocheck.der");
+    let ext = Extension::ocsp_no_check().unwrap().to_der().unwrap();
+    assert!(expected
+        .windows(ext.len())
+        .any(|window| window == ext.as_slice()));
+    let (_, parsed) = ExtensionsRef::parse_untagged(&der).unwrap();
+    assert!(parsed.ocsp_no_check().unwrap());
+}
+
+#[test]
+fn test_extension_criticality() {
+    use super::{DistinguishedName, GeneralName};
+    use crate::parse::certificate::ExtensionsRef;
+
+    let names = GeneralNames::from(vec![GeneralName::dns_name("example.com").unwrap()]);
+    let empty = Name::DistinguishedName(DistinguishedName::default());
+    let san = Extension::subject_alternative_names_for(&empty, &names).unwrap();
+    assert!(san.is_critical());
+    let subject = DistinguishedName::builder()
+        .common_name("example.com")
+        .build()
+        .unwrap();
+    let subject = Name::DistinguishedName(subject);
+    let san = Extension::subject_alternative_names_for(&subject, &names).unwrap();
+    assert!(!san.is_critical());
+
+    let mut extensions = Extensions::default();
+    let ku = Extension::key_usage(&KeyUsage::default().digital_signature()).unwrap();
+    assert!(ku.is_critical());
+    extensions.add(ku.with_critical(false));
+    let eku = ExtendedKeyUsage::default().purpose(oids::KP_SERVER_AUTH);
+    extensions.add(
+        Extension::extended_key_usage(&eku)
+            .unwrap()
+            .with_critical(true),
+    );
+    let der = extensions.to_der().unwrap();
+
+    let (_, parsed) = ExtensionsRef::parse_untagged(&der).unwrap();
+    assert!(!parsed.find(oids::KEY_USAGE).unwrap().unwrap().critical());
+    assert!(parsed
+        .find(oids::EXT_KEY_USAGE)
+        .unwrap()
+        .unwrap()
+        .critical());
 }
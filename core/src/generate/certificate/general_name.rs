@@ -0,0 +1,142 @@
+use super::super::{
+    der::{DataType, IA5String, ToDer},
+    error::EncodingError,
+};
+use super::DistinguishedName;
+use std::convert::TryFrom;
+use std::net::IpAddr;
+
+/// the GeneralName CHOICE of RFC 5280 section 4.2.1.6, for the alternatives that are used in
+/// practice
+#[derive(Clone)]
+pub enum GeneralName {
+    Rfc822Name(IA5String),
+    DnsName(IA5String),
+    DirectoryName(DistinguishedName),
+    UniformResourceIdentifier(IA5String),
+    IpAddress(IpAddr),
+}
+
+impl GeneralName {
+    pub fn rfc822_name(email: &str) -> Result<Self, EncodingError> {
+        Ok(GeneralName::Rfc822Name(IA5String::try_from(email)?))
+    }
+
+    pub fn dns_name(name: &str) -> Result<Self, EncodingError> {
+        Ok(GeneralName::DnsName(IA5String::try_from(name)?))
+    }
+
+    pub fn uri(uri: &str) -> Result<Self, EncodingError> {
+        Ok(GeneralName::UniformResourceIdentifier(IA5String::try_from(
+            uri,
+        )?))
+    }
+}
+
+impl From<IpAddr> for GeneralName {
+    fn from(ip: IpAddr) -> Self {
+        GeneralName::IpAddress(ip)
+    }
+}
+
+impl From<DistinguishedName> for GeneralName {
+    fn from(name: DistinguishedName) -> Self {
+        GeneralName::DirectoryName(name)
+    }
+}
+
+impl ToDer for GeneralName {
+    fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
+        match self {
+            GeneralName::Rfc822Name(s)
+            | GeneralName::DnsName(s)
+            | GeneralName::UniformResourceIdentifier(s) => s.encode_inner(),
+            // Name is a CHOICE, so the tag is explicit
+            GeneralName::DirectoryName(name) => name.to_der(),
+            GeneralName::IpAddress(IpAddr::V4(ip)) => Ok(ip.octets().to_vec()),
+            GeneralName::IpAddress(IpAddr::V6(ip)) => Ok(ip.octets().to_vec()),
+        }
+    }
+
+    fn get_tag(&self) -> u8 {
+        // all alternatives are context specific, the strings are implicitly tagged
+        match self {
+            GeneralName::Rfc822Name(_) => 0x81,
+            GeneralName::DnsName(_) => 0x82,
+            GeneralName::DirectoryName(_) => 0xa4,
+            GeneralName::UniformResourceIdentifier(_) => 0x86,
+            GeneralName::IpAddress(_) => 0x87,
+        }
+    }
+}
+
+/// a SEQUENCE OF GeneralName, e.g. the value of the subjectAltName extension
+#[derive(Clone, Default)]
+pub struct GeneralNames(Vec<GeneralName>);
+
+impl GeneralNames {
+    pub fn add(&mut self, name: GeneralName) {
+        self.0.push(name);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<GeneralName>> for GeneralNames {
+    fn from(names: Vec<GeneralName>) -> Self {
+        Self(names)
+    }
+}
+
+impl ToDer for GeneralNames {
+    fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
+        let mut res = Vec::new();
+        for name in self.0.iter() {
+            res.extend_from_slice(&name.to_der()?);
+        }
+
+        Ok(res)
+    }
+
+    fn get_tag(&self) -> u8 {
+        DataType::Sequence.constructed()
+    }
+}
+
+#[test]
+fn test_general_names() {
+    use crate::parse::extensions::{GeneralNameRef, GeneralNamesRef};
+    use std::net::Ipv4Addr;
+
+    let names = GeneralNames::from(vec![
+        GeneralName::dns_name("example.com").unwrap(),
+        GeneralName::from(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+        GeneralName::from(
+            DistinguishedName::builder()
+                .common_name("x")
+                .build()
+                .unwrap(),
+        ),
+    ]);
+    let der = names.to_der().unwrap();
+
+    let parsed: Vec<_> = GeneralNamesRef::from_slice(&der)
+        .unwrap()
+        .iter()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(parsed.len(), 3);
+    assert!(
+        matches!(&parsed[0], GeneralNameRef::DnsName(n) if n.to_str().unwrap() == "example.com")
+    );
+    assert_eq!(parsed[1], GeneralNameRef::IpAddress(&[127, 0, 0, 1]));
+    assert!(matches!(parsed[2], GeneralNameRef::DirectoryName(_)));
+
+    assert!(GeneralName::dns_name("bücher.example").is_err());
+}
@@ -0,0 +1,87 @@
+use super::super::{
+    der::{BitString, ToDer},
+    error::EncodingError,
+};
+
+/// the value of the KeyUsage extension (RFC 5280 section 4.2.1.3)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyUsage {
+    bits: Vec<usize>,
+}
+
+impl KeyUsage {
+    fn with_bit(mut self, index: usize) -> Self {
+        if !self.bits.contains(&index) {
+            self.bits.push(index);
+        }
+        self
+    }
+
+    pub fn digital_signature(self) -> Self {
+        self.with_bit(0)
+    }
+
+    pub fn non_repudiation(self) -> Self {
+        self.with_bit(1)
+    }
+
+    pub fn key_encipherment(self) -> Self {
+        self.with_bit(2)
+    }
+
+    pub fn data_encipherment(self) -> Self {
+        self.with_bit(3)
+    }
+
+    pub fn key_agreement(self) -> Self {
+        self.with_bit(4)
+    }
+
+    pub fn key_cert_sign(self) -> Self {
+        self.with_bit(5)
+    }
+
+    pub fn crl_sign(self) -> Self {
+        self.with_bit(6)
+    }
+
+    pub fn encipher_only(self) -> Self {
+        self.with_bit(7)
+    }
+
+    pub fn decipher_only(self) -> Self {
+        self.with_bit(8)
+    }
+
+    pub fn to_bit_string(&self) -> BitString {
+        BitString::from_named_bits(&self.bits)
+    }
+}
+
+impl ToDer for KeyUsage {
+    fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
+        self.to_bit_string().encode_inner()
+    }
+
+    fn get_tag(&self) -> u8 {
+        self.to_bit_string().get_tag()
+    }
+}
+
+#[test]
+fn test_key_usage() {
+    let ku = KeyUsage::default().key_cert_sign().crl_sign();
+    let der = ku.to_der().unwrap();
+    assert_eq!(der, vec![0x03, 0x02, 0x01, 0x06]);
+
+    let parsed = crate::parse::extensions::KeyUsageRef::from_slice(&der).unwrap();
+    assert!(parsed.key_cert_sign());
+    assert!(parsed.crl_sign());
+    assert!(!parsed.digital_signature());
+
+    let ku = KeyUsage::default()
+        .digital_signature()
+        .key_encipherment()
+        .digital_signature();
+    assert_eq!(ku.to_der().unwrap(), vec![0x03, 0x02, 0x05, 0xa0]);
+}
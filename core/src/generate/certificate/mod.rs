@@ -1,13 +1,26 @@
 mod algorithm_identifier;
+mod basic_constraints;
+mod directory_string;
+mod extended_key_usage;
 mod extensions;
+mod general_name;
+mod key_usage;
 mod name;
 mod subject_public_key_info;
 mod validity;
 mod version;
 
-pub use algorithm_identifier::AlgorithmIdentifier;
+pub use algorithm_identifier::{AlgorithmIdentifier, AlgorithmParameters};
+pub use basic_constraints::BasicConstraints;
+pub use directory_string::DirectoryString;
+pub use extended_key_usage::ExtendedKeyUsage;
 pub use extensions::{Extension, Extensions};
-pub use name::{AttributeTypeAndValue, DistinguishedName, Name, RelativeDistinguishedName};
+pub use general_name::{GeneralName, GeneralNames};
+pub use key_usage::KeyUsage;
+pub use name::{
+    AttributeTypeAndValue, DistinguishedName, DistinguishedNameBuilder, Name,
+    RelativeDistinguishedName,
+};
 pub use subject_public_key_info::SubjectPublicKeyInfo;
 pub use validity::Validity;
 
@@ -15,8 +28,9 @@ use crate::parse::parsing::CertificateRef;
 
 use super::{
     builder::TBSCertificate,
-    der::{BitString, DataType, ToDer},
+    der::{tlv_len, write_header, BitString, DataType, ToDer},
 };
+use std::io::Write;
 
 pub struct Certificate {
     tbs_cert: TBSCertificate,
@@ -36,6 +50,12 @@ impl Certificate {
             signature,
         }
     }
+
+    fn inner_len(&self) -> Result<usize, super::error::EncodingError> {
+        Ok(self.tbs_cert.encoded_len()?
+            + self.signature_algorithm.encoded_len()?
+            + self.signature.encoded_len()?)
+    }
 }
 
 impl ToDer for Certificate {
@@ -48,7 +68,28 @@ impl ToDer for Certificate {
         Ok(cert)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, super::error::EncodingError> {
+        let mut len = self.tbs_cert.to_der_into(buf)?;
+        len += self.signature_algorithm.to_der_into(&mut buf[len..])?;
+        len += self.signature.to_der_into(&mut buf[len..])?;
+
+        Ok(len)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Sequence.constructed()
     }
+
+    fn encoded_len(&self) -> Result<usize, super::error::EncodingError> {
+        Ok(tlv_len(self.inner_len()?))
+    }
+
+    fn write_der(&self, out: &mut dyn Write) -> Result<usize, super::error::EncodingError> {
+        let mut len = write_header(self.get_tag(), self.inner_len()?, out)?;
+        len += self.tbs_cert.write_der(out)?;
+        len += self.signature_algorithm.write_der(out)?;
+        len += self.signature.write_der(out)?;
+
+        Ok(len)
+    }
 }
@@ -1,9 +1,14 @@
+use std::convert::TryFrom;
+
 use crate::generate::der::Data;
+use crate::oids;
+use crate::parse::{certificate::NameRef, der::ObjectIdentifierRef};
 
 use super::super::{
-    der::{DataType, ObjectIdentifier, ToDer},
+    der::{write_bytes, DataType, IA5String, ObjectIdentifier, ToDer},
     error::EncodingError,
 };
+use super::DirectoryString;
 
 #[derive(Clone)]
 pub struct AttributeTypeAndValue {
@@ -15,6 +20,18 @@ impl AttributeTypeAndValue {
     pub fn new(typ: ObjectIdentifier, value: Data) -> Self {
         Self { typ, value }
     }
+
+    /// the PKCS#9 emailAddress attribute, which is an IA5String
+    pub fn email_address(email: &str) -> Result<Self, EncodingError> {
+        let value = Data::IA5String(IA5String::try_from(email)?);
+        Ok(Self::new(oids::EMAIL_ADDRESS.into(), value))
+    }
+
+    /// the domainComponent attribute (RFC 4519), a single label of a DNS name as IA5String
+    pub fn domain_component(label: &str) -> Result<Self, EncodingError> {
+        let value = Data::IA5String(IA5String::try_from(label)?);
+        Ok(Self::new(oids::DOMAIN_COMPONENT.into(), value))
+    }
 }
 
 impl ToDer for AttributeTypeAndValue {
@@ -25,6 +42,13 @@ impl ToDer for AttributeTypeAndValue {
         Ok(inner)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut len = self.typ.to_der_into(buf)?;
+        len += self.value.to_der_into(&mut buf[len..])?;
+
+        Ok(len)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Sequence.constructed()
     }
@@ -54,6 +78,15 @@ impl ToDer for RelativeDistinguishedName {
         Ok(res)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut len = 0;
+        for value in self.values.iter() {
+            len += value.to_der_into(&mut buf[len..])?;
+        }
+
+        Ok(len)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Set.constructed()
     }
@@ -63,11 +96,102 @@ impl ToDer for RelativeDistinguishedName {
 pub struct DistinguishedName(Vec<RelativeDistinguishedName>);
 
 impl DistinguishedName {
+    /// builds a name attribute by attribute, e.g.
+    /// `DistinguishedName::builder().country("DE").common_name("example.com").build()`
+    pub fn builder() -> DistinguishedNameBuilder {
+        DistinguishedNameBuilder::default()
+    }
+
     pub fn push(&mut self, rdn: RelativeDistinguishedName) {
         self.0.push(rdn);
     }
 }
 
+/// adds one RDN per call, in the order of the calls. values are encoded as [`DirectoryString`]
+/// unless the attribute has a fixed string type. the first encoding error is returned by
+/// [`build`](DistinguishedNameBuilder::build).
+#[derive(Default)]
+pub struct DistinguishedNameBuilder {
+    name: DistinguishedName,
+    error: Option<EncodingError>,
+}
+
+impl DistinguishedNameBuilder {
+    /// countryName, a two letter code that is always a PrintableString
+    pub fn country(self, country: &str) -> Self {
+        let value = DirectoryString::printable(country);
+        self.push_result(oids::COUNTRY_NAME, value.map(Data::from))
+    }
+
+    pub fn state_or_province(self, state: &str) -> Self {
+        self.attribute(oids::STATE_OR_PROVINCE_NAME, state)
+    }
+
+    pub fn locality(self, locality: &str) -> Self {
+        self.attribute(oids::LOCALITY_NAME, locality)
+    }
+
+    pub fn organization(self, organization: &str) -> Self {
+        self.attribute(oids::ORGANIZATION_NAME, organization)
+    }
+
+    pub fn organizational_unit(self, unit: &str) -> Self {
+        self.attribute(oids::ORGANIZATIONAL_UNIT_NAME, unit)
+    }
+
+    pub fn common_name(self, common_name: &str) -> Self {
+        self.attribute(oids::COMMON_NAME, common_name)
+    }
+
+    /// serialNumber of the subject (not of the certificate), always a PrintableString
+    pub fn serial_number(self, serial_number: &str) -> Self {
+        let value = DirectoryString::printable(serial_number);
+        self.push_result(oids::SERIAL_NUMBER, value.map(Data::from))
+    }
+
+    pub fn email_address(self, email: &str) -> Self {
+        let attribute = AttributeTypeAndValue::email_address(email);
+        self.push_attribute(attribute)
+    }
+
+    pub fn domain_component(self, label: &str) -> Self {
+        let attribute = AttributeTypeAndValue::domain_component(label);
+        self.push_attribute(attribute)
+    }
+
+    /// any other attribute, with the value encoded as [`DirectoryString`]
+    pub fn attribute(self, typ: ObjectIdentifierRef, value: &str) -> Self {
+        let value = DirectoryString::new(value).into();
+        self.push_result(typ, Ok(value))
+    }
+
+    pub fn build(self) -> Result<DistinguishedName, EncodingError> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.name),
+        }
+    }
+
+    fn push_result(self, typ: ObjectIdentifierRef, value: Result<Data, EncodingError>) -> Self {
+        let attribute = value.map(|value| AttributeTypeAndValue::new(typ.into(), value));
+        self.push_attribute(attribute)
+    }
+
+    fn push_attribute(mut self, attribute: Result<AttributeTypeAndValue, EncodingError>) -> Self {
+        match attribute {
+            Ok(attribute) => {
+                let mut rdn = RelativeDistinguishedName::default();
+                rdn.insert(attribute);
+                self.name.push(rdn);
+            }
+            Err(e) => {
+                self.error.get_or_insert(e);
+            }
+        }
+        self
+    }
+}
+
 impl ToDer for DistinguishedName {
     fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
         let mut res = Vec::new();
@@ -78,6 +202,15 @@ impl ToDer for DistinguishedName {
         Ok(res)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut len = 0;
+        for dn in self.0.iter() {
+            len += dn.to_der_into(&mut buf[len..])?;
+        }
+
+        Ok(len)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Sequence.constructed()
     }
@@ -86,18 +219,144 @@ impl ToDer for DistinguishedName {
 #[derive(Clone)]
 pub enum Name {
     DistinguishedName(DistinguishedName),
+    /// the content octets of an already encoded Name, e.g. the subject of an issuer certificate
+    /// that has to be copied byte for byte into the issuer field
+    Encoded(Vec<u8>),
+}
+
+impl Name {
+    /// whether the name has no RDNs, as the subject of a certificate that only has subject
+    /// alternative names
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Name::DistinguishedName(dn) => dn.0.is_empty(),
+            Name::Encoded(content) => content.is_empty(),
+        }
+    }
+
+    /// the hash OpenSSL uses to look up certificates by name, see [`NameRef::openssl_hash`]
+    pub fn openssl_hash(&self) -> Result<u32, EncodingError> {
+        let der = self.to_der()?;
+        let (_, name) = NameRef::parse(&der).map_err(|_| EncodingError::InvalidEncodedValue)?;
+        name.openssl_hash()
+            .map_err(|_| EncodingError::InvalidEncodedValue)
+    }
+}
+
+impl From<&NameRef<'_>> for Name {
+    fn from(name: &NameRef) -> Self {
+        Name::Encoded(name.bytes().to_vec())
+    }
 }
 
 impl ToDer for Name {
     fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
         match self {
             Name::DistinguishedName(dn) => dn.encode_inner(),
+            Name::Encoded(content) => Ok(content.clone()),
+        }
+    }
+
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        match self {
+            Name::DistinguishedName(dn) => dn.encode_inner_into(buf),
+            Name::Encoded(content) => write_bytes(content, buf),
         }
     }
 
     fn get_tag(&self) -> u8 {
         match self {
             Name::DistinguishedName(dn) => dn.get_tag(),
+            Name::Encoded(_) => DataType::Sequence.constructed(),
         }
     }
 }
+
+#[test]
+fn test_legacy_attributes() {
+    let email = AttributeTypeAndValue::email_address("a@b").unwrap();
+    assert_eq!(
+        email.to_der().unwrap(),
+        [
+            0x30, 0x10, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x01, 0x16,
+            0x03, 0x61, 0x40, 0x62,
+        ]
+    );
+
+    let dc = AttributeTypeAndValue::domain_component("com").unwrap();
+    let der = dc.to_der().unwrap();
+    assert_eq!(&der[der.len() - 5..], b"\x16\x03com");
+
+    assert!(AttributeTypeAndValue::email_address("\u{e4}@b").is_err());
+}
+
+#[test]
+fn test_distinguished_name_builder() {
+    let name = DistinguishedName::builder()
+        .country("DE")
+        .organization("ACME")
+        .common_name("example.com")
+        .build()
+        .unwrap();
+    let der = name.to_der().unwrap();
+
+    let (_, parsed) = NameRef::parse(&der).unwrap();
+    let NameRef::DistinguishedNameRef(dn) = parsed;
+    let attributes: Vec<(String, String)> = dn
+        .iter()
+        .flat_map(|rdn| rdn.unwrap().iter())
+        .map(|attr| {
+            let attr = attr.unwrap();
+            (
+                attr.attribute_type().to_string(),
+                attr.value_as_str().unwrap().into_owned(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        attributes,
+        vec![
+            ("2.5.4.6".to_string(), "DE".to_string()),
+            ("2.5.4.10".to_string(), "ACME".to_string()),
+            ("2.5.4.3".to_string(), "example.com".to_string()),
+        ]
+    );
+
+    assert!(DistinguishedName::builder()
+        .country("D\u{e4}")
+        .common_name("example.com")
+        .build()
+        .is_err());
+}
+
+#[test]
+fn test_name_from_name_ref() {
+    use crate::parse::parsing::CertificateRef;
+
+    let cert = CertificateRef::from_slice(include_bytes!("../../../../certs/ocsp/ca.der")).unwrap();
+    let subject = cert.tbs_cert().subject();
+    let name = Name::from(subject);
+    assert_eq!(name.to_der().unwrap(), subject.raw_data());
+    let mut buf = vec![0; subject.raw_data().len()];
+    assert_eq!(name.to_der_into(&mut buf).unwrap(), buf.len());
+    assert_eq!(buf, subject.raw_data());
+}
+
+#[test]
+fn test_openssl_hash() {
+    let name = DistinguishedName::builder()
+        .country("DE")
+        .organization("x509-rs")
+        .common_name("x509-rs Test CA")
+        .build()
+        .unwrap();
+    // the subject of certs/ocsp/ca.der
+    assert_eq!(
+        Name::DistinguishedName(name).openssl_hash().unwrap(),
+        0xaabc6144
+    );
+    assert!(matches!(
+        Name::Encoded(vec![0x31, 0x05]).openssl_hash(),
+        Err(EncodingError::InvalidEncodedValue)
+    ));
+}
@@ -24,6 +24,13 @@ impl ToDer for SubjectPublicKeyInfo {
         Ok(res)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut len = self.algorithm.to_der_into(buf)?;
+        len += self.subject_public_key.to_der_into(&mut buf[len..])?;
+
+        Ok(len)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Sequence.constructed()
     }
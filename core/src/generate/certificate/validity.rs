@@ -1,19 +1,27 @@
 use super::super::der::{DataType, ToDer};
+use crate::common::time::UtcDateTime;
 use crate::generate::error::EncodingError;
-use chrono::{DateTime, Utc};
+use std::time::Duration;
 
 pub struct Validity {
-    not_before: DateTime<Utc>,
-    not_after: DateTime<Utc>,
+    not_before: UtcDateTime,
+    not_after: UtcDateTime,
 }
 
 impl Validity {
-    pub fn new(not_before: DateTime<Utc>, not_after: DateTime<Utc>) -> Self {
+    pub fn new(not_before: impl Into<UtcDateTime>, not_after: impl Into<UtcDateTime>) -> Self {
         Self {
-            not_before,
-            not_after,
+            not_before: not_before.into(),
+            not_after: not_after.into(),
         }
     }
+
+    /// the time from notBefore to notAfter, zero if notAfter is before notBefore
+    pub fn period(&self) -> Duration {
+        self.not_after
+            .duration_since(self.not_before)
+            .unwrap_or_default()
+    }
 }
 
 impl ToDer for Validity {
@@ -24,6 +32,13 @@ impl ToDer for Validity {
         Ok(res)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut len = self.not_before.to_der_into(buf)?;
+        len += self.not_after.to_der_into(&mut buf[len..])?;
+
+        Ok(len)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Sequence.constructed()
     }
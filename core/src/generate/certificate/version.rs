@@ -1,5 +1,5 @@
 use super::super::{
-    der::{Integer, ToDer},
+    der::{write_bytes, DataType, Integer, ToDer},
     error::EncodingError,
 };
 use crate::common::{certificate::Version, der::ExplicitTag};
@@ -12,6 +12,12 @@ impl ToDer for Version {
         i.to_der()
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        // versions are single byte integers, no need to go through Integer
+        let v: i64 = self.into();
+        write_bytes(&[DataType::Integer.into(), 0x01, v as u8], buf)
+    }
+
     fn get_tag(&self) -> u8 {
         ExplicitTag::try_new(0).unwrap().get_identifier_octet()
     }
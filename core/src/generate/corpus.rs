@@ -0,0 +1,414 @@
+//! Seed inputs for fuzzing certificate parsers: certificates built with the generator that cover
+//! the versions, string types, time encodings and extensions a parser has to handle, near misses
+//! derived from them, and an AFL dictionary of the tokens certificates are made of.
+//!
+//! the signatures are dummies, the entries are meant for parsers, not for path validation.
+
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+use super::{
+    builder::{TBSCertificate, TBSCertificateBuilder},
+    certificate::{
+        AlgorithmIdentifier, AttributeTypeAndValue, BasicConstraints, Certificate,
+        DistinguishedName, ExtendedKeyUsage, Extension, Extensions, GeneralName, GeneralNames,
+        KeyUsage, Name, SubjectPublicKeyInfo, Validity,
+    },
+    der::{encode_tlv, BitString, Data, Integer, Null, OctetString, ToDer, Utf8String},
+    error::EncodingError,
+};
+use crate::common::{certificate::Version, time::UtcDateTime};
+use crate::oids;
+use crate::parse::der::ObjectIdentifierRef;
+
+/// an input of the seed corpus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusEntry {
+    /// a name that can be used as file name, e.g. `v3-leaf`
+    pub name: &'static str,
+    pub der: Vec<u8>,
+    /// false for the near misses, which a strict DER parser has to reject
+    pub valid: bool,
+}
+
+/// builds the seed corpus. the result is the same on every call, so it can be checked in or
+/// regenerated at will.
+pub fn seed_corpus() -> Result<Vec<CorpusEntry>, EncodingError> {
+    let leaf = sign(leaf()?)?;
+    let mut corpus = vec![
+        valid("v3-leaf", leaf.clone()),
+        valid("v3-ca", sign(ca()?)?),
+        valid("v1", sign(v1()?)?),
+        valid("v2-unique-ids", sign(v2_unique_ids()?)?),
+        valid("generalized-time", sign(generalized_time()?)?),
+        valid("string-types", sign(string_types()?)?),
+        valid("multi-valued-rdn", sign(multi_valued_rdn()?)?),
+        valid("rsassa-pss", sign(rsassa_pss()?)?),
+        valid("ed25519", sign(ed25519()?)?),
+    ];
+
+    corpus.push(invalid("long-form-length", long_form_length(&leaf)));
+    corpus.push(invalid("indefinite-length", indefinite_length(&leaf)));
+    corpus.push(invalid("truncated", leaf[..leaf.len() - 1].to_vec()));
+    let mut trailing_data = leaf.clone();
+    trailing_data.push(0);
+    corpus.push(invalid("trailing-data", trailing_data));
+    let mut set_instead_of_sequence = leaf;
+    set_instead_of_sequence[0] = 0x31;
+    corpus.push(invalid("set-instead-of-sequence", set_instead_of_sequence));
+    corpus.push(invalid(
+        "double-wrapped-extension",
+        sign(double_wrapped_extension()?)?,
+    ));
+    corpus.push(invalid("negative-serial", sign(negative_serial()?)?));
+
+    Ok(corpus)
+}
+
+/// an AFL dictionary, one `name="value"` line per token: the headers of the outer structures,
+/// common DER values and the encoded object identifiers of algorithms, extensions and name
+/// attributes
+pub fn afl_dictionary() -> String {
+    let mut tokens: Vec<(String, Vec<u8>)> = vec![
+        ("certificate".to_string(), vec![0x30, 0x82]),
+        ("version_v3".to_string(), vec![0xa0, 0x03, 0x02, 0x01, 0x02]),
+        ("extensions".to_string(), vec![0xa3, 0x82]),
+        ("null".to_string(), vec![0x05, 0x00]),
+        ("true".to_string(), vec![0x01, 0x01, 0xff]),
+        ("empty_sequence".to_string(), vec![0x30, 0x00]),
+        ("utc_time".to_string(), b"\x17\x0d991231235959Z".to_vec()),
+        (
+            "generalized_time".to_string(),
+            b"\x18\x0f20500101000000Z".to_vec(),
+        ),
+    ];
+    let oids = [
+        ("oid_rsa_encryption", oids::RSA_ENCRYPTION),
+        ("oid_sha256_with_rsa", oids::SHA256_WITH_RSA_ENCRYPTION),
+        ("oid_rsassa_pss", oids::RSASSA_PSS),
+        ("oid_ec_public_key", oids::EC_PUBLIC_KEY),
+        ("oid_ecdsa_with_sha256", oids::ECDSA_WITH_SHA256),
+        ("oid_secp256r1", oids::SECP256R1),
+        ("oid_ed25519", oids::ED25519),
+        ("oid_common_name", oids::COMMON_NAME),
+        ("oid_country_name", oids::COUNTRY_NAME),
+        ("oid_organization_name", oids::ORGANIZATION_NAME),
+        ("oid_email_address", oids::EMAIL_ADDRESS),
+        ("oid_subject_key_identifier", oids::SUBJECT_KEY_IDENTIFIER),
+        ("oid_key_usage", oids::KEY_USAGE),
+        ("oid_subject_alt_name", oids::SUBJECT_ALT_NAME),
+        ("oid_basic_constraints", oids::BASIC_CONSTRAINTS),
+        ("oid_crl_distribution_points", oids::CRL_DISTRIBUTION_POINTS),
+        ("oid_certificate_policies", oids::CERTIFICATE_POLICIES),
+        (
+            "oid_authority_key_identifier",
+            oids::AUTHORITY_KEY_IDENTIFIER,
+        ),
+        ("oid_ext_key_usage", oids::EXT_KEY_USAGE),
+        ("oid_authority_info_access", oids::AUTHORITY_INFO_ACCESS),
+    ];
+    for (name, oid) in oids.iter() {
+        tokens.push((name.to_string(), encode_tlv(0x06, oid.as_bytes())));
+    }
+
+    let mut dictionary = String::new();
+    for (name, value) in tokens {
+        dictionary.push_str(&name);
+        dictionary.push_str("=\"");
+        for b in value {
+            // quotes and backslashes are escaped like the unprintable bytes
+            if (0x20..=0x7e).contains(&b) && b != b'"' && b != b'\\' {
+                dictionary.push(b as char);
+            } else {
+                write!(dictionary, "\\x{:02x}", b).unwrap();
+            }
+        }
+        dictionary.push_str("\"\n");
+    }
+
+    dictionary
+}
+
+fn valid(name: &'static str, der: Vec<u8>) -> CorpusEntry {
+    CorpusEntry {
+        name,
+        der,
+        valid: true,
+    }
+}
+
+fn invalid(name: &'static str, der: Vec<u8>) -> CorpusEntry {
+    CorpusEntry {
+        name,
+        der,
+        valid: false,
+    }
+}
+
+fn sign(tbs: TBSCertificate) -> Result<Vec<u8>, EncodingError> {
+    let algorithm = tbs.signature.clone();
+    Certificate::new(tbs, algorithm, BitString::new(vec![0x5a; 64], 64 * 8)).to_der()
+}
+
+fn time(year: i32) -> UtcDateTime {
+    UtcDateTime::from_ymd_hms(year, 1, 1, 0, 0, 0).expect("a valid date")
+}
+
+fn name(common_name: &str) -> Result<Name, EncodingError> {
+    let name = DistinguishedName::builder()
+        .country("DE")
+        .organization("x509-rs")
+        .common_name(common_name)
+        .build()?;
+
+    Ok(Name::DistinguishedName(name))
+}
+
+fn ec_key() -> SubjectPublicKeyInfo {
+    let mut point = vec![0x04];
+    point.extend_from_slice(&[0x42; 64]);
+    let algorithm = AlgorithmIdentifier::new(
+        oids::EC_PUBLIC_KEY.into(),
+        Data::ObjectIdentifier(oids::SECP256R1.into()),
+    );
+    SubjectPublicKeyInfo::new(algorithm, BitString::new(point, 65 * 8))
+}
+
+fn rsa_key() -> Result<SubjectPublicKeyInfo, EncodingError> {
+    let mut key = Integer::from_unsigned_bytes(&[0xc1; 256]).to_der()?;
+    key.extend_from_slice(&Integer::from_i64(65537).to_der()?);
+    let key = encode_tlv(0x30, &key);
+    let algorithm = AlgorithmIdentifier::new(oids::RSA_ENCRYPTION.into(), Data::Null(Null()));
+
+    Ok(SubjectPublicKeyInfo::new(
+        algorithm,
+        BitString::new(key.clone(), key.len() * 8),
+    ))
+}
+
+fn builder(common_name: &str) -> Result<TBSCertificateBuilder, EncodingError> {
+    Ok(TBSCertificateBuilder::default()
+        .serial_number(Integer::from_i64(0x1234))
+        .signature(AlgorithmIdentifier::without_parameters(
+            oids::ECDSA_WITH_SHA256.into(),
+        ))
+        .issuer(name("x509-rs Corpus CA")?)
+        .validity(Validity::new(time(2026), time(2027)))
+        .subject(name(common_name)?)
+        .subject_public_key_info(ec_key())
+        .extensions(None))
+}
+
+/// the combinations of version and fields are fixed, so the builder can't fail
+fn build(builder: TBSCertificateBuilder) -> Result<TBSCertificate, EncodingError> {
+    Ok(builder.build().expect("valid fields for the version"))
+}
+
+fn leaf() -> Result<TBSCertificate, EncodingError> {
+    let mut extensions = Extensions::default();
+    extensions.add(Extension::basic_constraints(
+        &BasicConstraints::end_entity(),
+    )?);
+    extensions.add(Extension::key_usage(
+        &KeyUsage::default().digital_signature(),
+    )?);
+    let names = GeneralNames::from(vec![
+        GeneralName::dns_name("example.com")?,
+        GeneralName::dns_name("*.example.com")?,
+    ]);
+    extensions.add(Extension::subject_alternative_names(&names)?);
+    extensions.add(Extension::extended_key_usage(
+        &ExtendedKeyUsage::default().purpose(oids::KP_SERVER_AUTH),
+    )?);
+
+    build(builder("example.com")?.extensions(Some(extensions)))
+}
+
+fn ca() -> Result<TBSCertificate, EncodingError> {
+    let mut extensions = Extensions::default();
+    extensions.add(Extension::basic_constraints(&BasicConstraints::ca(Some(
+        0,
+    )))?);
+    extensions.add(Extension::key_usage(
+        &KeyUsage::default().key_cert_sign().crl_sign(),
+    )?);
+
+    build(
+        builder("x509-rs Corpus CA")?
+            .signature(AlgorithmIdentifier::new(
+                oids::SHA256_WITH_RSA_ENCRYPTION.into(),
+                Data::Null(Null()),
+            ))
+            .subject_public_key_info(rsa_key()?)
+            .extensions(Some(extensions)),
+    )
+}
+
+fn v1() -> Result<TBSCertificate, EncodingError> {
+    build(builder("v1.example.com")?.version(Version::V1))
+}
+
+fn v2_unique_ids() -> Result<TBSCertificate, EncodingError> {
+    build(
+        builder("v2.example.com")?
+            .version(Version::V2)
+            .issuer_unique_id(Some(BitString::new(vec![0x01, 0x02], 16)))
+            .subject_unique_id(Some(BitString::new(vec![0xf0], 4))),
+    )
+}
+
+/// notAfter in 2050 and later is a GeneralizedTime
+fn generalized_time() -> Result<TBSCertificate, EncodingError> {
+    build(builder("long.example.com")?.validity(Validity::new(time(1950), time(9999))))
+}
+
+/// UTF8String, PrintableString and IA5String through the builder, BMPString and TeletexString
+/// in a name encoded by hand
+fn string_types() -> Result<TBSCertificate, EncodingError> {
+    let subject = DistinguishedName::builder()
+        .country("DE")
+        .locality("Zürich")
+        .organization("x509-rs")
+        .domain_component("example")
+        .email_address("corpus@example.com")
+        .build()?;
+
+    let mut issuer = Vec::new();
+    for (tag, value) in [(0x1e, &b"\x00B\x00M\x00P"[..]), (0x14, &b"T\xe461"[..])].iter() {
+        let mut attribute = encode_tlv(0x06, oids::COMMON_NAME.as_bytes());
+        attribute.extend_from_slice(&encode_tlv(*tag, value));
+        issuer.extend_from_slice(&encode_tlv(0x31, &encode_tlv(0x30, &attribute)));
+    }
+
+    build(
+        builder("")?
+            .issuer(Name::Encoded(issuer))
+            .subject(Name::DistinguishedName(subject)),
+    )
+}
+
+/// a SET with two attributes, encoded by hand because `RelativeDistinguishedName` holds one
+fn multi_valued_rdn() -> Result<TBSCertificate, EncodingError> {
+    let mut attributes = [
+        attribute(oids::ORGANIZATIONAL_UNIT_NAME, "Unit")?,
+        attribute(oids::COMMON_NAME, "multi.example.com")?,
+    ];
+    // DER sorts the elements of a SET OF by their encoding
+    attributes.sort();
+    let subject = encode_tlv(0x31, &attributes.concat());
+
+    build(builder("")?.subject(Name::Encoded(subject)))
+}
+
+fn attribute(typ: ObjectIdentifierRef, value: &str) -> Result<Vec<u8>, EncodingError> {
+    AttributeTypeAndValue::new(typ.into(), Data::Utf8String(Utf8String::from_str(value))).to_der()
+}
+
+fn rsassa_pss() -> Result<TBSCertificate, EncodingError> {
+    build(
+        builder("pss.example.com")?
+            .signature(AlgorithmIdentifier::rsassa_pss(oids::SHA256.into(), 32))
+            .subject_public_key_info(rsa_key()?),
+    )
+}
+
+fn ed25519() -> Result<TBSCertificate, EncodingError> {
+    let algorithm = AlgorithmIdentifier::without_parameters(oids::ED25519.into());
+    build(
+        builder("ed25519.example.com")?
+            .signature(algorithm.clone())
+            .subject_public_key_info(SubjectPublicKeyInfo::new(
+                algorithm,
+                BitString::new(vec![0x17; 32], 32 * 8),
+            )),
+    )
+}
+
+/// basicConstraints with its value wrapped in a second OCTET STRING
+fn double_wrapped_extension() -> Result<TBSCertificate, EncodingError> {
+    let value = OctetString::from_der(&BasicConstraints::end_entity())?;
+    let mut extensions = Extensions::default();
+    extensions.add(Extension::new(
+        oids::BASIC_CONSTRAINTS.into(),
+        true.into(),
+        OctetString::from_der(&value)?,
+    ));
+
+    build(builder("wrapped.example.com")?.extensions(Some(extensions)))
+}
+
+fn negative_serial() -> Result<TBSCertificate, EncodingError> {
+    build(builder("negative.example.com")?.serial_number(Integer::from_i64(-1)))
+}
+
+/// the outer SEQUENCE with its length in 4 octets instead of the shortest form
+fn long_form_length(der: &[u8]) -> Vec<u8> {
+    let (header_len, len) = outer_header(der);
+    let mut res = vec![der[0], 0x84];
+    res.extend_from_slice(&u32::try_from(len).expect("short input").to_be_bytes());
+    res.extend_from_slice(&der[header_len..]);
+    res
+}
+
+/// the outer SEQUENCE with the indefinite length of BER, terminated by two zero octets
+fn indefinite_length(der: &[u8]) -> Vec<u8> {
+    let (header_len, _) = outer_header(der);
+    let mut res = vec![der[0], 0x80];
+    res.extend_from_slice(&der[header_len..]);
+    res.extend_from_slice(&[0, 0]);
+    res
+}
+
+/// the length of the header of the DER element `der` and of its content
+fn outer_header(der: &[u8]) -> (usize, usize) {
+    if der[1] & 0x80 == 0 {
+        return (2, der[1] as usize);
+    }
+    let octets = (der[1] & 0x7f) as usize;
+    let len = der[2..2 + octets]
+        .iter()
+        .fold(0, |len, b| (len << 8) | *b as usize);
+    (2 + octets, len)
+}
+
+#[test]
+fn test_seed_corpus() {
+    use crate::parse::parsing::CertificateRef;
+
+    let corpus = seed_corpus().unwrap();
+    assert_eq!(corpus, seed_corpus().unwrap());
+    let mut names: Vec<_> = corpus.iter().map(|entry| entry.name).collect();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), corpus.len());
+
+    for entry in corpus.iter().filter(|entry| entry.valid) {
+        let cert = CertificateRef::from_slice(&entry.der);
+        assert!(cert.is_ok(), "{} doesn't parse", entry.name);
+    }
+    let entry = |name| corpus.iter().find(|entry| entry.name == name).unwrap();
+    assert!(CertificateRef::from_slice(&entry("truncated").der).is_err());
+    assert!(CertificateRef::from_slice(&entry("set-instead-of-sequence").der).is_err());
+    let v3_leaf = &entry("v3-leaf").der;
+    assert_eq!(entry("long-form-length").der.len(), v3_leaf.len() + 2);
+    assert_eq!(
+        entry("indefinite-length").der[2..],
+        [&v3_leaf[4..], &[0, 0]].concat()[..]
+    );
+
+    let v1 = CertificateRef::from_slice(&entry("v1").der).unwrap();
+    assert!(v1.tbs_cert().extensions().is_none());
+    let long = CertificateRef::from_slice(&entry("generalized-time").der).unwrap();
+    assert_eq!(long.tbs_cert().validity().not_after().unwrap(), time(9999));
+}
+
+#[test]
+fn test_afl_dictionary() {
+    let dictionary = afl_dictionary();
+    assert!(dictionary.contains("null=\"\\x05\\x00\"\n"));
+    // 2.5.29.19 is 55 1d 13
+    assert!(dictionary.contains("oid_basic_constraints=\"\\x06\\x03U\\x1d\\x13\"\n"));
+    assert!(dictionary
+        .lines()
+        .all(|line| line.split_once('=').is_some() && line.ends_with('"')));
+}
@@ -1,13 +1,15 @@
 use super::super::error::EncodingError;
-use super::{DataType, ToDer};
+use super::{write_bytes, DataType, ToDer};
 
-#[derive(Clone)]
+/// a bit string, bit 0 is the most significant bit of the first byte
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct BitString {
     data: Vec<u8>,
     padding_bits: u8,
 }
 
 impl BitString {
+    /// panics if `data` doesn't hold exactly `used_bits` bits, see [`BitString::try_new`]
     pub fn new(data: Vec<u8>, used_bits: usize) -> Self {
         let mut byte_len = used_bits / 8;
         let padding_bits = (used_bits % 8) as u8;
@@ -19,6 +21,54 @@ impl BitString {
 
         Self { data, padding_bits }
     }
+
+    /// fails if `data` doesn't hold exactly `used_bits` bits or if any of the unused bits at the
+    /// end is set, DER requires them to be zero
+    pub fn try_new(data: Vec<u8>, used_bits: usize) -> Result<Self, EncodingError> {
+        if used_bits.div_ceil(8) != data.len() {
+            return Err(EncodingError::InvalidBitString);
+        }
+        let padding_bits = (data.len() * 8 - used_bits) as u8;
+        if let Some(last) = data.last() {
+            if last & ((1u16 << padding_bits) - 1) as u8 != 0 {
+                return Err(EncodingError::InvalidBitString);
+            }
+        }
+
+        Ok(Self { data, padding_bits })
+    }
+
+    /// a bit string with the bits at `indices` set and as many bits as needed for the highest
+    /// one. this is the DER encoding of a named bit list like KeyUsage, where trailing zero
+    /// bits are left out.
+    pub fn from_named_bits(indices: &[usize]) -> Self {
+        let mut bits = Self::default();
+        for &index in indices {
+            bits.set_bit(index);
+        }
+
+        bits
+    }
+
+    /// sets the bit at `index`, growing the string if it is shorter
+    pub fn set_bit(&mut self, index: usize) {
+        let used_bits = self.used_bits().max(index + 1);
+        let byte = index / 8;
+        if byte >= self.data.len() {
+            self.data.resize(byte + 1, 0);
+        }
+        self.data[byte] |= 0x80 >> (index % 8);
+
+        self.padding_bits = (self.data.len() * 8 - used_bits) as u8;
+    }
+
+    pub fn bit(&self, index: usize) -> bool {
+        index < self.used_bits() && self.data[index / 8] & (0x80 >> (index % 8)) != 0
+    }
+
+    pub fn used_bits(&self) -> usize {
+        self.data.len() * 8 - self.padding_bits as usize
+    }
 }
 
 impl ToDer for BitString {
@@ -30,7 +80,35 @@ impl ToDer for BitString {
         Ok(res)
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let len = write_bytes(&[self.padding_bits], buf)?;
+        Ok(len + write_bytes(&self.data, &mut buf[len..])?)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::BitString.into()
     }
 }
+
+#[test]
+fn test_bit_string() {
+    assert!(BitString::try_new(vec![0xff], 8).is_ok());
+    assert!(BitString::try_new(vec![0xfe], 7).is_ok());
+    assert!(BitString::try_new(vec![0xff], 7).is_err());
+    assert!(BitString::try_new(vec![0xff, 0x00], 8).is_err());
+    assert!(BitString::try_new(vec![], 0).is_ok());
+
+    let mut bits = BitString::default();
+    bits.set_bit(0);
+    bits.set_bit(5);
+    assert_eq!(bits.used_bits(), 6);
+    assert_eq!(bits.to_der().unwrap(), vec![0x03, 0x02, 0x02, 0x84]);
+    assert!(bits.bit(5) && !bits.bit(4) && !bits.bit(7));
+
+    // keyCertSign and cRLSign
+    let bits = BitString::from_named_bits(&[5, 6]);
+    assert_eq!(bits.to_der().unwrap(), vec![0x03, 0x02, 0x01, 0x06]);
+    // decipherOnly spills into a second byte
+    let bits = BitString::from_named_bits(&[0, 8]);
+    assert_eq!(bits.to_der().unwrap(), vec![0x03, 0x03, 0x07, 0x80, 0x80]);
+}
@@ -1,5 +1,5 @@
 use super::super::error::EncodingError;
-use super::{DataType, ToDer};
+use super::{write_bytes, DataType, ToDer};
 use std::fmt::{self, Debug, Formatter};
 
 #[derive(PartialEq, Clone)]
@@ -23,6 +23,10 @@ impl ToDer for Boolean {
         Ok(vec![self.0; 1])
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        write_bytes(&[self.0], buf)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Boolean.into()
     }
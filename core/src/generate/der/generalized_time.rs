@@ -0,0 +1,62 @@
+use super::super::error::EncodingError;
+use super::{write_bytes, write_time, DataType, ToDer};
+use crate::common::time::UtcDateTime;
+
+/// a time encoded as GeneralizedTime `YYYYMMDDHHMMSSZ`, without fractional seconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GeneralizedTime(UtcDateTime);
+
+impl GeneralizedTime {
+    /// fails with `EncodingError::TimeOutOfRange` outside of the years 0 to 9999
+    pub fn new(time: UtcDateTime) -> Result<Self, EncodingError> {
+        if !(0..=9999).contains(&time.year()) {
+            return Err(EncodingError::TimeOutOfRange);
+        }
+
+        Ok(Self(time))
+    }
+
+    pub fn time(&self) -> UtcDateTime {
+        self.0
+    }
+}
+
+impl ToDer for GeneralizedTime {
+    fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
+        let mut buf = [0u8; 15];
+        let len = write_time(&self.0, true, &mut buf)?;
+
+        Ok(buf[..len].to_vec())
+    }
+
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut time = [0u8; 15];
+        let len = write_time(&self.0, true, &mut time)?;
+
+        write_bytes(&time[..len], buf)
+    }
+
+    fn get_tag(&self) -> u8 {
+        DataType::GeneralizedTime.into()
+    }
+}
+
+#[test]
+fn test_generalized_time() {
+    use super::Data;
+
+    // OCSP and CRLs use GeneralizedTime before 2050 as well
+    let t = UtcDateTime::from_ymd_hms(2026, 10, 16, 12, 0, 5).unwrap();
+    let time = GeneralizedTime::new(t).unwrap();
+    assert_eq!(time.to_der().unwrap(), b"\x18\x0f20261016120005Z");
+    let mut buf = [0u8; 17];
+    assert_eq!(time.to_der_into(&mut buf).unwrap(), 17);
+    assert_eq!(buf.to_vec(), time.to_der().unwrap());
+    assert_eq!(Data::GeneralizedTime(time).to_der().unwrap(), buf);
+
+    let t = UtcDateTime::from_ymd_hms(10000, 1, 1, 0, 0, 0).unwrap();
+    assert!(matches!(
+        GeneralizedTime::new(t),
+        Err(EncodingError::TimeOutOfRange)
+    ));
+}
@@ -1,7 +1,8 @@
 use super::super::error::EncodingError;
-use super::{DataType, ToDer};
+use super::{write_bytes, DataType, ToDer};
 use std::convert::TryFrom;
 
+#[derive(Clone)]
 pub struct IA5String(Vec<u8>);
 
 impl TryFrom<&str> for IA5String {
@@ -21,6 +22,10 @@ impl ToDer for IA5String {
         Ok(self.0.clone())
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        write_bytes(&self.0, buf)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::IA5String.into()
     }
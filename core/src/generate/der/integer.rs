@@ -1,5 +1,5 @@
 use super::super::error::EncodingError;
-use super::{DataType, ToDer};
+use super::{write_bytes, DataType, ToDer};
 
 #[derive(PartialEq, Clone)]
 pub struct Integer(Vec<u8>);
@@ -11,6 +11,19 @@ impl Integer {
 
         Self(encoded)
     }
+
+    /// the non-negative number with the big endian magnitude `bs`
+    pub fn from_unsigned_bytes(bs: &[u8]) -> Self {
+        let mut unsigned = vec![0];
+        unsigned.extend_from_slice(bs);
+
+        Self(encode_integer(&unsigned))
+    }
+
+    /// the content octets, big endian two's complement
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
 }
 
 impl ToDer for Integer {
@@ -18,6 +31,10 @@ impl ToDer for Integer {
         Ok(self.0.clone())
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        write_bytes(&self.0, buf)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Integer.into()
     }
@@ -1,7 +1,8 @@
-use crate::common::der::ExplicitTag;
+use crate::common::der::{length_octets, ExplicitTag};
 
 use super::error::EncodingError;
-use chrono::prelude::*;
+use crate::common::time::UtcDateTime;
+use std::io::Write;
 
 #[derive(Eq, PartialEq)]
 pub enum DataType {
@@ -80,6 +81,56 @@ pub fn wrap_in_explicit_tag(inner: &[u8], tag: ExplicitTag) -> Vec<u8> {
     encode_tlv(tag.get_identifier_octet(), inner)
 }
 
+/// copies `bytes` to the start of `buf`, returning the number of bytes written
+pub fn write_bytes(bytes: &[u8], buf: &mut [u8]) -> Result<usize, EncodingError> {
+    let out = buf
+        .get_mut(..bytes.len())
+        .ok_or(EncodingError::BufferTooSmall)?;
+    out.copy_from_slice(bytes);
+
+    Ok(bytes.len())
+}
+
+/// encodes the tag and length octets of a TLV with `len` value bytes. returns the header
+/// buffer and how much of it is used.
+fn encode_header(tag: u8, len: usize) -> ([u8; 10], usize) {
+    let mut header = [0u8; 10];
+    header[0] = tag;
+    let (length_octets, length_len) = length_octets(len);
+    header[1..=length_len].copy_from_slice(&length_octets[..length_len]);
+
+    (header, 1 + length_len)
+}
+
+/// the length of a whole TLV with `len` value bytes
+pub fn tlv_len(len: usize) -> usize {
+    encode_header(0, len).1 + len
+}
+
+/// writes the tag and length octets of a TLV with `len` value bytes to `out`, returning the
+/// number of bytes written
+pub fn write_header(tag: u8, len: usize, out: &mut dyn Write) -> Result<usize, EncodingError> {
+    let (header, header_len) = encode_header(tag, len);
+    out.write_all(&header[..header_len])?;
+
+    Ok(header_len)
+}
+
+/// turns the `len` value bytes at the start of `buf` into a complete TLV by moving them
+/// behind a tag and length header. returns the length of the whole TLV.
+pub fn prepend_header(tag: u8, len: usize, buf: &mut [u8]) -> Result<usize, EncodingError> {
+    let (header, header_len) = encode_header(tag, len);
+
+    let total = header_len + len;
+    if total > buf.len() {
+        return Err(EncodingError::BufferTooSmall);
+    }
+    buf.copy_within(..len, header_len);
+    buf[..header_len].copy_from_slice(&header[..header_len]);
+
+    Ok(total)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,6 +140,35 @@ mod tests {
         let res = wrap_in_explicit_tag(&[0x10, 0x10], ExplicitTag::try_new(0x01).unwrap());
         assert_eq!(res, &[0xa1, 0x02, 0x10, 0x10]);
     }
+
+    #[test]
+    fn test_encode_time() {
+        let t = UtcDateTime::from_ymd_hms(2049, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(t.to_der().unwrap(), b"\x17\x0d491231235959Z");
+        let t = UtcDateTime::from_ymd_hms(2050, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(t.to_der().unwrap(), b"\x18\x0f20500101000000Z");
+    }
+
+    #[test]
+    fn test_to_der_into() {
+        let mut buf = [0u8; 300];
+        let t = UtcDateTime::from_ymd_hms(2050, 1, 1, 0, 0, 0).unwrap();
+        let len = t.to_der_into(&mut buf).unwrap();
+        assert_eq!(&buf[..len], &t.to_der().unwrap()[..]);
+
+        // long form length
+        let s = OctetString::new(vec![7; 200]);
+        let len = s.to_der_into(&mut buf).unwrap();
+        assert_eq!(len, 203);
+        assert_eq!(&buf[..len], &s.to_der().unwrap()[..]);
+
+        // exactly large enough, and one byte short
+        assert_eq!(s.to_der_into(&mut buf[..203]).unwrap(), 203);
+        assert!(matches!(
+            s.to_der_into(&mut buf[..202]),
+            Err(EncodingError::BufferTooSmall)
+        ));
+    }
 }
 
 pub trait ToDer {
@@ -101,43 +181,54 @@ pub trait ToDer {
         Ok(tlv)
     }
     fn get_tag(&self) -> u8;
+
+    /// encodes the inner value into the start of `buf`, returning the number of bytes written.
+    /// the default implementation goes through `encode_inner` and therefore allocates.
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        write_bytes(&self.encode_inner()?, buf)
+    }
+    /// encodes the whole value into the start of `buf` without allocating, returning the
+    /// number of bytes written. fails with `EncodingError::BufferTooSmall` if it doesn't fit.
+    fn to_der_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let len = self.encode_inner_into(buf)?;
+        prepend_header(self.get_tag(), len, buf)
+    }
+
+    /// the length of the whole encoding. the default implementation encodes the value.
+    fn encoded_len(&self) -> Result<usize, EncodingError> {
+        Ok(self.to_der()?.len())
+    }
+    /// writes the whole encoding to `out`, returning the number of bytes written. containers
+    /// override this together with `encoded_len` to write their children one after another,
+    /// so large structures can be hashed without encoding them into a single buffer.
+    fn write_der(&self, out: &mut dyn Write) -> Result<usize, EncodingError> {
+        let der = self.to_der()?;
+        out.write_all(&der)?;
+
+        Ok(der.len())
+    }
 }
 
-impl ToDer for DateTime<Utc> {
+/// picks the type RFC 5280 section 4.1.2.5 requires for certificate validity: dates through 2049
+/// are encoded as UTCTime, later ones as GeneralizedTime. use [`UTCTime`] or [`GeneralizedTime`]
+/// to choose the type explicitly.
+impl ToDer for UtcDateTime {
     fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
-        let year = self.year();
-        if year >= 1950 && year < 2049 {
-            let yy = year % 100;
-            let s = format!(
-                "{:02}{:02}{:02}{:02}{:02}{:02}Z",
-                yy,
-                self.month(),
-                self.day(),
-                self.hour(),
-                self.minute(),
-                self.second()
-            );
-
-            Ok(s.as_bytes().to_vec())
-        } else {
-            let yy = year % 100;
-            let s = format!(
-                "{:04}{:02}{:02}{:02}{:02}{:02}Z",
-                yy,
-                self.month(),
-                self.day(),
-                self.hour(),
-                self.minute(),
-                self.second()
-            );
-
-            Ok(s.as_bytes().to_vec())
+        match UTCTime::new(*self) {
+            Ok(time) => time.encode_inner(),
+            Err(_) => GeneralizedTime::new(*self)?.encode_inner(),
+        }
+    }
+
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        match UTCTime::new(*self) {
+            Ok(time) => time.encode_inner_into(buf),
+            Err(_) => GeneralizedTime::new(*self)?.encode_inner_into(buf),
         }
     }
 
     fn get_tag(&self) -> u8 {
-        let year = self.year();
-        if year >= 1950 && year < 2049 {
+        if UTCTime::new(*self).is_ok() {
             DataType::UTCTime.into()
         } else {
             DataType::GeneralizedTime.into()
@@ -145,6 +236,40 @@ impl ToDer for DateTime<Utc> {
     }
 }
 
+/// writes `time` as `YYMMDDHHMMSSZ`, or with a four digit year, into the start of `buf`.
+/// fractional seconds are never encoded.
+fn write_time(
+    time: &UtcDateTime,
+    four_digit_year: bool,
+    buf: &mut [u8],
+) -> Result<usize, EncodingError> {
+    let year = time.year() as u32;
+    let mut digits = [0u8; 15];
+    let mut pos = 0;
+    if four_digit_year {
+        pos += write_two_digits(year / 100, &mut digits[pos..]);
+    }
+    pos += write_two_digits(year % 100, &mut digits[pos..]);
+    for v in [
+        time.month(),
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second(),
+    ] {
+        pos += write_two_digits(v, &mut digits[pos..]);
+    }
+    digits[pos] = b'Z';
+
+    write_bytes(&digits[..pos + 1], buf)
+}
+
+fn write_two_digits(v: u32, out: &mut [u8]) -> usize {
+    out[0] = b'0' + (v / 10) as u8;
+    out[1] = b'0' + (v % 10) as u8;
+    2
+}
+
 #[derive(Clone)]
 pub enum Data {
     Boolean(Boolean),
@@ -156,11 +281,11 @@ pub enum Data {
     Utf8String(Utf8String),
     // Sequence(Sequence),
     // Set(Set),
-    // PrintableString(PrintableString),
+    PrintableString(PrintableString),
     // T61String(T61String),
-    // IA5String(IA5String),
-    // UTCTime(UTCTime),
-    // GeneralizedTime(GeneralizedTime),
+    IA5String(IA5String),
+    UTCTime(UTCTime),
+    GeneralizedTime(GeneralizedTime),
     // VisibleString(VisibleString),
     // BMPString(BMPString),
 }
@@ -179,6 +304,26 @@ impl ToDer for Data {
             Data::Null(d) => d.to_der(),
             Data::ObjectIdentifier(d) => d.to_der(),
             Data::Utf8String(d) => d.to_der(),
+            Data::PrintableString(d) => d.to_der(),
+            Data::IA5String(d) => d.to_der(),
+            Data::UTCTime(d) => d.to_der(),
+            Data::GeneralizedTime(d) => d.to_der(),
+        }
+    }
+
+    fn to_der_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        match self {
+            Data::Boolean(d) => d.to_der_into(buf),
+            Data::Integer(d) => d.to_der_into(buf),
+            Data::BitString(d) => d.to_der_into(buf),
+            Data::OctetString(d) => d.to_der_into(buf),
+            Data::Null(d) => d.to_der_into(buf),
+            Data::ObjectIdentifier(d) => d.to_der_into(buf),
+            Data::Utf8String(d) => d.to_der_into(buf),
+            Data::PrintableString(d) => d.to_der_into(buf),
+            Data::IA5String(d) => d.to_der_into(buf),
+            Data::UTCTime(d) => d.to_der_into(buf),
+            Data::GeneralizedTime(d) => d.to_der_into(buf),
         }
     }
 
@@ -189,19 +334,26 @@ impl ToDer for Data {
 
 pub use bit_string::BitString;
 pub use boolean::Boolean;
+pub use generalized_time::GeneralizedTime;
+pub use ia5_string::IA5String;
 pub use integer::Integer;
 pub use null::Null;
 pub use object_identifier::ObjectIdentifier;
 pub use octet_string::{OctetString, OctetStringRef};
+pub use printable_string::PrintableString;
+pub use utc_time::UTCTime;
 pub use utf8_string::Utf8String;
 pub use visible_string::VisibleStringRef;
 
 mod bit_string;
 mod boolean;
+mod generalized_time;
 mod ia5_string;
 mod integer;
 mod null;
 mod object_identifier;
 mod octet_string;
+mod printable_string;
+mod utc_time;
 mod utf8_string;
 mod visible_string;
@@ -9,6 +9,10 @@ impl ToDer for Null {
         Ok(vec![])
     }
 
+    fn encode_inner_into(&self, _buf: &mut [u8]) -> Result<usize, EncodingError> {
+        Ok(0)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Null.into()
     }
@@ -1,5 +1,5 @@
 use super::super::error::EncodingError;
-use super::{DataType, ToDer};
+use super::{write_bytes, DataType, ToDer};
 pub use crate::parse::der::ObjectIdentifier;
 use crate::parse::der::ObjectIdentifierRef;
 
@@ -8,6 +8,10 @@ impl ToDer for ObjectIdentifier {
         Ok(self.0.clone())
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        write_bytes(&self.0, buf)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::ObjectIdentifier.into()
     }
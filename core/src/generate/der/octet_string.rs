@@ -1,5 +1,6 @@
 use super::super::error::EncodingError;
-use super::{DataType, ToDer};
+use super::{tlv_len, write_bytes, write_header, DataType, ToDer};
+use std::io::Write;
 
 #[derive(Debug, PartialEq)]
 pub struct OctetStringRef<'a>(&'a [u8]);
@@ -11,6 +12,11 @@ impl OctetString {
     pub fn new(data: Vec<u8>) -> Self {
         Self(data)
     }
+
+    /// wraps the DER encoding of `value`, e.g. to build the extnValue of an extension
+    pub fn from_der(value: &impl ToDer) -> Result<Self, EncodingError> {
+        Ok(Self(value.to_der()?))
+    }
 }
 
 impl ToDer for OctetString {
@@ -18,7 +24,33 @@ impl ToDer for OctetString {
         Ok(self.0.clone())
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        write_bytes(&self.0, buf)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::OctetString.into()
     }
+
+    fn encoded_len(&self) -> Result<usize, EncodingError> {
+        Ok(tlv_len(self.0.len()))
+    }
+
+    fn write_der(&self, out: &mut dyn Write) -> Result<usize, EncodingError> {
+        let len = write_header(self.get_tag(), self.0.len(), out)?;
+        out.write_all(&self.0)?;
+
+        Ok(len + self.0.len())
+    }
+}
+
+#[test]
+fn test_octet_string_from_der() {
+    use super::Boolean;
+
+    let wrapped = OctetString::from_der(&Boolean::from(true)).unwrap();
+    assert_eq!(
+        wrapped.to_der().unwrap(),
+        vec![0x04, 0x03, 0x01, 0x01, 0xff]
+    );
 }
@@ -0,0 +1,33 @@
+use super::super::error::EncodingError;
+use super::{write_bytes, DataType, ToDer};
+use crate::common::der::is_printable;
+use std::convert::TryFrom;
+
+#[derive(Clone)]
+pub struct PrintableString(Vec<u8>);
+
+impl TryFrom<&str> for PrintableString {
+    type Error = EncodingError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if !value.bytes().all(is_printable) {
+            return Err(EncodingError::StringNotPrintable);
+        }
+
+        Ok(Self(value.as_bytes().to_vec()))
+    }
+}
+
+impl ToDer for PrintableString {
+    fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
+        Ok(self.0.clone())
+    }
+
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        write_bytes(&self.0, buf)
+    }
+
+    fn get_tag(&self) -> u8 {
+        DataType::PrintableString.into()
+    }
+}
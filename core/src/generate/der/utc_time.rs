@@ -0,0 +1,56 @@
+use super::super::error::EncodingError;
+use super::{write_bytes, write_time, DataType, ToDer};
+use crate::common::time::UtcDateTime;
+
+/// a time encoded as UTCTime `YYMMDDHHMMSSZ`, which covers the years 1950 to 2049
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UTCTime(UtcDateTime);
+
+impl UTCTime {
+    /// fails with `EncodingError::TimeOutOfRange` outside of the years 1950 to 2049
+    pub fn new(time: UtcDateTime) -> Result<Self, EncodingError> {
+        if !(1950..=2049).contains(&time.year()) {
+            return Err(EncodingError::TimeOutOfRange);
+        }
+
+        Ok(Self(time))
+    }
+
+    pub fn time(&self) -> UtcDateTime {
+        self.0
+    }
+}
+
+impl ToDer for UTCTime {
+    fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
+        let mut buf = [0u8; 13];
+        let len = write_time(&self.0, false, &mut buf)?;
+
+        Ok(buf[..len].to_vec())
+    }
+
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        let mut time = [0u8; 13];
+        let len = write_time(&self.0, false, &mut time)?;
+
+        write_bytes(&time[..len], buf)
+    }
+
+    fn get_tag(&self) -> u8 {
+        DataType::UTCTime.into()
+    }
+}
+
+#[test]
+fn test_utc_time() {
+    let t = UtcDateTime::from_ymd_hms(1950, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        UTCTime::new(t).unwrap().to_der().unwrap(),
+        b"\x17\x0d500101000000Z"
+    );
+    let t = UtcDateTime::from_ymd_hms(2050, 1, 1, 0, 0, 0).unwrap();
+    assert!(matches!(
+        UTCTime::new(t),
+        Err(EncodingError::TimeOutOfRange)
+    ));
+}
@@ -1,5 +1,5 @@
 use super::super::error::EncodingError;
-use super::{DataType, ToDer};
+use super::{write_bytes, DataType, ToDer};
 
 #[derive(Clone)]
 pub struct Utf8String(Vec<u8>);
@@ -15,6 +15,10 @@ impl ToDer for Utf8String {
         Ok(self.0.clone())
     }
 
+    fn encode_inner_into(&self, buf: &mut [u8]) -> Result<usize, EncodingError> {
+        write_bytes(&self.0, buf)
+    }
+
     fn get_tag(&self) -> u8 {
         DataType::Utf8String.into()
     }
@@ -1,13 +1,18 @@
-use std::fmt::{self, Debug, Display, Formatter};
-
+use std::fmt::{self, Debug, Display, Formatter, Write};
 
 #[derive(PartialEq)]
 pub struct VisibleStringRef<'a>(&'a [u8]);
 
 impl<'a> Display for VisibleStringRef<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        let s = String::from_utf8_lossy(self.0);
-        write!(f, "{}", s)
+        // like String::from_utf8_lossy, without building the string
+        for chunk in self.0.utf8_chunks() {
+            f.write_str(chunk.valid())?;
+            if !chunk.invalid().is_empty() {
+                f.write_char(char::REPLACEMENT_CHARACTER)?;
+            }
+        }
+        Ok(())
     }
 }
 
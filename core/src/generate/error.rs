@@ -1,5 +1,29 @@
 #[derive(Debug)]
 pub enum EncodingError {
     StringNotAscii,
+    /// the string contains characters outside of the PrintableString alphabet
+    StringNotPrintable,
     MissingRequiredField(&'static str),
+    /// the data doesn't match the number of bits, or the unused bits are not zero
+    InvalidBitString,
+    TimeOutOfRange,
+    BufferTooSmall,
+    /// the system random number generator failed, e.g. while making up a serial number
+    RandomnessUnavailable,
+    /// writing the encoding to a `std::io::Write` failed
+    Io(std::io::Error),
+    /// an already encoded value, like a [`Name::Encoded`](super::certificate::Name::Encoded), is
+    /// not valid DER
+    InvalidEncodedValue,
+    /// the parameters of an AlgorithmIdentifier are not allowed for its algorithm, see
+    /// [`ParameterRule`](crate::common::certificate::ParameterRule)
+    InvalidParameters,
+    /// the extensions field is present but empty, RFC 5280 requires at least one extension
+    EmptyExtensions,
+}
+
+impl From<std::io::Error> for EncodingError {
+    fn from(e: std::io::Error) -> Self {
+        EncodingError::Io(e)
+    }
 }
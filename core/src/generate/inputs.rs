@@ -0,0 +1,137 @@
+//! The inputs of certificate generation that don't come from the caller: the serial number and
+//! the start of the validity period. both can be injected, so tests generate the same certificate
+//! every time and can compare it to a golden file.
+
+use super::der::Integer;
+use super::error::EncodingError;
+use crate::common::time::UtcDateTime;
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// hands out the serial numbers of new certificates
+pub trait SerialNumberSource {
+    fn next_serial_number(&mut self) -> Result<Integer, EncodingError>;
+}
+
+/// positive serial numbers with 127 random bits, so they are unpredictable and never repeat in
+/// practice (the CA/Browser Forum requires at least 64)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomSerialNumbers;
+
+impl SerialNumberSource for RandomSerialNumbers {
+    fn next_serial_number(&mut self) -> Result<Integer, EncodingError> {
+        let mut serial = [0u8; 16];
+        SystemRandom::new()
+            .fill(&mut serial)
+            .map_err(|_| EncodingError::RandomnessUnavailable)?;
+        // RFC 5280 allows at most 20 octets and no negative numbers
+        serial[0] &= 0x7f;
+
+        Ok(Integer::from_unsigned_bytes(&serial))
+    }
+}
+
+/// the serial numbers `first`, `first + 1` and so on
+#[derive(Debug, Clone)]
+pub struct SequentialSerialNumbers {
+    next: i64,
+}
+
+impl SequentialSerialNumbers {
+    pub fn starting_at(first: i64) -> Self {
+        Self { next: first }
+    }
+}
+
+impl SerialNumberSource for SequentialSerialNumbers {
+    fn next_serial_number(&mut self) -> Result<Integer, EncodingError> {
+        let serial = Integer::from_i64(self.next);
+        self.next += 1;
+
+        Ok(serial)
+    }
+}
+
+/// the time new certificates become valid
+pub trait Clock {
+    fn now(&self) -> UtcDateTime;
+}
+
+/// [`UtcDateTime::now`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> UtcDateTime {
+        UtcDateTime::now()
+    }
+}
+
+/// always the same time
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub UtcDateTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> UtcDateTime {
+        self.0
+    }
+}
+
+/// where [`TBSCertificateBuilder::issued_now`](super::builder::TBSCertificateBuilder::issued_now)
+/// takes the serial number and notBefore from
+pub struct GenerationInputs {
+    serial_numbers: Box<dyn SerialNumberSource>,
+    clock: Box<dyn Clock>,
+}
+
+impl GenerationInputs {
+    pub fn new(serial_numbers: Box<dyn SerialNumberSource>, clock: Box<dyn Clock>) -> Self {
+        Self {
+            serial_numbers,
+            clock,
+        }
+    }
+
+    /// random serial numbers and the system time
+    pub fn system() -> Self {
+        Self::new(Box::new(RandomSerialNumbers), Box::new(SystemClock))
+    }
+
+    /// the serial numbers 1, 2, 3, ... and `now` as the current time. certificates built with
+    /// the same inputs in the same order are identical byte for byte, as long as the signature
+    /// algorithm is deterministic too (RSA PKCS #1 v1.5 is, ECDSA isn't).
+    pub fn deterministic(now: UtcDateTime) -> Self {
+        Self::new(
+            Box::new(SequentialSerialNumbers::starting_at(1)),
+            Box::new(FixedClock(now)),
+        )
+    }
+
+    pub fn next_serial_number(&mut self) -> Result<Integer, EncodingError> {
+        self.serial_numbers.next_serial_number()
+    }
+
+    pub fn now(&self) -> UtcDateTime {
+        self.clock.now()
+    }
+}
+
+impl Default for GenerationInputs {
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+#[test]
+fn test_serial_numbers() {
+    let mut random = RandomSerialNumbers;
+    let a = random.next_serial_number().unwrap();
+    let b = random.next_serial_number().unwrap();
+    assert!(a != b);
+    assert!(a.as_bytes().len() <= 17);
+    assert_eq!(a.as_bytes()[0] & 0x80, 0);
+
+    let mut inputs = GenerationInputs::deterministic(UtcDateTime::from_unix_timestamp(0).unwrap());
+    assert!(inputs.next_serial_number().unwrap() == Integer::from_i64(1));
+    assert!(inputs.next_serial_number().unwrap() == Integer::from_i64(2));
+    assert_eq!(inputs.now(), UtcDateTime::from_unix_timestamp(0).unwrap());
+}
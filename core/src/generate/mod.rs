@@ -1,4 +1,8 @@
 pub mod builder;
 pub mod certificate;
+pub mod corpus;
 pub mod der;
 pub mod error;
+pub mod inputs;
+pub mod pem;
+pub mod pkcs7;
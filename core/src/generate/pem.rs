@@ -0,0 +1,118 @@
+//! PEM armoring of DER encoded data.
+
+use crate::parse::parsing::CertificateRef;
+use std::io::{self, Write};
+
+/// wraps `contents` in a `-----BEGIN label-----` block with base64 lines of 64 characters
+pub fn encode_pem(label: &str, contents: &[u8]) -> String {
+    let base64 = base64::encode(contents);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    // base64 is ASCII, so splitting at any byte is fine
+    for line in base64.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+
+    pem
+}
+
+/// writes `certs` as a bundle of `CERTIFICATE` blocks, leaf first and every certificate
+/// followed by its issuer. this is the order TLS servers send their chain in. `certs` can be in
+/// any order, but they must form a single chain, otherwise an `InvalidInput` error is returned
+/// and nothing is written.
+pub fn write_chain_pem(certs: &[CertificateRef], out: &mut impl Write) -> io::Result<()> {
+    let chain = leaf_first(certs).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the certificates don't form a single chain",
+        )
+    })?;
+    for cert in chain {
+        out.write_all(encode_pem("CERTIFICATE", cert.raw_data()).as_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// orders `certs` by following the issuer names, starting at the only certificate that didn't
+/// issue any of the others
+fn leaf_first<'a, 'b>(certs: &'b [CertificateRef<'a>]) -> Option<Vec<&'b CertificateRef<'a>>> {
+    let issued = |issuer: &CertificateRef, subject: &CertificateRef| {
+        subject.tbs_cert().issuer() == issuer.tbs_cert().subject()
+    };
+
+    let mut leaves = certs.iter().enumerate().filter(|(i, c)| {
+        !certs
+            .iter()
+            .enumerate()
+            .any(|(j, other)| *i != j && issued(c, other))
+    });
+    let (mut index, mut current) = match (leaves.next(), leaves.next()) {
+        (Some(leaf), None) => leaf,
+        (None, None) if certs.is_empty() => return Some(Vec::new()),
+        _ => return None,
+    };
+
+    let mut used = vec![false; certs.len()];
+    let mut chain = Vec::with_capacity(certs.len());
+    loop {
+        used[index] = true;
+        chain.push(current);
+        match certs
+            .iter()
+            .enumerate()
+            .find(|(i, c)| !used[*i] && issued(c, current))
+        {
+            Some((i, issuer)) => {
+                index = i;
+                current = issuer;
+            }
+            None => break,
+        }
+    }
+
+    if chain.len() == certs.len() {
+        Some(chain)
+    } else {
+        None
+    }
+}
+
+#[test]
+fn test_write_chain_pem() {
+    use crate::parse::pem::decode_pem;
+
+    let leaf_der = include_bytes!("../../../certs/ocsp/leaf.der");
+    let ca_der = include_bytes!("../../../certs/ocsp/ca.der");
+    let leaf = CertificateRef::from_slice(leaf_der).unwrap();
+    let ca = CertificateRef::from_slice(ca_der).unwrap();
+
+    let mut out = Vec::new();
+    write_chain_pem(&[ca, leaf], &mut out).unwrap();
+    let blocks = decode_pem(&out).unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].contents(), &leaf_der[..]);
+    assert_eq!(blocks[1].contents(), &ca_der[..]);
+
+    // the leaf and an unrelated certificate
+    let leaf = CertificateRef::from_slice(leaf_der).unwrap();
+    let other = CertificateRef::from_slice(include_bytes!("../../../certs/test.crt")).unwrap();
+    let mut out = Vec::new();
+    let err = write_chain_pem(&[leaf, other], &mut out).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(out.is_empty());
+}
+
+#[test]
+fn test_encode_pem() {
+    use crate::parse::pem::{decode_pem, Pem};
+
+    let contents: Vec<u8> = (0..100).collect();
+    let pem = encode_pem("CERTIFICATE", &contents);
+    assert!(pem.lines().all(|line| line.len() <= 64));
+    assert_eq!(
+        decode_pem(pem.as_bytes()).unwrap(),
+        vec![Pem::new("CERTIFICATE", contents)]
+    );
+}
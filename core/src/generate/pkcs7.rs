@@ -0,0 +1,110 @@
+//! Degenerate "certs-only" CMS SignedData (RFC 5652), the .p7b format Windows and Java
+//! tooling import certificate chains from.
+
+use super::{
+    certificate::Certificate,
+    der::{encode_tlv, wrap_in_explicit_tag, DataType, Integer, ObjectIdentifier, ToDer},
+    error::EncodingError,
+};
+use crate::common::der::ExplicitTag;
+use crate::oids;
+use crate::parse::parsing::CertificateRef;
+
+/// a SignedData without content and signers that only transports certificates.
+///
+/// the certificates are encoded in the order they were added, like `openssl crl2pkcs7` does.
+/// DER would sort the SET OF, but importers treat the bundle as a chain and keep the order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CertsOnly {
+    certificates: Vec<Vec<u8>>,
+}
+
+impl CertsOnly {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_certificate(&mut self, cert: &Certificate) -> Result<(), EncodingError> {
+        self.certificates.push(cert.to_der()?);
+
+        Ok(())
+    }
+
+    /// adds a parsed certificate, its encoding is copied as is
+    pub fn add_certificate_ref(&mut self, cert: &CertificateRef) {
+        self.certificates.push(cert.raw_data().to_vec());
+    }
+
+    pub fn len(&self) -> usize {
+        self.certificates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.certificates.is_empty()
+    }
+}
+
+impl ToDer for CertsOnly {
+    fn encode_inner(&self) -> Result<Vec<u8>, EncodingError> {
+        let content_type: ObjectIdentifier = oids::PKCS7_DATA.into();
+
+        let mut signed_data = Integer::from_i64(1).to_der()?;
+        // digestAlgorithms
+        signed_data.extend_from_slice(&encode_tlv(DataType::Set.constructed(), &[]));
+        // encapContentInfo without eContent
+        signed_data.extend_from_slice(&encode_tlv(
+            DataType::Sequence.constructed(),
+            &content_type.to_der()?,
+        ));
+        // certificates [0] IMPLICIT CertificateSet, a SET is constructed so the tag is the same
+        // as an explicit one
+        signed_data.extend_from_slice(&wrap_in_explicit_tag(
+            &self.certificates.concat(),
+            ExplicitTag::try_new(0).unwrap(),
+        ));
+        // signerInfos
+        signed_data.extend_from_slice(&encode_tlv(DataType::Set.constructed(), &[]));
+        let signed_data = encode_tlv(DataType::Sequence.constructed(), &signed_data);
+
+        let content_type: ObjectIdentifier = oids::PKCS7_SIGNED_DATA.into();
+        let mut content_info = content_type.to_der()?;
+        content_info.extend_from_slice(&wrap_in_explicit_tag(
+            &signed_data,
+            ExplicitTag::try_new(0).unwrap(),
+        ));
+
+        Ok(content_info)
+    }
+
+    fn get_tag(&self) -> u8 {
+        DataType::Sequence.constructed()
+    }
+}
+
+#[test]
+fn test_certs_only() {
+    use crate::parse::pkcs7::SignedDataRef;
+
+    let leaf = include_bytes!("../../../certs/ocsp/leaf.der");
+    let ca = include_bytes!("../../../certs/ocsp/ca.der");
+    let mut bundle = CertsOnly::new();
+    bundle.add_certificate_ref(&CertificateRef::from_slice(leaf).unwrap());
+    bundle.add_certificate_ref(&CertificateRef::from_slice(ca).unwrap());
+    assert_eq!(bundle.len(), 2);
+
+    // byte for byte what `openssl crl2pkcs7 -nocrl` produces
+    let der = bundle.to_der().unwrap();
+    assert_eq!(der, &include_bytes!("../../../certs/pkcs7/chain.p7b")[..]);
+
+    let parsed = SignedDataRef::from_slice(&der).unwrap();
+    let certs: Vec<_> = parsed.certificates().collect::<Result<_, _>>().unwrap();
+    assert_eq!(certs[0].raw_data(), &leaf[..]);
+    assert_eq!(certs[1].raw_data(), &ca[..]);
+}
+
+#[test]
+fn test_empty() {
+    let der = CertsOnly::new().to_der().unwrap();
+    let parsed = crate::parse::pkcs7::SignedDataRef::from_slice(&der).unwrap();
+    assert_eq!(parsed.certificates().count(), 0);
+}
@@ -1,9 +1,49 @@
+//! X.509 certificates, CRLs and related structures.
+//!
+//! certificates are parsed with the zero-copy [`CertificateRef`], which borrows from the DER
+//! input, and generated with [`TBSCertificate`] and [`Certificate`].
+//!
+//! # Thread safety
+//!
+//! all parsed and generated types are `Send + Sync`. none of them use interior mutability, so
+//! parsed certificates can be shared between threads by reference, e.g. from an `Arc` holding
+//! the DER buffer, and builders can be moved to worker threads without wrapping them in a lock.
+
 #[macro_use]
 extern crate derive_builder;
 
 pub mod common;
+pub mod diff;
 pub mod error;
 pub mod generate;
+pub mod lint;
+pub mod oids;
 pub mod parse;
+pub mod prelude;
 mod utils;
-mod x509;
+
+pub use generate::{builder::TBSCertificate, certificate::Certificate, der::ToDer};
+pub use parse::{error::ParseError, parsing::CertificateRef};
+
+// fails to compile if one of the public types stops being Send + Sync
+#[allow(dead_code)]
+fn assert_send_sync() {
+    fn is_send_sync<T: Send + Sync>() {}
+
+    is_send_sync::<CertificateRef<'static>>();
+    is_send_sync::<parse::parsing::TBSCertificateRef<'static>>();
+    is_send_sync::<parse::crl::CertificateListRef<'static>>();
+    is_send_sync::<parse::csr::CertificationRequestRef<'static>>();
+    is_send_sync::<parse::der::DerNode<'static>>();
+    is_send_sync::<parse::der::DerElementRef<'static>>();
+    is_send_sync::<parse::pem::PemItem>();
+    is_send_sync::<ParseError>();
+    is_send_sync::<TBSCertificate>();
+    is_send_sync::<generate::builder::TBSCertificateBuilder>();
+    is_send_sync::<Certificate>();
+    is_send_sync::<generate::certificate::GeneralNames>();
+    is_send_sync::<generate::certificate::DistinguishedName>();
+    is_send_sync::<generate::error::EncodingError>();
+    is_send_sync::<common::time::UtcDateTime>();
+    is_send_sync::<error::Error>();
+}
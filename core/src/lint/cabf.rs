@@ -0,0 +1,246 @@
+//! The CA/Browser Forum Baseline Requirements for publicly-trusted TLS server certificates.
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::oids;
+use crate::parse::{
+    certificate::ExtensionRef,
+    der::ObjectIdentifierRef,
+    error::ParseError,
+    extensions::{BasicConstraints, CertificatePoliciesRef, ExtendedKeyUsageRef},
+    parsing::CertificateRef,
+};
+
+/// the longest validity period the baseline requirements allow, in seconds
+const MAX_VALIDITY_SECONDS: i64 = 398 * 86_400;
+
+/// a requirement of the baseline requirements that a subscriber certificate does not meet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BRViolation {
+    /// the subjectAltName extension is missing or empty (7.1.2.7.12)
+    MissingSubjectAltName,
+    /// basicConstraints marks the certificate as a CA (7.1.2.7.8)
+    IsCA,
+    /// the extKeyUsage extension is missing (7.1.2.7.10)
+    MissingExtendedKeyUsage,
+    /// id-kp-serverAuth is not among the key purposes (7.1.2.7.10)
+    MissingServerAuth,
+    /// a key purpose other than serverAuth and clientAuth, in dotted form (7.1.2.7.10)
+    ForbiddenKeyPurpose(String),
+    /// notAfter - notBefore + 1s is longer than 398 days (6.3.2), `days` is rounded up
+    ValidityTooLong { days: i64 },
+    /// notBefore or notAfter can't be decoded
+    MalformedValidity,
+    /// certificatePolicies is missing or contains none of the reserved CA/B Forum ids (7.1.6.1)
+    MissingReservedPolicy,
+    /// more than one reserved CA/B Forum id is asserted (7.1.2.7.9)
+    MultipleReservedPolicies,
+    /// anyPolicy is not allowed in subscriber certificates (7.1.2.7.9)
+    AnyPolicy,
+    /// the extension with this id, in dotted form, can't be parsed
+    MalformedExtension(String),
+}
+
+impl Display for BRViolation {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            BRViolation::MissingSubjectAltName => write!(f, "subjectAltName is missing or empty"),
+            BRViolation::IsCA => write!(f, "subscriber certificate is a CA"),
+            BRViolation::MissingExtendedKeyUsage => write!(f, "extKeyUsage is missing"),
+            BRViolation::MissingServerAuth => write!(f, "extKeyUsage does not contain serverAuth"),
+            BRViolation::ForbiddenKeyPurpose(oid) => {
+                write!(f, "extKeyUsage contains forbidden purpose {}", oid)
+            }
+            BRViolation::ValidityTooLong { days } => {
+                write!(f, "validity of {} days exceeds 398 days", days)
+            }
+            BRViolation::MalformedValidity => write!(f, "validity can't be decoded"),
+            BRViolation::MissingReservedPolicy => {
+                write!(f, "no CA/Browser Forum reserved policy id is asserted")
+            }
+            BRViolation::MultipleReservedPolicies => {
+                write!(
+                    f,
+                    "more than one CA/Browser Forum reserved policy id is asserted"
+                )
+            }
+            BRViolation::AnyPolicy => write!(f, "anyPolicy is asserted"),
+            BRViolation::MalformedExtension(oid) => write!(f, "extension {} is malformed", oid),
+        }
+    }
+}
+
+/// checks a publicly-trusted TLS server (subscriber) certificate against the baseline
+/// requirements. returns all violations found, an empty list means the certificate conforms
+/// as far as these checks go.
+pub fn check_tls_server_certificate(cert: &CertificateRef) -> Vec<BRViolation> {
+    let mut violations = Vec::new();
+
+    check_subject_alt_name(cert, &mut violations);
+    check_basic_constraints(cert, &mut violations);
+    check_extended_key_usage(cert, &mut violations);
+    check_validity(cert, &mut violations);
+    check_policies(cert, &mut violations);
+
+    violations
+}
+
+fn find_extension<'a>(
+    cert: &'a CertificateRef,
+    id: ObjectIdentifierRef,
+) -> Result<Option<ExtensionRef<'a>>, ParseError> {
+    match cert.tbs_cert().extensions() {
+        Some(extensions) => extensions.find(id),
+        None => Ok(None),
+    }
+}
+
+fn malformed(id: ObjectIdentifierRef) -> BRViolation {
+    BRViolation::MalformedExtension(id.to_string())
+}
+
+fn check_subject_alt_name(cert: &CertificateRef, violations: &mut Vec<BRViolation>) {
+    match find_extension(cert, oids::SUBJECT_ALT_NAME) {
+        // GeneralNames is a sequence, an empty one is 0x30 0x00
+        Ok(Some(ext)) if ext.value().as_bytes().len() > 2 => {}
+        Ok(_) => violations.push(BRViolation::MissingSubjectAltName),
+        Err(_) => violations.push(malformed(oids::SUBJECT_ALT_NAME)),
+    }
+}
+
+fn check_basic_constraints(cert: &CertificateRef, violations: &mut Vec<BRViolation>) {
+    let ext = match find_extension(cert, oids::BASIC_CONSTRAINTS) {
+        Ok(Some(ext)) => ext,
+        Ok(None) => return,
+        Err(_) => return violations.push(malformed(oids::BASIC_CONSTRAINTS)),
+    };
+    match BasicConstraints::from_slice(ext.value().as_bytes()) {
+        Ok(constraints) if constraints.is_ca() => violations.push(BRViolation::IsCA),
+        Ok(_) => {}
+        Err(_) => violations.push(malformed(oids::BASIC_CONSTRAINTS)),
+    }
+}
+
+fn check_extended_key_usage(cert: &CertificateRef, violations: &mut Vec<BRViolation>) {
+    let ext = match find_extension(cert, oids::EXT_KEY_USAGE) {
+        Ok(Some(ext)) => ext,
+        Ok(None) => return violations.push(BRViolation::MissingExtendedKeyUsage),
+        Err(_) => return violations.push(malformed(oids::EXT_KEY_USAGE)),
+    };
+    let eku = match ExtendedKeyUsageRef::from_slice(ext.value().as_bytes()) {
+        Ok(eku) => eku,
+        Err(_) => return violations.push(malformed(oids::EXT_KEY_USAGE)),
+    };
+
+    let mut server_auth = false;
+    for purpose in eku.purposes() {
+        let purpose = match purpose {
+            Ok(purpose) => purpose,
+            Err(_) => return violations.push(malformed(oids::EXT_KEY_USAGE)),
+        };
+        if oids::is(&purpose, oids::KP_SERVER_AUTH) {
+            server_auth = true;
+        } else if !oids::is(&purpose, oids::KP_CLIENT_AUTH) {
+            violations.push(BRViolation::ForbiddenKeyPurpose(purpose.to_string()));
+        }
+    }
+    if !server_auth {
+        violations.push(BRViolation::MissingServerAuth);
+    }
+}
+
+fn check_validity(cert: &CertificateRef, violations: &mut Vec<BRViolation>) {
+    let validity = cert.tbs_cert().validity();
+    let (not_before, not_after) = match (validity.not_before(), validity.not_after()) {
+        (Ok(not_before), Ok(not_after)) => (not_before, not_after),
+        _ => return violations.push(BRViolation::MalformedValidity),
+    };
+
+    // the validity period includes both notBefore and notAfter
+    let seconds = not_after.unix_timestamp() - not_before.unix_timestamp() + 1;
+    if seconds > MAX_VALIDITY_SECONDS {
+        violations.push(BRViolation::ValidityTooLong {
+            days: (seconds + 86_399) / 86_400,
+        });
+    }
+}
+
+fn is_reserved_policy(id: &ObjectIdentifierRef) -> bool {
+    [
+        oids::CABF_EXTENDED_VALIDATION,
+        oids::CABF_DOMAIN_VALIDATED,
+        oids::CABF_ORGANIZATION_VALIDATED,
+        oids::CABF_INDIVIDUAL_VALIDATED,
+    ]
+    .iter()
+    .any(|reserved| oids::is(id, *reserved))
+}
+
+fn check_policies(cert: &CertificateRef, violations: &mut Vec<BRViolation>) {
+    let ext = match find_extension(cert, oids::CERTIFICATE_POLICIES) {
+        Ok(Some(ext)) => ext,
+        Ok(None) => return violations.push(BRViolation::MissingReservedPolicy),
+        Err(_) => return violations.push(malformed(oids::CERTIFICATE_POLICIES)),
+    };
+    let policies = match CertificatePoliciesRef::from_slice(ext.value().as_bytes()) {
+        Ok(policies) => policies,
+        Err(_) => return violations.push(malformed(oids::CERTIFICATE_POLICIES)),
+    };
+
+    let mut reserved = 0;
+    for policy in policies.policies() {
+        let policy = match policy {
+            Ok(policy) => policy,
+            Err(_) => return violations.push(malformed(oids::CERTIFICATE_POLICIES)),
+        };
+        if oids::is(policy.policy_identifier(), oids::ANY_POLICY) {
+            violations.push(BRViolation::AnyPolicy);
+        } else if is_reserved_policy(policy.policy_identifier()) {
+            reserved += 1;
+        }
+    }
+    match reserved {
+        0 => violations.push(BRViolation::MissingReservedPolicy),
+        1 => {}
+        _ => violations.push(BRViolation::MultipleReservedPolicies),
+    }
+}
+
+#[test]
+fn test_conforming_certificate() {
+    let cert =
+        CertificateRef::from_slice(include_bytes!("../../../certs/lint/server.der")).unwrap();
+    assert_eq!(check_tls_server_certificate(&cert), vec![]);
+}
+
+#[test]
+fn test_violations() {
+    // issued for 100 years and without certificate policies
+    let cert = CertificateRef::from_slice(include_bytes!("../../../certs/ocsp/leaf.der")).unwrap();
+    assert_eq!(
+        check_tls_server_certificate(&cert),
+        vec![
+            // 36500 days plus the second notAfter itself counts for, rounded up
+            BRViolation::ValidityTooLong { days: 36_501 },
+            BRViolation::MissingReservedPolicy,
+        ]
+    );
+
+    // a CA certificate has neither subjectAltName nor extKeyUsage
+    let cert = CertificateRef::from_slice(include_bytes!("../../../certs/ocsp/ca.der")).unwrap();
+    let violations = check_tls_server_certificate(&cert);
+    assert!(violations.contains(&BRViolation::MissingSubjectAltName));
+    assert!(violations.contains(&BRViolation::IsCA));
+    assert!(violations.contains(&BRViolation::MissingExtendedKeyUsage));
+}
+
+#[test]
+fn test_malformed_validity() {
+    // notBefore without the 'Z', the UTCTime keeps its length
+    let mut der = include_bytes!("../../../certs/ocsp/leaf.der").to_vec();
+    let pos = der.windows(13).position(|w| w == b"261016141817Z").unwrap();
+    der[pos + 12] = b'0';
+    let cert = CertificateRef::from_slice(&der).unwrap();
+
+    assert!(check_tls_server_certificate(&cert).contains(&BRViolation::MalformedValidity));
+}
@@ -0,0 +1,282 @@
+//! A strict check of how extension values are wrapped, for catching misissued certificates that
+//! lenient parsers accept.
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::oids;
+use crate::parse::{
+    der::{get_tlv, DataType, ObjectIdentifierRef},
+    parsing::CertificateRef,
+};
+
+/// nesting deeper than this is malformed, like in [`DerNode`](crate::parse::der::DerNode)
+const MAX_DEPTH: usize = 64;
+
+/// a problem with the OCTET STRING holding the value of an extension. `oid` is the id of the
+/// extension in dotted form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtensionEncodingViolation {
+    /// the value is empty or the element in it is cut off
+    Truncated { oid: String },
+    /// there is more than one element in the value
+    TrailingData { oid: String },
+    /// an element nested in the value is cut off, has a non-minimal or indefinite length or is
+    /// nested too deep
+    Malformed { oid: String },
+    /// the element doesn't have the tag the extension is defined with, e.g. an INTEGER for
+    /// keyUsage
+    UnexpectedTag {
+        oid: String,
+        expected: u8,
+        found: u8,
+    },
+    /// the value is wrapped in a second OCTET STRING
+    DoubleWrapped { oid: String },
+    /// the list of extensions itself can't be parsed
+    MalformedExtensions,
+}
+
+impl Display for ExtensionEncodingViolation {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ExtensionEncodingViolation::Truncated { oid } => {
+                write!(f, "value of extension {} is empty or truncated", oid)
+            }
+            ExtensionEncodingViolation::TrailingData { oid } => {
+                write!(f, "value of extension {} has trailing data", oid)
+            }
+            ExtensionEncodingViolation::Malformed { oid } => {
+                write!(f, "value of extension {} is not valid DER", oid)
+            }
+            ExtensionEncodingViolation::UnexpectedTag {
+                oid,
+                expected,
+                found,
+            } => write!(
+                f,
+                "value of extension {} has tag 0x{:02x} instead of 0x{:02x}",
+                oid, found, expected
+            ),
+            ExtensionEncodingViolation::DoubleWrapped { oid } => {
+                write!(f, "value of extension {} is wrapped twice", oid)
+            }
+            ExtensionEncodingViolation::MalformedExtensions => {
+                write!(f, "extensions can't be parsed")
+            }
+        }
+    }
+}
+
+/// checks that the value of every extension of `cert` is exactly one well-formed DER element
+/// and, for the extensions this crate knows, that it is of the type the extension is defined
+/// with. returns all violations found, in the order of the extensions.
+pub fn check_extension_encoding(cert: &CertificateRef) -> Vec<ExtensionEncodingViolation> {
+    let mut violations = Vec::new();
+    let extensions = match cert.tbs_cert().extensions() {
+        Some(extensions) => extensions,
+        None => return violations,
+    };
+
+    for ext in extensions {
+        match ext {
+            Ok(ext) => violations.extend(check_value(*ext.extension_id(), ext.value().as_bytes())),
+            Err(_) => {
+                violations.push(ExtensionEncodingViolation::MalformedExtensions);
+                break;
+            }
+        }
+    }
+
+    violations
+}
+
+/// the tag of the value of the extension `id`, None for extensions this crate doesn't know
+fn expected_tag(id: ObjectIdentifierRef) -> Option<u8> {
+    let sequences = [
+        oids::SUBJECT_ALT_NAME,
+        oids::ISSUER_ALT_NAME,
+        oids::BASIC_CONSTRAINTS,
+        oids::NAME_CONSTRAINTS,
+        oids::CRL_DISTRIBUTION_POINTS,
+        oids::CERTIFICATE_POLICIES,
+        oids::POLICY_MAPPINGS,
+        oids::AUTHORITY_KEY_IDENTIFIER,
+        oids::POLICY_CONSTRAINTS,
+        oids::EXT_KEY_USAGE,
+        oids::FRESHEST_CRL,
+        oids::AUTHORITY_INFO_ACCESS,
+        oids::SUBJECT_INFO_ACCESS,
+    ];
+    if sequences.iter().any(|oid| oids::is(&id, *oid)) {
+        Some(DataType::Sequence.constructed())
+    } else if oids::is(&id, oids::KEY_USAGE) {
+        Some(DataType::BitString.into())
+    } else if oids::is(&id, oids::SUBJECT_KEY_IDENTIFIER) || oids::is(&id, oids::CT_PRECERT_SCTS) {
+        Some(DataType::OctetString.into())
+    } else if oids::is(&id, oids::INHIBIT_ANY_POLICY) {
+        Some(DataType::Integer.into())
+    } else if oids::is(&id, oids::PKIX_OCSP_NOCHECK) || oids::is(&id, oids::CT_PRECERT_POISON) {
+        Some(DataType::Null.into())
+    } else {
+        None
+    }
+}
+
+fn check_value(id: ObjectIdentifierRef, value: &[u8]) -> Option<ExtensionEncodingViolation> {
+    let oid = id.to_string();
+    let (rest, tag, content) = match get_tlv(value) {
+        Ok(tlv) => tlv,
+        Err(_) => return Some(ExtensionEncodingViolation::Truncated { oid }),
+    };
+    if !rest.is_empty() {
+        return Some(ExtensionEncodingViolation::TrailingData { oid });
+    }
+    if !is_well_formed(value, 0) {
+        return Some(ExtensionEncodingViolation::Malformed { oid });
+    }
+
+    match expected_tag(id) {
+        Some(expected) if tag != expected => {
+            // an OCTET STRING with exactly the expected element in it
+            let octet_string: u8 = DataType::OctetString.into();
+            let wrapped = match get_tlv(content) {
+                Ok((rest, inner, _)) => rest.is_empty() && inner == expected,
+                Err(_) => false,
+            };
+            if tag == octet_string && wrapped {
+                Some(ExtensionEncodingViolation::DoubleWrapped { oid })
+            } else {
+                Some(ExtensionEncodingViolation::UnexpectedTag {
+                    oid,
+                    expected,
+                    found: tag,
+                })
+            }
+        }
+        _ => None,
+    }
+}
+
+/// whether all elements in `data` and the ones nested in them have a definite length in the
+/// shortest form and fit into their parent
+fn is_well_formed(mut data: &[u8], depth: usize) -> bool {
+    if depth > MAX_DEPTH {
+        return false;
+    }
+    while !data.is_empty() {
+        let (rest, tag, content) = match get_tlv(data) {
+            Ok(tlv) => tlv,
+            Err(_) => return false,
+        };
+        let header_len = data.len() - rest.len() - content.len();
+        // 0x80 is the indefinite length of BER
+        if data[1] == 0x80 || header_len != minimal_header_len(content.len()) {
+            return false;
+        }
+        if tag & 0x20 == 0x20 && !is_well_formed(content, depth + 1) {
+            return false;
+        }
+        data = rest;
+    }
+
+    true
+}
+
+/// the identifier octet and the length octets of an element with `len` content octets
+fn minimal_header_len(len: usize) -> usize {
+    if len < 0x80 {
+        2
+    } else {
+        let octets = (usize::BITS - len.leading_zeros()).div_ceil(8);
+        2 + octets as usize
+    }
+}
+
+#[test]
+fn test_conforming_extensions() {
+    for data in [
+        &include_bytes!("../../../certs/test.crt")[..],
+        &include_bytes!("../../../certs/lint/server.der")[..],
+        &include_bytes!("../../../certs/ocsp/responder-nocheck.der")[..],
+        &include_bytes!("../../../certs/ct/precert.der")[..],
+        &include_bytes!("../../../certs/ocsp/critical-ext.der")[..],
+    ] {
+        let cert = CertificateRef::from_slice(data).unwrap();
+        assert_eq!(check_extension_encoding(&cert), vec![]);
+    }
+}
+
+#[test]
+fn test_check_value() {
+    let oid = |id: ObjectIdentifierRef| id.to_string();
+
+    // CA:FALSE
+    assert_eq!(check_value(oids::BASIC_CONSTRAINTS, &[0x30, 0x00]), None);
+    assert_eq!(
+        check_value(oids::BASIC_CONSTRAINTS, &[0x04, 0x02, 0x30, 0x00]),
+        Some(ExtensionEncodingViolation::DoubleWrapped {
+            oid: oid(oids::BASIC_CONSTRAINTS)
+        })
+    );
+    assert_eq!(
+        check_value(oids::BASIC_CONSTRAINTS, &[0x30, 0x03, 0x01, 0x01]),
+        Some(ExtensionEncodingViolation::Truncated {
+            oid: oid(oids::BASIC_CONSTRAINTS)
+        })
+    );
+    assert_eq!(
+        check_value(oids::BASIC_CONSTRAINTS, &[]),
+        Some(ExtensionEncodingViolation::Truncated {
+            oid: oid(oids::BASIC_CONSTRAINTS)
+        })
+    );
+    assert_eq!(
+        check_value(oids::BASIC_CONSTRAINTS, &[0x30, 0x00, 0x30, 0x00]),
+        Some(ExtensionEncodingViolation::TrailingData {
+            oid: oid(oids::BASIC_CONSTRAINTS)
+        })
+    );
+    // the BOOLEAN inside claims 2 bytes, only one is left in the SEQUENCE
+    assert_eq!(
+        check_value(oids::BASIC_CONSTRAINTS, &[0x30, 0x03, 0x01, 0x02, 0xff]),
+        Some(ExtensionEncodingViolation::Malformed {
+            oid: oid(oids::BASIC_CONSTRAINTS)
+        })
+    );
+    // the length 0 in the long form
+    assert_eq!(
+        check_value(oids::BASIC_CONSTRAINTS, &[0x30, 0x81, 0x00]),
+        Some(ExtensionEncodingViolation::Malformed {
+            oid: oid(oids::BASIC_CONSTRAINTS)
+        })
+    );
+    // digitalSignature as an INTEGER
+    assert_eq!(
+        check_value(oids::KEY_USAGE, &[0x02, 0x01, 0x80]),
+        Some(ExtensionEncodingViolation::UnexpectedTag {
+            oid: oid(oids::KEY_USAGE),
+            expected: 0x03,
+            found: 0x02
+        })
+    );
+    assert_eq!(check_value(oids::PKIX_OCSP_NOCHECK, &[0x05, 0x00]), None);
+
+    // only the structure of unknown extensions is checked
+    let unknown = ObjectIdentifierRef::new(&[0x2a, 0x03]);
+    assert_eq!(check_value(unknown, &[0x02, 0x01, 0x80]), None);
+    assert_eq!(
+        check_value(unknown, &[0x02, 0x01]),
+        Some(ExtensionEncodingViolation::Truncated {
+            oid: "1.2.3".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_minimal_header_len() {
+    assert_eq!(minimal_header_len(0), 2);
+    assert_eq!(minimal_header_len(0x7f), 2);
+    assert_eq!(minimal_header_len(0x80), 3);
+    assert_eq!(minimal_header_len(0xff), 3);
+    assert_eq!(minimal_header_len(0x100), 4);
+}
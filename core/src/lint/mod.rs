@@ -0,0 +1,10 @@
+//! Conformance checks of certificates against issuance profiles.
+//!
+//! A lint never fails: problems with the certificate, including extensions that can't be
+//! parsed, are reported as violations of the profile.
+
+mod cabf;
+mod extension_encoding;
+
+pub use cabf::{check_tls_server_certificate, BRViolation};
+pub use extension_encoding::{check_extension_encoding, ExtensionEncodingViolation};
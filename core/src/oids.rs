@@ -0,0 +1,268 @@
+//! Well-known object identifiers used in certificates.
+//!
+//! The constants are typed as [`ObjectIdentifierRef`] so they can be compared directly against
+//! values coming out of the parser, e.g. `oids::is(ext.extension_id(), oids::SUBJECT_ALT_NAME)`.
+
+use crate::parse::der::ObjectIdentifierRef;
+use x509_macros::oid_str_to_bytes;
+
+// certificate extensions (RFC 5280 section 4.2)
+pub const SUBJECT_KEY_IDENTIFIER: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.14"));
+pub const KEY_USAGE: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.15"));
+pub const SUBJECT_ALT_NAME: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.17"));
+pub const ISSUER_ALT_NAME: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.18"));
+pub const BASIC_CONSTRAINTS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.19"));
+pub const CRL_NUMBER: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.20"));
+pub const CRL_REASON: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.21"));
+pub const INVALIDITY_DATE: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.24"));
+pub const DELTA_CRL_INDICATOR: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.27"));
+pub const ISSUING_DISTRIBUTION_POINT: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.28"));
+pub const NAME_CONSTRAINTS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.30"));
+pub const CRL_DISTRIBUTION_POINTS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.31"));
+pub const CERTIFICATE_POLICIES: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.32"));
+pub const POLICY_MAPPINGS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.33"));
+pub const AUTHORITY_KEY_IDENTIFIER: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.35"));
+pub const POLICY_CONSTRAINTS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.36"));
+pub const EXT_KEY_USAGE: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.37"));
+pub const FRESHEST_CRL: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.46"));
+pub const INHIBIT_ANY_POLICY: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.54"));
+pub const AUTHORITY_INFO_ACCESS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.1.1"));
+pub const SUBJECT_INFO_ACCESS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.1.11"));
+
+// name attribute types (RFC 5280 appendix A.1, RFC 4519)
+pub const COMMON_NAME: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.3"));
+pub const SURNAME: ObjectIdentifierRef<'static> = ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.4"));
+pub const SERIAL_NUMBER: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.5"));
+pub const COUNTRY_NAME: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.6"));
+pub const LOCALITY_NAME: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.7"));
+pub const STATE_OR_PROVINCE_NAME: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.8"));
+pub const STREET_ADDRESS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.9"));
+pub const ORGANIZATION_NAME: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.10"));
+pub const ORGANIZATIONAL_UNIT_NAME: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.11"));
+pub const TITLE: ObjectIdentifierRef<'static> = ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.12"));
+pub const GIVEN_NAME: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.42"));
+pub const INITIALS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.43"));
+pub const GENERATION_QUALIFIER: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.44"));
+pub const DN_QUALIFIER: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.46"));
+pub const PSEUDONYM: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.4.65"));
+pub const DOMAIN_COMPONENT: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("0.9.2342.19200300.100.1.25"));
+pub const USER_ID: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("0.9.2342.19200300.100.1.1"));
+pub const EMAIL_ADDRESS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.9.1"));
+
+// PKCS#9 attributes of certification requests (RFC 2985)
+pub const CHALLENGE_PASSWORD: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.9.7"));
+pub const EXTENSION_REQUEST: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.9.14"));
+
+// public key and signature algorithms (RFC 3279, RFC 4055, RFC 5758)
+pub const RSA_ENCRYPTION: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.1.1"));
+pub const MD5_WITH_RSA_ENCRYPTION: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.1.4"));
+pub const SHA1_WITH_RSA_ENCRYPTION: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.1.5"));
+pub const SHA256_WITH_RSA_ENCRYPTION: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.1.11"));
+pub const SHA384_WITH_RSA_ENCRYPTION: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.1.12"));
+pub const SHA512_WITH_RSA_ENCRYPTION: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.1.13"));
+pub const RSASSA_PSS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.1.10"));
+pub const MGF1: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.1.8"));
+pub const EC_PUBLIC_KEY: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.10045.2.1"));
+pub const ECDSA_WITH_SHA1: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.10045.4.1"));
+pub const ECDSA_WITH_SHA256: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.10045.4.3.2"));
+pub const ECDSA_WITH_SHA384: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.10045.4.3.3"));
+pub const ECDSA_WITH_SHA512: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.10045.4.3.4"));
+/// id-Ed25519 (RFC 8410), both the public key algorithm and the signature algorithm
+pub const ED25519: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.101.112"));
+
+// hash algorithms (RFC 3279, RFC 5754)
+pub const SHA1: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.14.3.2.26"));
+pub const SHA256: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.16.840.1.101.3.4.2.1"));
+pub const SHA384: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.16.840.1.101.3.4.2.2"));
+pub const SHA512: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.16.840.1.101.3.4.2.3"));
+
+// named elliptic curves (RFC 5480)
+pub const SECP256R1: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.10045.3.1.7"));
+pub const SECP384R1: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.132.0.34"));
+pub const SECP521R1: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.132.0.35"));
+
+// extended key usage purposes (RFC 5280 section 4.2.1.12)
+pub const ANY_EXTENDED_KEY_USAGE: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.37.0"));
+pub const KP_SERVER_AUTH: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.3.1"));
+pub const KP_CLIENT_AUTH: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.3.2"));
+pub const KP_CODE_SIGNING: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.3.3"));
+pub const KP_EMAIL_PROTECTION: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.3.4"));
+pub const KP_TIME_STAMPING: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.3.8"));
+pub const KP_OCSP_SIGNING: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.3.9"));
+
+// certificate policies (RFC 5280 section 4.2.1.4) and the CA/Browser Forum reserved ids
+pub const ANY_POLICY: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.5.29.32.0"));
+pub const CABF_EXTENDED_VALIDATION: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.23.140.1.1"));
+pub const CABF_DOMAIN_VALIDATED: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.23.140.1.2.1"));
+pub const CABF_ORGANIZATION_VALIDATED: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.23.140.1.2.2"));
+pub const CABF_INDIVIDUAL_VALIDATED: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.23.140.1.2.3"));
+
+// certificate transparency (RFC 6962)
+pub const CT_PRECERT_SCTS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.4.1.11129.2.4.2"));
+pub const CT_PRECERT_POISON: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.4.1.11129.2.4.3"));
+
+// CMS content types (RFC 5652)
+pub const PKCS7_DATA: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.7.1"));
+pub const PKCS7_SIGNED_DATA: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.7.2"));
+pub const PKCS7_ENCRYPTED_DATA: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.7.6"));
+
+// password based encryption of PKCS#8 keys (RFC 8018) and the AES-CBC ciphers it is used with
+pub const PBES2: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.5.13"));
+pub const PBKDF2: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.5.12"));
+pub const HMAC_WITH_SHA1: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.2.7"));
+pub const HMAC_WITH_SHA256: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.2.9"));
+pub const HMAC_WITH_SHA384: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.2.10"));
+pub const HMAC_WITH_SHA512: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.2.11"));
+pub const AES128_CBC: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.16.840.1.101.3.4.1.2"));
+pub const AES192_CBC: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.16.840.1.101.3.4.1.22"));
+pub const AES256_CBC: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("2.16.840.1.101.3.4.1.42"));
+
+// the legacy password based encryption of PKCS#12 (RFC 7292 appendix C)
+pub const PBE_WITH_SHA_AND_3KEY_TRIPLE_DES_CBC: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.12.1.3"));
+pub const PBE_WITH_SHA_AND_128BIT_RC2_CBC: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.12.1.5"));
+pub const PBE_WITH_SHA_AND_40BIT_RC2_CBC: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.12.1.6"));
+
+// PKCS#12 bag types and bag attributes (RFC 7292)
+pub const PKCS12_KEY_BAG: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.12.10.1.1"));
+pub const PKCS12_SHROUDED_KEY_BAG: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.12.10.1.2"));
+pub const PKCS12_CERT_BAG: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.12.10.1.3"));
+pub const PKCS9_X509_CERTIFICATE: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.9.22.1"));
+pub const PKCS9_FRIENDLY_NAME: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.9.20"));
+pub const PKCS9_LOCAL_KEY_ID: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.2.840.113549.1.9.21"));
+
+// access methods for authority/subject info access (RFC 5280 section 4.2.2)
+pub const AD_OCSP: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.48.1"));
+pub const AD_CA_ISSUERS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.48.2"));
+
+// OCSP response types and extensions (RFC 6960)
+pub const PKIX_OCSP_BASIC: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.48.1.1"));
+pub const PKIX_OCSP_NONCE: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.48.1.2"));
+/// id-pkix-ocsp-nocheck, a certificate extension marking a delegated responder whose own
+/// revocation status is not checked (RFC 6960 section 4.2.2.2.1)
+pub const PKIX_OCSP_NOCHECK: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.48.1.5"));
+
+// certificate policy qualifiers (RFC 5280 section 4.2.1.4)
+pub const QT_CPS: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.2.1"));
+pub const QT_UNOTICE: ObjectIdentifierRef<'static> =
+    ObjectIdentifierRef(oid_str_to_bytes!("1.3.6.1.5.5.7.2.2"));
+
+/// returns true if `oid` is the object identifier `expected`
+pub fn is(oid: &ObjectIdentifierRef, expected: ObjectIdentifierRef) -> bool {
+    oid.eq_oid(expected.as_bytes())
+}
+
+#[test]
+fn test_is() {
+    let data = include_bytes!("../../certs/test.crt");
+    let cert = crate::parse::parsing::CertificateRef::from_slice(data).unwrap();
+    let extensions = cert.tbs_cert().extensions().unwrap();
+    let san = extensions
+        .into_iter()
+        .map(Result::unwrap)
+        .find(|ext| is(ext.extension_id(), SUBJECT_ALT_NAME));
+    assert!(san.is_some());
+    assert!(!is(&SUBJECT_ALT_NAME, ISSUER_ALT_NAME));
+    assert_eq!(SUBJECT_ALT_NAME.to_string(), "2.5.29.17");
+    assert_eq!(DOMAIN_COMPONENT.to_string(), "0.9.2342.19200300.100.1.25");
+}
@@ -1,10 +1,14 @@
 use super::super::der::{
-    expect_object_identifier, expect_sequence, take_any, AnyRef, ObjectIdentifierRef,
+    expect_integer, expect_object_identifier, expect_sequence, take_any, try_get_explicit, AnyRef,
+    ExplicitTag, ObjectIdentifierRef,
 };
 use super::super::error::ParseError;
 use super::expect_empty;
+use crate::common::certificate::ParameterRule;
+use crate::oids;
+use std::convert::TryFrom;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct AlgorithmidentifierRef<'a> {
     algorithm_identifier: ObjectIdentifierRef<'a>,
     parameters: Option<AnyRef<'a>>,
@@ -18,6 +22,172 @@ impl<'a> AlgorithmidentifierRef<'a> {
     pub fn algorithm_identifier(&self) -> &ObjectIdentifierRef {
         &self.algorithm_identifier
     }
+
+    /// the parameters of a signature algorithm. RSASSA-PSS parameters are decoded, anything else
+    /// that is neither absent nor NULL is handed back as `Other` for the caller to interpret.
+    pub fn signature_parameters(&self) -> Result<SignatureParametersRef<'_>, ParseError> {
+        match &self.parameters {
+            None => Ok(SignatureParametersRef::Absent),
+            Some(AnyRef::Null) => Ok(SignatureParametersRef::Null),
+            Some(AnyRef::Sequence(params)) if self.algorithm_identifier == oids::RSASSA_PSS => Ok(
+                SignatureParametersRef::RsassaPss(RsassaPssParamsRef::from_content(params)?),
+            ),
+            Some(params) => Ok(SignatureParametersRef::Other(params)),
+        }
+    }
+
+    /// fails with [`ParseError::MalformedData`] if the [`ParameterRule`] of the algorithm
+    /// doesn't allow the parameters, e.g. NULL for ECDSA or explicit curve parameters. parsing
+    /// doesn't check this, certificates with such parameters are common enough.
+    pub fn check_parameters(&self) -> Result<(), ParseError> {
+        let conforms = match ParameterRule::for_algorithm(&self.algorithm_identifier) {
+            ParameterRule::Null => matches!(self.parameters, Some(AnyRef::Null)),
+            ParameterRule::Absent => self.parameters.is_none(),
+            ParameterRule::NullOrAbsent => {
+                matches!(self.parameters, None | Some(AnyRef::Null))
+            }
+            ParameterRule::NamedCurve => matches!(
+                self.public_key_parameters(),
+                PublicKeyParametersRef::NamedCurve(_)
+            ),
+            ParameterRule::RsassaPss => matches!(
+                self.signature_parameters()?,
+                SignatureParametersRef::Absent | SignatureParametersRef::RsassaPss(_)
+            ),
+            ParameterRule::Any => true,
+        };
+
+        if conforms {
+            Ok(())
+        } else {
+            Err(ParseError::MalformedData)
+        }
+    }
+
+    /// the parameters of the algorithm of a public key, as in a SubjectPublicKeyInfo. the named
+    /// curve of an EC key is decoded, anything else that is neither absent nor NULL is handed
+    /// back as `Other`, e.g. explicit curve parameters.
+    pub fn public_key_parameters(&self) -> PublicKeyParametersRef<'_> {
+        match &self.parameters {
+            None => PublicKeyParametersRef::Absent,
+            Some(AnyRef::Null) => PublicKeyParametersRef::Null,
+            Some(AnyRef::ObjectIdentifier(curve))
+                if self.algorithm_identifier == oids::EC_PUBLIC_KEY =>
+            {
+                PublicKeyParametersRef::NamedCurve(*curve)
+            }
+            Some(params) => PublicKeyParametersRef::Other(params),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PublicKeyParametersRef<'a> {
+    /// Ed25519 keys have no parameters
+    Absent,
+    /// RSA keys have NULL parameters
+    Null,
+    /// the curve of an EC key, e.g. `oids::SECP256R1`
+    NamedCurve(ObjectIdentifierRef<'a>),
+    Other(&'a AnyRef<'a>),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SignatureParametersRef<'a> {
+    /// the ECDSA algorithms omit the parameters
+    Absent,
+    /// the PKCS#1 v1.5 RSA algorithms have NULL parameters
+    Null,
+    RsassaPss(RsassaPssParamsRef<'a>),
+    Other(&'a AnyRef<'a>),
+}
+
+/// the RSASSA-PSS-params of RFC 4055, with the defaults of absent fields filled in
+#[derive(Debug, PartialEq)]
+pub struct RsassaPssParamsRef<'a> {
+    hash_algorithm: ObjectIdentifierRef<'a>,
+    mask_gen_algorithm: ObjectIdentifierRef<'a>,
+    mask_gen_hash_algorithm: ObjectIdentifierRef<'a>,
+    salt_length: u32,
+    trailer_field: u32,
+}
+
+impl<'a> RsassaPssParamsRef<'a> {
+    fn from_content(data: &'a [u8]) -> Result<Self, ParseError> {
+        // hashAlgorithm [0] HashAlgorithm DEFAULT sha1
+        let (data, hash_algorithm) = match try_get_explicit(data, ExplicitTag::try_new(0)?) {
+            Ok((data, hash_algorithm)) => (data, expect_algorithm_oid(hash_algorithm)?.1),
+            Err(_) => (data, oids::SHA1),
+        };
+        // maskGenAlgorithm [1] MaskGenAlgorithm DEFAULT mgf1SHA1
+        let (data, mask_gen_algorithm, mask_gen_hash_algorithm) =
+            match try_get_explicit(data, ExplicitTag::try_new(1)?) {
+                Ok((data, mask_gen)) => {
+                    let (mask_gen_params, mask_gen_algorithm) = expect_algorithm_oid(mask_gen)?;
+                    // the parameters of MGF1 are the AlgorithmIdentifier of its hash
+                    let (_, mask_gen_hash_algorithm) = expect_algorithm_oid(mask_gen_params)?;
+                    (data, mask_gen_algorithm, mask_gen_hash_algorithm)
+                }
+                Err(_) => (data, oids::MGF1, oids::SHA1),
+            };
+        // saltLength [2] INTEGER DEFAULT 20
+        let (data, salt_length) = match try_get_explicit(data, ExplicitTag::try_new(2)?) {
+            Ok((data, salt_length)) => (data, expect_u32(salt_length)?),
+            Err(_) => (data, 20),
+        };
+        // trailerField [3] TrailerField DEFAULT trailerFieldBC
+        let (data, trailer_field) = match try_get_explicit(data, ExplicitTag::try_new(3)?) {
+            Ok((data, trailer_field)) => (data, expect_u32(trailer_field)?),
+            Err(_) => (data, 1),
+        };
+        expect_empty(data)?;
+
+        Ok(Self {
+            hash_algorithm,
+            mask_gen_algorithm,
+            mask_gen_hash_algorithm,
+            salt_length,
+            trailer_field,
+        })
+    }
+
+    pub fn hash_algorithm(&self) -> &ObjectIdentifierRef<'a> {
+        &self.hash_algorithm
+    }
+
+    /// the mask generation function, MGF1 is the only one defined
+    pub fn mask_gen_algorithm(&self) -> &ObjectIdentifierRef<'a> {
+        &self.mask_gen_algorithm
+    }
+
+    /// the hash algorithm of the mask generation function
+    pub fn mask_gen_hash_algorithm(&self) -> &ObjectIdentifierRef<'a> {
+        &self.mask_gen_hash_algorithm
+    }
+
+    pub fn salt_length(&self) -> u32 {
+        self.salt_length
+    }
+
+    pub fn trailer_field(&self) -> u32 {
+        self.trailer_field
+    }
+}
+
+/// returns (parameters, algorithm) of the AlgorithmIdentifier in `data`
+fn expect_algorithm_oid(data: &[u8]) -> Result<(&[u8], ObjectIdentifierRef<'_>), ParseError> {
+    let (rest, inner) = expect_sequence(data)?;
+    expect_empty(rest)?;
+    let (parameters, algorithm) = expect_object_identifier(inner)?;
+
+    Ok((parameters, algorithm))
+}
+
+fn expect_u32(data: &[u8]) -> Result<u32, ParseError> {
+    let (rest, value) = expect_integer(data)?;
+    expect_empty(rest)?;
+
+    u32::try_from(value.to_i64()?).map_err(|_| ParseError::MalformedData)
 }
 
 pub fn parse_algorithm_identifier(
@@ -42,3 +212,134 @@ pub fn parse_algorithm_identifier(
         },
     ))
 }
+
+#[test]
+fn test_rsassa_pss_parameters() {
+    let data = include_bytes!("../../../../certs/identity/pss.der");
+    let cert = crate::parse::parsing::CertificateRef::from_slice(data).unwrap();
+    let algorithm = cert.signature_algorithm();
+    assert_eq!(algorithm.algorithm_identifier(), &oids::RSASSA_PSS);
+
+    let params = match algorithm.signature_parameters().unwrap() {
+        SignatureParametersRef::RsassaPss(params) => params,
+        p => panic!("unexpected parameters {:?}", p),
+    };
+    assert_eq!(params.hash_algorithm(), &oids::SHA256);
+    assert_eq!(params.mask_gen_algorithm(), &oids::MGF1);
+    assert_eq!(params.mask_gen_hash_algorithm(), &oids::SHA256);
+    assert_eq!(params.salt_length(), 32);
+    assert_eq!(params.trailer_field(), 1);
+}
+
+#[test]
+fn test_signature_parameters() {
+    let parse = |data| parse_algorithm_identifier(data).unwrap().1;
+
+    // sha256WithRSAEncryption
+    let algorithm = parse(&[
+        0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b, 0x05, 0x00,
+    ]);
+    assert_eq!(
+        algorithm.signature_parameters().unwrap(),
+        SignatureParametersRef::Null
+    );
+
+    // RSASSA-PSS with all fields defaulted
+    let algorithm = parse(&[
+        0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0a, 0x30, 0x00,
+    ]);
+    match algorithm.signature_parameters().unwrap() {
+        SignatureParametersRef::RsassaPss(params) => {
+            assert_eq!(params.hash_algorithm(), &oids::SHA1);
+            assert_eq!(params.mask_gen_hash_algorithm(), &oids::SHA1);
+            assert_eq!(params.salt_length(), 20);
+        }
+        p => panic!("unexpected parameters {:?}", p),
+    }
+
+    // ecdsa-with-SHA256 and an unknown algorithm with an OID as parameter
+    let algorithm = parse(&[
+        0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02,
+    ]);
+    assert_eq!(
+        algorithm.signature_parameters().unwrap(),
+        SignatureParametersRef::Absent
+    );
+    let algorithm = parse(&[0x30, 0x06, 0x06, 0x01, 0x2a, 0x06, 0x01, 0x2b]);
+    assert!(matches!(
+        algorithm.signature_parameters().unwrap(),
+        SignatureParametersRef::Other(AnyRef::ObjectIdentifier(_))
+    ));
+}
+
+#[test]
+fn test_public_key_parameters() {
+    use crate::parse::parsing::CertificateRef;
+
+    let ec = CertificateRef::from_slice(include_bytes!("../../../../certs/identity/ec-cert.der"))
+        .unwrap();
+    let spki = ec.tbs_cert().subject_public_key_info();
+    assert_eq!(
+        spki.algorithm_identifier().public_key_parameters(),
+        PublicKeyParametersRef::NamedCurve(oids::SECP256R1)
+    );
+    assert_eq!(spki.named_curve(), Some(oids::SECP256R1));
+
+    let rsa =
+        CertificateRef::from_slice(include_bytes!("../../../../certs/ocsp/leaf.der")).unwrap();
+    let spki = rsa.tbs_cert().subject_public_key_info();
+    assert_eq!(
+        spki.algorithm_identifier().public_key_parameters(),
+        PublicKeyParametersRef::Null
+    );
+    assert_eq!(spki.named_curve(), None);
+
+    let ed25519 = CertificateRef::from_slice(include_bytes!(
+        "../../../../certs/identity/ed25519-cert.der"
+    ))
+    .unwrap();
+    assert_eq!(
+        ed25519
+            .tbs_cert()
+            .subject_public_key_info()
+            .algorithm_identifier()
+            .public_key_parameters(),
+        PublicKeyParametersRef::Absent
+    );
+}
+
+#[test]
+fn test_check_parameters() {
+    let parse = |data| parse_algorithm_identifier(data).unwrap().1;
+
+    // sha256WithRSAEncryption with and without NULL
+    let algorithm = parse(&[
+        0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b, 0x05, 0x00,
+    ]);
+    assert!(algorithm.check_parameters().is_ok());
+    let algorithm = parse(&[
+        0x30, 0x0b, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b,
+    ]);
+    assert!(matches!(
+        algorithm.check_parameters(),
+        Err(ParseError::MalformedData)
+    ));
+
+    // ecdsa-with-SHA256 with NULL
+    let algorithm = parse(&[
+        0x30, 0x0c, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02, 0x05, 0x00,
+    ]);
+    assert!(algorithm.check_parameters().is_err());
+
+    let pss = crate::parse::parsing::CertificateRef::from_slice(include_bytes!(
+        "../../../../certs/identity/pss.der"
+    ))
+    .unwrap();
+    assert!(pss.signature_algorithm().check_parameters().is_ok());
+    let ec = crate::parse::parsing::CertificateRef::from_slice(include_bytes!(
+        "../../../../certs/identity/ec-cert.der"
+    ))
+    .unwrap();
+    let spki = ec.tbs_cert().subject_public_key_info();
+    assert!(spki.algorithm_identifier().check_parameters().is_ok());
+}
@@ -2,7 +2,7 @@ use std::fmt;
 
 use super::super::der::{
     expect_boolean, expect_object_identifier, expect_octet_string, expect_sequence,
-    try_get_explicit, ExplicitTag, ObjectIdentifierRef, OctetStringRef,
+    try_get_explicit, DerElementRef, DerNode, ExplicitTag, ObjectIdentifierRef, OctetStringRef,
 };
 use super::super::error::ParseError;
 use super::expect_empty;
@@ -12,7 +12,16 @@ pub struct ExtensionsRef<'a>(&'a [u8]);
 
 impl<'a> ExtensionsRef<'a> {
     pub(crate) fn parse(data: &'a [u8]) -> Result<(&'a [u8], Option<Self>), ParseError> {
-        match try_get_explicit(data, ExplicitTag::try_new(3)?) {
+        Self::parse_explicit(data, 3)
+    }
+
+    /// parses extensions wrapped in the explicit tag `tag`. certificates use [3], other
+    /// structures like OCSP responses use different tags.
+    pub(crate) fn parse_explicit(
+        data: &'a [u8],
+        tag: u8,
+    ) -> Result<(&'a [u8], Option<Self>), ParseError> {
+        match try_get_explicit(data, ExplicitTag::try_new(tag)?) {
             Ok((rest, inner)) => {
                 let (inner, extensions) = expect_sequence(inner)?;
                 expect_empty(inner)?;
@@ -21,6 +30,38 @@ impl<'a> ExtensionsRef<'a> {
             _ => Ok((data, None)),
         }
     }
+
+    /// parses extensions that are not wrapped in an explicit tag, e.g. crlEntryExtensions
+    pub(crate) fn parse_untagged(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, extensions) = expect_sequence(data)?;
+        Ok((rest, Self(extensions)))
+    }
+}
+
+impl<'a> ExtensionsRef<'a> {
+    /// returns the extension with the id `extension_id`, if present
+    pub fn find(
+        &self,
+        extension_id: ObjectIdentifierRef,
+    ) -> Result<Option<ExtensionRef<'a>>, ParseError> {
+        for ext in self.into_iter() {
+            let ext = ext?;
+            if ext.extension_id == extension_id {
+                return Ok(Some(ext));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// whether the id-pkix-ocsp-nocheck extension is present. its value has to be a NULL.
+    pub fn ocsp_no_check(&self) -> Result<bool, ParseError> {
+        match self.find(crate::oids::PKIX_OCSP_NOCHECK)? {
+            Some(ext) if ext.value().as_bytes() == [0x05, 0x00] => Ok(true),
+            Some(_) => Err(ParseError::MalformedData),
+            None => Ok(false),
+        }
+    }
 }
 
 impl<'a> fmt::Debug for ExtensionsRef<'a> {
@@ -84,6 +125,7 @@ impl<'a> Iterator for ExtensionsIter<'a> {
 
 #[derive(Debug)]
 pub struct ExtensionRef<'a> {
+    raw_data: &'a [u8],
     extension_id: ObjectIdentifierRef<'a>,
     critical: bool,
     value: OctetStringRef<'a>,
@@ -91,8 +133,9 @@ pub struct ExtensionRef<'a> {
 
 impl<'a> ExtensionRef<'a> {
     fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
-        let (rest, data) = expect_sequence(data)?;
-        let (data, extension_id) = expect_object_identifier(data)?;
+        let (rest, inner) = expect_sequence(data)?;
+        let raw_data = &data[..data.len() - rest.len()];
+        let (data, extension_id) = expect_object_identifier(inner)?;
         let (data, critical) = if let Ok((data, critical)) = expect_boolean(data) {
             (data, critical.to_bool())
         } else {
@@ -101,6 +144,7 @@ impl<'a> ExtensionRef<'a> {
         let (data, value) = expect_octet_string(data)?;
         expect_empty(data)?;
         let extension = Self {
+            raw_data,
             extension_id,
             critical,
             value,
@@ -119,6 +163,24 @@ impl<'a> ExtensionRef<'a> {
     pub fn value(&self) -> &OctetStringRef<'a> {
         &self.value
     }
+
+    /// the complete DER encoding of the Extension SEQUENCE with the OID, the critical flag and
+    /// the extnValue octet string, exactly as it is in the certificate
+    pub fn raw_der(&self) -> &'a [u8] {
+        self.raw_data
+    }
+
+    /// decodes the content of the extnValue octet string as a generic DER tree, for extensions
+    /// without a typed parser
+    pub fn value_as_der_tree(&self) -> Result<DerNode<'a>, ParseError> {
+        DerNode::from_slice(self.value.as_bytes())
+    }
+
+    /// like `value_as_der_tree`, but decodes the children only while they are iterated and
+    /// doesn't allocate, for bulk processing of many certificates
+    pub fn value_as_der_element(&self) -> Result<DerElementRef<'a>, ParseError> {
+        DerElementRef::from_slice(self.value.as_bytes())
+    }
 }
 
 #[test]
@@ -132,3 +194,57 @@ fn test_extensions() {
     let extensions: Vec<ExtensionRef> = extensions.into_iter().map(Result::unwrap).collect();
     assert_eq!(9, extensions.len());
 }
+
+#[test]
+fn test_raw_der() {
+    let data = include_bytes!("../../../../certs/test.crt");
+    let cert = crate::parse::parsing::CertificateRef::from_slice(data).unwrap();
+    let extensions = cert.tbs_cert().extensions().unwrap();
+    let basic_constraints = extensions
+        .find(crate::oids::BASIC_CONSTRAINTS)
+        .unwrap()
+        .unwrap();
+
+    // critical, CA:FALSE
+    assert_eq!(
+        basic_constraints.raw_der(),
+        [0x30, 0x0c, 0x06, 0x03, 0x55, 0x1d, 0x13, 0x01, 0x01, 0xff, 0x04, 0x02, 0x30, 0x00]
+    );
+}
+
+#[test]
+fn test_value_as_der_tree() {
+    let data = include_bytes!("../../../../certs/test.crt");
+    let cert = crate::parse::parsing::CertificateRef::from_slice(data).unwrap();
+    let extensions = cert.tbs_cert().extensions().unwrap();
+    let basic_constraints = extensions
+        .find(crate::oids::BASIC_CONSTRAINTS)
+        .unwrap()
+        .unwrap();
+
+    let tree = basic_constraints.value_as_der_tree().unwrap();
+    assert_eq!(tree.to_string(), "SEQUENCE\n");
+}
+
+#[test]
+fn test_value_as_der_element() {
+    let data = include_bytes!("../../../../certs/test.crt");
+    let cert = crate::parse::parsing::CertificateRef::from_slice(data).unwrap();
+    let extensions = cert.tbs_cert().extensions().unwrap();
+    let key_usage = extensions.find(crate::oids::KEY_USAGE).unwrap().unwrap();
+
+    let element = key_usage.value_as_der_element().unwrap();
+    assert_eq!(element.tag(), 0x03);
+    assert_eq!(element.value(), &[0x05, 0xa0]);
+}
+
+#[test]
+fn test_ocsp_no_check() {
+    let data = include_bytes!("../../../../certs/ocsp/responder-nocheck.der");
+    let cert = crate::parse::parsing::CertificateRef::from_slice(data).unwrap();
+    assert!(cert.tbs_cert().extensions().unwrap().ocsp_no_check().unwrap());
+
+    let data = include_bytes!("../../../../certs/ocsp/responder.der");
+    let cert = crate::parse::parsing::CertificateRef::from_slice(data).unwrap();
+    assert!(!cert.tbs_cert().extensions().unwrap().ocsp_no_check().unwrap());
+}
@@ -5,10 +5,14 @@ mod subject_public_key_info;
 mod validity;
 mod version;
 
-pub use algorithm_identifier::{parse_algorithm_identifier, AlgorithmidentifierRef};
+pub use algorithm_identifier::{
+    parse_algorithm_identifier, AlgorithmidentifierRef, PublicKeyParametersRef, RsassaPssParamsRef,
+    SignatureParametersRef,
+};
 pub use extensions::{ExtensionRef, ExtensionsRef};
-pub use name::NameRef;
+pub use name::{NameRef, RelativeDistinguishedNameRef};
 pub use subject_public_key_info::SubjectPublicKeyInfoRef;
+pub(crate) use validity::TimeRef;
 pub use validity::ValidityRef;
 pub use version::{parse_version};
 
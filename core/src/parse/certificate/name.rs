@@ -1,13 +1,15 @@
+use std::borrow::Cow;
 use std::fmt;
 
 use super::super::{
     der::{
-        expect_object_identifier, expect_sequence, expect_set, take_any, AnyRef,
-        ObjectIdentifierRef,
+        expect_object_identifier, expect_sequence, expect_set, get_tlv, take_any, AnyRef,
+        BMPStringRef, DataType, ObjectIdentifierRef,
     },
     error::ParseError,
 };
 use super::expect_empty;
+use crate::generate::der::encode_tlv;
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum NameRef<'a> {
@@ -26,6 +28,111 @@ impl<'a> NameRef<'a> {
             &NameRef::DistinguishedNameRef(dn) => dn.data,
         }
     }
+
+    /// the complete DER encoding of the name, including the sequence header
+    pub fn raw_data(&self) -> &'a [u8] {
+        match &self {
+            &NameRef::DistinguishedNameRef(dn) => dn.raw_data,
+        }
+    }
+
+    /// the hash OpenSSL uses to look up certificates by name, e.g. the file names `<hash>.0` of
+    /// a CApath directory made by `c_rehash`, formatted with `{:08x}`. the same as
+    /// `openssl x509 -subject_hash` for the subject of a certificate.
+    ///
+    /// it is computed over a canonical form of the name: string values are converted to
+    /// UTF8String, ASCII letters are lowercased, leading and trailing whitespace is removed and
+    /// runs of whitespace are collapsed to one space. the first 4 bytes of the SHA-1 of that form
+    /// are read as a little endian number.
+    pub fn openssl_hash(&self) -> Result<u32, ParseError> {
+        let mut canonical = Vec::new();
+        let (_, mut rdns) = expect_sequence(self.raw_data())?;
+        while !rdns.is_empty() {
+            let (rest, rdn) = expect_set(rdns)?;
+            canonical.extend_from_slice(&canonical_rdn(rdn)?);
+            rdns = rest;
+        }
+
+        let digest = ring::digest::digest(&ring::digest::SHA1_FOR_LEGACY_USE_ONLY, &canonical);
+        let d = digest.as_ref();
+        Ok(u32::from_le_bytes([d[0], d[1], d[2], d[3]]))
+    }
+}
+
+/// the SET of the canonical attributes of an RDN, sorted again since canonicalization can
+/// change their order
+fn canonical_rdn(mut data: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let mut attributes = Vec::new();
+    while !data.is_empty() {
+        let (rest, attribute) = expect_sequence(data)?;
+        let (value, _) = expect_object_identifier(attribute)?;
+        let oid = &attribute[..attribute.len() - value.len()];
+        let (left, tag, content) = get_tlv(value)?;
+        expect_empty(left)?;
+
+        let mut encoded = oid.to_vec();
+        match canonical_string(tag, content)? {
+            Some(s) => encoded.extend_from_slice(&encode_tlv(DataType::Utf8String.into(), &s)),
+            None => encoded.extend_from_slice(value),
+        }
+        attributes.push(encode_tlv(DataType::Sequence.constructed(), &encoded));
+        data = rest;
+    }
+    attributes.sort();
+
+    Ok(encode_tlv(
+        DataType::Set.constructed(),
+        &attributes.concat(),
+    ))
+}
+
+/// the canonical form of a string value as UTF-8, None for values that are not strings and are
+/// kept as they are. the single byte string types are read as Latin-1 like OpenSSL does.
+fn canonical_string(tag: u8, content: &[u8]) -> Result<Option<Vec<u8>>, ParseError> {
+    let text: String = match tag {
+        0x0c => std::str::from_utf8(content)
+            .map_err(|_| ParseError::StringEncoding)?
+            .to_string(),
+        // PrintableString, T61String, IA5String, VisibleString
+        0x13 | 0x14 | 0x16 | 0x1a => content.iter().map(|b| *b as char).collect(),
+        0x1e => BMPStringRef(content).to_str()?.into_owned(),
+        // UniversalString, UCS-4
+        0x1c if content.len().is_multiple_of(4) => content
+            .chunks(4)
+            .map(|c| char::from_u32(u32::from_be_bytes([c[0], c[1], c[2], c[3]])))
+            .collect::<Option<_>>()
+            .ok_or(ParseError::StringEncoding)?,
+        0x1c => return Err(ParseError::StringEncoding),
+        _ => return Ok(None),
+    };
+
+    // isspace() of the C locale, which includes the vertical tab
+    let is_space = |b: &u8| matches!(b, b' ' | b'\t' | b'\n' | 0x0b | 0x0c | b'\r');
+    let bytes = text.as_bytes();
+    let start = bytes
+        .iter()
+        .position(|b| !is_space(b))
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|b| !is_space(b))
+        .map_or(start, |i| i + 1);
+
+    let mut canonical = Vec::with_capacity(end - start);
+    let mut in_space = false;
+    for b in &bytes[start..end] {
+        if is_space(b) {
+            if !in_space {
+                canonical.push(b' ');
+            }
+            in_space = true;
+        } else {
+            canonical.push(b.to_ascii_lowercase());
+            in_space = false;
+        }
+    }
+
+    Ok(Some(canonical))
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -50,6 +157,18 @@ impl<'a> AttributeTypeAndValueRef<'a> {
     pub fn attribute_type(&self) -> &ObjectIdentifierRef<'a> {
         &self.attribute_type
     }
+
+    /// the attribute value. values of types the parser doesn't know are returned as
+    /// `AnyRef::Unknown` so no part of a name gets lost.
+    pub fn value(&self) -> &AnyRef<'a> {
+        &self.value
+    }
+
+    /// the value as text, whichever of the DirectoryString types (or IA5String, which is
+    /// used for emailAddress and domainComponent) it is encoded as
+    pub fn value_as_str(&self) -> Result<Cow<'a, str>, ParseError> {
+        self.value.to_str()
+    }
 }
 
 pub struct RelativeDistinguishedNameRef<'a> {
@@ -57,7 +176,7 @@ pub struct RelativeDistinguishedNameRef<'a> {
 }
 
 impl<'a> RelativeDistinguishedNameRef<'a> {
-    pub fn iter(&self) -> RDNIter {
+    pub fn iter(&self) -> RDNIter<'a> {
         RDNIter {
             pos: self.data,
             failure: false,
@@ -115,10 +234,16 @@ impl<'a> RelativeDistinguishedNameRef<'a> {
 
         Ok((rest, rdns))
     }
+
+    /// wraps the content of an implicitly tagged SET, e.g. nameRelativeToCRLIssuer
+    pub(crate) fn from_content(data: &'a [u8]) -> Self {
+        RelativeDistinguishedNameRef { data }
+    }
 }
 
 #[derive(PartialEq, Eq)]
 pub struct DistinguishedNameRef<'a> {
+    raw_data: &'a [u8],
     data: &'a [u8],
 }
 
@@ -138,11 +263,18 @@ impl<'a> fmt::Debug for DistinguishedNameRef<'a> {
 
 impl<'a> DistinguishedNameRef<'a> {
     fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
-        let (rest, data) = expect_sequence(data)?;
-        Ok((rest, Self { data }))
+        let (rest, inner) = expect_sequence(data)?;
+        let raw_data = &data[..data.len() - rest.len()];
+        Ok((
+            rest,
+            Self {
+                raw_data,
+                data: inner,
+            },
+        ))
     }
 
-    pub fn iter(&self) -> DNIter {
+    pub fn iter(&self) -> DNIter<'a> {
         DNIter {
             pos: self.data,
             failure: false,
@@ -179,3 +311,76 @@ impl<'a> Iterator for DNIter<'a> {
         }
     }
 }
+
+#[test]
+fn test_unknown_attributes_are_kept() {
+    // a name with a single emailAddress attribute, which is an IA5String
+    let data = [
+        0x30, 0x14, 0x31, 0x12, 0x30, 0x10, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01,
+        0x09, 0x01, 0x16, 0x03, 0x61, 0x40, 0x62,
+    ];
+    let (rest, name) = NameRef::parse(&data).unwrap();
+    assert!(rest.is_empty());
+
+    let NameRef::DistinguishedNameRef(dn) = name;
+    let attributes: Vec<_> = dn
+        .iter()
+        .flat_map(|rdn| rdn.unwrap().iter().collect::<Vec<_>>())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(attributes.len(), 1);
+    assert!(crate::oids::is(
+        attributes[0].attribute_type(),
+        crate::oids::EMAIL_ADDRESS
+    ));
+    assert_eq!(attributes[0].value_as_str().unwrap(), "a@b");
+}
+
+#[test]
+fn test_openssl_hash() {
+    use crate::parse::parsing::CertificateRef;
+
+    // openssl x509 -subject_hash
+    let hash = |data| {
+        let cert = CertificateRef::from_slice(data).unwrap();
+        let hash = cert.tbs_cert().subject().openssl_hash().unwrap();
+        format!("{:08x}", hash)
+    };
+    assert_eq!(
+        hash(include_bytes!("../../../../certs/ocsp/ca.der")),
+        "aabc6144"
+    );
+    assert_eq!(
+        hash(include_bytes!("../../../../certs/test.crt")),
+        "d1c233e9"
+    );
+    // C=DE, O="  Example   GmbH ", OU=Ünit<TAB>Two + CN=Foo.EXAMPLE.com
+    assert_eq!(
+        hash(include_bytes!("../../../../certs/names/canon.der")),
+        "f9b46127"
+    );
+}
+
+#[test]
+fn test_canonical_string() {
+    let canonical = |tag, content| canonical_string(tag, content).unwrap();
+
+    assert_eq!(
+        canonical(0x13, b"  Foo \t\x0b Bar "),
+        Some(b"foo bar".to_vec())
+    );
+    assert_eq!(
+        canonical(0x0c, "ÄB".as_bytes()),
+        Some("Äb".as_bytes().to_vec())
+    );
+    // Latin-1 and BMPString both become UTF-8
+    assert_eq!(canonical(0x14, b"\xc4"), Some("Ä".as_bytes().to_vec()));
+    assert_eq!(
+        canonical(0x1e, b"\x00\xc4\x00B"),
+        Some("Äb".as_bytes().to_vec())
+    );
+    assert_eq!(canonical(0x1c, b"\x00\x00\x00A"), Some(b"a".to_vec()));
+    assert_eq!(canonical(0x13, b"   "), Some(Vec::new()));
+    assert_eq!(canonical(0x02, b"\x01"), None);
+    assert!(canonical_string(0x1c, b"\x00A").is_err());
+}
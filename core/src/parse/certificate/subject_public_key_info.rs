@@ -1,22 +1,34 @@
 use super::super::{
-    der::{expect_bit_string, expect_sequence, BitStringRef},
+    der::{expect_bit_string, expect_sequence, AnyRef, BitStringRef, ObjectIdentifierRef},
     error::ParseError,
 };
 use super::{expect_empty, parse_algorithm_identifier, AlgorithmidentifierRef};
+use crate::oids;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct SubjectPublicKeyInfoRef<'a> {
+    raw_data: &'a [u8],
     algorithm: AlgorithmidentifierRef<'a>,
     subject_public_key: BitStringRef<'a>,
 }
 
 impl<'a> SubjectPublicKeyInfoRef<'a> {
+    /// parses a DER encoded SubjectPublicKeyInfo on its own, e.g. the key of a CT log
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, spki) = Self::parse(data)?;
+        expect_empty(rest)?;
+
+        Ok(spki)
+    }
+
     pub(crate) fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
-        let (rest, data) = expect_sequence(data)?;
-        let (data, algorithm) = parse_algorithm_identifier(data)?;
-        let (data, subject_public_key) = expect_bit_string(data)?;
-        expect_empty(data)?;
+        let (rest, inner) = expect_sequence(data)?;
+        let raw_data = &data[..data.len() - rest.len()];
+        let (inner, algorithm) = parse_algorithm_identifier(inner)?;
+        let (inner, subject_public_key) = expect_bit_string(inner)?;
+        expect_empty(inner)?;
         let spki = Self {
+            raw_data,
             algorithm,
             subject_public_key,
         };
@@ -24,6 +36,11 @@ impl<'a> SubjectPublicKeyInfoRef<'a> {
         Ok((rest, spki))
     }
 
+    /// the DER encoding of the whole SubjectPublicKeyInfo, this is what key pins are computed over
+    pub fn raw_data(&self) -> &'a [u8] {
+        self.raw_data
+    }
+
     pub fn algorithm_identifier(&self) -> &AlgorithmidentifierRef {
         &self.algorithm
     }
@@ -31,4 +48,16 @@ impl<'a> SubjectPublicKeyInfoRef<'a> {
     pub fn subject_public_key(&self) -> &BitStringRef {
         &self.subject_public_key
     }
+
+    /// the curve of an EC key, None for other keys and EC keys with explicit parameters
+    pub fn named_curve(&self) -> Option<ObjectIdentifierRef<'a>> {
+        match self.algorithm.parameters() {
+            Some(AnyRef::ObjectIdentifier(curve))
+                if self.algorithm.algorithm_identifier() == &oids::EC_PUBLIC_KEY =>
+            {
+                Some(*curve)
+            }
+            _ => None,
+        }
+    }
 }
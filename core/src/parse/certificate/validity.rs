@@ -5,15 +5,19 @@ use super::super::{
     error::ParseError,
 };
 use super::expect_empty;
+use crate::common::time::UtcDateTime;
+use crate::error::Error;
+use std::time::Duration;
 
+/// the Time CHOICE of RFC 5280, shared by certificates and CRLs
 #[derive(Debug)]
-enum TimeRef<'a> {
+pub(crate) enum TimeRef<'a> {
     UTCTimeRef(UTCTimeRef<'a>),
     GeneralizedTimeRef(GeneralizedTimeRef<'a>),
 }
 
 impl<'a> TimeRef<'a> {
-    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+    pub(crate) fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
         if let Ok((rest, utc)) = expect_utc_time(data) {
             return Ok((rest, Self::UTCTimeRef(utc)));
         }
@@ -23,6 +27,13 @@ impl<'a> TimeRef<'a> {
 
         Err(ParseError::MalformedData)
     }
+
+    pub(crate) fn to_datetime(&self) -> Result<UtcDateTime, Error> {
+        match self {
+            TimeRef::UTCTimeRef(t) => t.to_datetime(),
+            TimeRef::GeneralizedTimeRef(t) => t.to_datetime(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -44,4 +55,21 @@ impl<'a> ValidityRef<'a> {
 
         Ok((rest, validity))
     }
+
+    pub fn not_before(&self) -> Result<UtcDateTime, Error> {
+        self.not_before.to_datetime()
+    }
+
+    pub fn not_after(&self) -> Result<UtcDateTime, Error> {
+        self.not_after.to_datetime()
+    }
+
+    /// the time from notBefore to notAfter, zero if notAfter is before notBefore
+    pub fn period(&self) -> Result<Duration, Error> {
+        let not_before = self.not_before()?;
+        Ok(self
+            .not_after()?
+            .duration_since(not_before)
+            .unwrap_or_default())
+    }
 }
@@ -0,0 +1,318 @@
+//! Certificate revocation lists as defined in RFC 5280 section 5.
+
+use std::convert::TryFrom;
+
+use super::{
+    certificate::{
+        expect_empty, parse_algorithm_identifier, AlgorithmidentifierRef, ExtensionsRef, NameRef,
+        TimeRef,
+    },
+    der::{
+        expect_bit_string, expect_enumerated, expect_integer, expect_sequence, BitStringRef,
+        IntegerRef,
+    },
+    error::ParseError,
+};
+use crate::common::certificate::{CRLReason, Version};
+use crate::common::time::UtcDateTime;
+use crate::error::Error;
+use crate::oids;
+
+#[derive(Debug)]
+pub struct TBSCertListRef<'a> {
+    raw_data: &'a [u8],
+
+    version: Version,
+    signature: AlgorithmidentifierRef<'a>,
+    issuer: NameRef<'a>,
+    this_update: TimeRef<'a>,
+    next_update: Option<TimeRef<'a>>,
+    revoked_certificates: &'a [u8],
+    crl_extensions: Option<ExtensionsRef<'a>>,
+}
+
+impl<'a> TBSCertListRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        let raw_data = &data[..data.len() - rest.len()];
+
+        // version is optional and only present in v2 CRLs
+        let (inner, version) = match expect_integer(inner) {
+            Ok((inner, version)) => (inner, Version::try_from(version.to_i64()?)?),
+            _ => (inner, Version::V1),
+        };
+        let (inner, signature) = parse_algorithm_identifier(inner)?;
+        let (inner, issuer) = NameRef::parse(inner)?;
+        let (inner, this_update) = TimeRef::parse(inner)?;
+        let (inner, next_update) = match TimeRef::parse(inner) {
+            Ok((inner, next_update)) => (inner, Some(next_update)),
+            _ => (inner, None),
+        };
+        // revokedCertificates is left out entirely when the list is empty
+        let (inner, revoked_certificates) = match expect_sequence(inner) {
+            Ok((inner, revoked_certificates)) => (inner, revoked_certificates),
+            _ => (inner, &inner[..0]),
+        };
+        let (inner, crl_extensions) = ExtensionsRef::parse_explicit(inner, 0)?;
+        expect_empty(inner)?;
+
+        let tbs = Self {
+            raw_data,
+
+            version,
+            signature,
+            issuer,
+            this_update,
+            next_update,
+            revoked_certificates,
+            crl_extensions,
+        };
+
+        Ok((rest, tbs))
+    }
+
+    /// the DER encoding of tbsCertList, this is what the signature is calculated over
+    pub fn raw_data(&self) -> &'a [u8] {
+        self.raw_data
+    }
+
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    pub fn signature(&self) -> &AlgorithmidentifierRef<'a> {
+        &self.signature
+    }
+
+    pub fn issuer(&self) -> &NameRef<'a> {
+        &self.issuer
+    }
+
+    pub fn this_update(&self) -> Result<UtcDateTime, Error> {
+        self.this_update.to_datetime()
+    }
+
+    /// the date by which the next CRL will be issued, None if the issuer didn't say
+    pub fn next_update(&self) -> Result<Option<UtcDateTime>, Error> {
+        self.next_update
+            .as_ref()
+            .map(TimeRef::to_datetime)
+            .transpose()
+    }
+
+    pub fn revoked_certificates(&self) -> RevokedCertificatesIter<'a> {
+        RevokedCertificatesIter {
+            pos: self.revoked_certificates,
+            failure: false,
+        }
+    }
+
+    /// the entry for the certificate with serial number `serial_number`, if it is revoked
+    pub fn find_revoked(
+        &self,
+        serial_number: &IntegerRef,
+    ) -> Result<Option<RevokedCertificateRef<'a>>, ParseError> {
+        for entry in self.revoked_certificates() {
+            let entry = entry?;
+            if entry.serial_number() == serial_number {
+                return Ok(Some(entry));
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn crl_extensions(&self) -> Option<ExtensionsRef<'a>> {
+        self.crl_extensions
+    }
+}
+
+#[derive(Debug)]
+pub struct RevokedCertificateRef<'a> {
+    serial_number: IntegerRef<'a>,
+    revocation_date: TimeRef<'a>,
+    extensions: Option<ExtensionsRef<'a>>,
+}
+
+impl<'a> RevokedCertificateRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        let (inner, serial_number) = expect_integer(inner)?;
+        let (inner, revocation_date) = TimeRef::parse(inner)?;
+        let extensions = if inner.is_empty() {
+            None
+        } else {
+            let (inner, extensions) = ExtensionsRef::parse_untagged(inner)?;
+            expect_empty(inner)?;
+            Some(extensions)
+        };
+
+        let entry = Self {
+            serial_number,
+            revocation_date,
+            extensions,
+        };
+
+        Ok((rest, entry))
+    }
+
+    pub fn serial_number(&self) -> &IntegerRef<'a> {
+        &self.serial_number
+    }
+
+    pub fn revocation_date(&self) -> Result<UtcDateTime, Error> {
+        self.revocation_date.to_datetime()
+    }
+
+    pub fn extensions(&self) -> Option<ExtensionsRef<'a>> {
+        self.extensions
+    }
+
+    /// the value of the reasonCode entry extension, if present
+    pub fn reason(&self) -> Result<Option<CRLReason>, ParseError> {
+        let ext = match self.extensions.map(|e| e.find(oids::CRL_REASON)) {
+            Some(ext) => ext?,
+            None => None,
+        };
+        match ext {
+            Some(ext) => {
+                let (rest, reason) = expect_enumerated(ext.value().as_bytes())?;
+                expect_empty(rest)?;
+                Ok(Some(CRLReason::try_from(reason.to_i64()?)?))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+pub struct RevokedCertificatesIter<'a> {
+    pos: &'a [u8],
+    failure: bool,
+}
+
+impl<'a> Iterator for RevokedCertificatesIter<'a> {
+    type Item = Result<RevokedCertificateRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        if self.failure {
+            //this iterator is in error state, continue returning ParseError
+            return Some(Err(ParseError::MalformedData));
+        }
+        match RevokedCertificateRef::parse(self.pos) {
+            Ok((rest, entry)) => {
+                self.pos = rest;
+                Some(Ok(entry))
+            }
+            Err(e) => {
+                self.failure = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CertificateListRef<'a> {
+    tbs_cert_list: TBSCertListRef<'a>,
+    signature_algorithm: AlgorithmidentifierRef<'a>,
+    signature: BitStringRef<'a>,
+}
+
+impl<'a> CertificateListRef<'a> {
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (left, root) = expect_sequence(data)?;
+        // the root sequence should take up all the space in the buffer
+        expect_empty(left)?;
+
+        let (data, tbs_cert_list) = TBSCertListRef::parse(root)?;
+        let (data, signature_algorithm) = parse_algorithm_identifier(data)?;
+        let (data, signature) = expect_bit_string(data)?;
+        expect_empty(data)?;
+
+        Ok(Self {
+            tbs_cert_list,
+            signature_algorithm,
+            signature,
+        })
+    }
+
+    pub fn tbs_cert_list(&self) -> &TBSCertListRef<'a> {
+        &self.tbs_cert_list
+    }
+
+    pub fn signature_algorithm(&self) -> &AlgorithmidentifierRef<'a> {
+        &self.signature_algorithm
+    }
+
+    pub fn signature(&self) -> &BitStringRef<'a> {
+        &self.signature
+    }
+}
+
+#[test]
+fn test_crl() {
+    let data = include_bytes!("../../../certs/crl/ca.crl");
+    let crl = CertificateListRef::from_slice(data).unwrap();
+    assert_eq!(crl.tbs_cert_list().version(), &Version::V2);
+
+    let ca = crate::parse::parsing::CertificateRef::from_slice(include_bytes!(
+        "../../../certs/ocsp/ca.der"
+    ))
+    .unwrap();
+    assert_eq!(crl.tbs_cert_list().issuer(), ca.tbs_cert().subject());
+
+    let tbs = crl.tbs_cert_list();
+    assert!(tbs.this_update().unwrap() < tbs.next_update().unwrap().unwrap());
+    assert!(tbs.crl_extensions().is_some());
+
+    let entries: Vec<_> = tbs.revoked_certificates().collect();
+    assert_eq!(entries.len(), 1);
+    let entry = entries[0].as_ref().unwrap();
+    assert_eq!(entry.serial_number().to_i64().unwrap(), 0x1000);
+    assert_eq!(
+        entry.revocation_date().unwrap(),
+        UtcDateTime::from_ymd_hms(2026, 10, 1, 12, 0, 0).unwrap()
+    );
+    assert_eq!(entry.reason().unwrap(), Some(CRLReason::KeyCompromise));
+    assert!(tbs
+        .find_revoked(ca.tbs_cert().serial_number())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_crl_optional_fields() {
+    use crate::generate::{
+        certificate::{AlgorithmIdentifier, DistinguishedName, Name},
+        der::{encode_tlv, BitString, Data, DataType, Null, ToDer},
+    };
+
+    // a v1 CRL without nextUpdate, revokedCertificates and crlExtensions
+    let algorithm =
+        AlgorithmIdentifier::new(oids::SHA256_WITH_RSA_ENCRYPTION.into(), Data::Null(Null()))
+            .to_der()
+            .unwrap();
+    let this_update = UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 0, 0).unwrap();
+    let mut tbs = algorithm.clone();
+    tbs.extend_from_slice(
+        &Name::DistinguishedName(DistinguishedName::default())
+            .to_der()
+            .unwrap(),
+    );
+    tbs.extend_from_slice(&this_update.to_der().unwrap());
+    let mut crl = encode_tlv(DataType::Sequence.constructed(), &tbs);
+    crl.extend_from_slice(&algorithm);
+    crl.extend_from_slice(&BitString::new(vec![0; 4], 32).to_der().unwrap());
+    let crl = encode_tlv(DataType::Sequence.constructed(), &crl);
+
+    let crl = CertificateListRef::from_slice(&crl).unwrap();
+    let tbs = crl.tbs_cert_list();
+    assert_eq!(tbs.version(), &Version::V1);
+    assert_eq!(tbs.this_update().unwrap(), this_update);
+    assert_eq!(tbs.next_update().unwrap(), None);
+    assert_eq!(tbs.revoked_certificates().count(), 0);
+    assert!(tbs.crl_extensions().is_none());
+}
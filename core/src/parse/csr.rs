@@ -0,0 +1,236 @@
+//! Certification requests as defined in PKCS#10 (RFC 2986).
+
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+use super::{
+    certificate::{
+        expect_empty, parse_algorithm_identifier, AlgorithmidentifierRef, ExtensionsRef, NameRef,
+        SubjectPublicKeyInfoRef,
+    },
+    der::{
+        expect_bit_string, expect_integer, expect_object_identifier, expect_sequence, expect_set,
+        take_any, try_get_implicit, BitStringRef, ObjectIdentifierRef,
+    },
+    error::ParseError,
+};
+use crate::common::certificate::Version;
+use crate::oids;
+
+#[derive(Debug)]
+pub struct CertificationRequestInfoRef<'a> {
+    raw_data: &'a [u8],
+
+    version: Version,
+    subject: NameRef<'a>,
+    subject_public_key_info: SubjectPublicKeyInfoRef<'a>,
+    attributes: &'a [u8],
+}
+
+impl<'a> CertificationRequestInfoRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        let raw_data = &data[..data.len() - rest.len()];
+
+        let (inner, version) = expect_integer(inner)?;
+        let version = Version::try_from(version.to_i64()?)?;
+        let (inner, subject) = NameRef::parse(inner)?;
+        let (inner, subject_public_key_info) = SubjectPublicKeyInfoRef::parse(inner)?;
+        // attributes [0] IMPLICIT SET OF Attribute
+        let (inner, attributes) = try_get_implicit(inner, 0)?;
+        expect_empty(inner)?;
+
+        let info = Self {
+            raw_data,
+
+            version,
+            subject,
+            subject_public_key_info,
+            attributes,
+        };
+
+        Ok((rest, info))
+    }
+
+    /// the DER encoding of certificationRequestInfo, this is what the signature is calculated over
+    pub fn raw_data(&self) -> &'a [u8] {
+        self.raw_data
+    }
+
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    pub fn subject(&self) -> &NameRef<'a> {
+        &self.subject
+    }
+
+    pub fn subject_public_key_info(&self) -> &SubjectPublicKeyInfoRef<'a> {
+        &self.subject_public_key_info
+    }
+
+    pub fn attributes(&self) -> AttributesIter<'a> {
+        AttributesIter {
+            pos: self.attributes,
+            failure: false,
+        }
+    }
+
+    /// the first value of the attribute with the type `attribute_type`, if present
+    fn find_attribute(
+        &self,
+        attribute_type: ObjectIdentifierRef,
+    ) -> Result<Option<&'a [u8]>, ParseError> {
+        for attribute in self.attributes() {
+            let attribute = attribute?;
+            if attribute.attribute_type() == &attribute_type {
+                return Ok(attribute.values().next());
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// the PKCS#9 challengePassword attribute, if present
+    pub fn challenge_password(&self) -> Result<Option<Cow<'a, str>>, ParseError> {
+        match self.find_attribute(oids::CHALLENGE_PASSWORD)? {
+            Some(value) => {
+                let (rest, value) = take_any(value)?;
+                expect_empty(rest)?;
+                Ok(Some(value.to_str()?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// the extensions requested through the PKCS#9 extensionRequest attribute, if present
+    pub fn extension_request(&self) -> Result<Option<ExtensionsRef<'a>>, ParseError> {
+        match self.find_attribute(oids::EXTENSION_REQUEST)? {
+            Some(value) => {
+                let (rest, extensions) = ExtensionsRef::parse_untagged(value)?;
+                expect_empty(rest)?;
+                Ok(Some(extensions))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AttributeRef<'a> {
+    attribute_type: ObjectIdentifierRef<'a>,
+    values: &'a [u8],
+}
+
+impl<'a> AttributeRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        let (inner, attribute_type) = expect_object_identifier(inner)?;
+        let (inner, values) = expect_set(inner)?;
+        expect_empty(inner)?;
+
+        let attribute = Self {
+            attribute_type,
+            values,
+        };
+
+        Ok((rest, attribute))
+    }
+
+    pub fn attribute_type(&self) -> &ObjectIdentifierRef<'a> {
+        &self.attribute_type
+    }
+
+    /// the DER encoded values, one TLV per item. a value that isn't valid DER ends the iteration.
+    pub fn values(&self) -> impl Iterator<Item = &'a [u8]> {
+        let mut pos = self.values;
+        std::iter::from_fn(move || {
+            let (rest, _) = take_any(pos).ok()?;
+            let value = &pos[..pos.len() - rest.len()];
+            pos = rest;
+            Some(value)
+        })
+    }
+}
+
+pub struct AttributesIter<'a> {
+    pos: &'a [u8],
+    failure: bool,
+}
+
+impl<'a> Iterator for AttributesIter<'a> {
+    type Item = Result<AttributeRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        if self.failure {
+            //this iterator is in error state, continue returning ParseError
+            return Some(Err(ParseError::MalformedData));
+        }
+        match AttributeRef::parse(self.pos) {
+            Ok((rest, attribute)) => {
+                self.pos = rest;
+                Some(Ok(attribute))
+            }
+            Err(e) => {
+                self.failure = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CertificationRequestRef<'a> {
+    certification_request_info: CertificationRequestInfoRef<'a>,
+    signature_algorithm: AlgorithmidentifierRef<'a>,
+    signature: BitStringRef<'a>,
+}
+
+impl<'a> CertificationRequestRef<'a> {
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (left, root) = expect_sequence(data)?;
+        // the root sequence should take up all the space in the buffer
+        expect_empty(left)?;
+
+        let (data, certification_request_info) = CertificationRequestInfoRef::parse(root)?;
+        let (data, signature_algorithm) = parse_algorithm_identifier(data)?;
+        let (data, signature) = expect_bit_string(data)?;
+        expect_empty(data)?;
+
+        Ok(Self {
+            certification_request_info,
+            signature_algorithm,
+            signature,
+        })
+    }
+
+    pub fn certification_request_info(&self) -> &CertificationRequestInfoRef<'a> {
+        &self.certification_request_info
+    }
+
+    pub fn signature_algorithm(&self) -> &AlgorithmidentifierRef<'a> {
+        &self.signature_algorithm
+    }
+
+    pub fn signature(&self) -> &BitStringRef<'a> {
+        &self.signature
+    }
+}
+
+#[test]
+fn test_csr_attributes() {
+    let csr =
+        CertificationRequestRef::from_slice(include_bytes!("../../../certs/csr/req.der")).unwrap();
+    let info = csr.certification_request_info();
+    assert_eq!(info.version(), &Version::V1);
+    assert_eq!(info.attributes().count(), 2);
+    assert_eq!(info.challenge_password().unwrap().unwrap(), "secret123");
+
+    let extensions = info.extension_request().unwrap().unwrap();
+    assert!(extensions.find(oids::SUBJECT_ALT_NAME).unwrap().is_some());
+    let key_usage = extensions.find(oids::KEY_USAGE).unwrap().unwrap();
+    assert!(key_usage.critical());
+}
@@ -0,0 +1,332 @@
+//! Pairing of certificate transparency log entries: a CA logs a precertificate, a certificate
+//! with the critical poison extension, and issues the final certificate with the SCTs of the logs
+//! embedded. apart from these two extensions the TBSCertificates are the same (RFC 6962, 3.1).
+
+use std::convert::TryInto;
+
+use super::certificate::{expect_empty, ExtensionsRef};
+use super::der::{encode_tlv, expect_octet_string, expect_sequence, get_tlv, DataType};
+use super::error::ParseError;
+use super::parsing::CertificateRef;
+use crate::common::time::UtcDateTime;
+use crate::oids;
+
+/// the context specific tag of the extensions in a TBSCertificate
+const EXTENSIONS_TAG: u8 = 0xa3;
+
+/// whether `cert` is the final certificate of the precertificate `precert`. `precert` must carry
+/// the critical poison extension and `cert` must not.
+///
+/// a precertificate issued by a Precertificate Signing Certificate names that certificate as the
+/// issuer and doesn't match, use [`matches_precert_tbs`] with the TBSCertificate of the log entry
+/// for those. malformed certificates don't match anything.
+pub fn matches_precertificate(cert: &CertificateRef, precert: &CertificateRef) -> bool {
+    let poison = precert
+        .tbs_cert()
+        .extensions()
+        .and_then(|e| e.find(oids::CT_PRECERT_POISON).ok()?);
+    if !poison.is_some_and(|p| p.critical()) {
+        return false;
+    }
+
+    matches_precert_tbs(cert, precert.tbs_cert().raw_data())
+}
+
+/// whether `cert` is the final certificate of a precertificate log entry, given the DER encoded
+/// `tbs_certificate` of its PreCert structure. the log has already removed the poison extension
+/// and replaced the issuer by the one of the final certificate there.
+pub fn matches_precert_tbs(cert: &CertificateRef, tbs_certificate: &[u8]) -> bool {
+    if cert
+        .tbs_cert()
+        .extensions()
+        .is_some_and(|e| !matches!(e.find(oids::CT_PRECERT_POISON), Ok(None)))
+    {
+        return false;
+    }
+
+    tbs_equal_without_ct_extensions(cert.tbs_cert().raw_data(), tbs_certificate).unwrap_or(false)
+}
+
+/// compares the fields of two TBSCertificates byte for byte, except for the poison and SCT list
+/// extensions
+fn tbs_equal_without_ct_extensions(a: &[u8], b: &[u8]) -> Result<bool, ParseError> {
+    let (_, mut a) = expect_sequence(a)?;
+    let (_, mut b) = expect_sequence(b)?;
+
+    while !a.is_empty() && !b.is_empty() {
+        let (a_rest, a_tag, a_value) = get_tlv(a)?;
+        let (b_rest, b_tag, b_value) = get_tlv(b)?;
+        if a_tag != b_tag {
+            return Ok(false);
+        }
+        let equal = if a_tag == EXTENSIONS_TAG {
+            extensions_equal_without_ct(a_value, b_value)?
+        } else {
+            a_value == b_value
+        };
+        if !equal {
+            return Ok(false);
+        }
+        a = a_rest;
+        b = b_rest;
+    }
+
+    Ok(a.is_empty() && b.is_empty())
+}
+
+fn extensions_equal_without_ct(a: &[u8], b: &[u8]) -> Result<bool, ParseError> {
+    let (_, a) = ExtensionsRef::parse_untagged(a)?;
+    let (_, b) = ExtensionsRef::parse_untagged(b)?;
+    let is_ct = |id: &_| *id == oids::CT_PRECERT_POISON || *id == oids::CT_PRECERT_SCTS;
+
+    let mut a = a.into_iter();
+    let mut b = b.into_iter();
+    loop {
+        let next_a = a
+            .by_ref()
+            .find(|e| !matches!(e, Ok(e) if is_ct(e.extension_id())));
+        let next_b = b
+            .by_ref()
+            .find(|e| !matches!(e, Ok(e) if is_ct(e.extension_id())));
+        match (next_a.transpose()?, next_b.transpose()?) {
+            (None, None) => return Ok(true),
+            (Some(a), Some(b)) => {
+                if a.extension_id() != b.extension_id()
+                    || a.critical() != b.critical()
+                    || a.value().as_bytes() != b.value().as_bytes()
+                {
+                    return Ok(false);
+                }
+            }
+            _ => return Ok(false),
+        }
+    }
+}
+
+/// a signed certificate timestamp of version 1 (RFC 6962, 3.2), a log's promise to publish a
+/// certificate. unlike the rest of a certificate it is TLS encoded, not DER.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedCertificateTimestampRef<'a> {
+    log_id: &'a [u8],
+    timestamp: u64,
+    extensions: &'a [u8],
+    hash_algorithm: u8,
+    signature_algorithm: u8,
+    signature: &'a [u8],
+}
+
+impl<'a> SignedCertificateTimestampRef<'a> {
+    /// the SHA-256 hash of the key of the log that issued the SCT
+    pub fn log_id(&self) -> &'a [u8] {
+        self.log_id
+    }
+
+    /// milliseconds since the epoch, leap seconds ignored
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// the timestamp as a date, None if it is too far in the future to be one
+    pub fn time(&self) -> Option<UtcDateTime> {
+        let seconds = (self.timestamp / 1000).try_into().ok()?;
+        let millis = (self.timestamp % 1000) as u32;
+
+        UtcDateTime::from_unix_timestamp(seconds)?.with_nanosecond(millis * 1_000_000)
+    }
+
+    /// the CtExtensions, no extensions are defined so far
+    pub fn extensions(&self) -> &'a [u8] {
+        self.extensions
+    }
+
+    /// the HashAlgorithm of the TLS signature, 4 is SHA-256
+    pub fn hash_algorithm(&self) -> u8 {
+        self.hash_algorithm
+    }
+
+    /// the SignatureAlgorithm of the TLS signature, 1 is RSA and 3 is ECDSA
+    pub fn signature_algorithm(&self) -> u8 {
+        self.signature_algorithm
+    }
+
+    /// the signature over the certificate entry, the ECDSA-Sig-Value for ECDSA
+    pub fn signature(&self) -> &'a [u8] {
+        self.signature
+    }
+}
+
+/// the SCTs in the extnValue of the embedded SCT list extension, an OCTET STRING with the TLS
+/// encoded SignedCertificateTimestampList. SCTs of other versions than 1 are skipped, as RFC 6962
+/// asks clients to.
+pub fn parse_sct_list(
+    extension_value: &[u8],
+) -> Result<Vec<SignedCertificateTimestampRef<'_>>, ParseError> {
+    let (rest, list) = expect_octet_string(extension_value)?;
+    expect_empty(rest)?;
+    let mut list = list.as_bytes();
+    let mut entries = take_u16_prefixed(&mut list)?;
+    expect_empty(list)?;
+
+    let mut scts = Vec::new();
+    while !entries.is_empty() {
+        let mut sct = take_u16_prefixed(&mut entries)?;
+        if take(&mut sct, 1)?[0] != 0 {
+            continue;
+        }
+        let log_id = take(&mut sct, 32)?;
+        let timestamp = u64::from_be_bytes(take(&mut sct, 8)?.try_into().expect("8 bytes"));
+        let extensions = take_u16_prefixed(&mut sct)?;
+        let algorithms = take(&mut sct, 2)?;
+        let signature = take_u16_prefixed(&mut sct)?;
+        expect_empty(sct)?;
+
+        scts.push(SignedCertificateTimestampRef {
+            log_id,
+            timestamp,
+            extensions,
+            hash_algorithm: algorithms[0],
+            signature_algorithm: algorithms[1],
+            signature,
+        });
+    }
+
+    Ok(scts)
+}
+
+/// the DER encoded TBSCertificate of `cert` without the SCT list extension. this is the
+/// tbs_certificate of the precertificate entry the embedded SCTs are signed over, if the
+/// precertificate was issued by the CA itself.
+pub fn precert_tbs(cert: &CertificateRef) -> Result<Vec<u8>, ParseError> {
+    let (_, mut fields) = expect_sequence(cert.tbs_cert().raw_data())?;
+
+    let mut tbs = Vec::with_capacity(fields.len());
+    while !fields.is_empty() {
+        let (rest, tag, value) = get_tlv(fields)?;
+        if tag == EXTENSIONS_TAG {
+            let (_, extensions) = ExtensionsRef::parse_untagged(value)?;
+            let mut kept = Vec::new();
+            for ext in extensions {
+                let ext = ext?;
+                if *ext.extension_id() != oids::CT_PRECERT_SCTS {
+                    kept.extend_from_slice(ext.raw_der());
+                }
+            }
+            // the extensions field is left out rather than empty if the SCTs were the only one
+            if !kept.is_empty() {
+                let sequence = encode_tlv(DataType::Sequence.constructed(), &kept);
+                tbs.extend(encode_tlv(EXTENSIONS_TAG, &sequence));
+            }
+        } else {
+            tbs.extend_from_slice(&fields[..fields.len() - rest.len()]);
+        }
+        fields = rest;
+    }
+
+    Ok(encode_tlv(DataType::Sequence.constructed(), &tbs))
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], ParseError> {
+    if data.len() < len {
+        return Err(ParseError::InvalidLength);
+    }
+    let (taken, rest) = data.split_at(len);
+    *data = rest;
+
+    Ok(taken)
+}
+
+/// a TLS opaque vector with a two byte length
+fn take_u16_prefixed<'a>(data: &mut &'a [u8]) -> Result<&'a [u8], ParseError> {
+    let len = take(data, 2)?;
+    take(data, u16::from_be_bytes([len[0], len[1]]) as usize)
+}
+
+#[test]
+fn test_matches_precertificate() {
+    let precert =
+        CertificateRef::from_slice(include_bytes!("../../../certs/ct/precert.der")).unwrap();
+    let cert = CertificateRef::from_slice(include_bytes!("../../../certs/ct/final.der")).unwrap();
+    let other =
+        CertificateRef::from_slice(include_bytes!("../../../certs/ct/other-final.der")).unwrap();
+
+    assert!(matches_precertificate(&cert, &precert));
+    // another SAN
+    assert!(!matches_precertificate(&other, &precert));
+    // the precertificate needs the poison, the final certificate must not have it
+    assert!(!matches_precertificate(&cert, &cert));
+    assert!(!matches_precertificate(&precert, &precert));
+}
+
+#[test]
+fn test_matches_precert_tbs() {
+    let precert =
+        CertificateRef::from_slice(include_bytes!("../../../certs/ct/precert.der")).unwrap();
+    let cert = CertificateRef::from_slice(include_bytes!("../../../certs/ct/final.der")).unwrap();
+    let tbs = precert.tbs_cert().raw_data();
+
+    assert!(matches_precert_tbs(&cert, tbs));
+    assert!(!matches_precert_tbs(&cert, &tbs[..tbs.len() - 1]));
+    assert!(!matches_precert_tbs(&cert, &[]));
+    // the final certificate has no CT extension left to ignore
+    assert!(matches_precert_tbs(&cert, cert.tbs_cert().raw_data()));
+}
+
+#[test]
+fn test_parse_sct_list() {
+    let cert = CertificateRef::from_slice(include_bytes!("../../../certs/ct/scts.der")).unwrap();
+    let ext = cert
+        .tbs_cert()
+        .extensions()
+        .unwrap()
+        .find(oids::CT_PRECERT_SCTS)
+        .unwrap()
+        .unwrap();
+    let scts = parse_sct_list(ext.value().as_bytes()).unwrap();
+
+    assert_eq!(scts.len(), 3);
+    assert_eq!(scts[0].log_id()[..4], [0x63, 0xc7, 0xd5, 0x51]);
+    assert_eq!(scts[0].timestamp(), 1_772_323_200_000);
+    assert_eq!(
+        scts[0].time(),
+        UtcDateTime::from_ymd_hms(2026, 3, 1, 0, 0, 0)
+    );
+    assert_eq!(scts[1].log_id()[..4], [0x8a, 0xb2, 0xc6, 0x2c]);
+    assert!(scts.iter().all(|sct| sct.extensions().is_empty()
+        && sct.hash_algorithm() == 4
+        && sct.signature_algorithm() == 3));
+
+    // the dummy list of final.der holds a single SCT of version 1 with nothing after the version
+    let cert = CertificateRef::from_slice(include_bytes!("../../../certs/ct/final.der")).unwrap();
+    let ext = cert
+        .tbs_cert()
+        .extensions()
+        .unwrap()
+        .find(oids::CT_PRECERT_SCTS)
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        parse_sct_list(ext.value().as_bytes()),
+        Err(ParseError::InvalidLength)
+    ));
+
+    // a version 2 SCT is skipped, a truncated list is not
+    assert!(parse_sct_list(&[0x04, 0x05, 0x00, 0x03, 0x00, 0x01, 0x01])
+        .unwrap()
+        .is_empty());
+    assert!(matches!(
+        parse_sct_list(&[0x04, 0x04, 0x00, 0x03, 0x00, 0x01]),
+        Err(ParseError::InvalidLength)
+    ));
+}
+
+#[test]
+fn test_precert_tbs() {
+    let cert = CertificateRef::from_slice(include_bytes!("../../../certs/ct/scts.der")).unwrap();
+    let tbs = precert_tbs(&cert).unwrap();
+    assert!(tbs.len() < cert.tbs_cert().raw_data().len());
+    assert!(matches_precert_tbs(&cert, &tbs));
+
+    // nothing to remove
+    let cert = CertificateRef::from_slice(include_bytes!("../../../certs/ocsp/leaf.der")).unwrap();
+    assert_eq!(precert_tbs(&cert).unwrap(), cert.tbs_cert().raw_data());
+}
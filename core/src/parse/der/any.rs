@@ -1,7 +1,9 @@
 use super::super::error::ParseError;
 use super::{
-    get_tlv, DataType, IntegerRef, ObjectIdentifierRef, PrintableStringRef, Utf8StringRef,
+    get_tlv, BMPStringRef, DataType, IA5StringRef, IntegerRef, ObjectIdentifierRef,
+    PrintableStringRef, T61StringRef, Utf8StringRef, VisibleStringRef,
 };
+use std::borrow::Cow;
 use std::convert::TryFrom;
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -11,22 +13,78 @@ pub enum AnyRef<'a> {
     Integer(IntegerRef<'a>),
     PrintableString(PrintableStringRef<'a>),
     Utf8String(Utf8StringRef<'a>),
+    T61String(T61StringRef<'a>),
+    IA5String(IA5StringRef<'a>),
+    VisibleString(VisibleStringRef<'a>),
+    BMPString(BMPStringRef<'a>),
     Sequence(&'a [u8]),
+    /// a value of a type that isn't decoded here, holding the tag and the content octets
+    Unknown(u8, &'a [u8]),
+}
+
+impl<'a> AnyRef<'a> {
+    /// decodes any of the string types as text, e.g. the DirectoryString values of names.
+    /// fails with `UnexpectedTag` if the value is not a string.
+    pub fn to_str(&self) -> Result<Cow<'a, str>, ParseError> {
+        match self {
+            AnyRef::PrintableString(s) => s.to_str(),
+            AnyRef::Utf8String(s) => s.to_str(),
+            AnyRef::T61String(s) => Ok(s.to_str()),
+            AnyRef::IA5String(s) => s.to_str(),
+            AnyRef::VisibleString(s) => s.to_str(),
+            AnyRef::BMPString(s) => s.to_str(),
+            AnyRef::Unknown(tag, _) => Err(ParseError::UnexpectedTag(*tag)),
+            AnyRef::ObjectIdentifier(_) => Err(ParseError::UnexpectedTag(0x06)),
+            AnyRef::Null => Err(ParseError::UnexpectedTag(0x05)),
+            AnyRef::Integer(_) => Err(ParseError::UnexpectedTag(0x02)),
+            AnyRef::Sequence(_) => Err(ParseError::UnexpectedTag(0x30)),
+        }
+    }
 }
 
 // TODO parse properly
 pub fn take_any(data: &[u8]) -> Result<(&[u8], AnyRef), ParseError> {
     let (rest, tag, data) = get_tlv(data)?;
-    match DataType::try_from(tag)? {
-        DataType::Null => Ok((rest, AnyRef::Null)),
-        DataType::Integer => Ok((rest, AnyRef::Integer(IntegerRef(data)))),
-        DataType::ObjectIdentifier => {
+    match DataType::try_from(tag) {
+        Ok(DataType::Null) => Ok((rest, AnyRef::Null)),
+        Ok(DataType::Integer) => Ok((rest, AnyRef::Integer(IntegerRef(data)))),
+        Ok(DataType::ObjectIdentifier) => {
             Ok((rest, AnyRef::ObjectIdentifier(ObjectIdentifierRef(data))))
         }
-        DataType::PrintableString => Ok((rest, AnyRef::PrintableString(PrintableStringRef(data)))),
-        DataType::Utf8String => Ok((rest, AnyRef::Utf8String(Utf8StringRef(data)))),
-        DataType::Sequence => Ok((rest, AnyRef::Sequence(data))),
+        Ok(DataType::PrintableString) => {
+            Ok((rest, AnyRef::PrintableString(PrintableStringRef(data))))
+        }
+        Ok(DataType::Utf8String) => Ok((rest, AnyRef::Utf8String(Utf8StringRef(data)))),
+        Ok(DataType::T61String) => Ok((rest, AnyRef::T61String(T61StringRef(data)))),
+        Ok(DataType::IA5String) => Ok((rest, AnyRef::IA5String(IA5StringRef(data)))),
+        Ok(DataType::VisibleString) => Ok((rest, AnyRef::VisibleString(VisibleStringRef(data)))),
+        Ok(DataType::BMPString) => Ok((rest, AnyRef::BMPString(BMPStringRef(data)))),
+        Ok(DataType::Sequence) => Ok((rest, AnyRef::Sequence(data))),
 
-        _ => Err(ParseError::UnsupportedTag(tag)),
+        // keep everything else around instead of failing the whole structure
+        _ => Ok((rest, AnyRef::Unknown(tag, data))),
     }
 }
+
+#[test]
+fn test_take_any_unknown() {
+    // UniversalString "a"
+    let (rest, value) = take_any(&[0x1c, 0x04, 0x00, 0x00, 0x00, 0x61]).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(value, AnyRef::Unknown(0x1c, &[0x00, 0x00, 0x00, 0x61]));
+    assert!(value.to_str().is_err());
+}
+
+#[test]
+fn test_to_str() {
+    let decode = |data: &[u8]| take_any(data).unwrap().1.to_str().unwrap().into_owned();
+
+    assert_eq!(decode(&[0x13, 0x02, 0x44, 0x45]), "DE");
+    assert_eq!(decode(&[0x0c, 0x02, 0xc3, 0xa4]), "\u{e4}");
+    assert_eq!(decode(&[0x14, 0x01, 0xe4]), "\u{e4}");
+    assert_eq!(decode(&[0x16, 0x03, 0x61, 0x40, 0x62]), "a@b");
+    assert_eq!(decode(&[0x1e, 0x04, 0x00, 0xe4, 0x00, 0x61]), "\u{e4}a");
+
+    // '@' is not part of the PrintableString alphabet
+    assert!(take_any(&[0x13, 0x01, 0x40]).unwrap().1.to_str().is_err());
+}
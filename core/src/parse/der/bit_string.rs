@@ -28,6 +28,14 @@ impl<'a> BitStringRef<'a> {
         Ok(byte & mask == mask)
     }
 
+    /// wraps the content octets of an implicitly tagged BIT STRING
+    pub(crate) fn from_content(content: &'a [u8]) -> Result<Self, ParseError> {
+        match content.first() {
+            Some(unused_bits) if *unused_bits < 8 => Ok(Self(content)),
+            _ => Err(ParseError::MalformedData),
+        }
+    }
+
     pub fn data(&self) -> (u8, &'a [u8]) {
         (self.0[0], &self.0[1..])
     }
@@ -1,20 +1,47 @@
-use std::fmt::{self, Debug, Display, Formatter};
+use super::super::error::ParseError;
+use std::borrow::Cow;
+use std::char::DecodeUtf16Error;
+use std::fmt::{self, Debug, Display, Formatter, Write};
 
-#[derive(PartialEq)]
-pub struct BMPStringRef<'a>(&'a [u8]);
+#[derive(PartialEq, Eq, Hash)]
+pub struct BMPStringRef<'a>(pub(crate) &'a [u8]);
+
+impl<'a> BMPStringRef<'a> {
+    /// decodes the big endian UTF-16 content
+    pub fn to_str(&self) -> Result<Cow<'a, str>, ParseError> {
+        if !self.0.len().is_multiple_of(2) {
+            return Err(ParseError::StringEncoding);
+        }
+        self.chars()
+            .collect::<Result<String, _>>()
+            .map(Cow::Owned)
+            .map_err(|_| ParseError::StringEncoding)
+    }
+
+    /// true if the content is an even number of bytes without unpaired surrogates
+    pub(crate) fn is_valid(&self) -> bool {
+        self.0.len().is_multiple_of(2) && self.chars().all(|c| c.is_ok())
+    }
+
+    /// decodes the UTF-16 code units, a trailing odd byte is ignored
+    fn chars(&self) -> impl Iterator<Item = Result<char, DecodeUtf16Error>> + 'a {
+        char::decode_utf16(
+            self.0
+                .chunks_exact(2)
+                .map(|a| u16::from_be_bytes([a[0], a[1]])),
+        )
+    }
+}
 
 impl<'a> Display for BMPStringRef<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        let u16s: Vec<u16> = self
-            .0
-            .chunks(2)
-            .map(|a| u16::from_be_bytes([a[0], a[1]]))
-            .collect();
-        if let Ok(s) = String::from_utf16(&u16s) {
-            write!(f, "{}", s)
-        } else {
-            Err(fmt::Error::default())
+        if !self.0.len().is_multiple_of(2) {
+            return Err(fmt::Error);
+        }
+        for c in self.chars() {
+            f.write_char(c.map_err(|_| fmt::Error)?)?;
         }
+        Ok(())
     }
 }
 
@@ -23,3 +50,22 @@ impl<'a> Debug for BMPStringRef<'a> {
         write!(f, "\"{}\"", self)
     }
 }
+
+#[test]
+fn test_bmp_string_display() {
+    // "Zürich"
+    let s = BMPStringRef(&[0, 0x5a, 0, 0xfc, 0, 0x72, 0, 0x69, 0, 0x63, 0, 0x68]);
+    assert_eq!(s.to_string(), "Zürich");
+    assert_eq!(s.to_str().unwrap(), "Zürich");
+
+    // a surrogate pair, U+1F600
+    let s = BMPStringRef(&[0xd8, 0x3d, 0xde, 0x00]);
+    assert_eq!(s.to_string(), "\u{1f600}");
+
+    // an unpaired surrogate and an odd number of bytes
+    let mut out = String::new();
+    assert!(fmt::write(&mut out, format_args!("{}", BMPStringRef(&[0xd8, 0x3d]))).is_err());
+    assert!(fmt::write(&mut out, format_args!("{}", BMPStringRef(&[0, 0x5a, 0]))).is_err());
+    assert!(BMPStringRef(&[0, 0x5a, 0]).to_str().is_err());
+    assert!(!BMPStringRef(&[0xd8, 0x3d]).is_valid());
+}
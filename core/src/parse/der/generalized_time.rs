@@ -1,71 +1,118 @@
 use super::super::error::ParseError;
-use super::{ascii_slice_to_u32, expect_type, DataType};
+use super::{expect_type, DataType};
+use crate::common::time::UtcDateTime;
 use crate::error::Error;
-use chrono::prelude::*;
 use std::fmt::{self, Debug, Display, Formatter};
 
 #[derive(PartialEq)]
-pub struct GeneralizedTimeRef<'a>(&'a [u8]);
+pub struct GeneralizedTimeRef<'a>(pub(crate) &'a [u8]);
 
 impl<'a> GeneralizedTimeRef<'a> {
-    pub fn to_datetime(&self) -> Result<DateTime<FixedOffset>, Error> {
-        let data = self.0;
-        let year = ascii_slice_to_u32(&data[..4])?;
-
-        let month = ascii_slice_to_u32(&data[4..6])?;
-        let day = ascii_slice_to_u32(&data[6..8])?;
-
-        let hour = ascii_slice_to_u32(&data[8..10])?;
-        let minute = ascii_slice_to_u32(&data[10..12])?;
-        let second = ascii_slice_to_u32(&data[12..14])?;
-
-        let nanos: u32;
-        if data[14] == 0x2e {
-            // '.'
-            // handle fractional seconds
-            let mut fractional_len = 0;
-            for (i, &ch) in (&data[15..]).iter().enumerate() {
-                if ch > 0x39 || ch < 0x30 {
-                    //end of fractional seconds
-                    fractional_len = i;
-                }
+    /// accepts all forms of X.680: the hour with optional minutes and seconds, a fraction of
+    /// the last of these introduced by '.' or ',', followed by 'Z', an offset like "+0100" or
+    /// "-05", or nothing. without 'Z' or an offset the value is local time, which is unknown
+    /// here and treated as UTC.
+    pub fn to_datetime(&self) -> Result<UtcDateTime, Error> {
+        parse_generalized_time(self.0, false).ok_or(Error::ParseError(ParseError::MalformedData))
+    }
+
+    /// only accepts YYYYMMDDHHMMSSZ, the form required by RFC 5280
+    pub fn to_datetime_strict(&self) -> Result<UtcDateTime, Error> {
+        parse_generalized_time(self.0, true).ok_or(Error::ParseError(ParseError::MalformedData))
+    }
+}
+
+fn parse_generalized_time(data: &[u8], strict: bool) -> Option<UtcDateTime> {
+    let (data, year) = take_digits(data, 4)?;
+    let (data, month) = take_digits(data, 2)?;
+    let (data, day) = take_digits(data, 2)?;
+    let (data, hour) = take_digits(data, 2)?;
+
+    // the length in seconds of the last unit present, a fraction refers to it
+    let mut unit = 3600;
+    let (data, minute) = match take_digits(data, 2) {
+        Some((data, minute)) => {
+            unit = 60;
+            (data, minute)
+        }
+        None => (data, 0),
+    };
+    let (data, second) = match take_digits(data, 2) {
+        Some((data, second)) if unit == 60 => {
+            unit = 1;
+            (data, second)
+        }
+        _ => (data, 0),
+    };
+
+    let (data, fraction_nanos) = match data.split_first() {
+        Some((b'.', rest)) | Some((b',', rest)) => {
+            let len = rest.iter().take_while(|d| d.is_ascii_digit()).count();
+            if len == 0 {
+                return None;
             }
-            nanos = ascii_slice_to_u32(&data[15..15 + fractional_len])?;
-        } else {
-            nanos = 0;
+            (&rest[len..], fraction_to_nanos(&rest[..len], unit))
         }
+        _ => (data, 0),
+    };
 
-        let utc_offset: i32;
-        if data[data.len() - 1] == 0x5a {
-            // 'Z'
-            utc_offset = 0;
-        } else {
-            let data = &data[data.len() - 6..];
-            let hour_offset = ascii_slice_to_u32(&data[1..3])?;
-            let minute_offset = ascii_slice_to_u32(&data[3..5])?;
-            let factor = if data[0] == 0x2d {
-                // '-'
-                -1
+    let utc_offset: i64 = match data {
+        [b'Z'] => 0,
+        [] => 0,
+        [sign @ b'+', rest @ ..] | [sign @ b'-', rest @ ..] => {
+            let (rest, hour_offset) = take_digits(rest, 2)?;
+            let (rest, minute_offset) = if rest.is_empty() {
+                (rest, 0)
             } else {
-                1
+                take_digits(rest, 2)?
             };
-
-            utc_offset = factor * (hour_offset * 3600 + minute_offset * 60) as i32;
+            if !rest.is_empty() || hour_offset > 23 || minute_offset > 59 {
+                return None;
+            }
+            let factor = if *sign == b'-' { -1 } else { 1 };
+            factor * (hour_offset * 3600 + minute_offset * 60) as i64
         }
+        _ => return None,
+    };
+
+    if strict && (unit != 1 || fraction_nanos != 0 || data != b"Z") {
+        return None;
+    }
 
-        let offset = FixedOffset::east(utc_offset);
-        let dt = offset
-            .ymd(year as i32, month, day)
-            .and_hms_nano(hour, minute, second, nanos);
+    UtcDateTime::from_ymd_hms(year as i32, month, day, hour, minute, second)?
+        .checked_add_seconds((fraction_nanos / 1_000_000_000) as i64 - utc_offset)?
+        .with_nanosecond((fraction_nanos % 1_000_000_000) as u32)
+}
 
-        Ok(dt)
+/// reads exactly `n` ascii digits
+pub(super) fn take_digits(data: &[u8], n: usize) -> Option<(&[u8], u32)> {
+    if data.len() < n || !data[..n].iter().all(u8::is_ascii_digit) {
+        return None;
     }
+    let value = data[..n]
+        .iter()
+        .fold(0, |acc, d| acc * 10 + (d - b'0') as u32);
+
+    Some((&data[n..], value))
+}
+
+/// converts the decimal fraction `digits` of a unit that is `unit` seconds long to nanoseconds.
+/// digits beyond the 20th are below nanosecond precision and ignored.
+fn fraction_to_nanos(digits: &[u8], unit: u64) -> u64 {
+    let mut numerator: u128 = 0;
+    let mut denominator: u128 = 1;
+    for d in digits.iter().take(20) {
+        numerator = numerator * 10 + (d - b'0') as u128;
+        denominator *= 10;
+    }
+
+    (numerator * unit as u128 * 1_000_000_000 / denominator) as u64
 }
 
 impl<'a> Display for GeneralizedTimeRef<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
         if let Ok(dt) = self.to_datetime() {
-            write!(f, "{}", dt.to_rfc3339())
+            write!(f, "{}", dt)
         } else {
             Err(fmt::Error::default())
         }
@@ -83,3 +130,105 @@ pub fn expect_generalized_time(data: &[u8]) -> Result<(&[u8], GeneralizedTimeRef
 
     Ok((rest, GeneralizedTimeRef(value)))
 }
+
+#[test]
+fn test_generalized_time_parsing() {
+    let ref_dt = UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 44, 40).unwrap();
+    assert_eq!(
+        GeneralizedTimeRef(b"20210731164440Z")
+            .to_datetime()
+            .unwrap(),
+        ref_dt
+    );
+    assert_eq!(
+        GeneralizedTimeRef(b"20210731174440+0100")
+            .to_datetime()
+            .unwrap(),
+        ref_dt
+    );
+    assert_eq!(
+        GeneralizedTimeRef(b"20210731144440-02")
+            .to_datetime()
+            .unwrap(),
+        ref_dt
+    );
+    // local time
+    assert_eq!(
+        GeneralizedTimeRef(b"20210731164440").to_datetime().unwrap(),
+        ref_dt
+    );
+
+    // hour and minutes only
+    assert_eq!(
+        GeneralizedTimeRef(b"2021073116Z").to_datetime().unwrap(),
+        UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 0, 0).unwrap()
+    );
+    assert_eq!(
+        GeneralizedTimeRef(b"202107311644Z").to_datetime().unwrap(),
+        UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 44, 0).unwrap()
+    );
+}
+
+#[test]
+fn test_generalized_time_fractions() {
+    let ref_dt = UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 44, 40).unwrap();
+    assert_eq!(
+        GeneralizedTimeRef(b"20210731164440.5Z")
+            .to_datetime()
+            .unwrap(),
+        ref_dt.with_nanosecond(500_000_000).unwrap()
+    );
+    assert_eq!(
+        GeneralizedTimeRef(b"20210731164440,123456789123Z")
+            .to_datetime()
+            .unwrap(),
+        ref_dt.with_nanosecond(123_456_789).unwrap()
+    );
+    // fractions of minutes and hours
+    assert_eq!(
+        GeneralizedTimeRef(b"202107311644.5Z")
+            .to_datetime()
+            .unwrap(),
+        UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 44, 30).unwrap()
+    );
+    assert_eq!(
+        GeneralizedTimeRef(b"2021073116.25Z").to_datetime().unwrap(),
+        UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 15, 0).unwrap()
+    );
+}
+
+#[test]
+fn test_generalized_time_malformed() {
+    for input in [
+        &b""[..],
+        b"2021",
+        b"20210731",
+        b"20210731164440.Z",
+        b"20210731164440+1",
+        b"20210731164440Zjunk",
+        b"20210231164440Z",
+    ]
+    .iter()
+    {
+        assert!(GeneralizedTimeRef(input).to_datetime().is_err());
+    }
+}
+
+#[test]
+fn test_generalized_time_strict() {
+    assert!(GeneralizedTimeRef(b"20210731164440Z")
+        .to_datetime_strict()
+        .is_ok());
+    assert!(GeneralizedTimeRef(b"20210731164440.5Z")
+        .to_datetime_strict()
+        .is_err());
+    assert!(GeneralizedTimeRef(b"202107311644Z")
+        .to_datetime_strict()
+        .is_err());
+    assert!(GeneralizedTimeRef(b"20210731164440+0100")
+        .to_datetime_strict()
+        .is_err());
+    assert!(GeneralizedTimeRef(b"20210731164440")
+        .to_datetime_strict()
+        .is_err());
+}
@@ -1,9 +1,10 @@
 use super::super::error::ParseError;
 use crate::error::Error;
+use std::borrow::Cow;
 use std::fmt::{self, Debug, Display, Formatter};
 
-#[derive(PartialEq)]
-pub struct IA5StringRef<'a>(&'a [u8]);
+#[derive(PartialEq, Eq, Hash)]
+pub struct IA5StringRef<'a>(pub(crate) &'a [u8]);
 
 impl<'a> IA5StringRef<'a> {
     pub fn to_string(&self) -> Result<String, Error> {
@@ -12,15 +13,22 @@ impl<'a> IA5StringRef<'a> {
         }
         Err(Error::ParseError(ParseError::StringEncoding))
     }
+
+    /// the string as text, fails if it contains characters outside of ASCII
+    pub fn to_str(&self) -> Result<Cow<'a, str>, ParseError> {
+        if !self.0.iter().all(u8::is_ascii) {
+            return Err(ParseError::StringEncoding);
+        }
+        std::str::from_utf8(self.0)
+            .map(Cow::Borrowed)
+            .map_err(|_| ParseError::StringEncoding)
+    }
 }
 
 impl<'a> Display for IA5StringRef<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        if let Ok(s) = String::from_utf8(self.0.to_vec()) {
-            write!(f, "{}", s)
-        } else {
-            Err(fmt::Error::default())
-        }
+        let s = std::str::from_utf8(self.0).map_err(|_| fmt::Error)?;
+        f.write_str(s)
     }
 }
 
@@ -51,6 +51,13 @@ pub fn expect_integer(data: &[u8]) -> Result<(&[u8], IntegerRef), ParseError> {
     Ok((rest, IntegerRef(value)))
 }
 
+/// ENUMERATED values share the integer encoding
+pub fn expect_enumerated(data: &[u8]) -> Result<(&[u8], IntegerRef<'_>), ParseError> {
+    let (rest, value) = expect_type(data, DataType::Enumerated)?;
+
+    Ok((rest, IntegerRef(value)))
+}
+
 #[test]
 fn test_parse_int() {
     let i = IntegerRef(&[0x00]);
@@ -1,25 +1,7 @@
-use crate::error::Error;
-
 use super::error::ParseError;
+use crate::common::der::{length_octets, read_length, LengthRules};
 use std::convert::TryFrom;
 
-pub(crate) fn ascii_to_digit(d: u8) -> Result<u32, Error> {
-    if d >= 0x30 && d <= 0x39 {
-        Ok((d & 0x0f) as u32)
-    } else {
-        Err(Error::ParseError(ParseError::MalformedData))
-    }
-}
-
-pub(crate) fn ascii_slice_to_u32(data: &[u8]) -> Result<u32, Error> {
-    let mut res: u32 = 0;
-    for &ch in data {
-        res = res * 10 + ascii_to_digit(ch)?;
-    }
-
-    Ok(res)
-}
-
 #[derive(Eq, PartialEq)]
 pub enum DataType {
     Boolean,
@@ -28,6 +10,7 @@ pub enum DataType {
     OctetString,
     Null,
     ObjectIdentifier,
+    Enumerated,
     Utf8String,
     Sequence,
     Set,
@@ -58,6 +41,7 @@ impl TryFrom<u8> for DataType {
             (0x04, _) => Ok(DataType::OctetString),
             (0x05, false) => Ok(DataType::Null),
             (0x06, false) => Ok(DataType::ObjectIdentifier),
+            (0x0a, false) => Ok(DataType::Enumerated),
             (0x0c, _) => Ok(DataType::Utf8String),
             (0x10, true) => Ok(DataType::Sequence),
             (0x11, true) => Ok(DataType::Set),
@@ -83,6 +67,7 @@ impl From<DataType> for u8 {
             DataType::OctetString => 0x04,
             DataType::Null => 0x05,
             DataType::ObjectIdentifier => 0x06,
+            DataType::Enumerated => 0x0a,
             DataType::Utf8String => 0x0c,
             DataType::Sequence => 0x10,
             DataType::Set => 0x11,
@@ -105,75 +90,30 @@ impl DataType {
 }
 
 pub fn encode_tlv(tag: u8, value: &[u8]) -> Vec<u8> {
-    let mut res = Vec::new();
+    let (length_octets, length_len) = length_octets(value.len());
+    let mut res = Vec::with_capacity(1 + length_len + value.len());
     res.push(tag);
-
-    let len = value.len();
-    if len <= 127 {
-        // short encoding
-        res.push(len as u8);
-    } else {
-        // long encoding
-        let length_bytes = len.to_be_bytes();
-        let pos = length_bytes.iter().position(|b| *b != 0x00).unwrap(); // we can unwrap, since len > 127
-        let length_bytes_no_prefix = &length_bytes[pos..];
-        let length_length = length_bytes_no_prefix.len() as u8; // length of length is guaranteed to be <= 8 on 64bit systems
-        let length_length_flagged = length_length | 0x80;
-        res.push(length_length_flagged);
-        res.extend_from_slice(length_bytes_no_prefix);
-    }
+    res.extend_from_slice(&length_octets[..length_len]);
     res.extend_from_slice(value);
 
     res
 }
 
 /// returns (rest, tag, value)
-fn get_tlv(data: &[u8]) -> Result<(&[u8], u8, &[u8]), ParseError> {
+pub(crate) fn get_tlv(data: &[u8]) -> Result<(&[u8], u8, &[u8]), ParseError> {
     if data.len() < 2 {
         // we need at least a tag and a length (which may be 0)
         return Err(ParseError::MalformedData);
     }
 
-    let mut consumed: usize = 0;
-
     // we only handle single byte tags
     let tag = data[0];
-    consumed += 1;
-
-    let first_length_octet = data[consumed];
-    consumed += 1;
-
-    let long_length_encoding = first_length_octet & 0x80 == 0x80;
-
-    let length;
-    if long_length_encoding {
-        let length_length = (first_length_octet & 0x7f) as usize;
-        if length_length > 8 {
-            return Err(ParseError::InvalidLength);
-        }
-        if length_length + 2 > data.len() {
-            return Err(ParseError::InvalidLength);
-        }
-        let mut tmp_length: usize = 0;
-        for &octet in &data[2..2 + length_length] {
-            consumed += 1;
-            tmp_length = tmp_length << 8;
-            tmp_length += octet as usize;
-        }
-        length = tmp_length;
-    } else {
-        length = (first_length_octet & 0x7f) as usize;
-    }
-
-    if length + consumed > data.len() {
+    let (data, length) = read_length(&data[1..], LengthRules::Ber)?;
+    if length > data.len() {
         return Err(ParseError::InvalidLength);
     }
 
-    Ok((
-        &data[consumed + length..],
-        tag,
-        &data[consumed..consumed + length],
-    ))
+    Ok((&data[length..], tag, &data[..length]))
 }
 
 pub fn expect_type(data: &[u8], expected: DataType) -> Result<(&[u8], &[u8]), ParseError> {
@@ -226,6 +166,22 @@ pub fn try_get_explicit(data: &[u8], expected: ExplicitTag) -> Result<(&[u8], &[
     Ok((rest, inner_data))
 }
 
+/// returns (rest, value) of a context specific, implicitly tagged value with tag number `expected`.
+/// the value may be primitive or constructed, depending on the type it replaces.
+pub fn try_get_implicit(data: &[u8], expected: u8) -> Result<(&[u8], &[u8]), ParseError> {
+    let (rest, tag, inner_data) = get_tlv(data)?;
+    if tag & 0xc0 != 0x80 {
+        // this tag is not context specific
+        return Err(ParseError::UnexpectedTag(tag));
+    }
+
+    if tag & 0x1f != expected {
+        return Err(ParseError::UnexpectedTag(tag));
+    }
+
+    Ok((rest, inner_data))
+}
+
 pub fn wrap_in_explicit_tag(inner: &[u8], tag: ExplicitTag) -> Vec<u8> {
     encode_tlv(tag.get_identifier_octet(), inner)
 }
@@ -281,6 +237,18 @@ mod tests {
         assert!(r.is_err());
     }
 
+    #[test]
+    fn test_try_get_implicit() {
+        // [0] IMPLICIT NULL and [1] IMPLICIT SEQUENCE
+        let d = hex::decode("8000a103020101").unwrap();
+        let (rest, inner) = try_get_implicit(&d, 0).unwrap();
+        assert!(inner.is_empty());
+        assert!(try_get_implicit(rest, 0).is_err());
+        let (rest, inner) = try_get_implicit(rest, 1).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(inner, &[0x02, 0x01, 0x01]);
+    }
+
     #[test]
     fn test_expect_sequence() {
         let d = hex::decode("3020170d3134303830313030303030305a180f32303530303930343030303030305a")
@@ -304,12 +272,13 @@ pub use bmp_string::BMPStringRef;
 pub use boolean::{expect_boolean, Boolean};
 pub use generalized_time::{expect_generalized_time, GeneralizedTimeRef};
 pub use ia5_string::IA5StringRef;
-pub use integer::{expect_integer, IntegerRef};
+pub use integer::{expect_enumerated, expect_integer, IntegerRef};
 pub use null::Null;
 pub use object_identifier::{expect_object_identifier, ObjectIdentifier, ObjectIdentifierRef};
 pub use octet_string::{expect_octet_string, OctetStringRef};
 pub use printable_string::PrintableStringRef;
 pub use t61_string::T61StringRef;
+pub use tree::{DerElementIter, DerElementRef, DerNode};
 pub use utc_time::{expect_utc_time, UTCTimeRef};
 pub use utf8_string::Utf8StringRef;
 pub use visible_string::VisibleStringRef;
@@ -326,6 +295,7 @@ mod object_identifier;
 mod octet_string;
 mod printable_string;
 mod t61_string;
+mod tree;
 mod utc_time;
 mod utf8_string;
 mod visible_string;
@@ -63,7 +63,7 @@ impl<'a> Into<ObjectIdentifier> for ObjectIdentifierRef<'a> {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ObjectIdentifierRef<'a>(pub(crate) &'a [u8]);
 
 impl<'a> ObjectIdentifierRef<'a> {
@@ -71,43 +71,63 @@ impl<'a> ObjectIdentifierRef<'a> {
         Self(data)
     }
 
-    pub fn as_bytes(&self) -> &[u8] {
+    pub const fn as_bytes(&self) -> &'a [u8] {
         self.0
     }
 
-    pub fn to_parts(&self) -> Vec<u64> {
-        let mut res = Vec::new();
-        let data = self.0;
-        let y = data[0] % 40;
-        let x = (data[0] - y) / 40;
-
-        res.push(x as u64);
-        res.push(y as u64);
+    /// compares with the content octets of another OID, e.g. `oids::SECP256R1.as_bytes()`,
+    /// without decoding either of them
+    pub fn eq_oid(&self, other: &[u8]) -> bool {
+        self.0 == other
+    }
 
-        let mut sub_id: u64 = 0;
+    /// returns true if this OID is `arc` or lies below it, e.g. every extended key usage purpose
+    /// is below id-kp (1.3.6.1.5.5.7.3). `arc` is given as content octets, like `eq_oid`.
+    pub fn starts_with_arc(&self, arc: &[u8]) -> bool {
+        // only the last octet of a subidentifier has bit 8 clear, so a matching prefix that ends
+        // in such an octet ends on a subidentifier boundary
+        match arc.last() {
+            Some(last) if last & 0x80 == 0 => self.0.starts_with(arc),
+            _ => false,
+        }
+    }
 
-        for &octet in &data[1..] {
-            sub_id = sub_id << 7;
-            sub_id += (octet & 0x7f) as u64;
+    pub fn to_parts(&self) -> Vec<u64> {
+        self.arcs().collect()
+    }
 
-            if octet & 0x80 == 0 {
-                //last part of subid.
-                res.push(sub_id);
-                sub_id = 0;
+    /// decodes the arcs one by one, the first subidentifier holds the first two of them
+    fn arcs(&self) -> impl Iterator<Item = u64> + 'a {
+        let mut data = self.0;
+        let mut sub_ids = std::iter::from_fn(move || {
+            let mut sub_id: u64 = 0;
+            while let Some((&octet, rest)) = data.split_first() {
+                data = rest;
+                sub_id = (sub_id << 7) + (octet & 0x7f) as u64;
+                if octet & 0x80 == 0 {
+                    //last part of subid.
+                    return Some(sub_id);
+                }
             }
-        }
-
-        res
+            None
+        });
+
+        let first = sub_ids.next().map(|sub_id| match sub_id {
+            0..=39 => [0, sub_id],
+            40..=79 => [1, sub_id - 40],
+            _ => [2, sub_id - 80],
+        });
+        first.into_iter().flatten().chain(sub_ids)
     }
 }
 
 impl<'a> Display for ObjectIdentifierRef<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        for (index, &sub_id) in self.to_parts().iter().enumerate() {
+        for (index, arc) in self.arcs().enumerate() {
             if index > 0 {
-                write!(f, ".")?;
+                f.write_str(".")?;
             }
-            write!(f, "{}", sub_id)?;
+            write!(f, "{}", arc)?;
         }
         Ok(())
     }
@@ -126,3 +146,46 @@ pub fn expect_object_identifier(data: &[u8]) -> Result<(&[u8], ObjectIdentifierR
 
     Ok((rest, ObjectIdentifierRef(inner)))
 }
+
+#[test]
+fn test_display() {
+    use crate::oids;
+
+    assert_eq!(oids::SUBJECT_ALT_NAME.to_string(), "2.5.29.17");
+    assert_eq!(oids::KP_SERVER_AUTH.to_string(), "1.3.6.1.5.5.7.3.1");
+    // the first subidentifier takes more than one octet, 2.999.3
+    let oid = ObjectIdentifierRef::new(&[0x88, 0x37, 0x03]);
+    assert_eq!(oid.to_string(), "2.999.3");
+    assert_eq!(oid.to_parts(), vec![2, 999, 3]);
+}
+
+#[test]
+fn test_eq_oid() {
+    use crate::oids;
+
+    assert!(oids::SUBJECT_ALT_NAME.eq_oid(&[0x55, 0x1d, 0x11]));
+    assert!(!oids::SUBJECT_ALT_NAME.eq_oid(oids::ISSUER_ALT_NAME.as_bytes()));
+    assert!(!oids::SUBJECT_ALT_NAME.eq_oid(&[0x55, 0x1d]));
+}
+
+#[test]
+fn test_starts_with_arc() {
+    use crate::oids;
+
+    // id-kp, 1.3.6.1.5.5.7.3
+    let id_kp = [0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03];
+    assert!(oids::KP_SERVER_AUTH.starts_with_arc(&id_kp));
+    assert!(oids::KP_OCSP_SIGNING.starts_with_arc(&id_kp));
+    assert!(ObjectIdentifierRef::new(&id_kp).starts_with_arc(&id_kp));
+    assert!(!oids::AD_OCSP.starts_with_arc(&id_kp));
+
+    // 2.23.140.1.2 is below 2.23.140 but 2.23.140 is not below 2.23.140.1.2
+    assert!(oids::CABF_DOMAIN_VALIDATED.starts_with_arc(&[0x67, 0x81, 0x0c]));
+    assert!(!ObjectIdentifierRef::new(&[0x67, 0x81, 0x0c])
+        .starts_with_arc(oids::CABF_DOMAIN_VALIDATED.as_bytes()));
+
+    // 1.2.840.113549 starts with the octet 0x86 of the multi-octet 840, which is not an arc
+    assert!(!oids::RSA_ENCRYPTION.starts_with_arc(&[0x2a, 0x86]));
+    assert!(oids::RSA_ENCRYPTION.starts_with_arc(&[0x2a, 0x86, 0x48]));
+    assert!(!oids::RSA_ENCRYPTION.starts_with_arc(&[]));
+}
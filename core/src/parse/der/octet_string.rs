@@ -1,9 +1,15 @@
 use super::super::error::ParseError;
 use super::{expect_type, DataType};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct OctetStringRef<'a>(&'a [u8]);
 
+impl<'a> OctetStringRef<'a> {
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
 pub fn expect_octet_string(data: &[u8]) -> Result<(&[u8], OctetStringRef), ParseError> {
     let (rest, value) = expect_type(data, DataType::OctetString)?;
 
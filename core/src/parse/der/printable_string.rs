@@ -1,3 +1,6 @@
+use super::super::error::ParseError;
+use crate::common::der::is_printable;
+use std::borrow::Cow;
 use std::fmt::{self, Debug, Display, Formatter};
 
 #[derive(PartialEq, Eq, Hash)]
@@ -7,15 +10,22 @@ impl<'a> PrintableStringRef<'a> {
     pub fn new(data: &'a [u8]) -> Self {
         Self(data)
     }
+
+    /// the string as text, fails if it contains characters outside of the PrintableString alphabet
+    pub fn to_str(&self) -> Result<Cow<'a, str>, ParseError> {
+        if !self.0.iter().all(|b| is_printable(*b)) {
+            return Err(ParseError::StringEncoding);
+        }
+        std::str::from_utf8(self.0)
+            .map(Cow::Borrowed)
+            .map_err(|_| ParseError::StringEncoding)
+    }
 }
 
 impl<'a> Display for PrintableStringRef<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        if let Ok(s) = String::from_utf8(self.0.to_vec()) {
-            write!(f, "{}", s)
-        } else {
-            Err(fmt::Error::default())
-        }
+        let s = std::str::from_utf8(self.0).map_err(|_| fmt::Error)?;
+        f.write_str(s)
     }
 }
 
@@ -1,11 +1,27 @@
-use std::fmt::{self, Debug, Display, Formatter};
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Display, Formatter, Write};
 
-#[derive(PartialEq)]
-pub struct T61StringRef<'a>(&'a [u8]);
+#[derive(PartialEq, Eq, Hash)]
+pub struct T61StringRef<'a>(pub(crate) &'a [u8]);
+
+impl<'a> T61StringRef<'a> {
+    /// the string as text. in practice T61String is used for Latin-1, so that is how it's
+    /// decoded; the escape sequences of real T.61 are not supported.
+    pub fn to_str(&self) -> Cow<'a, str> {
+        match std::str::from_utf8(self.0) {
+            Ok(s) if self.0.is_ascii() => Cow::Borrowed(s),
+            _ => Cow::Owned(self.0.iter().map(|b| *b as char).collect()),
+        }
+    }
+}
 
 impl<'a> Display for T61StringRef<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        write!(f, "{}", String::from_utf8_lossy(self.0))
+        // the same Latin-1 decoding as to_str
+        match std::str::from_utf8(self.0) {
+            Ok(s) if self.0.is_ascii() => f.write_str(s),
+            _ => self.0.iter().try_for_each(|b| f.write_char(*b as char)),
+        }
     }
 }
 
@@ -14,3 +30,12 @@ impl<'a> Debug for T61StringRef<'a> {
         write!(f, "\"{}\"", self)
     }
 }
+
+#[test]
+fn test_t61_string_display() {
+    assert_eq!(T61StringRef(b"Muenchen").to_string(), "Muenchen");
+    // Latin-1, like to_str
+    let s = T61StringRef(&[0x4d, 0xfc, 0x6e, 0x63, 0x68, 0x65, 0x6e]);
+    assert_eq!(s.to_string(), "München");
+    assert_eq!(s.to_string(), s.to_str());
+}
@@ -0,0 +1,333 @@
+use super::super::error::ParseError;
+use super::{
+    get_tlv, BMPStringRef, DataType, GeneralizedTimeRef, IA5StringRef, IntegerRef,
+    ObjectIdentifierRef, PrintableStringRef, T61StringRef, UTCTimeRef, Utf8StringRef,
+    VisibleStringRef,
+};
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+
+/// nesting deeper than this is rejected, so hostile input can't exhaust the stack
+const MAX_DEPTH: usize = 64;
+
+/// a generic DER element, for inspecting structures this crate has no type for. constructed
+/// elements are decoded into their children, primitive ones are kept as content octets.
+///
+/// the Display impl prints an indented dump with one element per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerNode<'a> {
+    tag: u8,
+    value: &'a [u8],
+    children: Vec<DerNode<'a>>,
+}
+
+impl<'a> DerNode<'a> {
+    /// parses exactly one element, that must take up all of `data`
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, node) = Self::parse(data, 0)?;
+        if !rest.is_empty() {
+            return Err(ParseError::MalformedData);
+        }
+
+        Ok(node)
+    }
+
+    fn parse(data: &'a [u8], depth: usize) -> Result<(&'a [u8], Self), ParseError> {
+        if depth > MAX_DEPTH {
+            return Err(ParseError::MalformedData);
+        }
+        let (rest, tag, value) = get_tlv(data)?;
+
+        let mut children = Vec::new();
+        if tag & 0x20 == 0x20 {
+            let mut pos = value;
+            while !pos.is_empty() {
+                let (next, child) = Self::parse(pos, depth + 1)?;
+                children.push(child);
+                pos = next;
+            }
+        }
+
+        Ok((
+            rest,
+            Self {
+                tag,
+                value,
+                children,
+            },
+        ))
+    }
+
+    /// the identifier octet
+    pub fn tag(&self) -> u8 {
+        self.tag
+    }
+
+    pub fn is_constructed(&self) -> bool {
+        self.tag & 0x20 == 0x20
+    }
+
+    /// the content octets
+    pub fn value(&self) -> &'a [u8] {
+        self.value
+    }
+
+    /// the elements inside a constructed element, empty for primitive ones
+    pub fn children(&self) -> &[DerNode<'a>] {
+        &self.children
+    }
+
+    fn fmt_indented(&self, f: &mut Formatter, depth: usize) -> fmt::Result {
+        write!(f, "{:width$}", "", width = depth * 2)?;
+        self.fmt_tag(f)?;
+        if self.is_constructed() {
+            writeln!(f)?;
+            for child in &self.children {
+                child.fmt_indented(f, depth + 1)?;
+            }
+            Ok(())
+        } else {
+            self.fmt_value(f)?;
+            writeln!(f)
+        }
+    }
+
+    fn fmt_tag(&self, f: &mut Formatter) -> fmt::Result {
+        let number = self.tag & 0x1f;
+        match self.tag & 0xc0 {
+            0x40 => return write!(f, "[APPLICATION {}]", number),
+            0x80 => return write!(f, "[{}]", number),
+            0xc0 => return write!(f, "[PRIVATE {}]", number),
+            _ => {}
+        }
+
+        let name = match DataType::try_from(self.tag) {
+            Ok(DataType::Boolean) => "BOOLEAN",
+            Ok(DataType::Integer) => "INTEGER",
+            Ok(DataType::BitString) => "BIT STRING",
+            Ok(DataType::OctetString) => "OCTET STRING",
+            Ok(DataType::Null) => "NULL",
+            Ok(DataType::ObjectIdentifier) => "OBJECT IDENTIFIER",
+            Ok(DataType::Enumerated) => "ENUMERATED",
+            Ok(DataType::Utf8String) => "UTF8String",
+            Ok(DataType::Sequence) => "SEQUENCE",
+            Ok(DataType::Set) => "SET",
+            Ok(DataType::PrintableString) => "PrintableString",
+            Ok(DataType::T61String) => "T61String",
+            Ok(DataType::IA5String) => "IA5String",
+            Ok(DataType::UTCTime) => "UTCTime",
+            Ok(DataType::GeneralizedTime) => "GeneralizedTime",
+            Ok(DataType::VisibleString) => "VisibleString",
+            Ok(DataType::BMPString) => "BMPString",
+            Err(_) => return write!(f, "[UNIVERSAL {}]", number),
+        };
+        write!(f, "{}", name)
+    }
+
+    fn fmt_value(&self, f: &mut Formatter) -> fmt::Result {
+        let data = self.value;
+        // values that can be shown as text are written as such, the rest falls through to hex
+        match DataType::try_from(self.tag) {
+            Ok(DataType::Null) if data.is_empty() => return Ok(()),
+            Ok(DataType::Boolean) if data.len() == 1 => return write!(f, " {}", data[0] != 0),
+            Ok(DataType::Integer) | Ok(DataType::Enumerated) if !data.is_empty() => {
+                return write!(f, " {}", IntegerRef(data).to_big_int());
+            }
+            Ok(DataType::ObjectIdentifier) if !data.is_empty() => {
+                return write!(f, " {}", ObjectIdentifierRef(data));
+            }
+            Ok(DataType::Utf8String) => {
+                if let Ok(s) = Utf8StringRef(data).to_str() {
+                    return write!(f, " {}", s);
+                }
+            }
+            Ok(DataType::PrintableString) => {
+                if let Ok(s) = PrintableStringRef(data).to_str() {
+                    return write!(f, " {}", s);
+                }
+            }
+            Ok(DataType::T61String) => return write!(f, " {}", T61StringRef(data)),
+            Ok(DataType::IA5String) => {
+                if let Ok(s) = IA5StringRef(data).to_str() {
+                    return write!(f, " {}", s);
+                }
+            }
+            Ok(DataType::VisibleString) => {
+                if let Ok(s) = VisibleStringRef(data).to_str() {
+                    return write!(f, " {}", s);
+                }
+            }
+            Ok(DataType::BMPString) if BMPStringRef(data).is_valid() => {
+                return write!(f, " {}", BMPStringRef(data));
+            }
+            Ok(DataType::UTCTime) => {
+                if let Ok(time) = UTCTimeRef(data).to_datetime() {
+                    return write!(f, " {}", time);
+                }
+            }
+            Ok(DataType::GeneralizedTime) => {
+                if let Ok(time) = GeneralizedTimeRef(data).to_datetime() {
+                    return write!(f, " {}", time);
+                }
+            }
+            _ => {}
+        }
+
+        if data.is_empty() {
+            return Ok(());
+        }
+        write!(f, " ")?;
+        for b in data {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Display for DerNode<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// a generic DER element like [`DerNode`], but the children of constructed elements are decoded
+/// lazily while iterating instead of up front, so walking a structure allocates nothing. the
+/// flip side is that malformed children are only noticed once the iteration reaches them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DerElementRef<'a> {
+    tag: u8,
+    value: &'a [u8],
+}
+
+impl<'a> DerElementRef<'a> {
+    /// parses exactly one element, that must take up all of `data`
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, element) = Self::parse(data)?;
+        if !rest.is_empty() {
+            return Err(ParseError::MalformedData);
+        }
+
+        Ok(element)
+    }
+
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, tag, value) = get_tlv(data)?;
+
+        Ok((rest, Self { tag, value }))
+    }
+
+    /// the identifier octet
+    pub fn tag(&self) -> u8 {
+        self.tag
+    }
+
+    pub fn is_constructed(&self) -> bool {
+        self.tag & 0x20 == 0x20
+    }
+
+    /// the content octets
+    pub fn value(&self) -> &'a [u8] {
+        self.value
+    }
+
+    /// the elements inside a constructed element, nothing for primitive ones
+    pub fn children(&self) -> DerElementIter<'a> {
+        DerElementIter {
+            pos: if self.is_constructed() {
+                self.value
+            } else {
+                &[]
+            },
+            failure: false,
+        }
+    }
+}
+
+pub struct DerElementIter<'a> {
+    pos: &'a [u8],
+    failure: bool,
+}
+
+impl<'a> Iterator for DerElementIter<'a> {
+    type Item = Result<DerElementRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        if self.failure {
+            //this iterator is in error state, continue returning ParseError
+            return Some(Err(ParseError::MalformedData));
+        }
+        match DerElementRef::parse(self.pos) {
+            Ok((rest, element)) => {
+                self.pos = rest;
+                Some(Ok(element))
+            }
+            Err(e) => {
+                self.failure = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_der_tree() {
+    // SEQUENCE { OID 2.5.29.19, [0] { INTEGER 5 }, OCTET STRING 0102 }
+    let data = [
+        0x30, 0x0e, 0x06, 0x03, 0x55, 0x1d, 0x13, 0xa0, 0x03, 0x02, 0x01, 0x05, 0x04, 0x02, 0x01,
+        0x02,
+    ];
+    let tree = DerNode::from_slice(&data).unwrap();
+
+    assert_eq!(tree.tag(), 0x30);
+    assert_eq!(tree.children().len(), 3);
+    assert_eq!(tree.children()[1].children()[0].value(), &[0x05]);
+    assert_eq!(
+        tree.to_string(),
+        "SEQUENCE\n  OBJECT IDENTIFIER 2.5.29.19\n  [0]\n    INTEGER 5\n  OCTET STRING 0102\n"
+    );
+
+    assert!(DerNode::from_slice(&data[..data.len() - 1]).is_err());
+    assert!(DerNode::from_slice(&[0x05, 0x00, 0x05, 0x00]).is_err());
+}
+
+#[test]
+fn test_der_tree_depth() {
+    let nested = |depth: usize| {
+        let mut data = vec![0x30, 0x00];
+        for _ in 0..depth {
+            data = super::encode_tlv(0x30, &data);
+        }
+        data
+    };
+
+    assert!(DerNode::from_slice(&nested(MAX_DEPTH)).is_ok());
+    assert!(DerNode::from_slice(&nested(MAX_DEPTH + 1)).is_err());
+}
+
+#[test]
+fn test_der_element() {
+    // SEQUENCE { OID 2.5.29.19, [0] { INTEGER 5 }, OCTET STRING 0102 }
+    let data = [
+        0x30, 0x0e, 0x06, 0x03, 0x55, 0x1d, 0x13, 0xa0, 0x03, 0x02, 0x01, 0x05, 0x04, 0x02, 0x01,
+        0x02,
+    ];
+    let element = DerElementRef::from_slice(&data).unwrap();
+
+    assert_eq!(element.tag(), 0x30);
+    let children: Vec<_> = element.children().collect::<Result<_, _>>().unwrap();
+    assert_eq!(children.len(), 3);
+    assert_eq!(children[0].tag(), 0x06);
+    assert_eq!(children[2].children().count(), 0);
+    let tagged = children[1].children().next().unwrap().unwrap();
+    assert_eq!(tagged.value(), &[0x05]);
+
+    // the truncated last child is only found when the iteration gets to it
+    let element = DerElementRef::from_slice(&[0x30, 0x05, 0x05, 0x00, 0x04, 0x02, 0x01]).unwrap();
+    let mut children = element.children();
+    assert!(children.next().unwrap().is_ok());
+    assert!(children.next().unwrap().is_err());
+    assert!(children.next().unwrap().is_err());
+}
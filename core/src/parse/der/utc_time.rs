@@ -1,65 +1,51 @@
 use super::super::error::ParseError;
-use super::{ascii_slice_to_u32, expect_type, DataType};
+use super::{expect_type, generalized_time::take_digits, DataType};
+use crate::common::time::UtcDateTime;
 use crate::error::Error;
-use chrono::prelude::*;
 use std::fmt::{self, Debug, Display, Formatter};
 
 #[derive(PartialEq)]
-pub struct UTCTimeRef<'a>(&'a [u8]);
+pub struct UTCTimeRef<'a>(pub(crate) &'a [u8]);
 
 impl<'a> UTCTimeRef<'a> {
-    pub fn to_datetime(&self) -> Result<DateTime<Utc>, Error> {
-        let data = self.0;
-        let year = ascii_slice_to_u32(&data[..2])?;
-        // the two digits represent dates from 1950 to 2050
-        let year = if year > 50 { year + 1900 } else { year + 2000 };
-
-        let month = ascii_slice_to_u32(&data[2..4])?;
-        let day = ascii_slice_to_u32(&data[4..6])?;
-
-        let hour = ascii_slice_to_u32(&data[6..8])?;
-        let minute = ascii_slice_to_u32(&data[8..10])?;
-
-        let mut data = &data[10..];
-        let second;
-        if data.len() == 3 || data.len() == 7 {
-            second = ascii_slice_to_u32(&data[0..2])?;
-            data = &data[2..];
-        } else {
-            second = 0;
-        }
+    /// accepts YYMMDDhhmm with optional seconds, followed by 'Z' or an offset like "+0100"
+    pub fn to_datetime(&self) -> Result<UtcDateTime, Error> {
+        parse_utc_time(self.0).ok_or(Error::ParseError(ParseError::MalformedData))
+    }
+}
 
-        let utc_offset: i32;
-        if data[0] == 0x5a {
-            // 'Z'
-            utc_offset = 0;
-        } else {
-            assert!(data.len() == 5);
-            let hour_offset = ascii_slice_to_u32(&data[1..3])?;
-            let minute_offset = ascii_slice_to_u32(&data[3..5])?;
-            let factor = if data[0] == 0x2d {
-                // '-'
-                -1
-            } else {
-                1
-            };
-
-            utc_offset = factor * (hour_offset * 3600 + minute_offset * 60) as i32;
+fn parse_utc_time(data: &[u8]) -> Option<UtcDateTime> {
+    let (data, year) = take_digits(data, 2)?;
+    // the two digits represent dates from 1950 to 2049
+    let year = if year >= 50 { year + 1900 } else { year + 2000 };
+    let (data, month) = take_digits(data, 2)?;
+    let (data, day) = take_digits(data, 2)?;
+    let (data, hour) = take_digits(data, 2)?;
+    let (data, minute) = take_digits(data, 2)?;
+    let (data, second) = take_digits(data, 2).unwrap_or((data, 0));
+
+    let utc_offset: i64 = match data {
+        [b'Z'] => 0,
+        [sign @ b'+', rest @ ..] | [sign @ b'-', rest @ ..] => {
+            let (rest, hour_offset) = take_digits(rest, 2)?;
+            let (rest, minute_offset) = take_digits(rest, 2)?;
+            if !rest.is_empty() || hour_offset > 23 || minute_offset > 59 {
+                return None;
+            }
+            let factor = if *sign == b'-' { -1 } else { 1 };
+            factor * (hour_offset * 3600 + minute_offset * 60) as i64
         }
+        _ => return None,
+    };
 
-        let offset = FixedOffset::east(utc_offset);
-        let dt = offset
-            .ymd(year as i32, month, day)
-            .and_hms(hour, minute, second);
-
-        Ok(dt.with_timezone(&Utc))
-    }
+    UtcDateTime::from_ymd_hms(year as i32, month, day, hour, minute, second)?
+        .checked_add_seconds(-utc_offset)
 }
 
 impl<'a> Display for UTCTimeRef<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
         if let Ok(dt) = self.to_datetime() {
-            write!(f, "{}", dt.to_rfc3339())
+            write!(f, "{}", dt)
         } else {
             Err(fmt::Error::default())
         }
@@ -81,9 +67,8 @@ pub fn expect_utc_time(data: &[u8]) -> Result<(&[u8], UTCTimeRef), ParseError> {
 #[test]
 fn test_utc_time_parsing_no_secs_zulu() {
     //YYMMDDhhmmZ
-    let ref_dt = Utc.ymd(2021, 7, 31).and_hms(16, 44, 00);
-    let d = ref_dt.format("%y%m%d%H%MZ").to_string();
-    let utc = UTCTimeRef(&d.as_bytes());
+    let ref_dt = UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 44, 00).unwrap();
+    let utc = UTCTimeRef(b"2107311644Z");
     let res = utc.to_datetime();
     assert_eq!(res.unwrap(), ref_dt);
 }
@@ -91,31 +76,26 @@ fn test_utc_time_parsing_no_secs_zulu() {
 #[test]
 fn test_utc_time_parsing_no_secs_pos_tz_offset() {
     // YYMMDDhhmm+hh'mm'
-    let offset = FixedOffset::east(3600);
-    let ref_dt_no_secs = offset.ymd(2021, 7, 31).and_hms(16, 44, 00);
-    let d = ref_dt_no_secs.format("%y%m%d%H%M%z").to_string();
-    let utc = UTCTimeRef(&d.as_bytes());
+    let ref_dt = UtcDateTime::from_ymd_hms(2021, 7, 31, 15, 44, 00).unwrap();
+    let utc = UTCTimeRef(b"2107311644+0100");
     let res = utc.to_datetime().unwrap();
-    assert_eq!(res, ref_dt_no_secs);
+    assert_eq!(res, ref_dt);
 }
 
 #[test]
 fn test_utc_time_parsing_no_secs_neg_tz_offset() {
     // YYMMDDhhmm-hh'mm'
-    let offset = FixedOffset::west(3600);
-    let ref_dt_no_secs = offset.ymd(2021, 7, 31).and_hms(16, 44, 00);
-    let d = ref_dt_no_secs.format("%y%m%d%H%M%z").to_string();
-    let utc = UTCTimeRef(&d.as_bytes());
+    let ref_dt = UtcDateTime::from_ymd_hms(2021, 7, 31, 17, 44, 00).unwrap();
+    let utc = UTCTimeRef(b"2107311644-0100");
     let res = utc.to_datetime().unwrap();
-    assert_eq!(res, ref_dt_no_secs);
+    assert_eq!(res, ref_dt);
 }
 
 #[test]
 fn test_utc_time_parsing_with_secs_zulu() {
     // YYMMDDhhmmssZ
-    let ref_dt = Utc.ymd(2021, 7, 31).and_hms(16, 44, 40);
-    let d = ref_dt.format("%y%m%d%H%M%SZ").to_string();
-    let utc = UTCTimeRef(&d.as_bytes());
+    let ref_dt = UtcDateTime::from_ymd_hms(2021, 7, 31, 16, 44, 40).unwrap();
+    let utc = UTCTimeRef(b"210731164440Z");
     let res = utc.to_datetime();
     assert_eq!(res.unwrap(), ref_dt);
 }
@@ -123,21 +103,70 @@ fn test_utc_time_parsing_with_secs_zulu() {
 #[test]
 fn test_utc_time_parsing_with_secs_pos_tz_offset() {
     // YYMMDDhhmmss+hh'mm'
-    let offset = FixedOffset::east(3600);
-    let ref_dt_no_secs = offset.ymd(2021, 7, 31).and_hms(16, 44, 00);
-    let d = ref_dt_no_secs.format("%y%m%d%H%M%S%z").to_string();
-    let utc = UTCTimeRef(&d.as_bytes());
+    let ref_dt = UtcDateTime::from_ymd_hms(2021, 7, 31, 15, 44, 00).unwrap();
+    let utc = UTCTimeRef(b"210731164400+0100");
     let res = utc.to_datetime().unwrap();
-    assert_eq!(res, ref_dt_no_secs);
+    assert_eq!(res, ref_dt);
 }
 
 #[test]
 fn test_utc_time_parsing_with_secs_neg_tz_offset() {
     // YYMMDDhhmmss-hh'mm'
-    let offset = FixedOffset::west(3600);
-    let ref_dt_no_secs = offset.ymd(2021, 7, 31).and_hms(16, 44, 00);
-    let d = ref_dt_no_secs.format("%y%m%d%H%M%S%z").to_string();
-    let utc = UTCTimeRef(&d.as_bytes());
+    let ref_dt = UtcDateTime::from_ymd_hms(2021, 7, 31, 17, 44, 00).unwrap();
+    let utc = UTCTimeRef(b"210731164400-0100");
     let res = utc.to_datetime().unwrap();
-    assert_eq!(res, ref_dt_no_secs);
+    assert_eq!(res, ref_dt);
+}
+
+#[test]
+fn test_utc_time_parsing_invalid_date() {
+    let utc = UTCTimeRef(b"210230164400Z");
+    assert!(utc.to_datetime().is_err());
+}
+
+#[test]
+fn test_utc_time_parsing_malformed() {
+    for malformed in [
+        &b""[..],
+        b"21",
+        b"2107311644",
+        b"2107311644400",
+        b"210731164440",
+        b"21073116444Z",
+        b"210731164440+01",
+        b"210731164440+0100Z",
+        b"2107311644-2400",
+        b"21O7311644Z",
+    ]
+    .iter()
+    {
+        assert!(UTCTimeRef(malformed).to_datetime().is_err());
+    }
+}
+
+#[test]
+fn test_utc_time_parsing_century() {
+    let utc = UTCTimeRef(b"500101000000Z");
+    assert_eq!(
+        utc.to_datetime().unwrap(),
+        UtcDateTime::from_ymd_hms(1950, 1, 1, 0, 0, 0).unwrap()
+    );
+    let utc = UTCTimeRef(b"491231235959Z");
+    assert_eq!(
+        utc.to_datetime().unwrap(),
+        UtcDateTime::from_ymd_hms(2049, 12, 31, 23, 59, 59).unwrap()
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_utc_time_to_chrono() {
+    use chrono::{DateTime, Utc};
+
+    let ref_dt = DateTime::parse_from_rfc3339("2021-07-31T16:44:40+00:00")
+        .unwrap()
+        .with_timezone(&Utc);
+    let utc = UTCTimeRef(b"210731164440Z");
+    let res: DateTime<Utc> = utc.to_datetime().unwrap().into();
+    assert_eq!(res, ref_dt);
 }
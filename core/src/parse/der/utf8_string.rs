@@ -1,15 +1,22 @@
+use super::super::error::ParseError;
+use std::borrow::Cow;
 use std::fmt::{self, Debug, Display, Formatter};
 
 #[derive(PartialEq, Eq, Hash)]
 pub struct Utf8StringRef<'a>(pub(crate) &'a [u8]);
 
+impl<'a> Utf8StringRef<'a> {
+    pub fn to_str(&self) -> Result<Cow<'a, str>, ParseError> {
+        std::str::from_utf8(self.0)
+            .map(Cow::Borrowed)
+            .map_err(|_| ParseError::StringEncoding)
+    }
+}
+
 impl<'a> Display for Utf8StringRef<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        if let Ok(s) = String::from_utf8(self.0.to_vec()) {
-            write!(f, "{}", s)
-        } else {
-            Err(fmt::Error::default())
-        }
+        let s = std::str::from_utf8(self.0).map_err(|_| fmt::Error)?;
+        f.write_str(s)
     }
 }
 
@@ -1,12 +1,32 @@
-use std::fmt::{self, Debug, Display, Formatter};
+use super::super::error::ParseError;
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Display, Formatter, Write};
 
-#[derive(PartialEq)]
-pub struct VisibleStringRef<'a>(&'a [u8]);
+#[derive(PartialEq, Eq, Hash)]
+pub struct VisibleStringRef<'a>(pub(crate) &'a [u8]);
+
+impl<'a> VisibleStringRef<'a> {
+    /// the string as text, fails if it contains characters outside of printable ASCII
+    pub fn to_str(&self) -> Result<Cow<'a, str>, ParseError> {
+        if !self.0.iter().all(|b| (0x20..0x7f).contains(b)) {
+            return Err(ParseError::StringEncoding);
+        }
+        std::str::from_utf8(self.0)
+            .map(Cow::Borrowed)
+            .map_err(|_| ParseError::StringEncoding)
+    }
+}
 
 impl<'a> Display for VisibleStringRef<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        let s = String::from_utf8_lossy(self.0);
-        write!(f, "{}", s)
+        // like String::from_utf8_lossy, without building the string
+        for chunk in self.0.utf8_chunks() {
+            f.write_str(chunk.valid())?;
+            if !chunk.invalid().is_empty() {
+                f.write_char(char::REPLACEMENT_CHARACTER)?;
+            }
+        }
+        Ok(())
     }
 }
 
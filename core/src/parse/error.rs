@@ -20,6 +20,11 @@ pub enum ParseError {
     StringEncoding,
     UnexpectedTag(u8),
     InvalidVersion,
+    /// a PEM block carries a legacy `Proc-Type: 4,ENCRYPTED` header, decryption is not supported
+    EncryptedPem,
+    /// the input or one of its elements is longer than the
+    /// [`ParseLimits`](super::limits::ParseLimits) allow
+    TooLarge,
 }
 
 #[derive(Debug)]
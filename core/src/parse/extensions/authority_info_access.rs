@@ -0,0 +1,140 @@
+use super::super::{
+    certificate::expect_empty,
+    der::{expect_object_identifier, expect_sequence, ObjectIdentifierRef},
+    error::ParseError,
+};
+use super::GeneralNameRef;
+use crate::oids;
+
+/// the authorityInfoAccess extension, and subjectInfoAccess which has the same syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthorityInfoAccessRef<'a>(&'a [u8]);
+
+impl<'a> AuthorityInfoAccessRef<'a> {
+    /// parses the extension value, i.e. the content of the extnValue octet string
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, descriptions) = expect_sequence(data)?;
+        expect_empty(rest)?;
+
+        Ok(Self(descriptions))
+    }
+
+    pub fn access_descriptions(&self) -> AccessDescriptionIter<'a> {
+        AccessDescriptionIter {
+            pos: self.0,
+            failure: false,
+        }
+    }
+}
+
+/// an AccessDescription. access methods other than OCSP and caIssuers, e.g. the ones of
+/// subjectInfoAccess, are returned as `Other` instead of failing.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AccessDescriptionRef<'a> {
+    Ocsp(GeneralNameRef<'a>),
+    CaIssuers(GeneralNameRef<'a>),
+    Other(ObjectIdentifierRef<'a>, GeneralNameRef<'a>),
+}
+
+impl<'a> AccessDescriptionRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        let (inner, access_method) = expect_object_identifier(inner)?;
+        let (inner, access_location) = GeneralNameRef::parse(inner)?;
+        expect_empty(inner)?;
+
+        let description = match access_method {
+            oids::AD_OCSP => AccessDescriptionRef::Ocsp(access_location),
+            oids::AD_CA_ISSUERS => AccessDescriptionRef::CaIssuers(access_location),
+            method => AccessDescriptionRef::Other(method, access_location),
+        };
+
+        Ok((rest, description))
+    }
+
+    pub fn access_location(&self) -> &GeneralNameRef<'a> {
+        match self {
+            AccessDescriptionRef::Ocsp(location)
+            | AccessDescriptionRef::CaIssuers(location)
+            | AccessDescriptionRef::Other(_, location) => location,
+        }
+    }
+}
+
+pub struct AccessDescriptionIter<'a> {
+    pos: &'a [u8],
+    failure: bool,
+}
+
+impl<'a> Iterator for AccessDescriptionIter<'a> {
+    type Item = Result<AccessDescriptionRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        if self.failure {
+            //this iterator is in error state, continue returning ParseError
+            return Some(Err(ParseError::MalformedData));
+        }
+        match AccessDescriptionRef::parse(self.pos) {
+            Ok((rest, description)) => {
+                self.pos = rest;
+                Some(Ok(description))
+            }
+            Err(e) => {
+                self.failure = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_authority_info_access() {
+    let data = include_bytes!("../../../../certs/test.crt");
+    let cert = crate::parse::parsing::CertificateRef::from_slice(data).unwrap();
+    let ext = cert
+        .tbs_cert()
+        .extensions()
+        .unwrap()
+        .find(oids::AUTHORITY_INFO_ACCESS)
+        .unwrap()
+        .unwrap();
+    let aia = AuthorityInfoAccessRef::from_slice(ext.value().as_bytes()).unwrap();
+
+    let descriptions: Vec<_> = aia.access_descriptions().collect::<Result<_, _>>().unwrap();
+    assert_eq!(descriptions.len(), 2);
+    match &descriptions[0] {
+        AccessDescriptionRef::Ocsp(GeneralNameRef::UniformResourceIdentifier(uri)) => {
+            assert_eq!(uri.to_str().unwrap(), "http://ocsp.int-x3.letsencrypt.org")
+        }
+        d => panic!("unexpected access description {:?}", d),
+    }
+    assert!(matches!(
+        descriptions[1],
+        AccessDescriptionRef::CaIssuers(_)
+    ));
+}
+
+#[test]
+fn test_unknown_access_method() {
+    // id-ad-caRepository from subjectInfoAccess, with the URI "x"
+    let data = [
+        0x30, 0x0f, 0x30, 0x0d, 0x06, 0x08, 0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x05, 0x86,
+        0x01, b'x',
+    ];
+    let aia = AuthorityInfoAccessRef::from_slice(&data).unwrap();
+    let description = aia.access_descriptions().next().unwrap().unwrap();
+
+    match &description {
+        AccessDescriptionRef::Other(method, _) => {
+            assert_eq!(method.to_string(), "1.3.6.1.5.5.7.48.5")
+        }
+        d => panic!("unexpected access description {:?}", d),
+    }
+    assert!(matches!(
+        description.access_location(),
+        GeneralNameRef::UniformResourceIdentifier(_)
+    ));
+}
@@ -0,0 +1,105 @@
+use super::super::{
+    certificate::expect_empty,
+    der::{expect_sequence, try_get_implicit, IntegerRef},
+    error::ParseError,
+};
+use super::GeneralNamesRef;
+
+#[derive(Debug)]
+pub struct AuthorityKeyIdentifierRef<'a> {
+    key_identifier: Option<&'a [u8]>,
+    authority_cert_issuer: Option<GeneralNamesRef<'a>>,
+    authority_cert_serial_number: Option<IntegerRef<'a>>,
+}
+
+impl<'a> AuthorityKeyIdentifierRef<'a> {
+    /// parses the extension value, i.e. the content of the extnValue octet string
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        expect_empty(rest)?;
+
+        // all fields are optional and implicitly tagged
+        let (inner, key_identifier) = match try_get_implicit(inner, 0) {
+            Ok((inner, key_identifier)) => (inner, Some(key_identifier)),
+            _ => (inner, None),
+        };
+        let (inner, authority_cert_issuer) = match try_get_implicit(inner, 1) {
+            Ok((inner, names)) => (inner, Some(GeneralNamesRef::from_content(names))),
+            _ => (inner, None),
+        };
+        let (inner, authority_cert_serial_number) = match try_get_implicit(inner, 2) {
+            Ok((inner, serial)) => (inner, Some(IntegerRef(serial))),
+            _ => (inner, None),
+        };
+        expect_empty(inner)?;
+
+        Ok(Self {
+            key_identifier,
+            authority_cert_issuer,
+            authority_cert_serial_number,
+        })
+    }
+
+    /// the subject key identifier of the issuer's certificate
+    pub fn key_identifier(&self) -> Option<&'a [u8]> {
+        self.key_identifier
+    }
+
+    /// the issuer of the issuer's certificate, together with
+    /// [`authority_cert_serial_number`](Self::authority_cert_serial_number)
+    pub fn authority_cert_issuer(&self) -> Option<GeneralNamesRef<'a>> {
+        self.authority_cert_issuer
+    }
+
+    pub fn authority_cert_serial_number(&self) -> Option<&IntegerRef<'a>> {
+        self.authority_cert_serial_number.as_ref()
+    }
+}
+
+#[test]
+fn test_authority_key_identifier() {
+    use super::GeneralNameRef;
+
+    // keyIdentifier 0102, authorityCertIssuer { dNSName "example.com" }, serial 5
+    let data = [
+        0x30, 0x16, 0x80, 0x02, 0x01, 0x02, 0xa1, 0x0d, 0x82, 0x0b, b'e', b'x', b'a', b'm', b'p',
+        b'l', b'e', b'.', b'c', b'o', b'm', 0x82, 0x01, 0x05,
+    ];
+    let aki = AuthorityKeyIdentifierRef::from_slice(&data).unwrap();
+    assert_eq!(aki.key_identifier(), Some(&[0x01, 0x02][..]));
+    let issuer: Vec<_> = aki.authority_cert_issuer().unwrap().iter().collect();
+    assert_eq!(issuer.len(), 1);
+    assert!(matches!(issuer[0], Ok(GeneralNameRef::DnsName(_))));
+    assert_eq!(
+        aki.authority_cert_serial_number()
+            .unwrap()
+            .to_i64()
+            .unwrap(),
+        5
+    );
+
+    let aki = AuthorityKeyIdentifierRef::from_slice(&[0x30, 0x03, 0x80, 0x01, 0xaa]).unwrap();
+    assert_eq!(aki.key_identifier(), Some(&[0xaa][..]));
+    assert!(aki.authority_cert_issuer().is_none());
+    assert!(aki.authority_cert_serial_number().is_none());
+
+    // trailing garbage
+    assert!(AuthorityKeyIdentifierRef::from_slice(&[0x30, 0x02, 0x05, 0x00]).is_err());
+}
+
+#[test]
+fn test_authority_key_identifier_cert() {
+    let leaf = crate::parse::parsing::CertificateRef::from_slice(include_bytes!(
+        "../../../../certs/ocsp/leaf.der"
+    ))
+    .unwrap();
+    let ext = leaf
+        .tbs_cert()
+        .extensions()
+        .unwrap()
+        .find(crate::oids::AUTHORITY_KEY_IDENTIFIER)
+        .unwrap()
+        .unwrap();
+    let aki = AuthorityKeyIdentifierRef::from_slice(ext.value().as_bytes()).unwrap();
+    assert_eq!(aki.key_identifier().unwrap().len(), 20);
+}
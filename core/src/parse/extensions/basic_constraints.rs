@@ -0,0 +1,64 @@
+use super::super::{
+    certificate::expect_empty,
+    der::{expect_boolean, expect_integer, expect_sequence},
+    error::ParseError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BasicConstraints {
+    ca: bool,
+    path_len_constraint: Option<u64>,
+}
+
+impl BasicConstraints {
+    /// parses the extension value, i.e. the content of the extnValue octet string
+    pub fn from_slice(data: &[u8]) -> Result<Self, ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        expect_empty(rest)?;
+
+        // cA is DEFAULT FALSE and therefore omitted in DER when false
+        let (inner, ca) = match expect_boolean(inner) {
+            Ok((inner, ca)) => (inner, ca.to_bool()),
+            _ => (inner, false),
+        };
+        let (inner, path_len_constraint) = if inner.is_empty() {
+            (inner, None)
+        } else {
+            let (inner, path_len) = expect_integer(inner)?;
+            let path_len = path_len.to_i64()?;
+            if path_len < 0 {
+                return Err(ParseError::MalformedData);
+            }
+            (inner, Some(path_len as u64))
+        };
+        expect_empty(inner)?;
+
+        Ok(Self {
+            ca,
+            path_len_constraint,
+        })
+    }
+
+    pub fn is_ca(&self) -> bool {
+        self.ca
+    }
+
+    /// the maximum number of non-self-issued intermediate certificates that may follow this one
+    pub fn path_len_constraint(&self) -> Option<u64> {
+        self.path_len_constraint
+    }
+}
+
+#[test]
+fn test_basic_constraints() {
+    let bc =
+        BasicConstraints::from_slice(&[0x30, 0x06, 0x01, 0x01, 0xff, 0x02, 0x01, 0x00]).unwrap();
+    assert!(bc.is_ca());
+    assert_eq!(bc.path_len_constraint(), Some(0));
+
+    let bc = BasicConstraints::from_slice(&[0x30, 0x00]).unwrap();
+    assert!(!bc.is_ca());
+    assert_eq!(bc.path_len_constraint(), None);
+
+    assert!(BasicConstraints::from_slice(&[0x30, 0x03, 0x02, 0x01, 0xff]).is_err());
+}
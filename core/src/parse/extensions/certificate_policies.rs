@@ -0,0 +1,454 @@
+use super::super::{
+    certificate::expect_empty,
+    der::{
+        expect_integer, expect_object_identifier, expect_sequence, expect_type, get_tlv,
+        BMPStringRef, DataType, IA5StringRef, IntegerRef, ObjectIdentifierRef, Utf8StringRef,
+        VisibleStringRef,
+    },
+    error::ParseError,
+};
+use crate::oids;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct CertificatePoliciesRef<'a>(&'a [u8]);
+
+impl<'a> CertificatePoliciesRef<'a> {
+    /// parses the extension value, i.e. the content of the extnValue octet string
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, policies) = expect_sequence(data)?;
+        expect_empty(rest)?;
+
+        Ok(Self(policies))
+    }
+
+    pub fn policies(&self) -> PolicyInformationIter<'a> {
+        PolicyInformationIter {
+            pos: self.0,
+            failure: false,
+        }
+    }
+
+    /// only the policy OIDs, the qualifiers that may follow them are skipped without being
+    /// looked at. this is all that is needed for policy matching.
+    pub fn policy_identifiers(&self) -> PolicyIdentifierIter<'a> {
+        PolicyIdentifierIter {
+            pos: self.0,
+            failure: false,
+        }
+    }
+
+    pub fn contains(&self, policy: ObjectIdentifierRef) -> Result<bool, ParseError> {
+        for p in self.policy_identifiers() {
+            if p? == policy {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+#[derive(Debug)]
+pub struct PolicyInformationRef<'a> {
+    policy_identifier: ObjectIdentifierRef<'a>,
+    policy_qualifiers: &'a [u8],
+}
+
+impl<'a> PolicyInformationRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        let (inner, policy_identifier) = expect_object_identifier(inner)?;
+        let policy_qualifiers = if inner.is_empty() {
+            inner
+        } else {
+            let (inner, qualifiers) = expect_sequence(inner)?;
+            expect_empty(inner)?;
+            qualifiers
+        };
+
+        let policy = Self {
+            policy_identifier,
+            policy_qualifiers,
+        };
+
+        Ok((rest, policy))
+    }
+
+    pub fn policy_identifier(&self) -> &ObjectIdentifierRef<'a> {
+        &self.policy_identifier
+    }
+
+    /// the content of the policyQualifiers sequence, empty if there are none
+    pub fn policy_qualifiers(&self) -> &'a [u8] {
+        self.policy_qualifiers
+    }
+
+    pub fn qualifiers(&self) -> PolicyQualifierIter<'a> {
+        PolicyQualifierIter {
+            pos: self.policy_qualifiers,
+            failure: false,
+        }
+    }
+}
+
+/// a PolicyQualifierInfo. qualifiers other than the CPS pointer and the user notice are not
+/// an error, certificates in the wild contain all kinds of them.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PolicyQualifierRef<'a> {
+    CpsUri(IA5StringRef<'a>),
+    UserNotice(UserNoticeRef<'a>),
+    Unknown {
+        id: ObjectIdentifierRef<'a>,
+        /// the DER encoding of the qualifier
+        qualifier: &'a [u8],
+    },
+}
+
+impl<'a> PolicyQualifierRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        let (inner, id) = expect_object_identifier(inner)?;
+
+        let qualifier = match id {
+            oids::QT_CPS => {
+                let (inner, uri) = expect_type(inner, DataType::IA5String)?;
+                expect_empty(inner)?;
+                PolicyQualifierRef::CpsUri(IA5StringRef(uri))
+            }
+            oids::QT_UNOTICE => {
+                let (inner, notice) = expect_sequence(inner)?;
+                expect_empty(inner)?;
+                PolicyQualifierRef::UserNotice(UserNoticeRef::parse(notice)?)
+            }
+            id => PolicyQualifierRef::Unknown {
+                id,
+                qualifier: inner,
+            },
+        };
+
+        Ok((rest, qualifier))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UserNoticeRef<'a> {
+    notice_ref: Option<NoticeReferenceRef<'a>>,
+    explicit_text: Option<DisplayTextRef<'a>>,
+}
+
+impl<'a> UserNoticeRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, ParseError> {
+        // both fields are optional, but noticeRef is the only one that is a SEQUENCE
+        let (data, notice_ref) = match expect_sequence(data) {
+            Ok((data, notice_ref)) => (data, Some(NoticeReferenceRef::parse(notice_ref)?)),
+            Err(_) => (data, None),
+        };
+        let explicit_text = if data.is_empty() {
+            None
+        } else {
+            let (data, explicit_text) = DisplayTextRef::parse(data)?;
+            expect_empty(data)?;
+            Some(explicit_text)
+        };
+
+        Ok(Self {
+            notice_ref,
+            explicit_text,
+        })
+    }
+
+    pub fn notice_ref(&self) -> Option<&NoticeReferenceRef<'a>> {
+        self.notice_ref.as_ref()
+    }
+
+    pub fn explicit_text(&self) -> Option<&DisplayTextRef<'a>> {
+        self.explicit_text.as_ref()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NoticeReferenceRef<'a> {
+    organization: DisplayTextRef<'a>,
+    notice_numbers: Vec<IntegerRef<'a>>,
+}
+
+impl<'a> NoticeReferenceRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (data, organization) = DisplayTextRef::parse(data)?;
+        let (data, mut numbers) = expect_sequence(data)?;
+        expect_empty(data)?;
+
+        let mut notice_numbers = Vec::new();
+        while !numbers.is_empty() {
+            let (rest, number) = expect_integer(numbers)?;
+            notice_numbers.push(number);
+            numbers = rest;
+        }
+
+        Ok(Self {
+            organization,
+            notice_numbers,
+        })
+    }
+
+    pub fn organization(&self) -> &DisplayTextRef<'a> {
+        &self.organization
+    }
+
+    pub fn notice_numbers(&self) -> &[IntegerRef<'a>] {
+        &self.notice_numbers
+    }
+}
+
+/// the DisplayText CHOICE of RFC 5280 section 4.2.1.4
+#[derive(Debug, PartialEq, Eq)]
+pub enum DisplayTextRef<'a> {
+    Ia5String(IA5StringRef<'a>),
+    VisibleString(VisibleStringRef<'a>),
+    BmpString(BMPStringRef<'a>),
+    Utf8String(Utf8StringRef<'a>),
+}
+
+impl<'a> DisplayTextRef<'a> {
+    /// fails with `ParseError::UnexpectedTag` for types DisplayText doesn't allow
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, tag, value) = get_tlv(data)?;
+        let text = match tag {
+            0x16 => DisplayTextRef::Ia5String(IA5StringRef(value)),
+            0x1a => DisplayTextRef::VisibleString(VisibleStringRef(value)),
+            0x1e => DisplayTextRef::BmpString(BMPStringRef(value)),
+            0x0c => DisplayTextRef::Utf8String(Utf8StringRef(value)),
+            tag => return Err(ParseError::UnexpectedTag(tag)),
+        };
+
+        Ok((rest, text))
+    }
+
+    /// the text, fails if it isn't valid in its string type
+    pub fn to_str(&self) -> Result<Cow<'a, str>, ParseError> {
+        match self {
+            DisplayTextRef::Ia5String(s) => s.to_str(),
+            DisplayTextRef::VisibleString(s) => s.to_str(),
+            DisplayTextRef::BmpString(s) => s.to_str(),
+            DisplayTextRef::Utf8String(s) => s.to_str(),
+        }
+    }
+}
+
+pub struct PolicyQualifierIter<'a> {
+    pos: &'a [u8],
+    failure: bool,
+}
+
+impl<'a> Iterator for PolicyQualifierIter<'a> {
+    type Item = Result<PolicyQualifierRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        if self.failure {
+            //this iterator is in error state, continue returning ParseError
+            return Some(Err(ParseError::MalformedData));
+        }
+        match PolicyQualifierRef::parse(self.pos) {
+            Ok((rest, qualifier)) => {
+                self.pos = rest;
+                Some(Ok(qualifier))
+            }
+            Err(e) => {
+                self.failure = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+pub struct PolicyInformationIter<'a> {
+    pos: &'a [u8],
+    failure: bool,
+}
+
+impl<'a> Iterator for PolicyInformationIter<'a> {
+    type Item = Result<PolicyInformationRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        if self.failure {
+            //this iterator is in error state, continue returning ParseError
+            return Some(Err(ParseError::MalformedData));
+        }
+        match PolicyInformationRef::parse(self.pos) {
+            Ok((rest, policy)) => {
+                self.pos = rest;
+                Some(Ok(policy))
+            }
+            Err(e) => {
+                self.failure = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+pub struct PolicyIdentifierIter<'a> {
+    pos: &'a [u8],
+    failure: bool,
+}
+
+impl<'a> Iterator for PolicyIdentifierIter<'a> {
+    type Item = Result<ObjectIdentifierRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        if self.failure {
+            //this iterator is in error state, continue returning ParseError
+            return Some(Err(ParseError::MalformedData));
+        }
+        let result = expect_sequence(self.pos).and_then(|(rest, inner)| {
+            let (_, policy_identifier) = expect_object_identifier(inner)?;
+            Ok((rest, policy_identifier))
+        });
+        match result {
+            Ok((rest, policy_identifier)) => {
+                self.pos = rest;
+                Some(Ok(policy_identifier))
+            }
+            Err(e) => {
+                self.failure = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_certificate_policies() {
+    // 2.23.140.1.2.1 without qualifiers
+    let data = [
+        0x30, 0x0a, 0x30, 0x08, 0x06, 0x06, 0x67, 0x81, 0x0c, 0x01, 0x02, 0x01,
+    ];
+    let policies = CertificatePoliciesRef::from_slice(&data).unwrap();
+    let policies: Vec<_> = policies.policies().collect::<Result<_, _>>().unwrap();
+    assert_eq!(policies.len(), 1);
+    assert_eq!(
+        policies[0].policy_identifier(),
+        &crate::oids::CABF_DOMAIN_VALIDATED
+    );
+    assert!(policies[0].policy_qualifiers().is_empty());
+}
+
+#[test]
+fn test_policy_identifiers() {
+    let data = include_bytes!("../../../../certs/test.crt");
+    let cert = crate::parse::parsing::CertificateRef::from_slice(data).unwrap();
+    let ext = cert
+        .tbs_cert()
+        .extensions()
+        .unwrap()
+        .find(crate::oids::CERTIFICATE_POLICIES)
+        .unwrap()
+        .unwrap();
+    let policies = CertificatePoliciesRef::from_slice(ext.value().as_bytes()).unwrap();
+
+    let ids: Vec<_> = policies
+        .policy_identifiers()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let full: Vec<_> = policies
+        .policies()
+        .map(|p| *p.unwrap().policy_identifier())
+        .collect();
+    assert_eq!(ids, full);
+    assert!(policies
+        .contains(crate::oids::CABF_DOMAIN_VALIDATED)
+        .unwrap());
+    assert!(!policies.contains(crate::oids::ANY_POLICY).unwrap());
+}
+
+#[test]
+fn test_policy_qualifiers() {
+    let data = include_bytes!("../../../../certs/test.crt");
+    let cert = crate::parse::parsing::CertificateRef::from_slice(data).unwrap();
+    let ext = cert
+        .tbs_cert()
+        .extensions()
+        .unwrap()
+        .find(crate::oids::CERTIFICATE_POLICIES)
+        .unwrap()
+        .unwrap();
+    let policies = CertificatePoliciesRef::from_slice(ext.value().as_bytes()).unwrap();
+
+    let qualifiers: Vec<_> = policies
+        .policies()
+        .flat_map(|p| p.unwrap().qualifiers())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(qualifiers.len(), 1);
+    match &qualifiers[0] {
+        PolicyQualifierRef::CpsUri(uri) => {
+            assert_eq!(uri.to_str().unwrap(), "http://cps.letsencrypt.org")
+        }
+        q => panic!("unexpected qualifier {:?}", q),
+    }
+}
+
+#[test]
+fn test_user_notice_and_unknown_qualifiers() {
+    // user notice with noticeRef { "Org", { 1, 2 } } and explicitText "Hi" as BMPString
+    let user_notice = [
+        0x30, 0x21, 0x06, 0x08, 0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x02, 0x02, 0x30, 0x15, 0x30,
+        0x0d, 0x0c, 0x03, b'O', b'r', b'g', 0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02, 0x1e,
+        0x04, 0x00, b'H', 0x00, b'i',
+    ];
+    // 1.2.3 with a NULL qualifier
+    let unknown = [0x30, 0x06, 0x06, 0x02, 0x2a, 0x03, 0x05, 0x00];
+    let qualifiers = [&user_notice[..], &unknown[..]].concat();
+    let iter = PolicyQualifierIter {
+        pos: &qualifiers,
+        failure: false,
+    };
+    let qualifiers: Vec<_> = iter.collect::<Result<_, _>>().unwrap();
+    assert_eq!(qualifiers.len(), 2);
+
+    let notice = match &qualifiers[0] {
+        PolicyQualifierRef::UserNotice(notice) => notice,
+        q => panic!("unexpected qualifier {:?}", q),
+    };
+    assert_eq!(notice.explicit_text().unwrap().to_str().unwrap(), "Hi");
+    let notice_ref = notice.notice_ref().unwrap();
+    assert_eq!(notice_ref.organization().to_str().unwrap(), "Org");
+    let numbers: Vec<_> = notice_ref
+        .notice_numbers()
+        .iter()
+        .map(|n| n.to_i64().unwrap())
+        .collect();
+    assert_eq!(numbers, vec![1, 2]);
+
+    assert_eq!(
+        qualifiers[1],
+        PolicyQualifierRef::Unknown {
+            id: ObjectIdentifierRef(&[0x2a, 0x03]),
+            qualifier: &[0x05, 0x00],
+        }
+    );
+
+    // explicitText as a PrintableString is not allowed
+    let bad = [
+        0x30, 0x0f, 0x06, 0x08, 0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x02, 0x02, 0x30, 0x03, 0x13,
+        0x01, b'x',
+    ];
+    let mut iter = PolicyQualifierIter {
+        pos: &bad,
+        failure: false,
+    };
+    assert!(matches!(
+        iter.next(),
+        Some(Err(ParseError::UnexpectedTag(0x13)))
+    ));
+}
@@ -0,0 +1,232 @@
+use super::super::{
+    certificate::{expect_empty, RelativeDistinguishedNameRef},
+    der::{expect_sequence, get_tlv, try_get_implicit, BitStringRef},
+    error::ParseError,
+};
+use super::GeneralNamesRef;
+
+/// the cRLDistributionPoints extension, and freshestCRL which has the same syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrlDistributionPointsRef<'a>(&'a [u8]);
+
+impl<'a> CrlDistributionPointsRef<'a> {
+    /// parses the extension value, i.e. the content of the extnValue octet string
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, points) = expect_sequence(data)?;
+        expect_empty(rest)?;
+
+        Ok(Self(points))
+    }
+
+    pub fn distribution_points(&self) -> DistributionPointIter<'a> {
+        DistributionPointIter {
+            pos: self.0,
+            failure: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DistributionPointRef<'a> {
+    distribution_point: Option<DistributionPointNameRef<'a>>,
+    reasons: Option<ReasonFlagsRef<'a>>,
+    crl_issuer: Option<GeneralNamesRef<'a>>,
+}
+
+impl<'a> DistributionPointRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        let (inner, distribution_point) = match try_get_implicit(inner, 0) {
+            Ok((inner, name)) => (inner, Some(DistributionPointNameRef::parse(name)?)),
+            Err(_) => (inner, None),
+        };
+        let (inner, reasons) = match try_get_implicit(inner, 1) {
+            Ok((inner, bits)) => (
+                inner,
+                Some(ReasonFlagsRef(BitStringRef::from_content(bits)?)),
+            ),
+            Err(_) => (inner, None),
+        };
+        let (inner, crl_issuer) = match try_get_implicit(inner, 2) {
+            Ok((inner, names)) => (inner, Some(GeneralNamesRef::from_content(names))),
+            Err(_) => (inner, None),
+        };
+        expect_empty(inner)?;
+
+        let point = Self {
+            distribution_point,
+            reasons,
+            crl_issuer,
+        };
+
+        Ok((rest, point))
+    }
+
+    pub fn distribution_point(&self) -> Option<&DistributionPointNameRef<'a>> {
+        self.distribution_point.as_ref()
+    }
+
+    /// the revocation reasons covered by this CRL, all reasons if absent
+    pub fn reasons(&self) -> Option<&ReasonFlagsRef<'a>> {
+        self.reasons.as_ref()
+    }
+
+    pub fn crl_issuer(&self) -> Option<&GeneralNamesRef<'a>> {
+        self.crl_issuer.as_ref()
+    }
+}
+
+#[derive(Debug)]
+pub enum DistributionPointNameRef<'a> {
+    FullName(GeneralNamesRef<'a>),
+    NameRelativeToCrlIssuer(RelativeDistinguishedNameRef<'a>),
+}
+
+impl<'a> DistributionPointNameRef<'a> {
+    // `data` is the content of the [0] tag, the CHOICE makes it explicit
+    pub(super) fn parse(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, tag, value) = get_tlv(data)?;
+        expect_empty(rest)?;
+
+        match tag {
+            0xa0 => Ok(DistributionPointNameRef::FullName(
+                GeneralNamesRef::from_content(value),
+            )),
+            0xa1 => Ok(DistributionPointNameRef::NameRelativeToCrlIssuer(
+                RelativeDistinguishedNameRef::from_content(value),
+            )),
+            _ => Err(ParseError::UnexpectedTag(tag)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ReasonFlagsRef<'a>(pub(super) BitStringRef<'a>);
+
+impl<'a> ReasonFlagsRef<'a> {
+    // bits that are not encoded are not set, so out of range bits are reported as false
+    fn bit(&self, index: usize) -> bool {
+        self.0.bit_at(index).unwrap_or(false)
+    }
+
+    pub fn key_compromise(&self) -> bool {
+        self.bit(1)
+    }
+
+    pub fn ca_compromise(&self) -> bool {
+        self.bit(2)
+    }
+
+    pub fn affiliation_changed(&self) -> bool {
+        self.bit(3)
+    }
+
+    pub fn superseded(&self) -> bool {
+        self.bit(4)
+    }
+
+    pub fn cessation_of_operation(&self) -> bool {
+        self.bit(5)
+    }
+
+    pub fn certificate_hold(&self) -> bool {
+        self.bit(6)
+    }
+
+    pub fn privilege_withdrawn(&self) -> bool {
+        self.bit(7)
+    }
+
+    pub fn aa_compromise(&self) -> bool {
+        self.bit(8)
+    }
+}
+
+pub struct DistributionPointIter<'a> {
+    pos: &'a [u8],
+    failure: bool,
+}
+
+impl<'a> Iterator for DistributionPointIter<'a> {
+    type Item = Result<DistributionPointRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        if self.failure {
+            //this iterator is in error state, continue returning ParseError
+            return Some(Err(ParseError::MalformedData));
+        }
+        match DistributionPointRef::parse(self.pos) {
+            Ok((rest, point)) => {
+                self.pos = rest;
+                Some(Ok(point))
+            }
+            Err(e) => {
+                self.failure = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_crl_distribution_points() {
+    // fullName URI "x", reasons keyCompromise and cACompromise, cRLIssuer dNSName "y"
+    let first = [
+        0x30, 0x10, 0xa0, 0x05, 0xa0, 0x03, 0x86, 0x01, b'x', 0x81, 0x02, 0x05, 0x60, 0xa2, 0x03,
+        0x82, 0x01, b'y',
+    ];
+    // nameRelativeToCRLIssuer CN=z
+    let second = [
+        0x30, 0x0e, 0xa0, 0x0c, 0xa1, 0x0a, 0x30, 0x08, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x01,
+        b'z',
+    ];
+    let data = [&[0x30, 0x22][..], &first, &second].concat();
+    let cdp = CrlDistributionPointsRef::from_slice(&data).unwrap();
+    let points: Vec<_> = cdp.distribution_points().collect::<Result<_, _>>().unwrap();
+    assert_eq!(points.len(), 2);
+
+    match points[0].distribution_point() {
+        Some(DistributionPointNameRef::FullName(names)) => match names.iter().next() {
+            Some(Ok(super::GeneralNameRef::UniformResourceIdentifier(uri))) => {
+                assert_eq!(uri.to_str().unwrap(), "x")
+            }
+            n => panic!("unexpected general name {:?}", n),
+        },
+        n => panic!("unexpected distribution point name {:?}", n),
+    }
+    let reasons = points[0].reasons().unwrap();
+    assert!(reasons.key_compromise());
+    assert!(reasons.ca_compromise());
+    assert!(!reasons.superseded());
+    assert!(!reasons.aa_compromise());
+    assert_eq!(points[0].crl_issuer().unwrap().iter().count(), 1);
+
+    match points[1].distribution_point() {
+        Some(DistributionPointNameRef::NameRelativeToCrlIssuer(rdn)) => {
+            let atv = rdn.iter().next().unwrap().unwrap();
+            assert_eq!(atv.value_as_str().unwrap(), "z");
+        }
+        n => panic!("unexpected distribution point name {:?}", n),
+    }
+    assert!(points[1].reasons().is_none());
+    assert!(points[1].crl_issuer().is_none());
+}
+
+#[test]
+fn test_malformed_reasons() {
+    // reasons without the unused bits octet
+    let data = [0x30, 0x04, 0x30, 0x02, 0x81, 0x00];
+    let cdp = CrlDistributionPointsRef::from_slice(&data).unwrap();
+    let mut points = cdp.distribution_points();
+    assert!(matches!(
+        points.next(),
+        Some(Err(ParseError::MalformedData))
+    ));
+    assert!(matches!(
+        points.next(),
+        Some(Err(ParseError::MalformedData))
+    ));
+}
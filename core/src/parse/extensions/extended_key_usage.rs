@@ -0,0 +1,173 @@
+use super::super::{
+    certificate::expect_empty,
+    der::{expect_object_identifier, expect_sequence, ObjectIdentifierRef},
+    error::ParseError,
+};
+use crate::oids;
+
+#[derive(Debug)]
+pub struct ExtendedKeyUsageRef<'a>(&'a [u8]);
+
+impl<'a> ExtendedKeyUsageRef<'a> {
+    /// parses the extension value, i.e. the content of the extnValue octet string
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, purposes) = expect_sequence(data)?;
+        expect_empty(rest)?;
+
+        Ok(Self(purposes))
+    }
+
+    /// the key purpose OIDs, in the order of the extension
+    pub fn purposes(&self) -> KeyPurposeOidIter<'a> {
+        KeyPurposeOidIter {
+            pos: self.0,
+            failure: false,
+        }
+    }
+
+    /// like [`purposes`](Self::purposes), with the well known purposes decoded
+    pub fn key_purposes(&self) -> KeyPurposeIter<'a> {
+        KeyPurposeIter(self.purposes())
+    }
+
+    pub fn contains(&self, purpose: ObjectIdentifierRef) -> Result<bool, ParseError> {
+        for p in self.purposes() {
+            if p? == purpose {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// true if `purpose` is listed or the extension contains anyExtendedKeyUsage
+    pub fn permits(&self, purpose: KeyPurpose) -> Result<bool, ParseError> {
+        for p in self.key_purposes() {
+            let p = p?;
+            if p == purpose || p == KeyPurpose::Any {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyPurpose<'a> {
+    ServerAuth,
+    ClientAuth,
+    CodeSigning,
+    EmailProtection,
+    TimeStamping,
+    OcspSigning,
+    /// anyExtendedKeyUsage
+    Any,
+    Unknown(ObjectIdentifierRef<'a>),
+}
+
+impl<'a> KeyPurpose<'a> {
+    pub fn from_oid(oid: ObjectIdentifierRef<'a>) -> Self {
+        match oid {
+            oids::KP_SERVER_AUTH => KeyPurpose::ServerAuth,
+            oids::KP_CLIENT_AUTH => KeyPurpose::ClientAuth,
+            oids::KP_CODE_SIGNING => KeyPurpose::CodeSigning,
+            oids::KP_EMAIL_PROTECTION => KeyPurpose::EmailProtection,
+            oids::KP_TIME_STAMPING => KeyPurpose::TimeStamping,
+            oids::KP_OCSP_SIGNING => KeyPurpose::OcspSigning,
+            oids::ANY_EXTENDED_KEY_USAGE => KeyPurpose::Any,
+            oid => KeyPurpose::Unknown(oid),
+        }
+    }
+
+    pub fn oid(&self) -> ObjectIdentifierRef<'a> {
+        match self {
+            KeyPurpose::ServerAuth => oids::KP_SERVER_AUTH,
+            KeyPurpose::ClientAuth => oids::KP_CLIENT_AUTH,
+            KeyPurpose::CodeSigning => oids::KP_CODE_SIGNING,
+            KeyPurpose::EmailProtection => oids::KP_EMAIL_PROTECTION,
+            KeyPurpose::TimeStamping => oids::KP_TIME_STAMPING,
+            KeyPurpose::OcspSigning => oids::KP_OCSP_SIGNING,
+            KeyPurpose::Any => oids::ANY_EXTENDED_KEY_USAGE,
+            KeyPurpose::Unknown(oid) => *oid,
+        }
+    }
+}
+
+pub struct KeyPurposeIter<'a>(KeyPurposeOidIter<'a>);
+
+impl<'a> Iterator for KeyPurposeIter<'a> {
+    type Item = Result<KeyPurpose<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|p| p.map(KeyPurpose::from_oid))
+    }
+}
+
+pub struct KeyPurposeOidIter<'a> {
+    pos: &'a [u8],
+    failure: bool,
+}
+
+impl<'a> Iterator for KeyPurposeOidIter<'a> {
+    type Item = Result<ObjectIdentifierRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        if self.failure {
+            //this iterator is in error state, continue returning ParseError
+            return Some(Err(ParseError::MalformedData));
+        }
+        match expect_object_identifier(self.pos) {
+            Ok((rest, purpose)) => {
+                self.pos = rest;
+                Some(Ok(purpose))
+            }
+            Err(e) => {
+                self.failure = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_extended_key_usage() {
+    // serverAuth, clientAuth
+    let data = [
+        0x30, 0x14, 0x06, 0x08, 0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x01, 0x06, 0x08, 0x2b,
+        0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x02,
+    ];
+    let eku = ExtendedKeyUsageRef::from_slice(&data).unwrap();
+    assert_eq!(eku.purposes().count(), 2);
+    assert!(eku.contains(crate::oids::KP_CLIENT_AUTH).unwrap());
+    assert!(!eku.contains(crate::oids::KP_OCSP_SIGNING).unwrap());
+
+    let purposes: Vec<_> = eku.key_purposes().map(Result::unwrap).collect();
+    assert_eq!(
+        purposes,
+        vec![KeyPurpose::ServerAuth, KeyPurpose::ClientAuth]
+    );
+    assert!(eku.permits(KeyPurpose::ClientAuth).unwrap());
+    assert!(!eku.permits(KeyPurpose::CodeSigning).unwrap());
+}
+
+#[test]
+fn test_key_purpose_any() {
+    // anyExtendedKeyUsage, 1.2.3
+    let data = [
+        0x30, 0x09, 0x06, 0x04, 0x55, 0x1d, 0x25, 0x00, 0x06, 0x01, 0x2a,
+    ];
+    let eku = ExtendedKeyUsageRef::from_slice(&data).unwrap();
+    let purposes: Vec<_> = eku.key_purposes().map(Result::unwrap).collect();
+
+    assert_eq!(purposes[0], KeyPurpose::Any);
+    assert_eq!(purposes[1].oid(), ObjectIdentifierRef::new(&[0x2a]));
+    assert!(eku.permits(KeyPurpose::TimeStamping).unwrap());
+    assert_eq!(
+        KeyPurpose::from_oid(KeyPurpose::OcspSigning.oid()),
+        KeyPurpose::OcspSigning
+    );
+}
@@ -0,0 +1,161 @@
+use super::super::{
+    certificate::{expect_empty, NameRef},
+    der::{expect_sequence, get_tlv, IA5StringRef, ObjectIdentifierRef},
+    error::ParseError,
+};
+use std::convert::TryFrom;
+use std::net::IpAddr;
+
+/// the GeneralName CHOICE of RFC 5280 section 4.2.1.6
+#[derive(Debug, PartialEq, Eq)]
+pub enum GeneralNameRef<'a> {
+    /// content of the [0] element: the type-id OID followed by the explicitly tagged value
+    OtherName(&'a [u8]),
+    Rfc822Name(IA5StringRef<'a>),
+    DnsName(IA5StringRef<'a>),
+    /// content of the [3] element
+    X400Address(&'a [u8]),
+    DirectoryName(NameRef<'a>),
+    /// content of the [5] element
+    EdiPartyName(&'a [u8]),
+    UniformResourceIdentifier(IA5StringRef<'a>),
+    /// 4 octets for IPv4, 16 for IPv6. in name constraints the address is followed by a mask
+    /// of the same length.
+    IpAddress(&'a [u8]),
+    RegisteredId(ObjectIdentifierRef<'a>),
+}
+
+impl<'a> GeneralNameRef<'a> {
+    pub(crate) fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, tag, value) = get_tlv(data)?;
+        if tag & 0xc0 != 0x80 {
+            // all alternatives are context specific
+            return Err(ParseError::UnexpectedTag(tag));
+        }
+
+        let constructed = tag & 0x20 == 0x20;
+        let name = match (tag & 0x1f, constructed) {
+            (0, true) => GeneralNameRef::OtherName(value),
+            (1, false) => GeneralNameRef::Rfc822Name(IA5StringRef(value)),
+            (2, false) => GeneralNameRef::DnsName(IA5StringRef(value)),
+            (3, true) => GeneralNameRef::X400Address(value),
+            (4, true) => {
+                // Name is a CHOICE, so the tag is explicit
+                let (inner, name) = NameRef::parse(value)?;
+                expect_empty(inner)?;
+                GeneralNameRef::DirectoryName(name)
+            }
+            (5, true) => GeneralNameRef::EdiPartyName(value),
+            (6, false) => GeneralNameRef::UniformResourceIdentifier(IA5StringRef(value)),
+            (7, false) => GeneralNameRef::IpAddress(value),
+            (8, false) => GeneralNameRef::RegisteredId(ObjectIdentifierRef(value)),
+            _ => return Err(ParseError::UnexpectedTag(tag)),
+        };
+
+        Ok((rest, name))
+    }
+
+    /// the address of an iPAddress entry. None for other alternatives and for addresses that
+    /// are neither 4 nor 16 octets long, like the ones with a mask in name constraints.
+    pub fn ip_address(&self) -> Option<IpAddr> {
+        match *self {
+            GeneralNameRef::IpAddress(octets) => match octets.len() {
+                4 => Some(IpAddr::from(<[u8; 4]>::try_from(octets).ok()?)),
+                16 => Some(IpAddr::from(<[u8; 16]>::try_from(octets).ok()?)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// a SEQUENCE OF GeneralName, e.g. the value of the subjectAltName extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneralNamesRef<'a>(&'a [u8]);
+
+impl<'a> GeneralNamesRef<'a> {
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, names) = expect_sequence(data)?;
+        expect_empty(rest)?;
+
+        Ok(Self(names))
+    }
+
+    /// wraps the content of an implicitly tagged GeneralNames
+    pub(crate) fn from_content(names: &'a [u8]) -> Self {
+        Self(names)
+    }
+
+    pub fn iter(&self) -> GeneralNamesIter<'a> {
+        GeneralNamesIter {
+            pos: self.0,
+            failure: false,
+        }
+    }
+}
+
+pub struct GeneralNamesIter<'a> {
+    pos: &'a [u8],
+    failure: bool,
+}
+
+impl<'a> Iterator for GeneralNamesIter<'a> {
+    type Item = Result<GeneralNameRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        if self.failure {
+            //this iterator is in error state, continue returning ParseError
+            return Some(Err(ParseError::MalformedData));
+        }
+        match GeneralNameRef::parse(self.pos) {
+            Ok((rest, name)) => {
+                self.pos = rest;
+                Some(Ok(name))
+            }
+            Err(e) => {
+                self.failure = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_general_names() {
+    // dNSName "a.test", iPAddress 127.0.0.1, directoryName { CN=x }, registeredID 1.2.3
+    let data = [
+        0x30, 0x22, 0x82, 0x06, b'a', b'.', b't', b'e', b's', b't', 0x87, 0x04, 0x7f, 0x00, 0x00,
+        0x01, 0xa4, 0x0e, 0x30, 0x0c, 0x31, 0x0a, 0x30, 0x08, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c,
+        0x01, b'x', 0x88, 0x02, 0x2a, 0x03,
+    ];
+    let names: Vec<_> = GeneralNamesRef::from_slice(&data)
+        .unwrap()
+        .iter()
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(names.len(), 4);
+    assert_eq!(names[0], GeneralNameRef::DnsName(IA5StringRef(b"a.test")));
+    assert_eq!(names[1], GeneralNameRef::IpAddress(&[127, 0, 0, 1]));
+    assert_eq!(names[1].ip_address(), Some(IpAddr::from([127, 0, 0, 1])));
+    assert_eq!(names[0].ip_address(), None);
+    assert_eq!(
+        GeneralNameRef::IpAddress(&[127, 0, 0, 1, 255, 0, 0, 0]).ip_address(),
+        None
+    );
+    assert!(matches!(names[2], GeneralNameRef::DirectoryName(_)));
+    assert_eq!(
+        names[3],
+        GeneralNameRef::RegisteredId(ObjectIdentifierRef(&[0x2a, 0x03]))
+    );
+
+    // a universal tag is not a GeneralName
+    let mut iter = GeneralNamesRef::from_slice(&[0x30, 0x02, 0x05, 0x00])
+        .unwrap()
+        .iter();
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().unwrap().is_err());
+}
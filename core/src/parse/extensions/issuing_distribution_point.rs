@@ -0,0 +1,121 @@
+use super::super::{
+    certificate::expect_empty,
+    der::{expect_sequence, try_get_implicit, BitStringRef},
+    error::ParseError,
+};
+use super::{DistributionPointNameRef, ReasonFlagsRef};
+
+/// the issuingDistributionPoint CRL extension of RFC 5280 section 5.2.5, which limits the
+/// certificates a CRL is authoritative for
+#[derive(Debug)]
+pub struct IssuingDistributionPointRef<'a> {
+    distribution_point: Option<DistributionPointNameRef<'a>>,
+    only_contains_user_certs: bool,
+    only_contains_ca_certs: bool,
+    only_some_reasons: Option<ReasonFlagsRef<'a>>,
+    indirect_crl: bool,
+    only_contains_attribute_certs: bool,
+}
+
+impl<'a> IssuingDistributionPointRef<'a> {
+    /// parses the extension value, i.e. the content of the extnValue octet string
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        expect_empty(rest)?;
+
+        let (inner, distribution_point) = match try_get_implicit(inner, 0) {
+            Ok((inner, name)) => (inner, Some(DistributionPointNameRef::parse(name)?)),
+            Err(_) => (inner, None),
+        };
+        let (inner, only_contains_user_certs) = implicit_boolean(inner, 1)?;
+        let (inner, only_contains_ca_certs) = implicit_boolean(inner, 2)?;
+        let (inner, only_some_reasons) = match try_get_implicit(inner, 3) {
+            Ok((inner, bits)) => (
+                inner,
+                Some(ReasonFlagsRef(BitStringRef::from_content(bits)?)),
+            ),
+            Err(_) => (inner, None),
+        };
+        let (inner, indirect_crl) = implicit_boolean(inner, 4)?;
+        let (inner, only_contains_attribute_certs) = implicit_boolean(inner, 5)?;
+        expect_empty(inner)?;
+
+        Ok(Self {
+            distribution_point,
+            only_contains_user_certs,
+            only_contains_ca_certs,
+            only_some_reasons,
+            indirect_crl,
+            only_contains_attribute_certs,
+        })
+    }
+
+    /// the distribution point the CRL belongs to, if it only covers the certificates that
+    /// name this point
+    pub fn distribution_point(&self) -> Option<&DistributionPointNameRef<'a>> {
+        self.distribution_point.as_ref()
+    }
+
+    pub fn only_contains_user_certs(&self) -> bool {
+        self.only_contains_user_certs
+    }
+
+    pub fn only_contains_ca_certs(&self) -> bool {
+        self.only_contains_ca_certs
+    }
+
+    /// the reasons the CRL covers, if it doesn't cover all of them
+    pub fn only_some_reasons(&self) -> Option<&ReasonFlagsRef<'a>> {
+        self.only_some_reasons.as_ref()
+    }
+
+    pub fn indirect_crl(&self) -> bool {
+        self.indirect_crl
+    }
+
+    pub fn only_contains_attribute_certs(&self) -> bool {
+        self.only_contains_attribute_certs
+    }
+}
+
+// the booleans are DEFAULT FALSE, so an absent value is false and DER doesn't allow an encoded
+// false
+fn implicit_boolean(data: &[u8], tag: u8) -> Result<(&[u8], bool), ParseError> {
+    match try_get_implicit(data, tag) {
+        Ok((rest, [0xff])) => Ok((rest, true)),
+        Ok((_, [_])) => Err(ParseError::MalformedData),
+        Ok(_) => Err(ParseError::InvalidLength),
+        Err(_) => Ok((data, false)),
+    }
+}
+
+#[test]
+fn test_issuing_distribution_point() {
+    // fullName URI "x", onlyContainsCACerts, onlySomeReasons keyCompromise
+    let data = [
+        0x30, 0x0e, 0xa0, 0x05, 0xa0, 0x03, 0x86, 0x01, b'x', 0x82, 0x01, 0xff, 0x83, 0x02, 0x06,
+        0x40,
+    ];
+    let idp = IssuingDistributionPointRef::from_slice(&data).unwrap();
+    assert!(matches!(
+        idp.distribution_point(),
+        Some(DistributionPointNameRef::FullName(_))
+    ));
+    assert!(!idp.only_contains_user_certs());
+    assert!(idp.only_contains_ca_certs());
+    assert!(idp.only_some_reasons().unwrap().key_compromise());
+    assert!(!idp.indirect_crl());
+    assert!(!idp.only_contains_attribute_certs());
+
+    let empty = IssuingDistributionPointRef::from_slice(&[0x30, 0x00]).unwrap();
+    assert!(empty.distribution_point().is_none());
+    assert!(!empty.only_contains_ca_certs());
+
+    // an explicitly encoded DEFAULT value
+    assert!(IssuingDistributionPointRef::from_slice(&[0x30, 0x03, 0x81, 0x01, 0x00]).is_err());
+    // out of order
+    assert!(IssuingDistributionPointRef::from_slice(&[
+        0x30, 0x06, 0x82, 0x01, 0xff, 0x81, 0x01, 0xff
+    ])
+    .is_err());
+}
@@ -0,0 +1,69 @@
+use super::super::{
+    certificate::expect_empty,
+    der::{expect_bit_string, BitStringRef},
+    error::ParseError,
+};
+
+#[derive(Debug)]
+pub struct KeyUsageRef<'a>(BitStringRef<'a>);
+
+impl<'a> KeyUsageRef<'a> {
+    /// parses the extension value, i.e. the content of the extnValue octet string
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, bits) = expect_bit_string(data)?;
+        expect_empty(rest)?;
+
+        Ok(Self(bits))
+    }
+
+    // bits that are not encoded are not set, so out of range bits are reported as false
+    fn bit(&self, index: usize) -> bool {
+        self.0.bit_at(index).unwrap_or(false)
+    }
+
+    pub fn digital_signature(&self) -> bool {
+        self.bit(0)
+    }
+
+    pub fn non_repudiation(&self) -> bool {
+        self.bit(1)
+    }
+
+    pub fn key_encipherment(&self) -> bool {
+        self.bit(2)
+    }
+
+    pub fn data_encipherment(&self) -> bool {
+        self.bit(3)
+    }
+
+    pub fn key_agreement(&self) -> bool {
+        self.bit(4)
+    }
+
+    pub fn key_cert_sign(&self) -> bool {
+        self.bit(5)
+    }
+
+    pub fn crl_sign(&self) -> bool {
+        self.bit(6)
+    }
+
+    pub fn encipher_only(&self) -> bool {
+        self.bit(7)
+    }
+
+    pub fn decipher_only(&self) -> bool {
+        self.bit(8)
+    }
+}
+
+#[test]
+fn test_key_usage() {
+    // keyCertSign and cRLSign
+    let ku = KeyUsageRef::from_slice(&[0x03, 0x02, 0x01, 0x06]).unwrap();
+    assert!(ku.key_cert_sign());
+    assert!(ku.crl_sign());
+    assert!(!ku.digital_signature());
+    assert!(!ku.decipher_only());
+}
@@ -0,0 +1,32 @@
+//! Typed decoding of certificate extension values on top of the zero-copy parser.
+
+mod authority_info_access;
+mod authority_key_identifier;
+mod basic_constraints;
+mod certificate_policies;
+mod crl_distribution_points;
+mod extended_key_usage;
+mod general_name;
+mod issuing_distribution_point;
+mod key_usage;
+mod subject_key_identifier;
+
+pub use authority_info_access::{
+    AccessDescriptionIter, AccessDescriptionRef, AuthorityInfoAccessRef,
+};
+pub use authority_key_identifier::AuthorityKeyIdentifierRef;
+pub use basic_constraints::BasicConstraints;
+pub use certificate_policies::{
+    CertificatePoliciesRef, DisplayTextRef, NoticeReferenceRef, PolicyIdentifierIter,
+    PolicyInformationIter, PolicyInformationRef, PolicyQualifierIter, PolicyQualifierRef,
+    UserNoticeRef,
+};
+pub use crl_distribution_points::{
+    CrlDistributionPointsRef, DistributionPointIter, DistributionPointNameRef, DistributionPointRef,
+    ReasonFlagsRef,
+};
+pub use extended_key_usage::{ExtendedKeyUsageRef, KeyPurpose, KeyPurposeIter, KeyPurposeOidIter};
+pub use general_name::{GeneralNameRef, GeneralNamesIter, GeneralNamesRef};
+pub use issuing_distribution_point::IssuingDistributionPointRef;
+pub use key_usage::KeyUsageRef;
+pub use subject_key_identifier::SubjectKeyIdentifierRef;
@@ -0,0 +1,27 @@
+use super::super::{certificate::expect_empty, der::expect_octet_string, error::ParseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubjectKeyIdentifierRef<'a>(&'a [u8]);
+
+impl<'a> SubjectKeyIdentifierRef<'a> {
+    /// parses the extension value, i.e. the content of the extnValue octet string
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (rest, key_identifier) = expect_octet_string(data)?;
+        expect_empty(rest)?;
+
+        Ok(Self(key_identifier.as_bytes()))
+    }
+
+    pub fn key_identifier(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+#[test]
+fn test_subject_key_identifier() {
+    let ski = SubjectKeyIdentifierRef::from_slice(&[0x04, 0x02, 0x01, 0x02]).unwrap();
+    assert_eq!(ski.key_identifier(), &[0x01, 0x02]);
+
+    assert!(SubjectKeyIdentifierRef::from_slice(&[0x04, 0x01, 0x01, 0x00]).is_err());
+    assert!(SubjectKeyIdentifierRef::from_slice(&[0x05, 0x00]).is_err());
+}
@@ -0,0 +1,104 @@
+//! Bounds on the size of untrusted input, checked before anything is parsed.
+
+use super::der::get_tlv;
+use super::error::ParseError;
+
+/// nesting deeper than this is rejected, like in [`DerNode`](super::der::DerNode)
+const MAX_DEPTH: usize = 64;
+
+/// the largest input and the longest single DER element that are accepted.
+/// [`CertificateRef::from_slice`](super::parsing::CertificateRef::from_slice) applies the default
+/// of 1 MiB for both, real certificates are a few KiB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    max_input_len: usize,
+    max_field_len: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_input_len: 1 << 20,
+            max_field_len: 1 << 20,
+        }
+    }
+}
+
+impl ParseLimits {
+    /// no limits at all, for input that is trusted
+    pub fn unlimited() -> Self {
+        Self {
+            max_input_len: usize::MAX,
+            max_field_len: usize::MAX,
+        }
+    }
+
+    /// the largest accepted input in bytes
+    pub fn max_input_len(mut self, len: usize) -> Self {
+        self.max_input_len = len;
+        self
+    }
+
+    /// the largest accepted content length of a single element in bytes. the contents of
+    /// constructed elements are checked as well, primitive ones like extension values are not
+    /// looked into.
+    pub fn max_field_len(mut self, len: usize) -> Self {
+        self.max_field_len = len;
+        self
+    }
+
+    /// fails with `ParseError::TooLarge` if `data` or one of its elements is longer than allowed
+    pub fn check(&self, data: &[u8]) -> Result<(), ParseError> {
+        if data.len() > self.max_input_len {
+            return Err(ParseError::TooLarge);
+        }
+        // no element can be longer than the whole input
+        if data.len() > self.max_field_len {
+            check_fields(data, self.max_field_len, 0)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn check_fields(mut data: &[u8], max_len: usize, depth: usize) -> Result<(), ParseError> {
+    if depth > MAX_DEPTH {
+        return Err(ParseError::MalformedData);
+    }
+    while !data.is_empty() {
+        let (rest, tag, value) = get_tlv(data)?;
+        if value.len() > max_len {
+            return Err(ParseError::TooLarge);
+        }
+        if tag & 0x20 == 0x20 {
+            check_fields(value, max_len, depth + 1)?;
+        }
+        data = rest;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_limits() {
+    let cert = include_bytes!("../../../certs/ocsp/leaf.der");
+
+    assert!(ParseLimits::default().check(cert).is_ok());
+    assert!(ParseLimits::unlimited().check(cert).is_ok());
+    assert!(matches!(
+        ParseLimits::default().max_input_len(100).check(cert),
+        Err(ParseError::TooLarge)
+    ));
+    // the content of the outer SEQUENCE, after a 4 byte header, is the longest element
+    let content_len = cert.len() - 4;
+    assert!(ParseLimits::default()
+        .max_field_len(content_len)
+        .check(cert)
+        .is_ok());
+    assert!(matches!(
+        ParseLimits::default()
+            .max_field_len(content_len - 1)
+            .check(cert),
+        Err(ParseError::TooLarge)
+    ));
+}
@@ -1,4 +1,14 @@
 pub mod certificate;
+pub mod crl;
+pub mod ct;
+pub mod csr;
 pub mod der;
 pub mod error;
+pub mod extensions;
+pub mod limits;
+pub mod ocsp;
 pub mod parsing;
+pub mod pem;
+pub mod pkcs7;
+pub mod pkcs8;
+pub mod sec1;
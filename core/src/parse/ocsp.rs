@@ -0,0 +1,490 @@
+//! OCSP responses as defined in RFC 6960.
+
+use std::convert::TryFrom;
+
+use super::{
+    certificate::{
+        expect_empty, parse_algorithm_identifier, AlgorithmidentifierRef, ExtensionsRef, NameRef,
+    },
+    der::{
+        expect_bit_string, expect_enumerated, expect_generalized_time, expect_integer,
+        expect_object_identifier, expect_octet_string, expect_sequence, try_get_explicit,
+        try_get_implicit, BitStringRef, ExplicitTag, GeneralizedTimeRef, IntegerRef,
+        OctetStringRef,
+    },
+    error::ParseError,
+};
+use crate::common::certificate::CRLReason;
+use crate::oids;
+
+pub use super::parsing::CertificatesIter;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OCSPResponseStatus {
+    Successful,
+    MalformedRequest,
+    InternalError,
+    TryLater,
+    SigRequired,
+    Unauthorized,
+}
+
+impl TryFrom<i64> for OCSPResponseStatus {
+    type Error = ParseError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(OCSPResponseStatus::Successful),
+            1 => Ok(OCSPResponseStatus::MalformedRequest),
+            2 => Ok(OCSPResponseStatus::InternalError),
+            3 => Ok(OCSPResponseStatus::TryLater),
+            // 4 is not used
+            5 => Ok(OCSPResponseStatus::SigRequired),
+            6 => Ok(OCSPResponseStatus::Unauthorized),
+            _ => Err(ParseError::MalformedData),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OCSPResponseRef<'a> {
+    status: OCSPResponseStatus,
+    basic_response: Option<BasicOCSPResponseRef<'a>>,
+}
+
+impl<'a> OCSPResponseRef<'a> {
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (left, root) = expect_sequence(data)?;
+        // the root sequence should take up all the space in the buffer
+        expect_empty(left)?;
+
+        let (data, status) = expect_enumerated(root)?;
+        let status = OCSPResponseStatus::try_from(status.to_i64()?)?;
+        let basic_response = match try_get_explicit(data, ExplicitTag::try_new(0)?) {
+            Ok((data, response_bytes)) => {
+                expect_empty(data)?;
+                Some(parse_response_bytes(response_bytes)?)
+            }
+            _ => {
+                expect_empty(data)?;
+                None
+            }
+        };
+
+        Ok(Self {
+            status,
+            basic_response,
+        })
+    }
+
+    pub fn status(&self) -> OCSPResponseStatus {
+        self.status
+    }
+
+    /// the response body, only present if the status is `Successful`
+    pub fn basic_response(&self) -> Option<&BasicOCSPResponseRef<'a>> {
+        self.basic_response.as_ref()
+    }
+}
+
+fn parse_response_bytes(data: &[u8]) -> Result<BasicOCSPResponseRef<'_>, ParseError> {
+    let (rest, data) = expect_sequence(data)?;
+    expect_empty(rest)?;
+    let (data, response_type) = expect_object_identifier(data)?;
+    let (data, response) = expect_octet_string(data)?;
+    expect_empty(data)?;
+
+    if !oids::is(&response_type, oids::PKIX_OCSP_BASIC) {
+        // id-pkix-ocsp-basic is the only response type defined
+        return Err(ParseError::MalformedData);
+    }
+
+    BasicOCSPResponseRef::from_slice(response.as_bytes())
+}
+
+#[derive(Debug)]
+pub struct BasicOCSPResponseRef<'a> {
+    tbs_response_data: ResponseDataRef<'a>,
+    signature_algorithm: AlgorithmidentifierRef<'a>,
+    signature: BitStringRef<'a>,
+    certs: &'a [u8],
+}
+
+impl<'a> BasicOCSPResponseRef<'a> {
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (left, root) = expect_sequence(data)?;
+        expect_empty(left)?;
+
+        let (data, tbs_response_data) = ResponseDataRef::parse(root)?;
+        let (data, signature_algorithm) = parse_algorithm_identifier(data)?;
+        let (data, signature) = expect_bit_string(data)?;
+        let (data, certs) = match try_get_explicit(data, ExplicitTag::try_new(0)?) {
+            Ok((data, inner)) => {
+                let (inner, certs) = expect_sequence(inner)?;
+                expect_empty(inner)?;
+                (data, certs)
+            }
+            _ => (data, &[][..]),
+        };
+        expect_empty(data)?;
+
+        Ok(Self {
+            tbs_response_data,
+            signature_algorithm,
+            signature,
+            certs,
+        })
+    }
+
+    pub fn tbs_response_data(&self) -> &ResponseDataRef<'a> {
+        &self.tbs_response_data
+    }
+
+    pub fn signature_algorithm(&self) -> &AlgorithmidentifierRef<'a> {
+        &self.signature_algorithm
+    }
+
+    pub fn signature(&self) -> &BitStringRef<'a> {
+        &self.signature
+    }
+
+    /// certificates the responder included to help verify the signature, usually a delegated
+    /// responder certificate
+    pub fn certs(&self) -> CertificatesIter<'a> {
+        CertificatesIter::new(self.certs)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ResponderIdRef<'a> {
+    ByName(NameRef<'a>),
+    /// SHA-1 hash of the responder's public key, excluding tag, length and unused bits
+    ByKey(OctetStringRef<'a>),
+}
+
+impl<'a> ResponderIdRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        if let Ok((rest, inner)) = try_get_explicit(data, ExplicitTag::try_new(1)?) {
+            let (inner, name) = NameRef::parse(inner)?;
+            expect_empty(inner)?;
+            return Ok((rest, ResponderIdRef::ByName(name)));
+        }
+
+        let (rest, inner) = try_get_explicit(data, ExplicitTag::try_new(2)?)?;
+        let (inner, key_hash) = expect_octet_string(inner)?;
+        expect_empty(inner)?;
+        Ok((rest, ResponderIdRef::ByKey(key_hash)))
+    }
+}
+
+#[derive(Debug)]
+pub struct ResponseDataRef<'a> {
+    raw_data: &'a [u8],
+
+    responder_id: ResponderIdRef<'a>,
+    produced_at: GeneralizedTimeRef<'a>,
+    responses: &'a [u8],
+    extensions: Option<ExtensionsRef<'a>>,
+}
+
+impl<'a> ResponseDataRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, inner) = expect_sequence(data)?;
+        let raw_data = &data[..data.len() - rest.len()];
+
+        // version is DEFAULT v1 and therefore omitted in DER
+        let inner = match try_get_explicit(inner, ExplicitTag::try_new(0)?) {
+            Ok((inner, version)) => {
+                let (version, v) = expect_integer(version)?;
+                expect_empty(version)?;
+                if v.to_i64()? != 0 {
+                    return Err(ParseError::InvalidVersion);
+                }
+                inner
+            }
+            _ => inner,
+        };
+        let (inner, responder_id) = ResponderIdRef::parse(inner)?;
+        let (inner, produced_at) = expect_generalized_time(inner)?;
+        let (inner, responses) = expect_sequence(inner)?;
+        let (inner, extensions) = ExtensionsRef::parse_explicit(inner, 1)?;
+        expect_empty(inner)?;
+
+        let response_data = Self {
+            raw_data,
+            responder_id,
+            produced_at,
+            responses,
+            extensions,
+        };
+
+        Ok((rest, response_data))
+    }
+
+    /// the DER encoding of tbsResponseData, this is what the signature is calculated over
+    pub fn raw_data(&self) -> &'a [u8] {
+        self.raw_data
+    }
+
+    pub fn responder_id(&self) -> &ResponderIdRef<'a> {
+        &self.responder_id
+    }
+
+    pub fn produced_at(&self) -> &GeneralizedTimeRef<'a> {
+        &self.produced_at
+    }
+
+    pub fn responses(&self) -> SingleResponsesIter<'a> {
+        SingleResponsesIter {
+            pos: self.responses,
+        }
+    }
+
+    pub fn extensions(&self) -> Option<ExtensionsRef<'a>> {
+        self.extensions
+    }
+}
+
+pub struct SingleResponsesIter<'a> {
+    pos: &'a [u8],
+}
+
+impl<'a> Iterator for SingleResponsesIter<'a> {
+    type Item = Result<SingleResponseRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        let result = SingleResponseRef::parse(self.pos);
+        match result {
+            Ok((rest, response)) => {
+                self.pos = rest;
+                Some(Ok(response))
+            }
+            Err(e) => {
+                // the rest can't be parsed either, end after the error
+                self.pos = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CertIdRef<'a> {
+    hash_algorithm: AlgorithmidentifierRef<'a>,
+    issuer_name_hash: OctetStringRef<'a>,
+    issuer_key_hash: OctetStringRef<'a>,
+    serial_number: IntegerRef<'a>,
+}
+
+impl<'a> CertIdRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, data) = expect_sequence(data)?;
+        let (data, hash_algorithm) = parse_algorithm_identifier(data)?;
+        let (data, issuer_name_hash) = expect_octet_string(data)?;
+        let (data, issuer_key_hash) = expect_octet_string(data)?;
+        let (data, serial_number) = expect_integer(data)?;
+        expect_empty(data)?;
+
+        let cert_id = Self {
+            hash_algorithm,
+            issuer_name_hash,
+            issuer_key_hash,
+            serial_number,
+        };
+
+        Ok((rest, cert_id))
+    }
+
+    pub fn hash_algorithm(&self) -> &AlgorithmidentifierRef<'a> {
+        &self.hash_algorithm
+    }
+
+    /// hash of the DER encoded issuer name
+    pub fn issuer_name_hash(&self) -> &OctetStringRef<'a> {
+        &self.issuer_name_hash
+    }
+
+    /// hash of the issuer's public key, excluding tag, length and unused bits
+    pub fn issuer_key_hash(&self) -> &OctetStringRef<'a> {
+        &self.issuer_key_hash
+    }
+
+    pub fn serial_number(&self) -> &IntegerRef<'a> {
+        &self.serial_number
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CertStatusRef<'a> {
+    Good,
+    Revoked {
+        revocation_time: GeneralizedTimeRef<'a>,
+        revocation_reason: Option<CRLReason>,
+    },
+    Unknown,
+}
+
+impl<'a> CertStatusRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        if let Ok((rest, inner)) = try_get_implicit(data, 0) {
+            // [0] IMPLICIT NULL
+            expect_empty(inner)?;
+            return Ok((rest, CertStatusRef::Good));
+        }
+        if let Ok((rest, inner)) = try_get_implicit(data, 1) {
+            // [1] IMPLICIT RevokedInfo
+            let (inner, revocation_time) = expect_generalized_time(inner)?;
+            let (inner, revocation_reason) = match try_get_explicit(inner, ExplicitTag::try_new(0)?)
+            {
+                Ok((inner, reason)) => {
+                    let (reason, r) = expect_enumerated(reason)?;
+                    expect_empty(reason)?;
+                    (inner, Some(CRLReason::try_from(r.to_i64()?)?))
+                }
+                _ => (inner, None),
+            };
+            expect_empty(inner)?;
+            return Ok((
+                rest,
+                CertStatusRef::Revoked {
+                    revocation_time,
+                    revocation_reason,
+                },
+            ));
+        }
+
+        let (rest, inner) = try_get_implicit(data, 2)?;
+        // [2] IMPLICIT UnknownInfo, which is NULL
+        expect_empty(inner)?;
+        Ok((rest, CertStatusRef::Unknown))
+    }
+}
+
+#[derive(Debug)]
+pub struct SingleResponseRef<'a> {
+    cert_id: CertIdRef<'a>,
+    cert_status: CertStatusRef<'a>,
+    this_update: GeneralizedTimeRef<'a>,
+    next_update: Option<GeneralizedTimeRef<'a>>,
+    extensions: Option<ExtensionsRef<'a>>,
+}
+
+impl<'a> SingleResponseRef<'a> {
+    fn parse(data: &'a [u8]) -> Result<(&'a [u8], Self), ParseError> {
+        let (rest, data) = expect_sequence(data)?;
+        let (data, cert_id) = CertIdRef::parse(data)?;
+        let (data, cert_status) = CertStatusRef::parse(data)?;
+        let (data, this_update) = expect_generalized_time(data)?;
+        let (data, next_update) = match try_get_explicit(data, ExplicitTag::try_new(0)?) {
+            Ok((data, inner)) => {
+                let (inner, next_update) = expect_generalized_time(inner)?;
+                expect_empty(inner)?;
+                (data, Some(next_update))
+            }
+            _ => (data, None),
+        };
+        let (data, extensions) = ExtensionsRef::parse_explicit(data, 1)?;
+        expect_empty(data)?;
+
+        let response = Self {
+            cert_id,
+            cert_status,
+            this_update,
+            next_update,
+            extensions,
+        };
+
+        Ok((rest, response))
+    }
+
+    pub fn cert_id(&self) -> &CertIdRef<'a> {
+        &self.cert_id
+    }
+
+    pub fn cert_status(&self) -> &CertStatusRef<'a> {
+        &self.cert_status
+    }
+
+    pub fn this_update(&self) -> &GeneralizedTimeRef<'a> {
+        &self.this_update
+    }
+
+    pub fn next_update(&self) -> Option<&GeneralizedTimeRef<'a>> {
+        self.next_update.as_ref()
+    }
+
+    pub fn extensions(&self) -> Option<ExtensionsRef<'a>> {
+        self.extensions
+    }
+}
+
+#[test]
+fn test_ocsp_response() {
+    let data = include_bytes!("../../../certs/ocsp/good-delegated.der");
+    let response = OCSPResponseRef::from_slice(data).unwrap();
+    assert_eq!(response.status(), OCSPResponseStatus::Successful);
+
+    let basic = response.basic_response().unwrap();
+    let tbs = basic.tbs_response_data();
+    assert!(matches!(tbs.responder_id(), ResponderIdRef::ByKey(_)));
+
+    let responses: Vec<SingleResponseRef> = tbs.responses().map(Result::unwrap).collect();
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0].cert_status(), &CertStatusRef::Good);
+    assert_eq!(
+        responses[0].cert_id().serial_number().to_i64().unwrap(),
+        0x1000
+    );
+    assert!(responses[0].next_update().is_some());
+
+    // the delegated responder certificate is included
+    assert_eq!(basic.certs().map(Result::unwrap).count(), 1);
+}
+
+#[test]
+fn test_ocsp_response_revoked() {
+    let data = include_bytes!("../../../certs/ocsp/revoked.der");
+    let response = OCSPResponseRef::from_slice(data).unwrap();
+    let basic = response.basic_response().unwrap();
+    assert!(matches!(
+        basic.tbs_response_data().responder_id(),
+        ResponderIdRef::ByName(_)
+    ));
+    assert_eq!(basic.certs().count(), 0);
+
+    let single = basic
+        .tbs_response_data()
+        .responses()
+        .next()
+        .unwrap()
+        .unwrap();
+    match single.cert_status() {
+        CertStatusRef::Revoked {
+            revocation_time,
+            revocation_reason,
+        } => {
+            assert_eq!(revocation_time.to_string(), "2026-10-01T12:00:00+00:00");
+            assert_eq!(revocation_reason, &Some(CRLReason::KeyCompromise));
+        }
+        s => panic!("unexpected status {:?}", s),
+    }
+}
+
+#[test]
+fn test_ocsp_response_unsuccessful() {
+    // OCSPResponse with status tryLater and no responseBytes
+    let data = [0x30, 0x03, 0x0a, 0x01, 0x03];
+    let response = OCSPResponseRef::from_slice(&data).unwrap();
+    assert_eq!(response.status(), OCSPResponseStatus::TryLater);
+    assert!(response.basic_response().is_none());
+}
+
+#[test]
+fn test_certificates_iter_ends_after_error() {
+    let mut iter = CertificatesIter::new(&[0x30, 0x01, 0xff]);
+    assert!(matches!(iter.next(), Some(Err(_))));
+    assert!(iter.next().is_none());
+}
@@ -4,12 +4,18 @@ use super::{
         ExtensionsRef, NameRef, SubjectPublicKeyInfoRef, ValidityRef,
     },
     der::{
-        expect_bit_string, expect_integer, expect_sequence, try_get_explicit, BitStringRef,
-        ExplicitTag, IntegerRef,
+        expect_bit_string, expect_integer, expect_sequence, get_tlv, try_get_implicit,
+        BitStringRef, IntegerRef,
     },
     error::ParseError,
+    limits::ParseLimits,
+    pem::decode_pem,
 };
 use crate::common::certificate::Version;
+use crate::error::Error;
+use crate::utils::to_hex_colon;
+use std::ops::Range;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct TBSCertificateRef<'a> {
@@ -25,6 +31,45 @@ pub struct TBSCertificateRef<'a> {
     issuer_unique_id: Option<BitStringRef<'a>>,
     subject_unique_id: Option<BitStringRef<'a>>,
     extensions: Option<ExtensionsRef<'a>>,
+
+    field_ranges: TbsFieldRanges,
+}
+
+/// where the top-level fields of the TBSCertificate are in the certificate, as byte offsets
+/// into the DER of the whole certificate. every range covers the complete element, tag and
+/// length included. fields that are absent have no range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TbsFieldRanges {
+    pub tbs_certificate: Range<usize>,
+    pub version: Option<Range<usize>>,
+    pub serial_number: Range<usize>,
+    pub signature: Range<usize>,
+    pub issuer: Range<usize>,
+    pub validity: Range<usize>,
+    pub subject: Range<usize>,
+    pub subject_public_key_info: Range<usize>,
+    pub issuer_unique_id: Option<Range<usize>>,
+    pub subject_unique_id: Option<Range<usize>>,
+    pub extensions: Option<Range<usize>>,
+}
+
+impl TbsFieldRanges {
+    fn shift(self, offset: usize) -> Self {
+        let shift = |range: Range<usize>| range.start + offset..range.end + offset;
+        TbsFieldRanges {
+            tbs_certificate: shift(self.tbs_certificate),
+            version: self.version.map(shift),
+            serial_number: shift(self.serial_number),
+            signature: shift(self.signature),
+            issuer: shift(self.issuer),
+            validity: shift(self.validity),
+            subject: shift(self.subject),
+            subject_public_key_info: shift(self.subject_public_key_info),
+            issuer_unique_id: self.issuer_unique_id.map(shift),
+            subject_unique_id: self.subject_unique_id.map(shift),
+            extensions: self.extensions.map(shift),
+        }
+    }
 }
 
 impl<'a> TBSCertificateRef<'a> {
@@ -32,6 +77,10 @@ impl<'a> TBSCertificateRef<'a> {
         &self.raw_data
     }
 
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
     pub fn serial_number(&self) -> &IntegerRef {
         &self.serial_number
     }
@@ -48,6 +97,20 @@ impl<'a> TBSCertificateRef<'a> {
         &self.issuer
     }
 
+    pub fn validity(&self) -> &ValidityRef<'a> {
+        &self.validity
+    }
+
+    /// the serial number as colon separated uppercase hex, the way browsers show it
+    pub fn serial_hex(&self) -> String {
+        to_hex_colon(self.serial_number.as_bytes())
+    }
+
+    /// see [`ValidityRef::period`]
+    pub fn validity_period(&self) -> Result<Duration, Error> {
+        self.validity.period()
+    }
+
     pub fn algorithm_identifier(&self) -> &AlgorithmidentifierRef {
         &self.algorithm_identifier
     }
@@ -55,33 +118,106 @@ impl<'a> TBSCertificateRef<'a> {
     pub fn subject_public_key_info(&self) -> &SubjectPublicKeyInfoRef {
         &self.subject_public_key_info
     }
+
+    /// the complete DER encoding of the SubjectPublicKeyInfo, the input of SPKI pins and of the
+    /// CT log ids
+    pub fn raw_spki_der(&self) -> &'a [u8] {
+        self.subject_public_key_info.raw_data()
+    }
+
+    pub fn issuer_unique_id(&self) -> Option<&BitStringRef<'a>> {
+        self.issuer_unique_id.as_ref()
+    }
+
+    pub fn subject_unique_id(&self) -> Option<&BitStringRef<'a>> {
+        self.subject_unique_id.as_ref()
+    }
 }
 
 #[derive(Debug)]
 pub struct CertificateRef<'a> {
+    raw_data: &'a [u8],
     tbs_cert: TBSCertificateRef<'a>,
     signature_algorithm: AlgorithmidentifierRef<'a>,
     signature: BitStringRef<'a>,
 }
 
 impl<'a> CertificateRef<'a> {
+    /// parses a DER encoded certificate that takes up all of `data`. inputs larger than the
+    /// default [`ParseLimits`] of 1 MiB are rejected with `ParseError::TooLarge`.
     pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
-        let (left, root) = expect_sequence(data)?;
+        Self::from_slice_with_limits(data, &ParseLimits::default())
+    }
+
+    /// like [`from_slice`](Self::from_slice), with other limits on the size of `data` and its
+    /// elements. use [`ParseLimits::unlimited`] for trusted input.
+    pub fn from_slice_with_limits(
+        data: &'a [u8],
+        limits: &ParseLimits,
+    ) -> Result<Self, ParseError> {
+        limits.check(data)?;
+        let (left, cert) = expect_certificate(data)?;
         // the root sequence should take up all the space in the buffer
         expect_empty(left)?;
 
-        let (data, tbs_cert) = expect_tbs(root)?;
-        let (data, signature_algorithm) = parse_algorithm_identifier(data)?;
-        let (data, signature) = expect_bit_string(data)?;
-        expect_empty(data)?;
+        Ok(cert)
+    }
 
-        let cert = Self {
-            tbs_cert,
-            signature_algorithm,
-            signature,
-        };
+    /// like [`from_slice`](Self::from_slice), also returns where each top-level field of the
+    /// TBSCertificate is in `data`. meant for tools that highlight or patch single fields.
+    pub fn from_slice_with_ranges(data: &'a [u8]) -> Result<(Self, TbsFieldRanges), ParseError> {
+        let cert = Self::from_slice(data)?;
+        // the TBSCertificate is the first element in the certificate sequence
+        let (_, root) = expect_sequence(data)?;
+        let ranges = cert.tbs_cert.field_ranges.clone();
 
-        Ok(cert)
+        Ok((cert, ranges.shift(data.len() - root.len())))
+    }
+
+    /// iterates over DER encoded certificates that follow each other in `data`, e.g. .der files
+    /// that were concatenated. each certificate is checked against the default
+    /// [`ParseLimits`]. the iterator ends after the first certificate that doesn't parse.
+    pub fn from_slice_multi(data: &'a [u8]) -> CertificatesIter<'a> {
+        Self::from_slice_multi_with_limits(data, &ParseLimits::default())
+    }
+
+    /// like [`from_slice_multi`](Self::from_slice_multi), with other limits for each
+    /// certificate
+    pub fn from_slice_multi_with_limits(
+        data: &'a [u8],
+        limits: &ParseLimits,
+    ) -> CertificatesIter<'a> {
+        CertificatesIter {
+            pos: data,
+            limits: *limits,
+        }
+    }
+
+    /// decodes a bare base64 encoded certificate without PEM armor, the format used by CT log
+    /// dumps and many databases. the DER is decoded into `buf`, so it can be reused when
+    /// processing many certificates.
+    pub fn from_base64(input: &str, buf: &'a mut Vec<u8>) -> Result<Self, ParseError> {
+        buf.clear();
+        base64::decode_config_buf(input.trim(), base64::STANDARD, buf)
+            .map_err(|_| ParseError::StringEncoding)?;
+        Self::from_slice(buf)
+    }
+
+    /// parses the first `CERTIFICATE` block in PEM encoded `input`, other blocks are skipped.
+    /// the DER is decoded into `buf`. fails with `ParseError::MalformedData` if there is no
+    /// certificate. use [`decode_pem`](super::pem::decode_pem) to get at all blocks.
+    pub fn from_pem(input: &[u8], buf: &'a mut Vec<u8>) -> Result<Self, ParseError> {
+        let pem = decode_pem(input)?
+            .into_iter()
+            .find(|pem| pem.label() == "CERTIFICATE")
+            .ok_or(ParseError::MalformedData)?;
+        *buf = pem.into_contents();
+        Self::from_slice(buf)
+    }
+
+    /// the complete DER encoding of the certificate
+    pub fn raw_data(&self) -> &'a [u8] {
+        self.raw_data
     }
 
     pub fn tbs_cert(&self) -> &TBSCertificateRef<'a> {
@@ -97,6 +233,70 @@ impl<'a> CertificateRef<'a> {
     }
 }
 
+pub struct CertificatesIter<'a> {
+    pos: &'a [u8],
+    limits: ParseLimits,
+}
+
+impl<'a> CertificatesIter<'a> {
+    /// iterates over DER encoded certificates that directly follow each other. they are part
+    /// of a structure that was parsed already, so no limits are applied.
+    pub(crate) fn new(certs: &'a [u8]) -> Self {
+        Self {
+            pos: certs,
+            limits: ParseLimits::unlimited(),
+        }
+    }
+
+    fn next_certificate(&self) -> Result<(&'a [u8], CertificateRef<'a>), ParseError> {
+        let (rest, _, _) = get_tlv(self.pos)?;
+        let len = self.pos.len() - rest.len();
+        self.limits.check(&self.pos[..len])?;
+        expect_certificate(self.pos)
+    }
+}
+
+impl<'a> Iterator for CertificatesIter<'a> {
+    type Item = Result<CertificateRef<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos.is_empty() {
+            return None;
+        }
+        match self.next_certificate() {
+            Ok((rest, cert)) => {
+                self.pos = rest;
+                Some(Ok(cert))
+            }
+            Err(e) => {
+                // the rest can't be parsed either, end after the error
+                self.pos = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// parses a single certificate from the start of `data`, returns the remaining bytes
+pub(crate) fn expect_certificate(data: &[u8]) -> Result<(&[u8], CertificateRef<'_>), ParseError> {
+    let (rest, root) = expect_sequence(data)?;
+    let raw_data = &data[..data.len() - rest.len()];
+
+    let (data, tbs_cert) = expect_tbs(root)?;
+    let (data, signature_algorithm) = parse_algorithm_identifier(data)?;
+    let (data, signature) = expect_bit_string(data)?;
+    expect_empty(data)?;
+
+    let cert = CertificateRef {
+        raw_data,
+        tbs_cert,
+        signature_algorithm,
+        signature,
+    };
+
+    Ok((rest, cert))
+}
+
 pub(crate) fn expect_tbs<'a>(data: &'a [u8]) -> Result<(&[u8], TBSCertificateRef), ParseError> {
     let (rest, tbs_data) = expect_sequence(data)?;
     let size_of_raw_tbs = data.len() - rest.len();
@@ -107,18 +307,48 @@ pub(crate) fn expect_tbs<'a>(data: &'a [u8]) -> Result<(&[u8], TBSCertificateRef
 
 /// raw_data is the slice containing all of TBS including the sequence wrapper. this is what will be checked in signature verification
 fn parse_tbs<'a>(data: &'a [u8], raw_data: &'a [u8]) -> Result<TBSCertificateRef<'a>, ParseError> {
-    let (data, version) = parse_version(data)?;
-    let (data, serial_number) = expect_integer(data)?;
-    let (data, algorithm_identifier) = parse_algorithm_identifier(data)?;
-    let (data, issuer) = NameRef::parse(data)?;
-    let (data, validity) = ValidityRef::parse(data)?;
-    let (data, subject) = NameRef::parse(data)?;
-    let (data, subject_public_key_info) = SubjectPublicKeyInfoRef::parse(data)?;
-    let (data, issuer_unique_id) = parse_issuer_unique_id(data)?;
-    let (data, subject_unique_id) = parse_subject_unique_id(data)?;
-    let (data, extensions) = ExtensionsRef::parse(data)?;
+    // offsets are relative to raw_data, which ends where data ends
+    let offset = |rest: &[u8]| raw_data.len() - rest.len();
+    let range = |before: &[u8], after: &[u8]| offset(before)..offset(after);
+    let optional =
+        |before: &[u8], after: &[u8]| Some(range(before, after)).filter(|range| !range.is_empty());
+
+    let (rest, version) = parse_version(data)?;
+    let version_range = optional(data, rest);
+    let (data, serial_number) = expect_integer(rest)?;
+    let serial_number_range = range(rest, data);
+    let (rest, algorithm_identifier) = parse_algorithm_identifier(data)?;
+    let signature_range = range(data, rest);
+    let (data, issuer) = NameRef::parse(rest)?;
+    let issuer_range = range(rest, data);
+    let (rest, validity) = ValidityRef::parse(data)?;
+    let validity_range = range(data, rest);
+    let (data, subject) = NameRef::parse(rest)?;
+    let subject_range = range(rest, data);
+    let (rest, subject_public_key_info) = SubjectPublicKeyInfoRef::parse(data)?;
+    let subject_public_key_info_range = range(data, rest);
+    let (data, issuer_unique_id) = parse_issuer_unique_id(rest)?;
+    let issuer_unique_id_range = optional(rest, data);
+    let (rest, subject_unique_id) = parse_subject_unique_id(data)?;
+    let subject_unique_id_range = optional(data, rest);
+    let (data, extensions) = ExtensionsRef::parse(rest)?;
+    let extensions_range = optional(rest, data);
     expect_empty(data)?;
 
+    let field_ranges = TbsFieldRanges {
+        tbs_certificate: 0..raw_data.len(),
+        version: version_range,
+        serial_number: serial_number_range,
+        signature: signature_range,
+        issuer: issuer_range,
+        validity: validity_range,
+        subject: subject_range,
+        subject_public_key_info: subject_public_key_info_range,
+        issuer_unique_id: issuer_unique_id_range,
+        subject_unique_id: subject_unique_id_range,
+        extensions: extensions_range,
+    };
+
     let tbs = TBSCertificateRef {
         raw_data,
 
@@ -132,6 +362,8 @@ fn parse_tbs<'a>(data: &'a [u8], raw_data: &'a [u8]) -> Result<TBSCertificateRef
         issuer_unique_id,
         subject_unique_id,
         extensions,
+
+        field_ranges,
     };
 
     Ok(tbs)
@@ -140,12 +372,9 @@ fn parse_tbs<'a>(data: &'a [u8], raw_data: &'a [u8]) -> Result<TBSCertificateRef
 fn parse_issuer_unique_id<'a>(
     data: &'a [u8],
 ) -> Result<(&[u8], Option<BitStringRef<'a>>), ParseError> {
-    match try_get_explicit(data, ExplicitTag::try_new(1)?) {
-        Ok((rest, inner)) => {
-            let (inner, identifier) = expect_bit_string(inner)?;
-            expect_empty(inner)?;
-            Ok((rest, Some(identifier)))
-        }
+    // issuerUniqueID [1] IMPLICIT UniqueIdentifier
+    match try_get_implicit(data, 1) {
+        Ok((rest, identifier)) => Ok((rest, Some(BitStringRef::from_content(identifier)?))),
         _ => Ok((data, None)),
     }
 }
@@ -153,12 +382,9 @@ fn parse_issuer_unique_id<'a>(
 fn parse_subject_unique_id<'a>(
     data: &'a [u8],
 ) -> Result<(&[u8], Option<BitStringRef<'a>>), ParseError> {
-    match try_get_explicit(data, ExplicitTag::try_new(2)?) {
-        Ok((rest, inner)) => {
-            let (inner, identifier) = expect_bit_string(inner)?;
-            expect_empty(inner)?;
-            Ok((rest, Some(identifier)))
-        }
+    // subjectUniqueID [2] IMPLICIT UniqueIdentifier
+    match try_get_implicit(data, 2) {
+        Ok((rest, identifier)) => Ok((rest, Some(BitStringRef::from_content(identifier)?))),
         _ => Ok((data, None)),
     }
 }
@@ -177,3 +403,167 @@ fn test_cert() {
         num_bigint::BigInt::from_str("333504890676592408951587385614406537514249").unwrap()
     );
 }
+
+#[test]
+fn test_serial_hex_and_validity_period() {
+    let data = include_bytes!("../../../certs/test.crt");
+    let cert = CertificateRef::from_slice(data).unwrap();
+    assert_eq!(
+        cert.tbs_cert().serial_hex(),
+        "03:D4:15:31:8E:2C:57:1D:29:05:FC:3E:05:27:68:9D:0D:09"
+    );
+    assert_eq!(
+        cert.tbs_cert().validity_period().unwrap(),
+        Duration::from_secs(90 * 24 * 60 * 60)
+    );
+}
+
+#[test]
+fn test_from_base64() {
+    let der = include_bytes!("../../../certs/ocsp/leaf.der");
+    let line = format!("{}\n", base64::encode(&der[..]));
+    let mut buf = Vec::new();
+
+    let cert = CertificateRef::from_base64(&line, &mut buf).unwrap();
+    assert_eq!(cert.tbs_cert().serial_number().to_i64().unwrap(), 0x1000);
+
+    assert!(CertificateRef::from_base64("not base64!", &mut buf).is_err());
+}
+
+#[test]
+fn test_from_pem() {
+    use crate::generate::pem::encode_pem;
+
+    let input = encode_pem("PRIVATE KEY", &[1, 2, 3])
+        + &encode_pem(
+            "CERTIFICATE",
+            include_bytes!("../../../certs/ocsp/leaf.der"),
+        )
+        + &encode_pem("CERTIFICATE", include_bytes!("../../../certs/ocsp/ca.der"));
+    let mut buf = Vec::new();
+
+    let cert = CertificateRef::from_pem(input.as_bytes(), &mut buf).unwrap();
+    assert_eq!(cert.tbs_cert().serial_number().to_i64().unwrap(), 0x1000);
+
+    let key_only = encode_pem("PRIVATE KEY", &[1, 2, 3]);
+    assert!(matches!(
+        CertificateRef::from_pem(key_only.as_bytes(), &mut buf),
+        Err(ParseError::MalformedData)
+    ));
+}
+
+#[test]
+fn test_from_slice_limits() {
+    let der = include_bytes!("../../../certs/ocsp/leaf.der");
+    let limits = ParseLimits::default().max_input_len(der.len() - 1);
+    assert!(matches!(
+        CertificateRef::from_slice_with_limits(der, &limits),
+        Err(ParseError::TooLarge)
+    ));
+
+    // rejected by size before the garbage is looked at
+    let huge = vec![0u8; (1 << 20) + 1];
+    assert!(matches!(
+        CertificateRef::from_slice(&huge),
+        Err(ParseError::TooLarge)
+    ));
+    assert!(!matches!(
+        CertificateRef::from_slice_with_limits(&huge, &ParseLimits::unlimited()),
+        Err(ParseError::TooLarge)
+    ));
+}
+
+#[test]
+fn test_raw_spki_der() {
+    let cert = CertificateRef::from_slice(include_bytes!("../../../certs/ocsp/leaf.der")).unwrap();
+    let spki = cert.tbs_cert().raw_spki_der();
+
+    // openssl x509 -pubkey | openssl pkey -pubin -outform der | sha256sum
+    let digest = ring::digest::digest(&ring::digest::SHA256, spki);
+    assert_eq!(
+        crate::utils::to_hex_colon(digest.as_ref()).replace(':', ""),
+        "4A98D8467816ADC5997AADAD64FC4597C6BF85FD2AE591DC4509BC8F036C93AD"
+    );
+}
+
+#[test]
+fn test_field_ranges() {
+    use super::der::get_tlv;
+
+    let der = include_bytes!("../../../certs/ocsp/leaf.der");
+    let (cert, ranges) = CertificateRef::from_slice_with_ranges(der).unwrap();
+
+    assert_eq!(
+        &der[ranges.tbs_certificate.clone()],
+        cert.tbs_cert().raw_data()
+    );
+    assert_eq!(
+        &der[ranges.subject_public_key_info.clone()],
+        cert.tbs_cert().raw_spki_der()
+    );
+    assert_eq!(ranges.version.as_ref().unwrap().start, 4 + 4);
+    assert!(ranges.issuer_unique_id.is_none());
+    assert!(ranges.subject_unique_id.is_none());
+
+    // the fields follow each other without gaps, each one is a single element
+    let fields = [
+        ranges.version.clone().unwrap(),
+        ranges.serial_number.clone(),
+        ranges.signature.clone(),
+        ranges.issuer.clone(),
+        ranges.validity.clone(),
+        ranges.subject.clone(),
+        ranges.subject_public_key_info.clone(),
+        ranges.extensions.clone().unwrap(),
+    ];
+    for pair in fields.windows(2) {
+        assert_eq!(pair[0].end, pair[1].start);
+    }
+    assert_eq!(fields[7].end, ranges.tbs_certificate.end);
+    for field in fields.iter() {
+        let (rest, _, _) = get_tlv(&der[field.clone()]).unwrap();
+        assert!(rest.is_empty());
+    }
+}
+
+#[test]
+fn test_from_slice_multi() {
+    let leaf = include_bytes!("../../../certs/ocsp/leaf.der");
+    let ca = include_bytes!("../../../certs/ocsp/ca.der");
+    let mut data = leaf.to_vec();
+    data.extend_from_slice(ca);
+
+    let certs = CertificateRef::from_slice_multi(&data)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(certs.len(), 2);
+    assert_eq!(certs[0].raw_data(), &leaf[..]);
+    assert_eq!(certs[1].raw_data(), &ca[..]);
+    assert!(CertificateRef::from_slice(&data).is_err());
+
+    assert_eq!(CertificateRef::from_slice_multi(&[]).count(), 0);
+
+    // trailing garbage is an error after the complete certificates, then the iterator ends
+    data.extend_from_slice(&[0x30, 0x05, 0x01]);
+    let mut iter = CertificateRef::from_slice_multi(&data);
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+    assert_eq!(
+        CertificateRef::from_slice_multi(&data)
+            .filter_map(Result::ok)
+            .count(),
+        2
+    );
+
+    // the limits apply to each certificate, not to all of them
+    let limits = ParseLimits::default().max_input_len(leaf.len().max(ca.len()));
+    let mut iter = CertificateRef::from_slice_multi_with_limits(&data, &limits);
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_ok());
+    let limits = ParseLimits::default().max_input_len(leaf.len() - 1);
+    let mut iter = CertificateRef::from_slice_multi_with_limits(&data, &limits);
+    assert!(matches!(iter.next(), Some(Err(ParseError::TooLarge))));
+    assert!(iter.next().is_none());
+}
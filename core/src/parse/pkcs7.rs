@@ -0,0 +1,110 @@
+//! Degenerate "certs-only" CMS SignedData as defined in RFC 5652, the format of .p7b/.p7c
+//! certificate bundles.
+
+use super::{
+    certificate::expect_empty,
+    der::{
+        expect_integer, expect_object_identifier, expect_sequence, expect_set, try_get_explicit,
+        try_get_implicit, ExplicitTag,
+    },
+    error::ParseError,
+    parsing::CertificatesIter,
+};
+use crate::oids;
+
+/// a ContentInfo holding SignedData. only the certificates are exposed, signerInfos are not
+/// verified or decoded, they are empty in certs-only bundles anyway.
+#[derive(Debug)]
+pub struct SignedDataRef<'a> {
+    certificates: &'a [u8],
+    crls: Option<&'a [u8]>,
+}
+
+impl<'a> SignedDataRef<'a> {
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (left, root) = expect_sequence(data)?;
+        // the root sequence should take up all the space in the buffer
+        expect_empty(left)?;
+
+        let (root, content_type) = expect_object_identifier(root)?;
+        if content_type != oids::PKCS7_SIGNED_DATA {
+            return Err(ParseError::MalformedData);
+        }
+        let (root, content) = try_get_explicit(root, ExplicitTag::try_new(0)?)?;
+        expect_empty(root)?;
+
+        let (content, inner) = expect_sequence(content)?;
+        expect_empty(content)?;
+        let (inner, _version) = expect_integer(inner)?;
+        let (inner, _digest_algorithms) = expect_set(inner)?;
+        let (inner, _encap_content_info) = expect_sequence(inner)?;
+        // certificates [0] IMPLICIT CertificateSet OPTIONAL
+        let (inner, certificates) = match try_get_implicit(inner, 0) {
+            Ok((inner, certificates)) => (inner, certificates),
+            Err(_) => (inner, &[][..]),
+        };
+        // crls [1] IMPLICIT RevocationInfoChoices OPTIONAL
+        let (inner, crls) = match try_get_implicit(inner, 1) {
+            Ok((inner, crls)) => (inner, Some(crls)),
+            Err(_) => (inner, None),
+        };
+        let (inner, _signer_infos) = expect_set(inner)?;
+        expect_empty(inner)?;
+
+        Ok(Self { certificates, crls })
+    }
+
+    /// the certificates of the bundle, in the order they were encoded. a SET OF has no
+    /// meaningful order, so don't expect the leaf to come first. attribute certificates and
+    /// other CertificateChoices are reported as errors.
+    pub fn certificates(&self) -> CertificatesIter<'a> {
+        CertificatesIter::new(self.certificates)
+    }
+
+    /// whether the bundle carries revocation information as well
+    pub fn has_crls(&self) -> bool {
+        self.crls.is_some()
+    }
+}
+
+#[test]
+fn test_certs_only() {
+    let data = include_bytes!("../../../certs/pkcs7/chain.p7b");
+    let signed_data = SignedDataRef::from_slice(data).unwrap();
+    assert!(!signed_data.has_crls());
+
+    let certs: Vec<_> = signed_data
+        .certificates()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(certs.len(), 2);
+    assert_eq!(
+        certs[0].raw_data(),
+        &include_bytes!("../../../certs/ocsp/leaf.der")[..]
+    );
+    assert_eq!(
+        certs[1].raw_data(),
+        &include_bytes!("../../../certs/ocsp/ca.der")[..]
+    );
+}
+
+#[test]
+fn test_with_crls() {
+    let data = include_bytes!("../../../certs/pkcs7/leaf-crl.p7b");
+    let signed_data = SignedDataRef::from_slice(data).unwrap();
+    assert!(signed_data.has_crls());
+    assert_eq!(signed_data.certificates().count(), 1);
+}
+
+#[test]
+fn test_not_signed_data() {
+    // a ContentInfo of type data
+    let data = [
+        0x30, 0x0f, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x01, 0xa0, 0x02,
+        0x04, 0x00,
+    ];
+    assert!(matches!(
+        SignedDataRef::from_slice(&data),
+        Err(ParseError::MalformedData)
+    ));
+}
@@ -0,0 +1,326 @@
+//! Private keys in the algorithm independent PKCS#8 format (RFC 5208, RFC 5958), PEM label
+//! `PRIVATE KEY`, and their password protected form, PEM label `ENCRYPTED PRIVATE KEY`.
+
+use super::{
+    certificate::{expect_empty, parse_algorithm_identifier, AlgorithmidentifierRef},
+    der::{
+        expect_integer, expect_object_identifier, expect_octet_string, expect_sequence,
+        try_get_implicit, AnyRef, BitStringRef, IntegerRef, ObjectIdentifierRef,
+    },
+    error::ParseError,
+};
+use crate::oids;
+use std::convert::TryFrom;
+
+/// a PrivateKeyInfo, or a OneAsymmetricKey which adds the public key. the private key octets
+/// are algorithm specific, e.g. an RSAPrivateKey for rsaEncryption or an ECPrivateKey for
+/// id-ecPublicKey.
+#[derive(Debug)]
+pub struct PrivateKeyInfoRef<'a> {
+    version: i64,
+    algorithm: AlgorithmidentifierRef<'a>,
+    private_key: &'a [u8],
+    public_key: Option<BitStringRef<'a>>,
+}
+
+impl<'a> PrivateKeyInfoRef<'a> {
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (left, root) = expect_sequence(data)?;
+        // the root sequence should take up all the space in the buffer
+        expect_empty(left)?;
+
+        // v1 is PrivateKeyInfo, v2 the OneAsymmetricKey of RFC 5958
+        let (root, version) = expect_integer(root)?;
+        let version = version.to_i64()?;
+        if version != 0 && version != 1 {
+            return Err(ParseError::InvalidVersion);
+        }
+        let (root, algorithm) = parse_algorithm_identifier(root)?;
+        let (root, private_key) = expect_octet_string(root)?;
+        // attributes [0] IMPLICIT Attributes OPTIONAL
+        let root = match try_get_implicit(root, 0) {
+            Ok((root, _attributes)) => root,
+            Err(_) => root,
+        };
+        // publicKey [1] IMPLICIT BIT STRING OPTIONAL
+        let (root, public_key) = match try_get_implicit(root, 1) {
+            Ok((root, public_key)) if version == 1 => {
+                (root, Some(BitStringRef::from_content(public_key)?))
+            }
+            _ => (root, None),
+        };
+        expect_empty(root)?;
+
+        Ok(Self {
+            version,
+            algorithm,
+            private_key: private_key.as_bytes(),
+            public_key,
+        })
+    }
+
+    /// 0 for PrivateKeyInfo, 1 for OneAsymmetricKey
+    pub fn version(&self) -> i64 {
+        self.version
+    }
+
+    pub fn algorithm(&self) -> &AlgorithmidentifierRef<'a> {
+        &self.algorithm
+    }
+
+    /// the content of the privateKey octet string
+    pub fn private_key(&self) -> &'a [u8] {
+        self.private_key
+    }
+
+    pub fn public_key(&self) -> Option<&BitStringRef<'a>> {
+        self.public_key.as_ref()
+    }
+}
+
+/// an EncryptedPrivateKeyInfo. decrypting `encrypted_data` with the scheme in `algorithm` yields
+/// the DER of a PrivateKeyInfo.
+#[derive(Debug)]
+pub struct EncryptedPrivateKeyInfoRef<'a> {
+    algorithm: AlgorithmidentifierRef<'a>,
+    encrypted_data: &'a [u8],
+}
+
+impl<'a> EncryptedPrivateKeyInfoRef<'a> {
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (left, root) = expect_sequence(data)?;
+        // the root sequence should take up all the space in the buffer
+        expect_empty(left)?;
+
+        let (root, algorithm) = parse_algorithm_identifier(root)?;
+        let (root, encrypted_data) = expect_octet_string(root)?;
+        expect_empty(root)?;
+
+        Ok(Self {
+            algorithm,
+            encrypted_data: encrypted_data.as_bytes(),
+        })
+    }
+
+    pub fn algorithm(&self) -> &AlgorithmidentifierRef<'a> {
+        &self.algorithm
+    }
+
+    /// the content of the encryptedData octet string
+    pub fn encrypted_data(&self) -> &'a [u8] {
+        self.encrypted_data
+    }
+
+    /// the PBES2 parameters, or `None` if the key is protected with another scheme, e.g. one of
+    /// the PKCS#12 password based schemes
+    pub fn pbes2_params(&self) -> Result<Option<Pbes2ParamsRef<'a>>, ParseError> {
+        Pbes2ParamsRef::from_algorithm(&self.algorithm)
+    }
+}
+
+/// the PBES2-params of RFC 8018. only PBKDF2 is defined as key derivation function, the
+/// encryption scheme is usually one of the AES-CBC ciphers with the IV as parameter.
+#[derive(Debug)]
+pub struct Pbes2ParamsRef<'a> {
+    salt: &'a [u8],
+    iteration_count: u32,
+    key_length: Option<u32>,
+    prf: ObjectIdentifierRef<'a>,
+    encryption_scheme: ObjectIdentifierRef<'a>,
+    iv: &'a [u8],
+}
+
+impl<'a> Pbes2ParamsRef<'a> {
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (left, params) = expect_sequence(data)?;
+        expect_empty(left)?;
+
+        Self::from_content(params)
+    }
+
+    /// the parameters of `algorithm`, or `None` if it isn't PBES2. PKCS#12 uses the same
+    /// AlgorithmIdentifier for its encrypted bags.
+    pub fn from_algorithm(
+        algorithm: &AlgorithmidentifierRef<'a>,
+    ) -> Result<Option<Self>, ParseError> {
+        if algorithm.algorithm_identifier() != &oids::PBES2 {
+            return Ok(None);
+        }
+        match algorithm.parameters() {
+            Some(AnyRef::Sequence(params)) => Ok(Some(Self::from_content(params)?)),
+            _ => Err(ParseError::MalformedData),
+        }
+    }
+
+    fn from_content(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (data, kdf) = expect_sequence(data)?;
+        let (kdf, kdf_algorithm) = expect_object_identifier(kdf)?;
+        if kdf_algorithm != oids::PBKDF2 {
+            return Err(ParseError::MalformedData);
+        }
+        let (kdf, kdf_params) = expect_sequence(kdf)?;
+        expect_empty(kdf)?;
+
+        // the otherSource alternative of the salt is reserved for future versions of RFC 8018
+        let (kdf_params, salt) = expect_octet_string(kdf_params)?;
+        let (kdf_params, iteration_count) = expect_integer(kdf_params)?;
+        let iteration_count = to_u32(&iteration_count)?;
+        let (kdf_params, key_length) = match expect_integer(kdf_params) {
+            Ok((kdf_params, key_length)) => (kdf_params, Some(to_u32(&key_length)?)),
+            Err(_) => (kdf_params, None),
+        };
+        // prf AlgorithmIdentifier DEFAULT algid-hmacWithSHA1
+        let (kdf_params, prf) = if kdf_params.is_empty() {
+            (kdf_params, oids::HMAC_WITH_SHA1)
+        } else {
+            // the parameters of the HMAC algorithms are NULL
+            let (kdf_params, prf) = expect_sequence(kdf_params)?;
+            let (_, prf) = expect_object_identifier(prf)?;
+            (kdf_params, prf)
+        };
+        expect_empty(kdf_params)?;
+
+        let (data, encryption_scheme) = expect_sequence(data)?;
+        expect_empty(data)?;
+        let (encryption_scheme, cipher) = expect_object_identifier(encryption_scheme)?;
+        // the AES-CBC ciphers of RFC 3565 take the IV as an OCTET STRING
+        let (encryption_scheme, iv) = expect_octet_string(encryption_scheme)?;
+        expect_empty(encryption_scheme)?;
+
+        Ok(Self {
+            salt: salt.as_bytes(),
+            iteration_count,
+            key_length,
+            prf,
+            encryption_scheme: cipher,
+            iv: iv.as_bytes(),
+        })
+    }
+
+    pub fn salt(&self) -> &'a [u8] {
+        self.salt
+    }
+
+    pub fn iteration_count(&self) -> u32 {
+        self.iteration_count
+    }
+
+    /// the length of the derived key in octets, if it is given explicitly instead of following
+    /// from the cipher
+    pub fn key_length(&self) -> Option<u32> {
+        self.key_length
+    }
+
+    /// the pseudorandom function of PBKDF2, hmacWithSHA1 when absent
+    pub fn prf(&self) -> &ObjectIdentifierRef<'a> {
+        &self.prf
+    }
+
+    /// the cipher the key is encrypted with, e.g. `oids::AES256_CBC`
+    pub fn encryption_scheme(&self) -> &ObjectIdentifierRef<'a> {
+        &self.encryption_scheme
+    }
+
+    pub fn iv(&self) -> &'a [u8] {
+        self.iv
+    }
+}
+
+fn to_u32(value: &IntegerRef) -> Result<u32, ParseError> {
+    u32::try_from(value.to_i64()?).map_err(|_| ParseError::MalformedData)
+}
+
+#[test]
+fn test_rsa_private_key() {
+    use crate::parse::pem::decode_pem;
+
+    let pem = include_bytes!("../../../certs/identity/leaf.key");
+    let der = decode_pem(pem).unwrap().remove(0).into_contents();
+    let key = PrivateKeyInfoRef::from_slice(&der).unwrap();
+
+    assert_eq!(key.version(), 0);
+    assert_eq!(
+        key.algorithm().algorithm_identifier(),
+        &oids::RSA_ENCRYPTION
+    );
+    // an RSAPrivateKey
+    assert_eq!(&key.private_key()[..4], &[0x30, 0x82, 0x04, 0xa3]);
+    assert!(key.public_key().is_none());
+}
+
+#[test]
+fn test_one_asymmetric_key() {
+    // the Ed25519 example of RFC 8410 section 10.3, with the public key
+    let der = [
+        &[
+            0x30, 0x72, 0x02, 0x01, 0x01, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22,
+            0x04, 0x20,
+        ][..],
+        &[
+            0xd4, 0xee, 0x72, 0xdb, 0xf9, 0x13, 0x58, 0x4a, 0xd5, 0xb6, 0xd8, 0xf1, 0xf7, 0x69,
+            0xf8, 0xad, 0x3a, 0xfe, 0x7c, 0x28, 0xcb, 0xf1, 0xd4, 0xfb, 0xe0, 0x97, 0xa8, 0x8f,
+            0x44, 0x75, 0x58, 0x42,
+        ],
+        // attributes with a friendly name
+        &[
+            0xa0, 0x1f, 0x30, 0x1d, 0x06, 0x0a, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09,
+            0x09, 0x14, 0x31, 0x0f, 0x0c, 0x0d, 0x43, 0x75, 0x72, 0x64, 0x6c, 0x65, 0x20, 0x43,
+            0x68, 0x61, 0x69, 0x72, 0x73,
+        ],
+        &[
+            0x81, 0x21, 0x00, 0x19, 0xbf, 0x44, 0x09, 0x69, 0x84, 0xcd, 0xfe, 0x85, 0x41, 0xba,
+            0xc1, 0x67, 0xdc, 0x3b, 0x96, 0xc8, 0x50, 0x86, 0xaa, 0x30, 0xb6, 0xb6, 0xcb, 0x0c,
+            0x5c, 0x38, 0xad, 0x70, 0x31, 0x66, 0xe1,
+        ],
+    ]
+    .concat();
+    let key = PrivateKeyInfoRef::from_slice(&der).unwrap();
+
+    assert_eq!(key.version(), 1);
+    assert_eq!(
+        key.algorithm().algorithm_identifier().to_string(),
+        "1.3.101.112"
+    );
+    assert_eq!(key.private_key().len(), 34);
+    assert_eq!(key.public_key().unwrap().data().1.len(), 32);
+}
+
+#[test]
+fn test_encrypted_private_key() {
+    // openssl pkcs8 -topk8 -v2 aes-256-cbc -in leaf.key -outform DER
+    let der = include_bytes!("../../../certs/identity/leaf-encrypted.der");
+    let key = EncryptedPrivateKeyInfoRef::from_slice(der).unwrap();
+
+    assert_eq!(key.algorithm().algorithm_identifier(), &oids::PBES2);
+    assert_eq!(key.encrypted_data().len(), 1232);
+
+    let params = key.pbes2_params().unwrap().unwrap();
+    assert_eq!(
+        params.salt(),
+        &[0x89, 0xd8, 0x1f, 0x87, 0x9b, 0x3c, 0x6a, 0x0b]
+    );
+    assert_eq!(params.iteration_count(), 2048);
+    assert_eq!(params.key_length(), None);
+    assert_eq!(params.prf(), &oids::HMAC_WITH_SHA256);
+    assert_eq!(params.encryption_scheme(), &oids::AES256_CBC);
+    assert_eq!(params.iv().len(), 16);
+}
+
+#[test]
+fn test_pbes2_default_prf() {
+    // PBKDF2 with salt "salt", 1000 iterations and a key length of 16, AES-128-CBC
+    let der = [
+        0x30, 0x3b, 0x30, 0x1a, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0c,
+        0x30, 0x0d, 0x04, 0x04, b's', b'a', b'l', b't', 0x02, 0x02, 0x03, 0xe8, 0x02, 0x01, 0x10,
+        0x30, 0x1d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x02, 0x04, 0x10,
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f,
+    ];
+    let params = Pbes2ParamsRef::from_slice(&der).unwrap();
+
+    assert_eq!(params.salt(), b"salt");
+    assert_eq!(params.iteration_count(), 1000);
+    assert_eq!(params.key_length(), Some(16));
+    assert_eq!(params.prf(), &oids::HMAC_WITH_SHA1);
+    assert_eq!(params.encryption_scheme(), &oids::AES128_CBC);
+}
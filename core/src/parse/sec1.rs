@@ -0,0 +1,106 @@
+//! Elliptic curve private keys in the SEC1 format (RFC 5915), PEM label `EC PRIVATE KEY`.
+
+use super::{
+    certificate::expect_empty,
+    der::{
+        expect_bit_string, expect_integer, expect_object_identifier, expect_octet_string,
+        expect_sequence, try_get_explicit, BitStringRef, ExplicitTag, ObjectIdentifierRef,
+    },
+    error::ParseError,
+};
+
+/// an ECPrivateKey. the curve is optional because a PKCS#8 PrivateKeyInfo already names it in
+/// its algorithm parameters, stand-alone keys always carry it.
+#[derive(Debug)]
+pub struct EcPrivateKeyRef<'a> {
+    private_key: &'a [u8],
+    named_curve: Option<ObjectIdentifierRef<'a>>,
+    public_key: Option<BitStringRef<'a>>,
+}
+
+impl<'a> EcPrivateKeyRef<'a> {
+    pub fn from_slice(data: &'a [u8]) -> Result<Self, ParseError> {
+        let (left, root) = expect_sequence(data)?;
+        // the root sequence should take up all the space in the buffer
+        expect_empty(left)?;
+
+        let (root, version) = expect_integer(root)?;
+        if version.to_i64()? != 1 {
+            return Err(ParseError::InvalidVersion);
+        }
+        let (root, private_key) = expect_octet_string(root)?;
+        // parameters [0] ECParameters {{ NamedCurve }} OPTIONAL
+        let (root, named_curve) = match try_get_explicit(root, ExplicitTag::try_new(0)?) {
+            Ok((root, parameters)) => {
+                let (parameters, named_curve) = expect_object_identifier(parameters)?;
+                expect_empty(parameters)?;
+                (root, Some(named_curve))
+            }
+            Err(_) => (root, None),
+        };
+        // publicKey [1] BIT STRING OPTIONAL
+        let (root, public_key) = match try_get_explicit(root, ExplicitTag::try_new(1)?) {
+            Ok((root, public_key)) => {
+                let (public_key, bit_string) = expect_bit_string(public_key)?;
+                expect_empty(public_key)?;
+                (root, Some(bit_string))
+            }
+            Err(_) => (root, None),
+        };
+        expect_empty(root)?;
+
+        Ok(Self {
+            private_key: private_key.as_bytes(),
+            named_curve,
+            public_key,
+        })
+    }
+
+    /// the private key as an octet string of the size of the curve order
+    pub fn private_key(&self) -> &'a [u8] {
+        self.private_key
+    }
+
+    /// the curve, e.g. `oids::SECP256R1`
+    pub fn named_curve(&self) -> Option<&ObjectIdentifierRef<'a>> {
+        self.named_curve.as_ref()
+    }
+
+    /// the public key as an encoded EC point
+    pub fn public_key(&self) -> Option<&BitStringRef<'a>> {
+        self.public_key.as_ref()
+    }
+}
+
+#[test]
+fn test_ec_private_key() {
+    use crate::oids;
+
+    // openssl ecparam -name prime256v1 -genkey -noout -outform DER
+    let der = include_bytes!("../../../certs/identity/ec-sec1.der");
+    let key = EcPrivateKeyRef::from_slice(der).unwrap();
+
+    assert_eq!(key.private_key().len(), 32);
+    assert_eq!(key.named_curve(), Some(&oids::SECP256R1));
+    // an uncompressed point
+    let (padding, point) = key.public_key().unwrap().data();
+    assert_eq!(padding, 0);
+    assert_eq!(point.len(), 65);
+    assert_eq!(point[0], 0x04);
+}
+
+#[test]
+fn test_ec_private_key_in_pkcs8() {
+    use crate::parse::pkcs8::PrivateKeyInfoRef;
+
+    // the same key after openssl pkcs8 -topk8, which drops the curve from the ECPrivateKey
+    let der = include_bytes!("../../../certs/identity/ec-pkcs8.der");
+    let info = PrivateKeyInfoRef::from_slice(der).unwrap();
+    let key = EcPrivateKeyRef::from_slice(info.private_key()).unwrap();
+
+    let sec1 = include_bytes!("../../../certs/identity/ec-sec1.der");
+    let sec1 = EcPrivateKeyRef::from_slice(sec1).unwrap();
+    assert_eq!(key.private_key(), sec1.private_key());
+    assert!(key.named_curve().is_none());
+    assert_eq!(key.public_key(), sec1.public_key());
+}
@@ -0,0 +1,35 @@
+//! Convenience re-exports of the types most users need.
+//!
+//! ```
+//! use x509_core::prelude::*;
+//! ```
+//! brings the zero-copy parser, the certificate builder and the DER encoding trait into scope.
+
+pub use crate::common::{certificate::Version, time::UtcDateTime};
+pub use crate::generate::{
+    builder::{TBSCertificate, TBSCertificateBuilder},
+    certificate::{
+        AlgorithmIdentifier, AttributeTypeAndValue, Certificate, DirectoryString,
+        DistinguishedName, Extension, Extensions, KeyUsage, Name, RelativeDistinguishedName,
+        SubjectPublicKeyInfo, Validity,
+    },
+    der::{
+        BitString, Data, GeneralizedTime, IA5String, Integer, Null, OctetString, PrintableString,
+        ToDer, UTCTime, Utf8String,
+    },
+    error::EncodingError,
+    inputs::GenerationInputs,
+};
+pub use crate::oids;
+pub use crate::parse::{
+    certificate::{
+        AlgorithmidentifierRef, ExtensionRef, ExtensionsRef, NameRef, SubjectPublicKeyInfoRef,
+        ValidityRef,
+    },
+    crl::CertificateListRef,
+    csr::CertificationRequestRef,
+    der::{ObjectIdentifier, ObjectIdentifierRef},
+    error::ParseError,
+    limits::ParseLimits,
+    parsing::{CertificateRef, TBSCertificateRef},
+};
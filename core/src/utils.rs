@@ -1,3 +1,19 @@
+/// formats `bytes` as colon separated uppercase hex like browsers show serial numbers,
+/// e.g. `0A:1B:FF`
+pub(crate) fn to_hex_colon(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
 pub fn u8_slice_to_16_vec(x: &[u8]) -> Vec<u16> {
     x.chunks(2).map(|a| u16::from_be_bytes([a[0], a[1]])).collect()
 }
+
+#[test]
+fn test_to_hex_colon() {
+    assert_eq!(to_hex_colon(&[0x0a, 0x1b, 0xff]), "0A:1B:FF");
+    assert_eq!(to_hex_colon(&[]), "");
+}
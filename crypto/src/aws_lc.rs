@@ -0,0 +1,454 @@
+//! The aws-lc-rs backend. aws-lc-rs keeps the API of ring, so this mirrors the ring backend.
+
+use crate::{private_key::PrivateKey, provider::CryptoProvider, Algorithm, Signer};
+use aws_lc_rs::{
+    rand,
+    signature::{self, KeyPair},
+};
+use std::num::NonZeroU32;
+use std::ops::RangeInclusive;
+use x509_core::{
+    oids,
+    parse::{
+        certificate::{AlgorithmidentifierRef, SubjectPublicKeyInfoRef},
+        der::ObjectIdentifierRef,
+        parsing::CertificateRef,
+        pkcs8::PrivateKeyInfoRef,
+    },
+};
+
+#[derive(Debug)]
+pub enum Error {
+    UnsupportedAlgorithm(String),
+    UnsupportedPublicKey(String),
+    VerifyFailed,
+    InvalidPrivateKey,
+    OOM,
+}
+
+pub fn check_signature(subject: &CertificateRef, issuer: &CertificateRef) -> Result<bool, Error> {
+    let pub_key = issuer.tbs_cert().subject_public_key_info();
+    let raw_tbs = subject.tbs_cert().raw_data();
+    let (_, signature) = subject.signature().data();
+
+    verify_signed_data(subject.signature_algorithm(), pub_key, raw_tbs, signature)
+}
+
+/// verifies `signature` over `raw_tbs` with the public key `pub_key`. this is the algorithm
+/// dispatch shared by everything that is signed by a certificate's key.
+pub fn verify_signed_data(
+    signature_algorithm: &AlgorithmidentifierRef,
+    pub_key: &SubjectPublicKeyInfoRef,
+    raw_tbs: &[u8],
+    signature: &[u8],
+) -> Result<bool, Error> {
+    let sig_algo = signature_algorithm.algorithm_identifier();
+
+    match *sig_algo {
+        oids::SHA1_WITH_RSA_ENCRYPTION => {
+            let (_, key_data) = pub_key.subject_public_key().data();
+            let pub_key = signature::UnparsedPublicKey::new(
+                &signature::RSA_PKCS1_1024_8192_SHA1_FOR_LEGACY_USE_ONLY,
+                key_data,
+            );
+            match pub_key.verify(raw_tbs, signature) {
+                Ok(()) => Ok(true),
+                Err(_) => Ok(false), // we don't know why the verify call failed. We assume it's due to a wrong signature
+            }
+        }
+        oids::SHA256_WITH_RSA_ENCRYPTION => {
+            let (_, key_data) = pub_key.subject_public_key().data();
+            let pub_key = signature::UnparsedPublicKey::new(
+                &signature::RSA_PKCS1_1024_8192_SHA256_FOR_LEGACY_USE_ONLY,
+                key_data,
+            );
+            match pub_key.verify(raw_tbs, signature) {
+                Ok(()) => Ok(true),
+                Err(_) => Ok(false), // we don't know why the verify call failed. We assume it's due to a wrong signature
+            }
+        }
+        oids::SHA384_WITH_RSA_ENCRYPTION => {
+            let (_, key_data) = pub_key.subject_public_key().data();
+            let pub_key =
+                signature::UnparsedPublicKey::new(&signature::RSA_PKCS1_2048_8192_SHA384, key_data);
+            match pub_key.verify(raw_tbs, signature) {
+                Ok(()) => Ok(true),
+                Err(_) => Ok(false), // we don't know why the verify call failed. We assume it's due to a wrong signature
+            }
+        }
+        oids::SHA512_WITH_RSA_ENCRYPTION => {
+            let (_, key_data) = pub_key.subject_public_key().data();
+            let pub_key = signature::UnparsedPublicKey::new(
+                &signature::RSA_PKCS1_1024_8192_SHA512_FOR_LEGACY_USE_ONLY,
+                key_data,
+            );
+            match pub_key.verify(raw_tbs, signature) {
+                Ok(()) => Ok(true),
+                Err(_) => Ok(false), // we don't know why the verify call failed. We assume it's due to a wrong signature
+            }
+        }
+        oids::RSASSA_PSS => {
+            // aws-lc-rs only implements PSS with MGF1 over the message hash and a salt as long as
+            // the hash, like ring
+            let algorithm: &'static signature::RsaParameters =
+                match Algorithm::try_from(signature_algorithm) {
+                    Ok(Algorithm::RSA_PSS_SHA256) => &signature::RSA_PSS_2048_8192_SHA256,
+                    Ok(Algorithm::RSA_PSS_SHA384) => &signature::RSA_PSS_2048_8192_SHA384,
+                    Ok(Algorithm::RSA_PSS_SHA512) => &signature::RSA_PSS_2048_8192_SHA512,
+                    _ => return Err(Error::UnsupportedAlgorithm(sig_algo.to_string())),
+                };
+
+            let (_, key_data) = pub_key.subject_public_key().data();
+            let pub_key = signature::UnparsedPublicKey::new(algorithm, key_data);
+            match pub_key.verify(raw_tbs, signature) {
+                Ok(()) => Ok(true),
+                Err(_) => Ok(false), // we don't know why the verify call failed. We assume it's due to a wrong signature
+            }
+        }
+        oids::ECDSA_WITH_SHA256 => {
+            let (_padding, key) = pub_key.subject_public_key().data();
+
+            let pub_key = match pub_key.named_curve() {
+                Some(oids::SECP256R1) => {
+                    signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, key)
+                }
+                Some(oids::SECP384R1) => {
+                    signature::UnparsedPublicKey::new(&signature::ECDSA_P384_SHA256_ASN1, key)
+                }
+                Some(curve) => return Err(Error::UnsupportedPublicKey(curve.to_string())),
+                None => {
+                    return Err(Error::UnsupportedPublicKey(
+                        pub_key
+                            .algorithm_identifier()
+                            .algorithm_identifier()
+                            .to_string(),
+                    ))
+                }
+            };
+            match pub_key.verify(raw_tbs, signature) {
+                Ok(()) => Ok(true),
+                Err(_) => Ok(false), // we don't know why the verify call failed. We assume it's due to a wrong signature
+            }
+        }
+        oids::ECDSA_WITH_SHA384 => {
+            let (_padding, key) = pub_key.subject_public_key().data();
+
+            let pub_key = match pub_key.named_curve() {
+                Some(oids::SECP384R1) => {
+                    signature::UnparsedPublicKey::new(&signature::ECDSA_P384_SHA384_ASN1, key)
+                }
+                Some(curve) => return Err(Error::UnsupportedPublicKey(curve.to_string())),
+                None => {
+                    return Err(Error::UnsupportedPublicKey(
+                        pub_key
+                            .algorithm_identifier()
+                            .algorithm_identifier()
+                            .to_string(),
+                    ))
+                }
+            };
+
+            match pub_key.verify(raw_tbs, signature) {
+                Ok(()) => Ok(true),
+                Err(_) => Ok(false), // we don't know why the verify call failed. We assume it's due to a wrong signature
+            }
+        }
+        oids::ED25519 => {
+            if pub_key.algorithm_identifier().algorithm_identifier() != &oids::ED25519 {
+                return Err(Error::UnsupportedPublicKey(
+                    pub_key
+                        .algorithm_identifier()
+                        .algorithm_identifier()
+                        .to_string(),
+                ));
+            }
+            let (_, key) = pub_key.subject_public_key().data();
+            let pub_key = signature::UnparsedPublicKey::new(&signature::ED25519, key);
+            match pub_key.verify(raw_tbs, signature) {
+                Ok(()) => Ok(true),
+                Err(_) => Ok(false), // we don't know why the verify call failed. We assume it's due to a wrong signature
+            }
+        }
+        oid => Err(Error::UnsupportedAlgorithm(oid.to_string())),
+    }
+}
+
+/// the RSA modulus sizes in bits aws-lc-rs verifies signatures of `algorithm` with, the
+/// same parameters `verify_signed_data` picks
+pub(crate) fn rsa_modulus_sizes(algorithm: Algorithm) -> RangeInclusive<usize> {
+    match algorithm {
+        Algorithm::RSA_SHA1 | Algorithm::RSA_SHA256 | Algorithm::RSA_SHA512 => 1024..=8192,
+        _ => 2048..=8192,
+    }
+}
+
+/// whether AWS-LC runs in FIPS mode. that is the case if the application depends on aws-lc-rs
+/// with its `fips` feature, which links the FIPS validated module into this backend as well.
+/// building that module needs CMake and Go.
+pub fn fips_mode() -> bool {
+    aws_lc_rs::try_fips_mode().is_ok()
+}
+
+/// hashes `data` with the hash algorithm identified by `algorithm`
+pub fn digest(algorithm: &ObjectIdentifierRef, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let algorithm = match *algorithm {
+        oids::SHA1 => &aws_lc_rs::digest::SHA1_FOR_LEGACY_USE_ONLY,
+        oids::SHA256 => &aws_lc_rs::digest::SHA256,
+        oids::SHA384 => &aws_lc_rs::digest::SHA384,
+        oids::SHA512 => &aws_lc_rs::digest::SHA512,
+        oid => return Err(Error::UnsupportedAlgorithm(oid.to_string())),
+    };
+
+    Ok(aws_lc_rs::digest::digest(algorithm, data).as_ref().to_vec())
+}
+
+/// fills `out` with a key derived from `password` with PBKDF2 (RFC 8018, 5.2), `prf` is the
+/// HMAC algorithm of the PBKDF2-params, e.g. [`oids::HMAC_WITH_SHA256`]
+pub fn pbkdf2(
+    prf: &ObjectIdentifierRef,
+    salt: &[u8],
+    iterations: NonZeroU32,
+    password: &[u8],
+    out: &mut [u8],
+) -> Result<(), Error> {
+    let algorithm = match *prf {
+        oids::HMAC_WITH_SHA1 => aws_lc_rs::pbkdf2::PBKDF2_HMAC_SHA1,
+        oids::HMAC_WITH_SHA256 => aws_lc_rs::pbkdf2::PBKDF2_HMAC_SHA256,
+        oids::HMAC_WITH_SHA384 => aws_lc_rs::pbkdf2::PBKDF2_HMAC_SHA384,
+        oids::HMAC_WITH_SHA512 => aws_lc_rs::pbkdf2::PBKDF2_HMAC_SHA512,
+        oid => return Err(Error::UnsupportedAlgorithm(oid.to_string())),
+    };
+    aws_lc_rs::pbkdf2::derive(algorithm, iterations, salt, password, out);
+
+    Ok(())
+}
+
+pub fn sign(
+    tbs: &[u8],
+    key_pair: &signature::RsaKeyPair,
+    algorithm: Algorithm,
+) -> Result<Vec<u8>, Error> {
+    let padding = rsa_padding(algorithm)?;
+    let rng = rand::SystemRandom::new();
+    let mut signature = vec![0; key_pair.public_modulus_len()];
+    key_pair
+        .sign(padding, &rng, tbs, &mut signature)
+        .map_err(|_| Error::OOM)?;
+    Ok(signature)
+}
+
+fn rsa_padding(algorithm: Algorithm) -> Result<&'static dyn signature::RsaEncoding, Error> {
+    match algorithm {
+        Algorithm::RSA_SHA256 => Ok(&signature::RSA_PKCS1_SHA256),
+        Algorithm::RSA_SHA384 => Ok(&signature::RSA_PKCS1_SHA384),
+        Algorithm::RSA_SHA512 => Ok(&signature::RSA_PKCS1_SHA512),
+        Algorithm::RSA_PSS_SHA256 => Ok(&signature::RSA_PSS_SHA256),
+        Algorithm::RSA_PSS_SHA384 => Ok(&signature::RSA_PSS_SHA384),
+        Algorithm::RSA_PSS_SHA512 => Ok(&signature::RSA_PSS_SHA512),
+        algorithm => Err(Error::UnsupportedAlgorithm(algorithm.oid().to_string())),
+    }
+}
+
+/// loads an RSA key from a PKCS#8 PrivateKeyInfo or a PKCS#1 RSAPrivateKey. PKCS#8 keys of
+/// other algorithms fail with `UnsupportedPublicKey`.
+pub fn rsa_key_pair(private_key: &[u8]) -> Result<signature::RsaKeyPair, Error> {
+    match PrivateKeyInfoRef::from_slice(private_key) {
+        Ok(info) => match *info.algorithm().algorithm_identifier() {
+            oids::RSA_ENCRYPTION => {
+                signature::RsaKeyPair::from_pkcs8(private_key).map_err(|_| Error::InvalidPrivateKey)
+            }
+            oid => Err(Error::UnsupportedPublicKey(oid.to_string())),
+        },
+        // an RSAPrivateKey starts with two INTEGERs, so it never parses as PKCS#8
+        Err(_) => {
+            signature::RsaKeyPair::from_der(private_key).map_err(|_| Error::InvalidPrivateKey)
+        }
+    }
+}
+
+/// signs with an RSA key using PKCS#1 v1.5 or PSS padding, depending on the algorithm.
+///
+/// aws-lc-rs only signs complete messages, not precomputed digests, so the chunks are
+/// collected into a buffer of the announced length. the TBS is still encoded only once,
+/// straight into that buffer.
+pub struct RsaSigner {
+    key_pair: signature::RsaKeyPair,
+    algorithm: Algorithm,
+}
+
+impl RsaSigner {
+    /// fails if `algorithm` is not an RSA algorithm aws-lc-rs can sign with
+    pub fn new(key_pair: signature::RsaKeyPair, algorithm: Algorithm) -> Result<Self, Error> {
+        rsa_padding(algorithm)?;
+
+        Ok(Self {
+            key_pair,
+            algorithm,
+        })
+    }
+
+    /// the public key, encoded as an RSAPublicKey structure
+    pub fn public_key(&self) -> &[u8] {
+        self.key_pair.public_key().as_ref()
+    }
+}
+
+impl Signer for RsaSigner {
+    type Input = Vec<u8>;
+
+    fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    fn begin(&self, len: usize) -> Self::Input {
+        Vec::with_capacity(len)
+    }
+
+    fn finish(&self, input: Self::Input) -> Result<Vec<u8>, Error> {
+        sign(&input, &self.key_pair, self.algorithm)
+    }
+}
+
+/// signs with an EC key on P-256 or P-384, the curve has to match the hash of the algorithm.
+///
+/// like [`RsaSigner`], the chunks are collected into a buffer because aws-lc-rs only signs complete
+/// messages.
+pub struct EcdsaSigner {
+    key_pair: signature::EcdsaKeyPair,
+    algorithm: Algorithm,
+    named_curve: ObjectIdentifierRef<'static>,
+}
+
+impl EcdsaSigner {
+    /// `private_key` is a PKCS#8 PrivateKeyInfo. fails with `UnsupportedAlgorithm` if aws-lc-rs
+    /// can't sign with `algorithm`, and with `InvalidPrivateKey` if the key is not on the curve
+    /// that goes with it.
+    pub fn new(private_key: &[u8], algorithm: Algorithm) -> Result<Self, Error> {
+        let (signing_algorithm, named_curve) = match algorithm {
+            Algorithm::ECDSA_SHA256 => {
+                (&signature::ECDSA_P256_SHA256_ASN1_SIGNING, oids::SECP256R1)
+            }
+            Algorithm::ECDSA_SHA384 => {
+                (&signature::ECDSA_P384_SHA384_ASN1_SIGNING, oids::SECP384R1)
+            }
+            algorithm => return Err(Error::UnsupportedAlgorithm(algorithm.oid().to_string())),
+        };
+        let key_pair = signature::EcdsaKeyPair::from_pkcs8(signing_algorithm, private_key)
+            .map_err(|_| Error::InvalidPrivateKey)?;
+
+        Ok(Self {
+            key_pair,
+            algorithm,
+            named_curve,
+        })
+    }
+
+    /// the public key, an uncompressed EC point
+    pub fn public_key(&self) -> &[u8] {
+        self.key_pair.public_key().as_ref()
+    }
+
+    /// the curve of the key, e.g. `oids::SECP256R1`
+    pub fn named_curve(&self) -> ObjectIdentifierRef<'static> {
+        self.named_curve
+    }
+}
+
+impl Signer for EcdsaSigner {
+    type Input = Vec<u8>;
+
+    fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    fn begin(&self, len: usize) -> Self::Input {
+        Vec::with_capacity(len)
+    }
+
+    fn finish(&self, input: Self::Input) -> Result<Vec<u8>, Error> {
+        let rng = rand::SystemRandom::new();
+        let signature = self.key_pair.sign(&rng, &input).map_err(|_| Error::OOM)?;
+
+        Ok(signature.as_ref().to_vec())
+    }
+}
+
+/// signs with an Ed25519 key. Ed25519 signatures are deterministic, the same key and data always
+/// give the same signature.
+///
+/// like [`RsaSigner`], the chunks are collected into a buffer, Ed25519 hashes the message twice
+/// and can't work on a stream.
+pub struct Ed25519Signer {
+    key_pair: signature::Ed25519KeyPair,
+}
+
+impl Ed25519Signer {
+    /// `private_key` is a PKCS#8 PrivateKeyInfo, with or without the public key. fails with
+    /// `UnsupportedAlgorithm` if `algorithm` is not Ed25519.
+    pub fn new(private_key: &[u8], algorithm: Algorithm) -> Result<Self, Error> {
+        if algorithm != Algorithm::ED25519 {
+            return Err(Error::UnsupportedAlgorithm(algorithm.oid().to_string()));
+        }
+        let key_pair = signature::Ed25519KeyPair::from_pkcs8_maybe_unchecked(private_key)
+            .map_err(|_| Error::InvalidPrivateKey)?;
+
+        Ok(Self { key_pair })
+    }
+
+    /// the public key, 32 bytes
+    pub fn public_key(&self) -> &[u8] {
+        self.key_pair.public_key().as_ref()
+    }
+}
+
+impl Signer for Ed25519Signer {
+    type Input = Vec<u8>;
+
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::ED25519
+    }
+
+    fn begin(&self, len: usize) -> Self::Input {
+        Vec::with_capacity(len)
+    }
+
+    fn finish(&self, input: Self::Input) -> Result<Vec<u8>, Error> {
+        Ok(self.key_pair.sign(&input).as_ref().to_vec())
+    }
+}
+
+/// the aws-lc-rs backend as a [`CryptoProvider`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AwsLcProvider;
+
+impl CryptoProvider for AwsLcProvider {
+    fn verify_signed_data(
+        &self,
+        signature_algorithm: &AlgorithmidentifierRef,
+        public_key: &SubjectPublicKeyInfoRef,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, Error> {
+        verify_signed_data(signature_algorithm, public_key, data, signature)
+    }
+
+    fn digest(&self, algorithm: &ObjectIdentifierRef, data: &[u8]) -> Result<Vec<u8>, Error> {
+        digest(algorithm, data)
+    }
+
+    fn sign(
+        &self,
+        algorithm: Algorithm,
+        private_key: &PrivateKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        // all signers collect the data into a buffer anyway
+        match private_key {
+            PrivateKey::Rsa(der) => {
+                RsaSigner::new(rsa_key_pair(der)?, algorithm)?.finish(data.to_vec())
+            }
+            PrivateKey::Ecdsa(der) => EcdsaSigner::new(der, algorithm)?.finish(data.to_vec()),
+            PrivateKey::Ed25519(der) => Ed25519Signer::new(der, algorithm)?.finish(data.to_vec()),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! The state of a small certificate authority, kept in a directory: an `index.txt` like the one
+//! of `openssl ca` with one line per issued certificate, and the certificates themselves.
+//!
+//! the store is meant for one process at a time, there is no locking.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use x509_core::{
+    common::time::UtcDateTime,
+    parse::{error::ParseError, parsing::CertificateRef},
+};
+
+use crate::verify::format_name;
+
+const INDEX: &str = "index.txt";
+const CERTS: &str = "certs";
+
+/// an entry of the index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssuedCertificate {
+    /// the serial number as uppercase hex, without leading zeros
+    pub serial: String,
+    /// the subject like `CN=example.com, O=Example`
+    pub subject: String,
+    pub not_after: UtcDateTime,
+}
+
+#[derive(Debug)]
+pub enum CaStoreError {
+    Io(io::Error),
+    Parse(ParseError),
+    /// a certificate with this serial number, in hex, was issued before
+    DuplicateSerial(String),
+    /// the index line with this number (starting at 1) can't be read
+    MalformedIndex(usize),
+}
+
+impl From<io::Error> for CaStoreError {
+    fn from(e: io::Error) -> Self {
+        CaStoreError::Io(e)
+    }
+}
+
+impl From<ParseError> for CaStoreError {
+    fn from(e: ParseError) -> Self {
+        CaStoreError::Parse(e)
+    }
+}
+
+/// the certificates a CA has issued, persisted in a directory. a serial number can only be
+/// recorded once.
+#[derive(Debug)]
+pub struct CaStore {
+    dir: PathBuf,
+    issued: Vec<IssuedCertificate>,
+}
+
+impl CaStore {
+    /// opens the store in `dir`, creating the directory and an empty index if there is none yet
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, CaStoreError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(dir.join(CERTS))?;
+
+        let index = match fs::read_to_string(dir.join(INDEX)) {
+            Ok(index) => index,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let issued = index
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| parse_line(line).ok_or(CaStoreError::MalformedIndex(i + 1)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { dir, issued })
+    }
+
+    /// records the issued certificate `cert`: it is saved as `certs/<serial>.der` and added to
+    /// the index. fails if its serial number is already taken.
+    pub fn record(&mut self, cert: &CertificateRef) -> Result<(), CaStoreError> {
+        let tbs = cert.tbs_cert();
+        let serial = serial_hex(tbs.serial_number().as_bytes());
+        if self.get(&serial).is_some() {
+            return Err(CaStoreError::DuplicateSerial(serial));
+        }
+        let not_after = tbs
+            .validity()
+            .not_after()
+            .map_err(|_| ParseError::MalformedData)?;
+        let entry = IssuedCertificate {
+            serial,
+            // the index is line and tab separated
+            subject: format_name(tbs.subject()).replace(['\t', '\r', '\n'], " "),
+            not_after,
+        };
+
+        fs::write(self.certificate_path(&entry.serial), cert.raw_data())?;
+        let mut index = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(INDEX))?;
+        index.write_all(format_line(&entry).as_bytes())?;
+        self.issued.push(entry);
+
+        Ok(())
+    }
+
+    /// whether a certificate with the serial number `serial`, the content octets of the INTEGER,
+    /// was issued
+    pub fn contains_serial(&self, serial: &[u8]) -> bool {
+        self.get(&serial_hex(serial)).is_some()
+    }
+
+    /// the entry of the serial number `serial` in hex, as in [`IssuedCertificate::serial`]
+    pub fn get(&self, serial: &str) -> Option<&IssuedCertificate> {
+        self.issued
+            .iter()
+            .find(|entry| entry.serial.eq_ignore_ascii_case(serial))
+    }
+
+    /// the DER encoded certificate of `entry`
+    pub fn certificate(&self, entry: &IssuedCertificate) -> io::Result<Vec<u8>> {
+        fs::read(self.certificate_path(&entry.serial))
+    }
+
+    /// the issued certificates, in the order they were recorded
+    pub fn iter(&self) -> impl Iterator<Item = &IssuedCertificate> {
+        self.issued.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.issued.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issued.is_empty()
+    }
+
+    fn certificate_path(&self, serial: &str) -> PathBuf {
+        self.dir.join(CERTS).join(format!("{}.der", serial))
+    }
+}
+
+fn serial_hex(serial: &[u8]) -> String {
+    let significant = serial.iter().position(|b| *b != 0).unwrap_or(serial.len());
+    let hex: String = serial[significant..]
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    if hex.is_empty() {
+        "00".to_string()
+    } else {
+        hex
+    }
+}
+
+/// `V`, notAfter as GeneralizedTime, an empty revocation date, the serial, the file name
+/// `unknown` and the subject, separated by tabs like `openssl ca` does it
+fn format_line(entry: &IssuedCertificate) -> String {
+    let t = entry.not_after;
+    format!(
+        "V\t{:04}{:02}{:02}{:02}{:02}{:02}Z\t\t{}\tunknown\t{}\n",
+        t.year(),
+        t.month(),
+        t.day(),
+        t.hour(),
+        t.minute(),
+        t.second(),
+        entry.serial,
+        entry.subject
+    )
+}
+
+fn parse_line(line: &str) -> Option<IssuedCertificate> {
+    let fields: Vec<&str> = line.splitn(6, '\t').collect();
+    if fields.len() != 6 || fields[0] != "V" {
+        return None;
+    }
+
+    let time = fields[1].strip_suffix('Z')?;
+    if time.len() != 14 || !time.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let number = |range: std::ops::Range<usize>| time[range].parse::<u32>().ok();
+    let not_after = UtcDateTime::from_ymd_hms(
+        number(0..4)? as i32,
+        number(4..6)?,
+        number(6..8)?,
+        number(8..10)?,
+        number(10..12)?,
+        number(12..14)?,
+    )?;
+    let serial = fields[3];
+    if serial.is_empty() || !serial.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Some(IssuedCertificate {
+        serial: serial.to_ascii_uppercase(),
+        subject: fields[5].to_string(),
+        not_after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ca_store() {
+        let dir = std::env::temp_dir().join(format!("x509-rs-ca-store-{}", std::process::id()));
+        let leaf = CertificateRef::from_slice(include_bytes!("../../certs/ocsp/leaf.der")).unwrap();
+        let other =
+            CertificateRef::from_slice(include_bytes!("../../certs/names/names.der")).unwrap();
+
+        let mut store = CaStore::open(&dir).unwrap();
+        assert!(store.is_empty());
+        store.record(&leaf).unwrap();
+        store.record(&other).unwrap();
+        assert!(matches!(
+            store.record(&leaf),
+            Err(CaStoreError::DuplicateSerial(serial)) if serial == "1000"
+        ));
+        assert!(store.contains_serial(&[0x00, 0x50, 0x00]));
+
+        // everything survives reopening
+        let store = CaStore::open(&dir).unwrap();
+        assert_eq!(store.len(), 2);
+        let entry = store.get("1000").unwrap();
+        assert_eq!(
+            entry.not_after,
+            leaf.tbs_cert().validity().not_after().unwrap()
+        );
+        assert_eq!(store.certificate(entry).unwrap(), leaf.raw_data());
+        assert_eq!(store.get("5000").unwrap().subject, "CN=www.example.com");
+        assert!(!store.contains_serial(&[0x10, 0x01]));
+
+        fs::write(
+            dir.join(INDEX),
+            "V\t20260101000000Z\t\t10\tunknown\tCN=x\nR\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            CaStore::open(&dir),
+            Err(CaStoreError::MalformedIndex(2))
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,334 @@
+//! The certificate transparency logs of a log list in the JSON format Google publishes for
+//! Chrome (`log_list.json`, schema version 3), so the keys SCTs are checked against don't have
+//! to be maintained by hand.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use x509_core::{common::time::UtcDateTime, oids};
+
+use crate::provider::default_provider;
+
+/// the state of a log in the log list. SCTs of qualified, usable and read-only logs are
+/// accepted, those of retired logs only if they were issued before the retirement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogState {
+    Pending,
+    Qualified,
+    Usable,
+    ReadOnly,
+    Retired,
+    Rejected,
+}
+
+/// a log of the log list, as far as SCT verification is concerned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CtLog {
+    pub description: String,
+    /// the name of the operator the log is listed under
+    pub operator: String,
+    /// the SHA-256 hash of the key, SCTs refer to their log by it
+    pub log_id: [u8; 32],
+    /// the DER encoded SubjectPublicKeyInfo the SCT signatures are made with
+    pub key: Vec<u8>,
+    /// None for logs that are not part of any log program yet
+    pub state: Option<LogState>,
+    /// the time the log entered its state
+    pub state_since: Option<UtcDateTime>,
+    /// the start (inclusive) and end (exclusive) of notAfter of the certificates a sharded log
+    /// accepts
+    pub temporal_interval: Option<(UtcDateTime, UtcDateTime)>,
+}
+
+impl CtLog {
+    /// whether an SCT of this log with the timestamp `sct_time` counts
+    pub fn accepts_sct_at(&self, sct_time: UtcDateTime) -> bool {
+        match self.state {
+            Some(LogState::Qualified) | Some(LogState::Usable) | Some(LogState::ReadOnly) => true,
+            Some(LogState::Retired) => self.state_since.is_some_and(|retired| sct_time < retired),
+            Some(LogState::Pending) | Some(LogState::Rejected) | None => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LogListError {
+    Json(serde_json::Error),
+    /// a key or log id that isn't valid base64
+    Base64(base64::DecodeError),
+    /// a timestamp that isn't of the form `2006-01-02T15:04:05Z`
+    MalformedTimestamp(String),
+    /// the log id of the log with this description is not the hash of its key
+    LogIdMismatch(String),
+    Digest(crate::Error),
+}
+
+impl From<serde_json::Error> for LogListError {
+    fn from(e: serde_json::Error) -> Self {
+        LogListError::Json(e)
+    }
+}
+
+impl From<base64::DecodeError> for LogListError {
+    fn from(e: base64::DecodeError) -> Self {
+        LogListError::Base64(e)
+    }
+}
+
+impl From<crate::Error> for LogListError {
+    fn from(e: crate::Error) -> Self {
+        LogListError::Digest(e)
+    }
+}
+
+/// the logs of a log list, looked up by log id. these are the logs
+/// [`check_embedded_scts`](crate::sct::check_embedded_scts) accepts SCTs of.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CtLogs {
+    logs: HashMap<[u8; 32], CtLog>,
+}
+
+impl CtLogs {
+    /// loads the logs of every operator in `json`, tiled logs included. every log id is checked
+    /// against the hash of its key.
+    pub fn from_log_list_json(json: &[u8]) -> Result<Self, LogListError> {
+        let list: RawLogList = serde_json::from_slice(json)?;
+
+        let mut logs = HashMap::new();
+        for operator in list.operators {
+            for log in operator.logs.into_iter().chain(operator.tiled_logs) {
+                let log = log.into_log(&operator.name)?;
+                logs.insert(log.log_id, log);
+            }
+        }
+
+        Ok(Self { logs })
+    }
+
+    /// the log with the id `log_id`, as found in an SCT
+    pub fn find(&self, log_id: &[u8]) -> Option<&CtLog> {
+        let log_id: [u8; 32] = log_id.try_into().ok()?;
+        self.logs.get(&log_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CtLog> {
+        self.logs.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.logs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.logs.is_empty()
+    }
+}
+
+#[derive(Deserialize)]
+struct RawLogList {
+    operators: Vec<RawOperator>,
+}
+
+#[derive(Deserialize)]
+struct RawOperator {
+    name: String,
+    #[serde(default)]
+    logs: Vec<RawLog>,
+    #[serde(default)]
+    tiled_logs: Vec<RawLog>,
+}
+
+#[derive(Deserialize)]
+struct RawLog {
+    description: String,
+    log_id: String,
+    key: String,
+    state: Option<RawState>,
+    temporal_interval: Option<RawTemporalInterval>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RawState {
+    Pending(RawTimestamp),
+    Qualified(RawTimestamp),
+    Usable(RawTimestamp),
+    Readonly(RawTimestamp),
+    Retired(RawTimestamp),
+    Rejected(RawTimestamp),
+}
+
+#[derive(Deserialize)]
+struct RawTimestamp {
+    timestamp: String,
+}
+
+#[derive(Deserialize)]
+struct RawTemporalInterval {
+    start_inclusive: String,
+    end_exclusive: String,
+}
+
+impl RawLog {
+    fn into_log(self, operator: &str) -> Result<CtLog, LogListError> {
+        let key = base64::decode(&self.key)?;
+        let log_id = base64::decode(&self.log_id)?;
+        if default_provider().digest(&oids::SHA256, &key)? != log_id {
+            return Err(LogListError::LogIdMismatch(self.description));
+        }
+
+        let (state, state_since) = match self.state {
+            Some(state) => {
+                let (state, since) = match state {
+                    RawState::Pending(t) => (LogState::Pending, t),
+                    RawState::Qualified(t) => (LogState::Qualified, t),
+                    RawState::Usable(t) => (LogState::Usable, t),
+                    RawState::Readonly(t) => (LogState::ReadOnly, t),
+                    RawState::Retired(t) => (LogState::Retired, t),
+                    RawState::Rejected(t) => (LogState::Rejected, t),
+                };
+                (Some(state), Some(parse_timestamp(&since.timestamp)?))
+            }
+            None => (None, None),
+        };
+        let temporal_interval = match self.temporal_interval {
+            Some(interval) => Some((
+                parse_timestamp(&interval.start_inclusive)?,
+                parse_timestamp(&interval.end_exclusive)?,
+            )),
+            None => None,
+        };
+
+        Ok(CtLog {
+            description: self.description,
+            operator: operator.to_string(),
+            log_id: log_id.try_into().expect("checked to be a SHA-256 hash"),
+            key,
+            state,
+            state_since,
+            temporal_interval,
+        })
+    }
+}
+
+/// parses the RFC 3339 UTC timestamps of the log list, a fraction of the second is ignored
+fn parse_timestamp(timestamp: &str) -> Result<UtcDateTime, LogListError> {
+    let malformed = || LogListError::MalformedTimestamp(timestamp.to_string());
+    let b = timestamp.as_bytes();
+    if b.len() < 20 || b[4] != b'-' || b[7] != b'-' || b[10] != b'T' || b[13] != b':' {
+        return Err(malformed());
+    }
+    if b[16] != b':' || b[b.len() - 1] != b'Z' {
+        return Err(malformed());
+    }
+    let fraction = &b[19..b.len() - 1];
+    if !fraction.is_empty()
+        && (fraction[0] != b'.'
+            || fraction.len() < 2
+            || !fraction[1..].iter().all(u8::is_ascii_digit))
+    {
+        return Err(malformed());
+    }
+
+    let number = |range: std::ops::Range<usize>| -> Result<u32, LogListError> {
+        let digits = &b[range];
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return Err(malformed());
+        }
+        Ok(digits.iter().fold(0, |acc, d| acc * 10 + (d - b'0') as u32))
+    };
+    UtcDateTime::from_ymd_hms(
+        number(0..4)? as i32,
+        number(5..7)?,
+        number(8..10)?,
+        number(11..13)?,
+        number(14..16)?,
+        number(17..19)?,
+    )
+    .ok_or_else(malformed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest;
+
+    #[test]
+    fn test_log_list() {
+        let logs =
+            CtLogs::from_log_list_json(include_bytes!("../../certs/ct/log_list.json")).unwrap();
+        assert_eq!(logs.len(), 3);
+
+        let log_id = base64::decode("Y8fVUcaziuP3p8EYOXexEnN5D2FYw1zp/tkWQdlDgas=").unwrap();
+        let log = logs.find(&log_id).unwrap();
+        assert_eq!(log.description, "x509-rs 'Aurora2026' log");
+        assert_eq!(log.operator, "x509-rs");
+        assert_eq!(log.state, Some(LogState::Usable));
+        assert_eq!(
+            log.temporal_interval,
+            Some((
+                UtcDateTime::from_ymd_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+                UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap()
+            ))
+        );
+        assert_eq!(digest(&oids::SHA256, &log.key).unwrap(), log_id);
+        assert!(logs.find(&log_id[..31]).is_none());
+
+        // the tiled log
+        let log = logs
+            .iter()
+            .find(|log| log.description.contains("Borealis"))
+            .unwrap();
+        assert_eq!(log.state, Some(LogState::ReadOnly));
+    }
+
+    #[test]
+    fn test_retired_log() {
+        let logs =
+            CtLogs::from_log_list_json(include_bytes!("../../certs/ct/log_list.json")).unwrap();
+        let log_id = base64::decode("irLGLKu7Mi5BqRioGy88L8AgPhyh+UmkpwyZlCq5y80=").unwrap();
+        let log = logs.find(&log_id).unwrap();
+
+        let retired = UtcDateTime::from_ymd_hms(2026, 2, 1, 0, 0, 0).unwrap();
+        assert_eq!(log.state_since, Some(retired));
+        assert!(log.accepts_sct_at(UtcDateTime::from_ymd_hms(2026, 1, 31, 0, 0, 0).unwrap()));
+        assert!(!log.accepts_sct_at(retired));
+    }
+
+    #[test]
+    fn test_malformed_log_list() {
+        let list = |log_id: &str, timestamp: &str| {
+            format!(
+                r#"{{"operators": [{{"name": "x", "logs": [{{"description": "d", "log_id": "{}",
+                "key": "MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE9yWylV8JDL7HCoIiFZMzK+NSRO3atbRHJIE03hm0hxEoeZd2nEcna0OM21djZDZ/Or9MO4Wtb7ukEF/kspnJFw==",
+                "state": {{"usable": {{"timestamp": "{}"}}}}}}]}}]}}"#,
+                log_id, timestamp
+            )
+        };
+        let log_id = "Y8fVUcaziuP3p8EYOXexEnN5D2FYw1zp/tkWQdlDgas=";
+        let other_log_id = "irLGLKu7Mi5BqRioGy88L8AgPhyh+UmkpwyZlCq5y80=";
+
+        assert!(
+            CtLogs::from_log_list_json(list(log_id, "2025-11-01T00:00:00Z").as_bytes()).is_ok()
+        );
+        assert!(matches!(
+            CtLogs::from_log_list_json(list(other_log_id, "2025-11-01T00:00:00Z").as_bytes()),
+            Err(LogListError::LogIdMismatch(d)) if d == "d"
+        ));
+        for timestamp in [
+            "2025-11-01 00:00:00Z",
+            "2025-11-01T00:00:00+01:00",
+            "2025-02-30T00:00:00Z",
+            "2025-11-01T00:00:00.Z",
+        ] {
+            assert!(matches!(
+                CtLogs::from_log_list_json(list(log_id, timestamp).as_bytes()),
+                Err(LogListError::MalformedTimestamp(_))
+            ));
+        }
+        assert!(matches!(
+            CtLogs::from_log_list_json(b"{}"),
+            Err(LogListError::Json(_))
+        ));
+    }
+}
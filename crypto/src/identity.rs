@@ -0,0 +1,305 @@
+//! A certificate chain together with the private key of the leaf.
+
+use std::{fs, io, path::Path};
+
+use crate::private_key::PrivateKey;
+use x509_core::{
+    generate::{certificate::Certificate, der::ToDer, error::EncodingError, pem::encode_pem},
+    parse::{
+        error::ParseError,
+        parsing::CertificateRef,
+        pem::{classify_pem, PemItem},
+    },
+};
+
+/// a DER encoded private key, tagged with its format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivateKeyDer {
+    /// RSAPrivateKey, PEM label `RSA PRIVATE KEY`
+    Pkcs1(Vec<u8>),
+    /// ECPrivateKey, PEM label `EC PRIVATE KEY`
+    Sec1(Vec<u8>),
+    /// PrivateKeyInfo, PEM label `PRIVATE KEY`
+    Pkcs8(Vec<u8>),
+}
+
+impl PrivateKeyDer {
+    fn from_pem_item(item: PemItem) -> Option<Self> {
+        match item {
+            PemItem::Pkcs1PrivateKey(der) => Some(PrivateKeyDer::Pkcs1(der)),
+            PemItem::Sec1PrivateKey(der) => Some(PrivateKeyDer::Sec1(der)),
+            PemItem::Pkcs8PrivateKey(der) => Some(PrivateKeyDer::Pkcs8(der)),
+            _ => None,
+        }
+    }
+
+    pub fn pem_label(&self) -> &'static str {
+        match self {
+            PrivateKeyDer::Pkcs1(_) => "RSA PRIVATE KEY",
+            PrivateKeyDer::Sec1(_) => "EC PRIVATE KEY",
+            PrivateKeyDer::Pkcs8(_) => "PRIVATE KEY",
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            PrivateKeyDer::Pkcs1(der) | PrivateKeyDer::Sec1(der) | PrivateKeyDer::Pkcs8(der) => der,
+        }
+    }
+
+    /// the key as the rustls type of the same format
+    #[cfg(feature = "rustls")]
+    pub fn to_rustls(&self) -> rustls_pki_types::PrivateKeyDer<'static> {
+        use rustls_pki_types::{PrivatePkcs1KeyDer, PrivatePkcs8KeyDer, PrivateSec1KeyDer};
+
+        match self {
+            PrivateKeyDer::Pkcs1(der) => PrivatePkcs1KeyDer::from(der.clone()).into(),
+            PrivateKeyDer::Sec1(der) => PrivateSec1KeyDer::from(der.clone()).into(),
+            PrivateKeyDer::Pkcs8(der) => PrivatePkcs8KeyDer::from(der.clone()).into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum IdentityError {
+    Parse(ParseError),
+    Io(io::Error),
+    Encoding(EncodingError),
+    /// an identity needs at least the leaf certificate
+    EmptyChain,
+    /// the key input contains no private key, or more than one
+    NoSinglePrivateKey,
+}
+
+impl From<ParseError> for IdentityError {
+    fn from(e: ParseError) -> Self {
+        IdentityError::Parse(e)
+    }
+}
+
+impl From<io::Error> for IdentityError {
+    fn from(e: io::Error) -> Self {
+        IdentityError::Io(e)
+    }
+}
+
+impl From<EncodingError> for IdentityError {
+    fn from(e: EncodingError) -> Self {
+        IdentityError::Encoding(e)
+    }
+}
+
+/// a certificate chain, leaf first, and the private key belonging to the leaf. this is what
+/// TLS stacks and signing APIs need to act as the subject of the leaf.
+#[derive(Debug, Clone)]
+pub struct Identity {
+    chain: Vec<Vec<u8>>,
+    private_key: PrivateKeyDer,
+}
+
+impl Identity {
+    /// `chain` holds DER encoded certificates, starting with the leaf
+    pub fn new(chain: Vec<Vec<u8>>, private_key: PrivateKeyDer) -> Result<Self, IdentityError> {
+        if chain.is_empty() {
+            return Err(IdentityError::EmptyChain);
+        }
+        for cert in &chain {
+            CertificateRef::from_slice(cert)?;
+        }
+
+        Ok(Self { chain, private_key })
+    }
+
+    /// the identity of freshly generated certificates, `chain` starting with the leaf, and the
+    /// key of the leaf
+    pub fn from_generated(
+        chain: &[Certificate],
+        private_key: &PrivateKey,
+    ) -> Result<Self, IdentityError> {
+        let chain = chain
+            .iter()
+            .map(|cert| cert.to_der())
+            .collect::<Result<_, _>>()?;
+
+        Self::new(
+            chain,
+            PrivateKeyDer::Pkcs8(private_key.pkcs8_der().to_vec()),
+        )
+    }
+
+    /// loads the identity from a PEM bundle of certificates (leaf first) and a PEM encoded key.
+    /// blocks other than certificates in `chain_pem` are ignored.
+    pub fn from_pem(chain_pem: &[u8], key_pem: &[u8]) -> Result<Self, IdentityError> {
+        let chain = classify_pem(chain_pem)?
+            .into_iter()
+            .filter_map(|item| match item {
+                PemItem::Certificate(der) => Some(der),
+                _ => None,
+            })
+            .collect();
+
+        let mut keys: Vec<PrivateKeyDer> = classify_pem(key_pem)?
+            .into_iter()
+            .filter_map(PrivateKeyDer::from_pem_item)
+            .collect();
+        if keys.len() != 1 {
+            return Err(IdentityError::NoSinglePrivateKey);
+        }
+
+        Self::new(chain, keys.remove(0))
+    }
+
+    /// encodes the identity as a PEM bundle of the chain and a PEM encoded key
+    pub fn to_pem(&self) -> (String, String) {
+        let chain = self
+            .chain
+            .iter()
+            .map(|cert| encode_pem("CERTIFICATE", cert))
+            .collect();
+        let key = encode_pem(self.private_key.pem_label(), self.private_key.as_bytes());
+
+        (chain, key)
+    }
+
+    pub fn load_pem_files<P: AsRef<Path>>(
+        chain_path: P,
+        key_path: P,
+    ) -> Result<Self, IdentityError> {
+        Self::from_pem(&fs::read(chain_path)?, &fs::read(key_path)?)
+    }
+
+    pub fn save_pem_files<P: AsRef<Path>>(&self, chain_path: P, key_path: P) -> io::Result<()> {
+        let (chain, key) = self.to_pem();
+        fs::write(chain_path, chain)?;
+        fs::write(key_path, key)
+    }
+
+    pub fn leaf(&self) -> CertificateRef<'_> {
+        self.chain()
+            .next()
+            .expect("an identity has at least one certificate")
+    }
+
+    /// the certificates, starting with the leaf
+    pub fn chain(&self) -> impl Iterator<Item = CertificateRef<'_>> {
+        self.chain.iter().map(|cert| {
+            CertificateRef::from_slice(cert).expect("checked when the identity was created")
+        })
+    }
+
+    /// the DER encoded certificates, starting with the leaf
+    pub fn chain_der(&self) -> &[Vec<u8>] {
+        &self.chain
+    }
+
+    pub fn private_key(&self) -> &PrivateKeyDer {
+        &self.private_key
+    }
+
+    /// the chain and key in the types rustls takes, e.g. for
+    /// `ServerConfig::builder().with_no_client_auth().with_single_cert(chain, key)`
+    #[cfg(feature = "rustls")]
+    pub fn to_rustls(
+        &self,
+    ) -> (
+        Vec<rustls_pki_types::CertificateDer<'static>>,
+        rustls_pki_types::PrivateKeyDer<'static>,
+    ) {
+        let chain = self
+            .chain
+            .iter()
+            .map(|cert| rustls_pki_types::CertificateDer::from(cert.clone()))
+            .collect();
+
+        (chain, self.private_key.to_rustls())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain_pem() -> String {
+        encode_pem("CERTIFICATE", include_bytes!("../../certs/ocsp/leaf.der"))
+            + &encode_pem("CERTIFICATE", include_bytes!("../../certs/ocsp/ca.der"))
+    }
+
+    #[test]
+    fn test_pem_round_trip() {
+        let key_pem = include_bytes!("../../certs/identity/leaf.key");
+        let identity = Identity::from_pem(chain_pem().as_bytes(), key_pem).unwrap();
+        assert_eq!(identity.chain().count(), 2);
+        assert_eq!(
+            identity.leaf().tbs_cert().serial_number().to_i64().unwrap(),
+            0x1000
+        );
+        assert!(matches!(identity.private_key(), PrivateKeyDer::Pkcs8(_)));
+
+        let (chain, key) = identity.to_pem();
+        let reloaded = Identity::from_pem(chain.as_bytes(), key.as_bytes()).unwrap();
+        assert_eq!(reloaded.chain_der(), identity.chain_der());
+        assert_eq!(reloaded.private_key(), identity.private_key());
+    }
+
+    #[test]
+    fn test_from_generated() {
+        use crate::prelude::*;
+
+        let key = PrivateKey::from_pem(include_bytes!("../../certs/identity/leaf.key")).unwrap();
+        let algorithm: AlgorithmIdentifier = Algorithm::RSA_SHA256.into();
+        let tbs = TBSCertificateBuilder::default()
+            .serial_number(Integer::from_i64(1))
+            .signature(algorithm.clone())
+            .issuer(Name::DistinguishedName(DistinguishedName::default()))
+            .validity(Validity::new(
+                UtcDateTime::from_ymd_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+                UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap(),
+            ))
+            .subject(Name::DistinguishedName(DistinguishedName::default()))
+            .subject_public_key_info(SubjectPublicKeyInfo::new(
+                algorithm,
+                BitString::new(vec![], 0),
+            ))
+            .extensions(None)
+            .build()
+            .unwrap();
+        let cert = tbs.self_sign(Algorithm::RSA_SHA256, &key).unwrap();
+
+        let identity = Identity::from_generated(&[cert], &key).unwrap();
+        assert!(identity.leaf().verify_signature(&identity.leaf()).unwrap());
+        assert_eq!(PrivateKey::try_from(identity.private_key()).unwrap(), key);
+        assert!(matches!(
+            Identity::from_generated(&[], &key),
+            Err(IdentityError::EmptyChain)
+        ));
+    }
+
+    #[cfg(feature = "rustls")]
+    #[test]
+    fn test_to_rustls() {
+        let key_pem = include_bytes!("../../certs/identity/leaf.key");
+        let identity = Identity::from_pem(chain_pem().as_bytes(), key_pem).unwrap();
+
+        let (chain, key) = identity.to_rustls();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(
+            chain[0].as_ref(),
+            &include_bytes!("../../certs/ocsp/leaf.der")[..]
+        );
+        assert!(matches!(key, rustls_pki_types::PrivateKeyDer::Pkcs8(_)));
+        assert_eq!(key.secret_der(), identity.private_key().as_bytes());
+    }
+
+    #[test]
+    fn test_missing_parts() {
+        let key_pem = include_bytes!("../../certs/identity/leaf.key");
+        assert!(matches!(
+            Identity::from_pem(b"", key_pem),
+            Err(IdentityError::EmptyChain)
+        ));
+        assert!(matches!(
+            Identity::from_pem(chain_pem().as_bytes(), chain_pem().as_bytes()),
+            Err(IdentityError::NoSinglePrivateKey)
+        ));
+    }
+}
@@ -0,0 +1,84 @@
+//! Key identifiers as used in the subjectKeyIdentifier and authorityKeyIdentifier extensions.
+
+use crate::{provider::default_provider, Error};
+use x509_core::{oids, parse::certificate::SubjectPublicKeyInfoRef};
+
+/// computes the key identifier with method 1 of RFC 5280 section 4.2.1.2, the SHA-1 hash of the
+/// subjectPublicKey bits. this is what most CAs use.
+pub fn compute_key_identifier(spki: &SubjectPublicKeyInfoRef) -> Result<Vec<u8>, Error> {
+    let (_, key) = spki.subject_public_key().data();
+    default_provider().digest(&oids::SHA1, key)
+}
+
+/// checks whether `key_identifier` was derived from the public key in `spki` by one of the
+/// common methods: both methods of RFC 5280 section 4.2.1.2 and the truncated SHA-2 hashes of
+/// RFC 7093. identifiers derived in some other way can't be checked and return false.
+pub fn key_identifier_matches(
+    key_identifier: &[u8],
+    spki: &SubjectPublicKeyInfoRef,
+) -> Result<bool, Error> {
+    let (_, key) = spki.subject_public_key().data();
+    let provider = default_provider();
+
+    let sha1 = provider.digest(&oids::SHA1, key)?;
+    if key_identifier == sha1.as_slice() {
+        return Ok(true);
+    }
+    // method 2: the four bits 0100 followed by the least significant 60 bits of the hash
+    if key_identifier.len() == 8
+        && key_identifier[0] == 0x40 | (sha1[12] & 0x0f)
+        && key_identifier[1..] == sha1[13..]
+    {
+        return Ok(true);
+    }
+
+    // RFC 7093 methods 1 to 3: the leftmost 160 bits of a SHA-2 hash
+    if key_identifier.len() == 20 {
+        for algorithm in [oids::SHA256, oids::SHA384, oids::SHA512] {
+            if provider.digest(&algorithm, key)?[..20] == *key_identifier {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest;
+    use x509_core::parse::{extensions::SubjectKeyIdentifierRef, parsing::CertificateRef};
+
+    #[test]
+    fn test_key_identifier() {
+        let ca = CertificateRef::from_slice(include_bytes!("../../certs/ocsp/ca.der")).unwrap();
+        let leaf = CertificateRef::from_slice(include_bytes!("../../certs/ocsp/leaf.der")).unwrap();
+        let ext = ca
+            .tbs_cert()
+            .extensions()
+            .unwrap()
+            .find(oids::SUBJECT_KEY_IDENTIFIER)
+            .unwrap()
+            .unwrap();
+        let ski = SubjectKeyIdentifierRef::from_slice(ext.value().as_bytes()).unwrap();
+        let spki = ca.tbs_cert().subject_public_key_info();
+
+        assert_eq!(compute_key_identifier(spki).unwrap(), ski.key_identifier());
+        assert!(key_identifier_matches(ski.key_identifier(), spki).unwrap());
+        assert!(!key_identifier_matches(
+            ski.key_identifier(),
+            leaf.tbs_cert().subject_public_key_info()
+        )
+        .unwrap());
+
+        let sha1 = compute_key_identifier(spki).unwrap();
+        let mut method2 = vec![0x40 | (sha1[12] & 0x0f)];
+        method2.extend_from_slice(&sha1[13..]);
+        assert!(key_identifier_matches(&method2, spki).unwrap());
+
+        let (_, key) = spki.subject_public_key().data();
+        let sha256 = digest(&oids::SHA256, key).unwrap();
+        assert!(key_identifier_matches(&sha256[..20], spki).unwrap());
+    }
+}
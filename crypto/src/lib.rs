@@ -1,80 +1,209 @@
-use std::{fs::File, io::Write};
+//! Signing and verification for the `x509_core` types, backed by ring or the RustCrypto crates.
+//!
+//! like the `x509_core` types, everything here is `Send + Sync`, so an [`identity::Identity`]
+//! or a [`verify::VerifyPolicy`] can be shared by all threads of a server.
+
+use std::fmt::{self, Display, Formatter};
+use std::io::Write;
 
-use ::ring::signature::{self, KeyPair, RsaKeyPair};
 use x509_core::{
     generate::{
         builder::TBSCertificate,
-        certificate::{AlgorithmIdentifier, Certificate, SubjectPublicKeyInfo},
+        certificate::{
+            AlgorithmIdentifier, AlgorithmParameters, Certificate, SubjectPublicKeyInfo,
+        },
         der::{BitString, Data, Null, ToDer},
         error::EncodingError,
     },
+    oids,
     parse::{
+        certificate::{AlgorithmidentifierRef, SignatureParametersRef, SubjectPublicKeyInfoRef},
+        crl::CertificateListRef,
         der::{ObjectIdentifier, ObjectIdentifierRef},
         parsing::CertificateRef,
     },
 };
-use x509_macros::oid_str_to_bytes;
 
-const RSA_MD5_OID: &[u8] = oid_str_to_bytes!("1.2.840.113549.1.1.4");
-const RSA_SHA1_OID: &[u8] = oid_str_to_bytes!("1.2.840.113549.1.1.5");
-const RSA_SHA256_OID: &[u8] = oid_str_to_bytes!("1.2.840.113549.1.1.11");
-const RSA_SHA384_OID: &[u8] = oid_str_to_bytes!("1.2.840.113549.1.1.12");
-const RSA_SHA512_OID: &[u8] = oid_str_to_bytes!("1.2.840.113549.1.1.13");
+use crate::private_key::PrivateKey;
+use crate::provider::{default_provider, CryptoProvider};
+
+/// the key type of a signature algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyType {
+    Rsa,
+    Ecdsa,
+    Ed25519,
+}
+
+/// the hash function of a signature algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Digest {
+    /// only the RustCrypto backend implements MD5
+    #[cfg(feature = "use-rust-crypto")]
+    Md5,
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+/// a signature algorithm, a key type paired with a digest. the only way to get one is through
+/// the constants below or an OID, and both only offer the pairs the enabled backend supports,
+/// so e.g. ECDSA with SHA-384 doesn't compile with the RustCrypto backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Algorithm {
+    key_type: KeyType,
+    digest: Digest,
+    oid: ObjectIdentifierRef<'static>,
+}
+
+impl Algorithm {
+    #[cfg(feature = "use-rust-crypto")]
+    pub const RSA_MD5: Algorithm =
+        Algorithm::new(KeyType::Rsa, Digest::Md5, oids::MD5_WITH_RSA_ENCRYPTION);
+    pub const RSA_SHA1: Algorithm =
+        Algorithm::new(KeyType::Rsa, Digest::Sha1, oids::SHA1_WITH_RSA_ENCRYPTION);
+    pub const RSA_SHA256: Algorithm = Algorithm::new(
+        KeyType::Rsa,
+        Digest::Sha256,
+        oids::SHA256_WITH_RSA_ENCRYPTION,
+    );
+    pub const RSA_SHA384: Algorithm = Algorithm::new(
+        KeyType::Rsa,
+        Digest::Sha384,
+        oids::SHA384_WITH_RSA_ENCRYPTION,
+    );
+    pub const RSA_SHA512: Algorithm = Algorithm::new(
+        KeyType::Rsa,
+        Digest::Sha512,
+        oids::SHA512_WITH_RSA_ENCRYPTION,
+    );
+
+    /// RSASSA-PSS with MGF1 over the same hash and a salt as long as the hash, the only
+    /// parameters ring signs with
+    pub const RSA_PSS_SHA256: Algorithm =
+        Algorithm::new(KeyType::Rsa, Digest::Sha256, oids::RSASSA_PSS);
+    pub const RSA_PSS_SHA384: Algorithm =
+        Algorithm::new(KeyType::Rsa, Digest::Sha384, oids::RSASSA_PSS);
+    pub const RSA_PSS_SHA512: Algorithm =
+        Algorithm::new(KeyType::Rsa, Digest::Sha512, oids::RSASSA_PSS);
+
+    pub const ECDSA_SHA256: Algorithm =
+        Algorithm::new(KeyType::Ecdsa, Digest::Sha256, oids::ECDSA_WITH_SHA256);
+    #[cfg(any(feature = "use-ring", feature = "use-aws-lc-rs"))]
+    pub const ECDSA_SHA384: Algorithm =
+        Algorithm::new(KeyType::Ecdsa, Digest::Sha384, oids::ECDSA_WITH_SHA384);
+
+    /// PureEdDSA, the message isn't hashed beforehand. SHA-512 is the hash used inside the
+    /// signature scheme.
+    pub const ED25519: Algorithm = Algorithm::new(KeyType::Ed25519, Digest::Sha512, oids::ED25519);
+
+    const fn new(key_type: KeyType, digest: Digest, oid: ObjectIdentifierRef<'static>) -> Self {
+        Self {
+            key_type,
+            digest,
+            oid,
+        }
+    }
+
+    pub fn key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    pub fn digest(&self) -> Digest {
+        self.digest
+    }
 
-const ECDSA_SHA256_OID: &[u8] = oid_str_to_bytes!("1.2.840.10045.4.3.2");
-const ECDSA_SHA384_OID: &[u8] = oid_str_to_bytes!("1.2.840.10045.4.3.3");
-const ECDSA_SHA512_OID: &[u8] = oid_str_to_bytes!("1.2.840.10045.4.3.4");
+    /// the OID of the signature algorithm, e.g. sha256WithRSAEncryption
+    pub fn oid(&self) -> ObjectIdentifierRef<'static> {
+        self.oid
+    }
+}
 
-const ECDSA_P256_OID: &[u8] = oid_str_to_bytes!("1.2.840.10045.3.1.7");
-const ECDSA_P384_OID: &[u8] = oid_str_to_bytes!("1.3.132.0.34");
-const ECDSA_P521_OID: &[u8] = oid_str_to_bytes!("1.3.132.0.35");
+/// the OID of a signature algorithm that is not supported, by this crate or by the backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedAlgorithmError<'a>(ObjectIdentifierRef<'a>);
 
-// TODO: only include algorithms supported by underlying crypto lib to move some runtime errors to compile errors
-#[derive(Clone, Copy)]
-pub enum Algorithm {
-    RSA_MD5,
-    RSA_SHA1,
-    RSA_SHA256,
-    RSA_SHA384,
-    RSA_SHA512,
+impl UnsupportedAlgorithmError<'_> {
+    /// the OID of the algorithm, owned so it can outlive the data it was found in
+    pub fn oid(&self) -> ObjectIdentifier {
+        self.0.into()
+    }
+}
 
-    ECDSA_SHA256,
-    ECDSA_SHA384,
-    ECDSA_SHA512,
+impl Display for UnsupportedAlgorithmError<'_> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(f, "unsupported signature algorithm {}", self.0)
+    }
 }
 
-pub struct UnsupportedAlgorithmError<'a>(&'a [u8]);
+impl std::error::Error for UnsupportedAlgorithmError<'_> {}
 
 impl<'a> TryFrom<&'a [u8]> for Algorithm {
     type Error = UnsupportedAlgorithmError<'a>;
 
     fn try_from(oid: &'a [u8]) -> Result<Self, Self::Error> {
-        match oid {
-            RSA_SHA1_OID => Ok(Algorithm::RSA_SHA1),
-            RSA_MD5_OID => Ok(Algorithm::RSA_MD5),
-            RSA_SHA256_OID => Ok(Algorithm::RSA_SHA256),
-            RSA_SHA384_OID => Ok(Algorithm::RSA_SHA384),
-            RSA_SHA512_OID => Ok(Algorithm::RSA_SHA512),
-            ECDSA_SHA256_OID => Ok(Algorithm::ECDSA_SHA256),
-            ECDSA_SHA384_OID => Ok(Algorithm::ECDSA_SHA384),
-            ECDSA_SHA512_OID => Ok(Algorithm::ECDSA_SHA512),
-            oid => Err(UnsupportedAlgorithmError(oid)),
+        match ObjectIdentifierRef::new(oid) {
+            #[cfg(feature = "use-rust-crypto")]
+            oids::MD5_WITH_RSA_ENCRYPTION => Ok(Algorithm::RSA_MD5),
+            oids::SHA1_WITH_RSA_ENCRYPTION => Ok(Algorithm::RSA_SHA1),
+            oids::SHA256_WITH_RSA_ENCRYPTION => Ok(Algorithm::RSA_SHA256),
+            oids::SHA384_WITH_RSA_ENCRYPTION => Ok(Algorithm::RSA_SHA384),
+            oids::SHA512_WITH_RSA_ENCRYPTION => Ok(Algorithm::RSA_SHA512),
+            oids::ECDSA_WITH_SHA256 => Ok(Algorithm::ECDSA_SHA256),
+            #[cfg(any(feature = "use-ring", feature = "use-aws-lc-rs"))]
+            oids::ECDSA_WITH_SHA384 => Ok(Algorithm::ECDSA_SHA384),
+            oids::ED25519 => Ok(Algorithm::ED25519),
+            _ => Err(UnsupportedAlgorithmError(ObjectIdentifierRef::new(oid))),
         }
     }
 }
 
+/// like the conversion from the OID alone, but RSASSA-PSS maps to the `RSA_PSS_*` algorithms if
+/// the parameters are one of their parameter sets. the other algorithms take NULL or absent
+/// parameters, anything else is rejected.
+impl<'a> TryFrom<&'a AlgorithmidentifierRef<'a>> for Algorithm {
+    type Error = UnsupportedAlgorithmError<'a>;
+
+    fn try_from(identifier: &'a AlgorithmidentifierRef<'a>) -> Result<Self, Self::Error> {
+        let oid = *identifier.algorithm_identifier();
+        let algorithm = match identifier.signature_parameters() {
+            Ok(SignatureParametersRef::RsassaPss(params)) => [
+                Algorithm::RSA_PSS_SHA256,
+                Algorithm::RSA_PSS_SHA384,
+                Algorithm::RSA_PSS_SHA512,
+            ]
+            .into_iter()
+            .find(|algorithm| {
+                rsassa_pss_parameters(algorithm.digest)
+                    == (*params.hash_algorithm(), params.salt_length())
+                    && params.mask_gen_algorithm() == &oids::MGF1
+                    && params.mask_gen_hash_algorithm() == params.hash_algorithm()
+                    && params.trailer_field() == 1
+            }),
+            Ok(SignatureParametersRef::Null | SignatureParametersRef::Absent) => {
+                Algorithm::try_from(oid.as_bytes()).ok()
+            }
+            _ => None,
+        };
+
+        algorithm.ok_or(UnsupportedAlgorithmError(oid))
+    }
+}
+
+/// the hash and salt length of the RSASSA-PSS algorithms, the salt is as long as the hash
+fn rsassa_pss_parameters(digest: Digest) -> (ObjectIdentifierRef<'static>, u32) {
+    match digest {
+        Digest::Sha256 => (oids::SHA256, 32),
+        Digest::Sha384 => (oids::SHA384, 48),
+        Digest::Sha512 => (oids::SHA512, 64),
+        digest => unreachable!("there is no RSASSA-PSS algorithm with {:?}", digest),
+    }
+}
+
 impl Into<&'static [u8]> for Algorithm {
     fn into(self) -> &'static [u8] {
-        match self {
-            Algorithm::RSA_MD5 => RSA_MD5_OID,
-            Algorithm::RSA_SHA1 => RSA_SHA1_OID,
-            Algorithm::RSA_SHA256 => RSA_SHA256_OID,
-            Algorithm::RSA_SHA384 => RSA_SHA384_OID,
-            Algorithm::RSA_SHA512 => RSA_SHA512_OID,
-            Algorithm::ECDSA_SHA256 => ECDSA_SHA256_OID,
-            Algorithm::ECDSA_SHA384 => ECDSA_SHA384_OID,
-            Algorithm::ECDSA_SHA512 => ECDSA_SHA512_OID,
-        }
+        self.oid.as_bytes()
     }
 }
 
@@ -82,26 +211,104 @@ impl Into<AlgorithmIdentifier> for Algorithm {
     fn into(self) -> AlgorithmIdentifier {
         let oid: ObjectIdentifier = ObjectIdentifierRef::new(self.into()).into();
 
-        AlgorithmIdentifier::new(oid, Data::Null(Null()))
+        match self.key_type {
+            // RFC 8410 section 3 and RFC 5758 section 3.2, the parameters must be absent
+            KeyType::Ed25519 | KeyType::Ecdsa => AlgorithmIdentifier::without_parameters(oid),
+            KeyType::Rsa if self.oid == oids::RSASSA_PSS => {
+                let (hash_algorithm, salt_length) = rsassa_pss_parameters(self.digest);
+                AlgorithmIdentifier::rsassa_pss(hash_algorithm.into(), salt_length)
+            }
+            _ => AlgorithmIdentifier::new(oid, Data::Null(Null())),
+        }
     }
 }
 
 pub trait VerifySignature {
-    fn verify_signature(&self, issuer: &CertificateRef) -> Result<bool, Error>;
+    /// whether this is signed by the key of `issuer`, checked with the backend the crate is
+    /// built with
+    fn verify_signature(&self, issuer: &CertificateRef) -> Result<bool, Error> {
+        self.verify_signature_with(default_provider(), issuer)
+    }
+
+    /// like [`verify_signature`](Self::verify_signature), with the signature checked by
+    /// `provider`
+    fn verify_signature_with(
+        &self,
+        provider: &dyn CryptoProvider,
+        issuer: &CertificateRef,
+    ) -> Result<bool, Error>;
 }
 
 impl<'a> VerifySignature for CertificateRef<'a> {
-    fn verify_signature(&self, issuer: &CertificateRef) -> Result<bool, Error> {
-        check_signature(self, issuer)
+    fn verify_signature_with(
+        &self,
+        provider: &dyn CryptoProvider,
+        issuer: &CertificateRef,
+    ) -> Result<bool, Error> {
+        provider.check_signature(self, issuer)
+    }
+}
+
+impl<'a> VerifySignature for CertificateListRef<'a> {
+    fn verify_signature_with(
+        &self,
+        provider: &dyn CryptoProvider,
+        issuer: &CertificateRef,
+    ) -> Result<bool, Error> {
+        let pub_key = issuer.tbs_cert().subject_public_key_info();
+        let raw_tbs = self.tbs_cert_list().raw_data();
+        let (_, signature) = self.signature().data();
+
+        provider.verify_signed_data(self.signature_algorithm(), pub_key, raw_tbs, signature)
     }
 }
 
+/// computes signatures over data that is written to it in chunks. the TBS structure is
+/// streamed into it by the encoder, so a signer that can work on a digest never needs the
+/// whole TBS in one buffer.
+pub trait Signer {
+    /// receives the encoded data, e.g. a hash context or a buffer
+    type Input: Write;
+
+    /// the signature algorithm, written to the signature fields of the certificate
+    fn algorithm(&self) -> Algorithm;
+    /// starts a new signature over exactly `len` bytes
+    fn begin(&self, len: usize) -> Self::Input;
+    /// computes the signature once all data has been written to `input`
+    fn finish(&self, input: Self::Input) -> Result<Vec<u8>, Error>;
+}
+
+/// signs the DER encoding of `tbs` without encoding it into an intermediate buffer
+pub fn sign_tbs<S: Signer>(tbs: &impl ToDer, signer: &S) -> Result<Vec<u8>, SigningError> {
+    let len = tbs.encoded_len().map_err(SigningError::EncodingError)?;
+    let mut input = signer.begin(len);
+    tbs.write_der(&mut input)
+        .map_err(SigningError::EncodingError)?;
+
+    signer.finish(input).map_err(SigningError::Signature)
+}
+
 pub trait SignCert {
+    /// signs with `private_key` and makes the certificate its own issuer. the subject public key
+    /// info is replaced with the public key of `private_key`.
     fn self_sign(
         self,
         algorithm: Algorithm,
-        private_key: &[u8],
+        private_key: &PrivateKey,
+    ) -> Result<Certificate, SigningError>;
+
+    /// signs with `issuer_key`, the private key of the CA certificate `issuer_cert`, and makes
+    /// the subject of `issuer_cert` the issuer. the subject public key info is kept. fails with
+    /// `IssuerKeyMismatch` if `issuer_key` doesn't belong to `issuer_cert`.
+    fn sign_with_issuer(
+        self,
+        issuer_cert: &CertificateRef,
+        algorithm: Algorithm,
+        issuer_key: &PrivateKey,
     ) -> Result<Certificate, SigningError>;
+
+    /// signs the certificate as is, only the signature algorithm is set from `signer`
+    fn sign_with<S: Signer>(self, signer: &S) -> Result<Certificate, SigningError>;
 }
 
 #[derive(Debug)]
@@ -109,34 +316,43 @@ pub enum SigningError {
     EncodingError(EncodingError),
     Signature(Error),
     InvalidPrivateKey,
+    /// the private key is not the one of the issuer certificate
+    IssuerKeyMismatch,
 }
 
 impl SignCert for TBSCertificate {
     fn self_sign(
         mut self,
         algorithm: Algorithm,
-        private_key: &[u8],
+        private_key: &PrivateKey,
     ) -> Result<Certificate, SigningError> {
-        let key_pair =
-            RsaKeyPair::from_der(&private_key).map_err(|_| SigningError::InvalidPrivateKey)?;
-
         self.issuer = self.subject.clone();
-        let algo_identifier: AlgorithmIdentifier = algorithm.into();
-        // todo set parameters for algorithm if necessary
-        self.signature = algo_identifier.clone();
 
-        let public_key = key_pair.public_key().as_ref();
-        let public_key = BitString::new(public_key.to_vec(), public_key.len() * 8);
-        let rsa_pkcs1_fmt = ObjectIdentifier::from_str("1.2.840.113549.1.1.1").unwrap();
-        let key_algo_id = AlgorithmIdentifier::new(rsa_pkcs1_fmt, Data::Null(Null()));
-        let spki = SubjectPublicKeyInfo::new(key_algo_id.clone(), public_key); // todo fix algo identifier, were using the algo oid for the signature not the key
-        self.subject_public_key_info = spki;
+        sign_with_key(self, algorithm, private_key, None)
+    }
 
-        let tbs = self
-            .to_der()
-            .map_err(|e| SigningError::EncodingError(e))?;
+    fn sign_with_issuer(
+        mut self,
+        issuer_cert: &CertificateRef,
+        algorithm: Algorithm,
+        issuer_key: &PrivateKey,
+    ) -> Result<Certificate, SigningError> {
+        let issuer = issuer_cert.tbs_cert();
+        self.issuer = issuer.subject().into();
+
+        sign_with_key(
+            self,
+            algorithm,
+            issuer_key,
+            Some(issuer.subject_public_key_info()),
+        )
+    }
+
+    fn sign_with<S: Signer>(mut self, signer: &S) -> Result<Certificate, SigningError> {
+        let algo_identifier: AlgorithmIdentifier = signer.algorithm().into();
+        self.signature = algo_identifier.clone();
 
-        let signature = sign(&tbs, key_pair, algorithm).map_err(|e| SigningError::Signature(e))?;
+        let signature = sign_tbs(&self, signer)?;
 
         let used_bits = signature.len() * 8;
         Ok(Certificate::new(
@@ -147,30 +363,140 @@ impl SignCert for TBSCertificate {
     }
 }
 
+/// signs `tbs` with `private_key`. without `issuer_key` the certificate is self-signed and gets
+/// the public key of `private_key`, otherwise that public key has to be `issuer_key`.
+fn sign_with_key(
+    tbs: TBSCertificate,
+    algorithm: Algorithm,
+    private_key: &PrivateKey,
+    issuer_key: Option<&SubjectPublicKeyInfoRef>,
+) -> Result<Certificate, SigningError> {
+    let signing_error = |e| match e {
+        Error::InvalidPrivateKey => SigningError::InvalidPrivateKey,
+        e => SigningError::Signature(e),
+    };
+
+    match private_key {
+        PrivateKey::Rsa(der) => {
+            let key_pair = rsa_key_pair(der).map_err(signing_error)?;
+            let signer = RsaSigner::new(key_pair, algorithm).map_err(SigningError::Signature)?;
+
+            let rsa_pkcs1_fmt: ObjectIdentifier = oids::RSA_ENCRYPTION.into();
+            let key_algo_id = AlgorithmIdentifier::new(rsa_pkcs1_fmt, Data::Null(Null()));
+            sign_as(tbs, &signer, signer.public_key(), key_algo_id, issuer_key)
+        }
+        PrivateKey::Ecdsa(der) => {
+            let signer = EcdsaSigner::new(der, algorithm).map_err(signing_error)?;
+
+            let named_curve = AlgorithmParameters::NamedCurve(signer.named_curve().into());
+            let key_algo_id =
+                AlgorithmIdentifier::with_parameters(oids::EC_PUBLIC_KEY.into(), named_curve)
+                    .map_err(SigningError::EncodingError)?;
+            sign_as(tbs, &signer, signer.public_key(), key_algo_id, issuer_key)
+        }
+        PrivateKey::Ed25519(der) => {
+            let signer = Ed25519Signer::new(der, algorithm).map_err(signing_error)?;
+
+            let key_algo_id = AlgorithmIdentifier::without_parameters(oids::ED25519.into());
+            sign_as(tbs, &signer, signer.public_key(), key_algo_id, issuer_key)
+        }
+    }
+}
+
+fn sign_as<S: Signer>(
+    mut tbs: TBSCertificate,
+    signer: &S,
+    public_key: &[u8],
+    key_algo_id: AlgorithmIdentifier,
+    issuer_key: Option<&SubjectPublicKeyInfoRef>,
+) -> Result<Certificate, SigningError> {
+    match issuer_key {
+        None => {
+            let public_key = BitString::new(public_key.to_vec(), public_key.len() * 8);
+            tbs.subject_public_key_info = SubjectPublicKeyInfo::new(key_algo_id, public_key);
+        }
+        Some(issuer_key) if issuer_key.subject_public_key().data() != (0, public_key) => {
+            return Err(SigningError::IssuerKeyMismatch);
+        }
+        Some(_) => {}
+    }
+
+    tbs.sign_with(signer)
+}
+
+pub mod ca_store;
+#[cfg(feature = "serde")]
+pub mod ct_logs;
+pub mod identity;
+pub mod key_id;
+pub mod ocsp;
+#[cfg(feature = "encrypted-keys")]
+mod pbe;
+pub mod pin;
+#[cfg(feature = "pkcs12")]
+pub mod pkcs12;
+pub mod pool;
+pub mod prelude;
+pub mod private_key;
+pub mod provider;
+pub mod revocation;
+#[cfg(feature = "serde")]
+pub mod sct;
+pub mod trust_store;
+pub mod verify;
+
 #[cfg(feature = "use-rust-crypto")]
 mod rust_crypto;
 #[cfg(feature = "use-rust-crypto")]
-pub use rust_crypto::{check_signature, Error};
+pub use rust_crypto::{
+    check_signature, digest, pbkdf2, rsa_key_pair, sign, verify_signed_data, EcdsaSigner,
+    Ed25519Signer, Error, RsaSigner, RustCryptoProvider,
+};
 
 #[cfg(feature = "use-ring")]
 mod ring;
 #[cfg(feature = "use-ring")]
-pub use crate::ring::{check_signature, sign, Error};
+pub use crate::ring::{
+    check_signature, digest, pbkdf2, rsa_key_pair, sign, verify_signed_data, EcdsaSigner,
+    Ed25519Signer, Error, RingProvider, RsaSigner,
+};
+
+#[cfg(feature = "use-aws-lc-rs")]
+mod aws_lc;
+#[cfg(feature = "use-aws-lc-rs")]
+pub use crate::aws_lc::{
+    check_signature, digest, fips_mode, pbkdf2, rsa_key_pair, sign, verify_signed_data,
+    AwsLcProvider, EcdsaSigner, Ed25519Signer, Error, RsaSigner,
+};
+
+// fails to compile if one of the public types stops being Send + Sync
+#[allow(dead_code)]
+fn assert_send_sync() {
+    fn is_send_sync<T: Send + Sync>() {}
+
+    #[cfg(feature = "serde")]
+    is_send_sync::<ct_logs::CtLogs>();
+    is_send_sync::<identity::Identity>();
+    is_send_sync::<identity::IdentityError>();
+    is_send_sync::<pin::PinSet>();
+    is_send_sync::<pool::CertificatePool>();
+    is_send_sync::<private_key::PrivateKey>();
+    is_send_sync::<private_key::PrivateKeyError>();
+    is_send_sync::<trust_store::RootStore>();
+    is_send_sync::<verify::VerifyPolicy>();
+    is_send_sync::<verify::VerificationReport>();
+    is_send_sync::<verify::SignatureCache>();
+    is_send_sync::<SigningError>();
+    is_send_sync::<Error>();
+    is_send_sync::<RsaSigner>();
+    is_send_sync::<EcdsaSigner>();
+    is_send_sync::<Ed25519Signer>();
+    is_send_sync::<provider::ProviderSigner>();
+}
 
 #[test]
 fn test_tbs_cert_builder() {
-    use x509_core::generate::der::Data;
-    use x509_core::{
-        generate::{
-            builder::TBSCertificateBuilder,
-            certificate::{
-                AlgorithmIdentifier, AttributeTypeAndValue, DistinguishedName, Extension,
-                Extensions, Name, RelativeDistinguishedName, SubjectPublicKeyInfo, Validity,
-            },
-            der::{BitString, Integer, Null, OctetString, ToDer, Utf8String},
-        },
-        parse::der::ObjectIdentifier,
-    };
+    use crate::prelude::*;
 
     let builder = TBSCertificateBuilder::default();
     //ISSUER
@@ -183,12 +509,8 @@ fn test_tbs_cert_builder() {
     issuer_dn.push(rdn_cn);
 
     //VALIDITY
-    let not_before = chrono::DateTime::parse_from_rfc3339("2021-07-31T12:33:53-00:00")
-        .unwrap()
-        .with_timezone(&chrono::Utc);
-    let not_after = chrono::DateTime::parse_from_rfc3339("2022-07-31T12:33:53-00:00")
-        .unwrap()
-        .with_timezone(&chrono::Utc);
+    let not_before = UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap();
+    let not_after = UtcDateTime::from_ymd_hms(2022, 7, 31, 12, 33, 53).unwrap();
     let validity = Validity::new(not_before, not_after);
 
     //SUBJECT
@@ -230,9 +552,9 @@ fn test_tbs_cert_builder() {
         .build()
         .unwrap();
 
-    let key_data = include_bytes!("../../test-key");
+    let key = PrivateKey::from_pkcs1_der(include_bytes!("../../test-key")).unwrap();
 
-    let cert = tbs.self_sign(Algorithm::RSA_SHA256, key_data).unwrap();
+    let cert = tbs.self_sign(Algorithm::RSA_SHA256, &key).unwrap();
     let cert_bytes = cert.to_der().unwrap();
     let cert = CertificateRef::from_slice(&cert_bytes).unwrap();
 
@@ -241,3 +563,402 @@ fn test_tbs_cert_builder() {
     let signature_valid = res.unwrap();
     assert!(signature_valid);
 }
+
+#[test]
+fn test_crl_signature() {
+    let ca = CertificateRef::from_slice(include_bytes!("../../certs/ocsp/ca.der")).unwrap();
+    let other =
+        CertificateRef::from_slice(include_bytes!("../../certs/ocsp/responder.der")).unwrap();
+    let crl = CertificateListRef::from_slice(include_bytes!("../../certs/crl/ca.crl")).unwrap();
+
+    assert!(crl.verify_signature(&ca).unwrap());
+    assert!(!crl.verify_signature(&other).unwrap());
+}
+
+#[test]
+fn test_rsassa_pss_signature() {
+    // self signed with -sigopt rsa_padding_mode:pss, SHA-256 and a 32 byte salt
+    let cert = CertificateRef::from_slice(include_bytes!("../../certs/identity/pss.der")).unwrap();
+    let other = CertificateRef::from_slice(include_bytes!("../../certs/ocsp/ca.der")).unwrap();
+
+    assert!(cert.verify_signature(&cert).unwrap());
+    assert!(!cert.verify_signature(&other).unwrap());
+}
+
+#[test]
+fn test_sign_with_issuer() {
+    use crate::prelude::*;
+
+    let tbs = |common_name| {
+        TBSCertificateBuilder::default()
+            .serial_number(Integer::from_i64(1))
+            .signature(Algorithm::RSA_SHA256.into())
+            .issuer(Name::DistinguishedName(DistinguishedName::default()))
+            .validity(Validity::new(
+                UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap(),
+                UtcDateTime::from_ymd_hms(2022, 7, 31, 12, 33, 53).unwrap(),
+            ))
+            .subject(Name::DistinguishedName(
+                DistinguishedName::builder()
+                    .common_name(common_name)
+                    .build()
+                    .unwrap(),
+            ))
+            .subject_public_key_info(SubjectPublicKeyInfo::new(
+                AlgorithmIdentifier::new(oids::EC_PUBLIC_KEY.into(), Data::Null(Null())),
+                BitString::new(vec![4; 65], 65 * 8),
+            ))
+            .extensions(None)
+            .build()
+            .unwrap()
+    };
+    let ca_key = PrivateKey::from_pkcs1_der(include_bytes!("../../test-key")).unwrap();
+    let ca = tbs("CA")
+        .self_sign(Algorithm::RSA_SHA256, &ca_key)
+        .unwrap()
+        .to_der()
+        .unwrap();
+    let ca = CertificateRef::from_slice(&ca).unwrap();
+
+    let leaf = tbs("leaf")
+        .sign_with_issuer(&ca, Algorithm::RSA_SHA384, &ca_key)
+        .unwrap()
+        .to_der()
+        .unwrap();
+    let leaf = CertificateRef::from_slice(&leaf).unwrap();
+    assert!(leaf.verify_signature(&ca).unwrap());
+    assert_eq!(leaf.tbs_cert().issuer(), ca.tbs_cert().subject());
+    assert_eq!(
+        leaf.tbs_cert()
+            .subject_public_key_info()
+            .subject_public_key()
+            .data(),
+        (0, &[4; 65][..])
+    );
+
+    // the key of another CA
+    let other = CertificateRef::from_slice(include_bytes!("../../certs/ocsp/ca.der")).unwrap();
+    assert!(matches!(
+        tbs("leaf").sign_with_issuer(&other, Algorithm::RSA_SHA256, &ca_key),
+        Err(SigningError::IssuerKeyMismatch)
+    ));
+}
+
+#[test]
+fn test_rsassa_pss_signing() {
+    use crate::prelude::*;
+    use x509_core::parse::certificate::SignatureParametersRef;
+
+    let key = PrivateKey::from_pkcs1_der(include_bytes!("../../test-key")).unwrap();
+    for (algorithm, hash_algorithm, salt_length) in [
+        (Algorithm::RSA_PSS_SHA256, oids::SHA256, 32),
+        (Algorithm::RSA_PSS_SHA384, oids::SHA384, 48),
+        (Algorithm::RSA_PSS_SHA512, oids::SHA512, 64),
+    ] {
+        let tbs = TBSCertificateBuilder::default()
+            .serial_number(Integer::from_i64(1))
+            .signature(algorithm.into())
+            .issuer(Name::DistinguishedName(DistinguishedName::default()))
+            .validity(Validity::new(
+                UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap(),
+                UtcDateTime::from_ymd_hms(2022, 7, 31, 12, 33, 53).unwrap(),
+            ))
+            .subject(Name::DistinguishedName(DistinguishedName::default()))
+            .subject_public_key_info(SubjectPublicKeyInfo::new(
+                AlgorithmIdentifier::new(oids::RSA_ENCRYPTION.into(), Data::Null(Null())),
+                BitString::new(vec![], 0),
+            ))
+            .extensions(None)
+            .build()
+            .unwrap();
+        let der = tbs.self_sign(algorithm, &key).unwrap().to_der().unwrap();
+        let cert = CertificateRef::from_slice(&der).unwrap();
+
+        assert!(cert.verify_signature(&cert).unwrap());
+        assert_eq!(
+            cert.tbs_cert().algorithm_identifier(),
+            cert.signature_algorithm()
+        );
+        match cert.signature_algorithm().signature_parameters() {
+            Ok(SignatureParametersRef::RsassaPss(params)) => {
+                assert_eq!(*params.hash_algorithm(), hash_algorithm);
+                assert_eq!(*params.mask_gen_hash_algorithm(), hash_algorithm);
+                assert_eq!(params.salt_length(), salt_length);
+            }
+            _ => panic!("no RSASSA-PSS parameters"),
+        }
+    }
+}
+
+#[cfg(feature = "use-ring")]
+#[test]
+fn test_sign_tbs_streams_the_encoding() {
+    use crate::prelude::*;
+
+    // hashes the chunks as they arrive and returns the digest as the "signature". the
+    // second field counts down the announced length.
+    struct DigestSigner;
+    struct DigestInput(::ring::digest::Context, usize);
+    impl Write for DigestInput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.update(buf);
+            self.1 -= buf.len();
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    impl Signer for DigestSigner {
+        type Input = DigestInput;
+        fn algorithm(&self) -> Algorithm {
+            Algorithm::RSA_SHA256
+        }
+        fn begin(&self, len: usize) -> Self::Input {
+            DigestInput(::ring::digest::Context::new(&::ring::digest::SHA256), len)
+        }
+        fn finish(&self, input: Self::Input) -> Result<Vec<u8>, Error> {
+            assert_eq!(input.1, 0);
+            Ok(input.0.finish().as_ref().to_vec())
+        }
+    }
+
+    let mut extensions = Extensions::default();
+    extensions.add(Extension::new(
+        oids::SUBJECT_ALT_NAME.into(),
+        false.into(),
+        OctetString::new(vec![0x30; 100_000]),
+    ));
+    let tbs = TBSCertificateBuilder::default()
+        .serial_number(Integer::from_i64(1))
+        .signature(Algorithm::RSA_SHA256.into())
+        .issuer(Name::DistinguishedName(DistinguishedName::default()))
+        .validity(Validity::new(
+            UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap(),
+            UtcDateTime::from_ymd_hms(2022, 7, 31, 12, 33, 53).unwrap(),
+        ))
+        .subject(Name::DistinguishedName(DistinguishedName::default()))
+        .subject_public_key_info(SubjectPublicKeyInfo::new(
+            AlgorithmIdentifier::new(oids::RSA_ENCRYPTION.into(), Data::Null(Null())),
+            BitString::new(vec![20; 256], 2048),
+        ))
+        .extensions(Some(extensions))
+        .build()
+        .unwrap();
+
+    let expected = digest(&oids::SHA256, &tbs.to_der().unwrap()).unwrap();
+    assert_eq!(sign_tbs(&tbs, &DigestSigner).unwrap(), expected);
+}
+
+#[test]
+fn test_algorithm_oids() {
+    let oid: &[u8] = Algorithm::RSA_SHA384.into();
+    assert!(Algorithm::try_from(oid).ok() == Some(Algorithm::RSA_SHA384));
+    assert_eq!(Algorithm::RSA_SHA384.key_type(), KeyType::Rsa);
+    assert_eq!(Algorithm::RSA_SHA384.digest(), Digest::Sha384);
+
+    // ECDSA with SHA-512 is supported by neither backend
+    let e = Algorithm::try_from(oids::ECDSA_WITH_SHA512.as_bytes()).unwrap_err();
+    assert!(e.oid() == oids::ECDSA_WITH_SHA512.into());
+    assert_eq!(
+        e.to_string(),
+        "unsupported signature algorithm 1.2.840.10045.4.3.4"
+    );
+    let e: Box<dyn std::error::Error> = Box::new(e);
+    assert!(e.source().is_none());
+}
+
+#[test]
+fn test_algorithm_from_identifier() {
+    use x509_core::parse::certificate::parse_algorithm_identifier;
+
+    let cert = CertificateRef::from_slice(include_bytes!("../../certs/identity/pss.der")).unwrap();
+    assert!(
+        Algorithm::try_from(cert.signature_algorithm()).ok() == Some(Algorithm::RSA_PSS_SHA256)
+    );
+    let cert = CertificateRef::from_slice(include_bytes!("../../certs/ocsp/ca.der")).unwrap();
+    assert!(Algorithm::try_from(cert.signature_algorithm()).ok() == Some(Algorithm::RSA_SHA256));
+
+    for algorithm in [
+        Algorithm::RSA_SHA256,
+        Algorithm::RSA_PSS_SHA384,
+        Algorithm::RSA_PSS_SHA512,
+        Algorithm::ECDSA_SHA256,
+        Algorithm::ED25519,
+    ] {
+        let identifier: AlgorithmIdentifier = algorithm.into();
+        let der = identifier.to_der().unwrap();
+        let (_, identifier) = parse_algorithm_identifier(&der).unwrap();
+        assert!(Algorithm::try_from(&identifier).ok() == Some(algorithm));
+    }
+
+    let unsupported = |der: &[u8]| {
+        let (_, identifier) = parse_algorithm_identifier(der).unwrap();
+        Algorithm::try_from(&identifier).is_err()
+    };
+    // RSASSA-PSS with all fields defaulted: SHA-1 and a 20 byte salt
+    assert!(unsupported(&[
+        0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0a, 0x30, 0x00,
+    ]));
+    // sha256WithRSAEncryption with an OID as parameters
+    assert!(unsupported(&[
+        0x30, 0x0e, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b, 0x06, 0x01,
+        0x2a,
+    ]));
+}
+
+#[test]
+fn test_self_sign_pkcs8() {
+    use crate::prelude::*;
+
+    let key = PrivateKey::from_pem(include_bytes!("../../certs/identity/leaf.key")).unwrap();
+    let algorithm: AlgorithmIdentifier = Algorithm::RSA_SHA256.into();
+    let tbs = TBSCertificateBuilder::default()
+        .serial_number(Integer::from_i64(1))
+        .signature(algorithm.clone())
+        .issuer(Name::DistinguishedName(DistinguishedName::default()))
+        .validity(Validity::new(
+            UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap(),
+            UtcDateTime::from_ymd_hms(2022, 7, 31, 12, 33, 53).unwrap(),
+        ))
+        .subject(Name::DistinguishedName(DistinguishedName::default()))
+        .subject_public_key_info(SubjectPublicKeyInfo::new(
+            algorithm,
+            BitString::new(vec![], 0),
+        ))
+        .extensions(None)
+        .build()
+        .unwrap();
+
+    let cert = tbs.self_sign(Algorithm::RSA_SHA256, &key).unwrap();
+    let cert_bytes = cert.to_der().unwrap();
+    let cert = CertificateRef::from_slice(&cert_bytes).unwrap();
+    assert!(cert.verify_signature(&cert).unwrap());
+
+    // the PKCS#1 key inside the PKCS#8 one is accepted as well
+    let key = key.pkcs8_der();
+    let info = x509_core::parse::pkcs8::PrivateKeyInfoRef::from_slice(key).unwrap();
+    assert!(rsa_key_pair(info.private_key()).is_ok());
+    assert!(matches!(
+        rsa_key_pair(&key[..key.len() - 1]),
+        Err(Error::InvalidPrivateKey)
+    ));
+}
+
+#[test]
+fn test_self_sign_ecdsa() {
+    use crate::prelude::*;
+
+    let key =
+        PrivateKey::from_sec1_der(include_bytes!("../../certs/identity/ec-sec1.der")).unwrap();
+    let algorithm: AlgorithmIdentifier = Algorithm::ECDSA_SHA256.into();
+    let tbs = TBSCertificateBuilder::default()
+        .serial_number(Integer::from_i64(1))
+        .signature(algorithm.clone())
+        .issuer(Name::DistinguishedName(DistinguishedName::default()))
+        .validity(Validity::new(
+            UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap(),
+            UtcDateTime::from_ymd_hms(2022, 7, 31, 12, 33, 53).unwrap(),
+        ))
+        .subject(Name::DistinguishedName(DistinguishedName::default()))
+        .subject_public_key_info(SubjectPublicKeyInfo::new(
+            algorithm,
+            BitString::new(vec![], 0),
+        ))
+        .extensions(None)
+        .build()
+        .unwrap();
+
+    // a P-256 key can't sign with SHA-384, nor an EC key with RSA
+    #[cfg(any(feature = "use-ring", feature = "use-aws-lc-rs"))]
+    assert!(matches!(
+        EcdsaSigner::new(key.pkcs8_der(), Algorithm::ECDSA_SHA384),
+        Err(Error::InvalidPrivateKey)
+    ));
+    assert!(matches!(
+        EcdsaSigner::new(key.pkcs8_der(), Algorithm::RSA_SHA256),
+        Err(Error::UnsupportedAlgorithm(_))
+    ));
+
+    let cert = tbs.self_sign(Algorithm::ECDSA_SHA256, &key).unwrap();
+    let cert_bytes = cert.to_der().unwrap();
+    let cert = CertificateRef::from_slice(&cert_bytes).unwrap();
+    assert_eq!(
+        cert.tbs_cert()
+            .subject_public_key_info()
+            .algorithm_identifier()
+            .algorithm_identifier(),
+        &oids::EC_PUBLIC_KEY
+    );
+    // the curve in the key and no parameters in the signature algorithm
+    let spki = cert.tbs_cert().subject_public_key_info();
+    assert!(spki.algorithm_identifier().check_parameters().is_ok());
+    assert!(cert.signature_algorithm().check_parameters().is_ok());
+    assert!(cert.verify_signature(&cert).unwrap());
+}
+
+#[test]
+fn test_ed25519() {
+    use crate::prelude::*;
+
+    // made by openssl
+    let openssl =
+        CertificateRef::from_slice(include_bytes!("../../certs/identity/ed25519-cert.der"))
+            .unwrap();
+    assert!(openssl.verify_signature(&openssl).unwrap());
+
+    let key =
+        PrivateKey::from_pkcs8_der(include_bytes!("../../certs/identity/ed25519.der")).unwrap();
+    let algorithm: AlgorithmIdentifier = Algorithm::ED25519.into();
+    let tbs = || {
+        TBSCertificateBuilder::default()
+            .serial_number(Integer::from_i64(1))
+            .signature(algorithm.clone())
+            .issuer(Name::DistinguishedName(DistinguishedName::default()))
+            .validity(Validity::new(
+                UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap(),
+                UtcDateTime::from_ymd_hms(2022, 7, 31, 12, 33, 53).unwrap(),
+            ))
+            .subject(Name::DistinguishedName(DistinguishedName::default()))
+            .subject_public_key_info(SubjectPublicKeyInfo::new(
+                algorithm.clone(),
+                BitString::new(vec![], 0),
+            ))
+            .extensions(None)
+            .build()
+            .unwrap()
+    };
+
+    let cert_bytes = tbs()
+        .self_sign(Algorithm::ED25519, &key)
+        .unwrap()
+        .to_der()
+        .unwrap();
+    let cert = CertificateRef::from_slice(&cert_bytes).unwrap();
+    assert!(cert.verify_signature(&cert).unwrap());
+    // the same public key as the one openssl put in its certificate
+    assert_eq!(
+        cert.tbs_cert().subject_public_key_info().raw_data(),
+        openssl.tbs_cert().subject_public_key_info().raw_data()
+    );
+    // the signatureAlgorithm in front of the 64 byte signature has no parameters, not even NULL
+    let signature_algorithm = cert_bytes.len() - 64 - 3 - 7;
+    assert_eq!(
+        &cert_bytes[signature_algorithm..signature_algorithm + 10],
+        &[0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x41, 0x00]
+    );
+    // deterministic signatures
+    assert_eq!(
+        tbs()
+            .self_sign(Algorithm::ED25519, &key)
+            .unwrap()
+            .to_der()
+            .unwrap(),
+        cert_bytes
+    );
+
+    assert!(matches!(
+        Ed25519Signer::new(key.pkcs8_der(), Algorithm::ECDSA_SHA256),
+        Err(Error::UnsupportedAlgorithm(_))
+    ));
+}
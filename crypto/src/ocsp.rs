@@ -0,0 +1,377 @@
+//! Validation of OCSP responses, e.g. the ones stapled to a TLS handshake.
+
+use crate::provider::{default_provider, CryptoProvider};
+use crate::revocation::{RevocationChecker, RevocationError, RevocationStatus};
+use crate::Error;
+use x509_core::{
+    common::time::UtcDateTime,
+    oids,
+    parse::{
+        error::ParseError,
+        extensions::{ExtendedKeyUsageRef, KeyPurpose},
+        ocsp::{
+            BasicOCSPResponseRef, CertIdRef, CertStatusRef, OCSPResponseRef, OCSPResponseStatus,
+            ResponderIdRef,
+        },
+        parsing::CertificateRef,
+    },
+};
+
+#[derive(Debug)]
+pub enum OcspError {
+    Parse(ParseError),
+    /// the responder did not return a response, e.g. tryLater
+    Unsuccessful(OCSPResponseStatus),
+    /// the response contains no status for the certificate in question
+    NoMatchingResponse,
+    /// the response was signed by neither the issuer nor a responder delegated by it
+    UnauthorizedResponder,
+    InvalidSignature,
+    /// thisUpdate is in the future
+    NotYetValid,
+    /// nextUpdate has passed
+    Expired,
+    Crypto(Error),
+}
+
+impl From<ParseError> for OcspError {
+    fn from(e: ParseError) -> Self {
+        OcspError::Parse(e)
+    }
+}
+
+impl From<Error> for OcspError {
+    fn from(e: Error) -> Self {
+        OcspError::Crypto(e)
+    }
+}
+
+/// validates a DER encoded OCSP response for `leaf`, which was issued by `issuer`, at time `now`.
+///
+/// checks that the response is signed by the issuer or by a responder certificate the issuer
+/// delegated OCSP signing to, and that it contains a current status for `leaf`.
+pub fn validate_stapled_ocsp(
+    leaf: &CertificateRef,
+    issuer: &CertificateRef,
+    ocsp_der: &[u8],
+    now: UtcDateTime,
+) -> Result<RevocationStatus, OcspError> {
+    validate_stapled_ocsp_with_provider(leaf, issuer, ocsp_der, now, default_provider())
+}
+
+/// like [`validate_stapled_ocsp`], with the signatures checked and the hashes computed by
+/// `provider`
+pub fn validate_stapled_ocsp_with_provider(
+    leaf: &CertificateRef,
+    issuer: &CertificateRef,
+    ocsp_der: &[u8],
+    now: UtcDateTime,
+    provider: &dyn CryptoProvider,
+) -> Result<RevocationStatus, OcspError> {
+    let response = OCSPResponseRef::from_slice(ocsp_der)?;
+    if response.status() != OCSPResponseStatus::Successful {
+        return Err(OcspError::Unsuccessful(response.status()));
+    }
+    let basic = response
+        .basic_response()
+        .ok_or(OcspError::Parse(ParseError::MalformedData))?;
+
+    check_response_signature(basic, issuer, provider)?;
+
+    for single in basic.tbs_response_data().responses() {
+        let single = single?;
+        if !cert_id_matches(single.cert_id(), leaf, issuer, provider)? {
+            continue;
+        }
+
+        if to_datetime(single.this_update().to_datetime())? > now {
+            return Err(OcspError::NotYetValid);
+        }
+        if let Some(next_update) = single.next_update() {
+            if to_datetime(next_update.to_datetime())? < now {
+                return Err(OcspError::Expired);
+            }
+        }
+
+        let status = match single.cert_status() {
+            CertStatusRef::Good => RevocationStatus::Good,
+            CertStatusRef::Revoked {
+                revocation_time,
+                revocation_reason,
+            } => RevocationStatus::Revoked {
+                revocation_time: to_datetime(revocation_time.to_datetime())?,
+                reason: *revocation_reason,
+            },
+            CertStatusRef::Unknown => RevocationStatus::Unknown,
+        };
+
+        return Ok(status);
+    }
+
+    Err(OcspError::NoMatchingResponse)
+}
+
+/// the OCSP responses stapled for the certificates of a chain, as a [`RevocationChecker`] for
+/// [`verify_chain_with_revocation`](crate::verify::verify_chain_with_revocation). every response
+/// is checked with [`validate_stapled_ocsp`], certificates without one are not checked.
+#[derive(Debug, Clone)]
+pub struct StapledOcsp<'r> {
+    /// the DER encoded certificate and its response
+    responses: Vec<(&'r [u8], &'r [u8])>,
+}
+
+impl<'r> StapledOcsp<'r> {
+    /// `responses[i]` is the DER encoded response for `chain[i]`, if there is one
+    pub fn new(chain: &[CertificateRef<'r>], responses: &[Option<&'r [u8]>]) -> Self {
+        let responses = chain
+            .iter()
+            .zip(responses)
+            .filter_map(|(cert, response)| Some((cert.raw_data(), (*response)?)))
+            .collect();
+
+        Self { responses }
+    }
+}
+
+impl RevocationChecker for StapledOcsp<'_> {
+    fn check(
+        &self,
+        cert: &CertificateRef,
+        issuer: &CertificateRef,
+        now: UtcDateTime,
+        provider: &dyn CryptoProvider,
+    ) -> Result<RevocationStatus, RevocationError> {
+        match self.responses.iter().find(|(c, _)| *c == cert.raw_data()) {
+            Some((_, response)) => Ok(validate_stapled_ocsp_with_provider(
+                cert, issuer, response, now, provider,
+            )?),
+            None => Ok(RevocationStatus::NotChecked),
+        }
+    }
+}
+
+fn to_datetime(t: Result<UtcDateTime, x509_core::error::Error>) -> Result<UtcDateTime, OcspError> {
+    t.map_err(|_| OcspError::Parse(ParseError::MalformedData))
+}
+
+/// finds the key that signed the response and verifies the signature with it
+fn check_response_signature(
+    basic: &BasicOCSPResponseRef,
+    issuer: &CertificateRef,
+    provider: &dyn CryptoProvider,
+) -> Result<(), OcspError> {
+    let tbs = basic.tbs_response_data();
+    let responder_id = tbs.responder_id();
+    let (_, signature) = basic.signature().data();
+
+    let valid = if responder_matches(responder_id, issuer, provider)? {
+        provider.verify_signed_data(
+            basic.signature_algorithm(),
+            issuer.tbs_cert().subject_public_key_info(),
+            tbs.raw_data(),
+            signature,
+        )?
+    } else {
+        let mut responder = None;
+        for cert in basic.certs() {
+            let cert = cert?;
+            if responder_matches(responder_id, &cert, provider).unwrap_or(false) {
+                responder = Some(cert);
+                break;
+            }
+        }
+        let responder = responder.ok_or(OcspError::UnauthorizedResponder)?;
+        check_delegation(&responder, issuer, provider)?;
+
+        provider.verify_signed_data(
+            basic.signature_algorithm(),
+            responder.tbs_cert().subject_public_key_info(),
+            tbs.raw_data(),
+            signature,
+        )?
+    };
+
+    if !valid {
+        return Err(OcspError::InvalidSignature);
+    }
+
+    Ok(())
+}
+
+fn responder_matches(
+    responder_id: &ResponderIdRef,
+    cert: &CertificateRef,
+    provider: &dyn CryptoProvider,
+) -> Result<bool, Error> {
+    match responder_id {
+        ResponderIdRef::ByName(name) => Ok(name == cert.tbs_cert().subject()),
+        ResponderIdRef::ByKey(key_hash) => {
+            let (_, key) = cert
+                .tbs_cert()
+                .subject_public_key_info()
+                .subject_public_key()
+                .data();
+            Ok(provider.digest(&oids::SHA1, key)? == key_hash.as_bytes())
+        }
+    }
+}
+
+/// a delegated responder must be issued by the CA itself and carry the OCSPSigning purpose
+fn check_delegation(
+    responder: &CertificateRef,
+    issuer: &CertificateRef,
+    provider: &dyn CryptoProvider,
+) -> Result<(), OcspError> {
+    if responder.tbs_cert().issuer() != issuer.tbs_cert().subject() {
+        return Err(OcspError::UnauthorizedResponder);
+    }
+    if !provider.check_signature(responder, issuer)? {
+        return Err(OcspError::UnauthorizedResponder);
+    }
+    if !has_ocsp_signing_purpose(responder)? {
+        return Err(OcspError::UnauthorizedResponder);
+    }
+
+    Ok(())
+}
+
+/// RFC 6960 4.2.2.2 requires id-kp-OCSPSigning itself, anyExtendedKeyUsage is not enough
+fn has_ocsp_signing_purpose(cert: &CertificateRef) -> Result<bool, ParseError> {
+    let ext = match cert.tbs_cert().extensions() {
+        Some(extensions) => extensions.find(oids::EXT_KEY_USAGE)?,
+        None => None,
+    };
+    let ext = match ext {
+        Some(ext) => ext,
+        None => return Ok(false),
+    };
+
+    for purpose in ExtendedKeyUsageRef::from_slice(ext.value().as_bytes())?.key_purposes() {
+        if purpose? == KeyPurpose::OcspSigning {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+fn cert_id_matches(
+    cert_id: &CertIdRef,
+    leaf: &CertificateRef,
+    issuer: &CertificateRef,
+    provider: &dyn CryptoProvider,
+) -> Result<bool, Error> {
+    if cert_id.serial_number() != leaf.tbs_cert().serial_number() {
+        return Ok(false);
+    }
+
+    let hash_algorithm = cert_id.hash_algorithm().algorithm_identifier();
+    let name_hash = provider.digest(hash_algorithm, leaf.tbs_cert().issuer().raw_data())?;
+    let (_, issuer_key) = issuer
+        .tbs_cert()
+        .subject_public_key_info()
+        .subject_public_key()
+        .data();
+    let key_hash = provider.digest(hash_algorithm, issuer_key)?;
+
+    Ok(name_hash == cert_id.issuer_name_hash().as_bytes()
+        && key_hash == cert_id.issuer_key_hash().as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x509_core::common::certificate::CRLReason;
+
+    fn certs() -> (CertificateRef<'static>, CertificateRef<'static>) {
+        let leaf = CertificateRef::from_slice(include_bytes!("../../certs/ocsp/leaf.der")).unwrap();
+        let issuer = CertificateRef::from_slice(include_bytes!("../../certs/ocsp/ca.der")).unwrap();
+        (leaf, issuer)
+    }
+
+    fn now() -> UtcDateTime {
+        UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_signed_by_issuer() {
+        let (leaf, issuer) = certs();
+        let status = validate_stapled_ocsp(
+            &leaf,
+            &issuer,
+            include_bytes!("../../certs/ocsp/good-ca.der"),
+            now(),
+        );
+        assert_eq!(status.unwrap(), RevocationStatus::Good);
+    }
+
+    #[test]
+    fn test_signed_by_delegated_responder() {
+        let (leaf, issuer) = certs();
+        let ocsp = include_bytes!("../../certs/ocsp/good-delegated.der");
+        let status = validate_stapled_ocsp(&leaf, &issuer, ocsp, now());
+        assert_eq!(status.unwrap(), RevocationStatus::Good);
+
+        // a responder certificate can't vouch for a certificate it wasn't issued by
+        let status = validate_stapled_ocsp(&leaf, &leaf, ocsp, now());
+        assert!(matches!(status, Err(OcspError::UnauthorizedResponder)));
+    }
+
+    #[test]
+    fn test_malformed_responder_certificate() {
+        let (leaf, issuer) = certs();
+        let mut ocsp = include_bytes!("../../certs/ocsp/good-delegated.der").to_vec();
+        let responder = include_bytes!("../../certs/ocsp/responder.der");
+        let pos = ocsp
+            .windows(responder.len())
+            .position(|window| window == &responder[..])
+            .unwrap();
+        // the embedded certificate claims one byte more than there is
+        ocsp[pos + 3] += 1;
+
+        let status = validate_stapled_ocsp(&leaf, &issuer, &ocsp, now());
+        assert!(matches!(status, Err(OcspError::Parse(_))));
+    }
+
+    #[test]
+    fn test_responder_without_ocsp_signing_purpose() {
+        // signed by the leaf, which is issued by the CA but only allowed to authenticate servers
+        let (leaf, issuer) = certs();
+        let ocsp = include_bytes!("../../certs/ocsp/good-unauthorized.der");
+        let status = validate_stapled_ocsp(&leaf, &issuer, ocsp, now());
+        assert!(matches!(status, Err(OcspError::UnauthorizedResponder)));
+    }
+
+    #[test]
+    fn test_revoked() {
+        let (leaf, issuer) = certs();
+        let status = validate_stapled_ocsp(
+            &leaf,
+            &issuer,
+            include_bytes!("../../certs/ocsp/revoked.der"),
+            now(),
+        )
+        .unwrap();
+        assert_eq!(
+            status,
+            RevocationStatus::Revoked {
+                revocation_time: UtcDateTime::from_ymd_hms(2026, 10, 1, 12, 0, 0).unwrap(),
+                reason: Some(CRLReason::KeyCompromise),
+            }
+        );
+    }
+
+    #[test]
+    fn test_wrong_certificate_and_time() {
+        let (leaf, issuer) = certs();
+        let ocsp = include_bytes!("../../certs/ocsp/good-ca.der");
+
+        // the response is about the leaf, not the CA itself
+        let status = validate_stapled_ocsp(&issuer, &issuer, ocsp, now());
+        assert!(matches!(status, Err(OcspError::NoMatchingResponse)));
+
+        let before = UtcDateTime::from_ymd_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        let status = validate_stapled_ocsp(&leaf, &issuer, ocsp, before);
+        assert!(matches!(status, Err(OcspError::NotYetValid)));
+    }
+}
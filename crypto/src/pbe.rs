@@ -0,0 +1,93 @@
+//! Password based decryption, PBES2 of RFC 8018 as used by encrypted PKCS#8 keys.
+//!
+//! the key is derived with the PBKDF2 of the crypto backend, none of the backends provides the
+//! CBC mode the ciphers run in, it comes from the RustCrypto block cipher crates.
+
+use std::num::NonZeroU32;
+
+use aes::cipher::{block_padding::Pkcs7, BlockCipher, BlockDecryptMut, KeyInit, KeyIvInit};
+use aes::{Aes128, Aes192, Aes256};
+use x509_core::{
+    oids,
+    parse::{error::ParseError, pkcs8::Pbes2ParamsRef},
+};
+
+use crate::{pbkdf2, Error};
+
+#[derive(Debug)]
+pub(crate) enum PbeError {
+    Parse(ParseError),
+    Crypto(Error),
+    /// the cipher or the PRF with this OID is not supported
+    Unsupported(String),
+    /// the padding of the decrypted data is wrong, usually the password is wrong
+    BadPadding,
+}
+
+impl From<ParseError> for PbeError {
+    fn from(e: ParseError) -> Self {
+        PbeError::Parse(e)
+    }
+}
+
+impl From<Error> for PbeError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::UnsupportedAlgorithm(oid) => PbeError::Unsupported(oid),
+            e => PbeError::Crypto(e),
+        }
+    }
+}
+
+/// decrypts `data` with a key derived from `password`. RFC 8018 leaves the encoding of the
+/// password to the application, the callers pass UTF-8.
+pub(crate) fn pbes2_decrypt(
+    params: &Pbes2ParamsRef,
+    password: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, PbeError> {
+    let key_length = match *params.encryption_scheme() {
+        oids::AES128_CBC => 16,
+        oids::AES192_CBC => 24,
+        oids::AES256_CBC => 32,
+        oid => return Err(PbeError::Unsupported(oid.to_string())),
+    };
+    if params
+        .key_length()
+        .is_some_and(|length| length != key_length)
+    {
+        return Err(ParseError::MalformedData.into());
+    }
+    let iterations = NonZeroU32::new(params.iteration_count()).ok_or(ParseError::MalformedData)?;
+
+    let mut key = [0; 32];
+    let key = &mut key[..key_length as usize];
+    pbkdf2(params.prf(), params.salt(), iterations, password, key)?;
+
+    match key_length {
+        16 => cbc_decrypt::<Aes128>(key, params.iv(), data),
+        24 => cbc_decrypt::<Aes192>(key, params.iv(), data),
+        _ => cbc_decrypt::<Aes256>(key, params.iv(), data),
+    }
+}
+
+/// decrypts `data` with the block cipher `C` in CBC mode and removes the PKCS#7 padding
+pub(crate) fn cbc_decrypt<C>(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, PbeError>
+where
+    C: BlockCipher + BlockDecryptMut + KeyInit,
+{
+    if data.is_empty() || !data.len().is_multiple_of(C::block_size()) {
+        return Err(ParseError::MalformedData.into());
+    }
+    let decryptor =
+        cbc::Decryptor::<C>::new_from_slices(key, iv).map_err(|_| ParseError::MalformedData)?;
+
+    let mut buffer = data.to_vec();
+    let length = decryptor
+        .decrypt_padded_mut::<Pkcs7>(&mut buffer)
+        .map_err(|_| PbeError::BadPadding)?
+        .len();
+    buffer.truncate(length);
+
+    Ok(buffer)
+}
@@ -0,0 +1,211 @@
+//! Public key pinning with SHA-256 hashes of the DER encoded SubjectPublicKeyInfo, the pin
+//! format of HPKP and most mobile and embedded TLS clients. the same hashes group the
+//! certificates of a pool by key, to find keys reused across subjects.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{provider::default_provider, Error};
+use x509_core::{
+    oids,
+    parse::{error::ParseError, parsing::CertificateRef},
+};
+
+/// a set of SPKI SHA-256 pins. pinning the keys of the intermediates or a backup key, not only
+/// the leaf, keeps clients working across certificate renewals.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PinSet {
+    pins: HashSet<[u8; 32]>,
+}
+
+impl PinSet {
+    pub fn add(&mut self, pin: [u8; 32]) {
+        self.pins.insert(pin);
+    }
+
+    /// adds a base64 encoded pin, optionally prefixed with `sha256/` like in HPKP headers and
+    /// OkHttp's CertificatePinner
+    pub fn add_base64(&mut self, pin: &str) -> Result<(), ParseError> {
+        let pin = pin.trim();
+        let pin = pin.strip_prefix("sha256/").unwrap_or(pin);
+        let pin = base64::decode(pin).map_err(|_| ParseError::StringEncoding)?;
+        self.add(pin.try_into().map_err(|_| ParseError::InvalidLength)?);
+
+        Ok(())
+    }
+
+    /// adds the pin of the public key in `cert`
+    pub fn add_certificate(&mut self, cert: &CertificateRef) -> Result<(), Error> {
+        self.add(spki_pin(cert)?);
+
+        Ok(())
+    }
+
+    /// whether the public key of `cert` is pinned
+    pub fn matches(&self, cert: &CertificateRef) -> Result<bool, Error> {
+        Ok(self.pins.contains(&spki_pin(cert)?))
+    }
+
+    /// whether any certificate in `chain` has a pinned public key
+    pub fn any_match(&self, chain: &[CertificateRef]) -> Result<bool, Error> {
+        for cert in chain {
+            if self.matches(cert)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+}
+
+/// certificates that have the same public key
+#[derive(Debug, Clone)]
+pub struct KeyGroup<'c, 'a> {
+    /// the [`spki_pin`] of the key
+    pub pin: [u8; 32],
+    /// the certificates with the key, in the order of the pool
+    pub certificates: Vec<&'c CertificateRef<'a>>,
+}
+
+impl KeyGroup<'_, '_> {
+    /// whether certificates with different subjects share the key. a certificate renewed with
+    /// the same key doesn't count.
+    pub fn is_reused(&self) -> bool {
+        let subjects = self
+            .certificates
+            .iter()
+            .map(|cert| cert.tbs_cert().subject());
+        subjects
+            .clone()
+            .any(|subject| subject != self.certificates[0].tbs_cert().subject())
+    }
+}
+
+/// groups the certificates in `pool` by their public key, the groups are in the order their
+/// keys first appear in the pool
+pub fn group_by_key<'c, 'a>(
+    pool: &'c [CertificateRef<'a>],
+) -> Result<Vec<KeyGroup<'c, 'a>>, Error> {
+    let mut groups: Vec<KeyGroup> = Vec::new();
+    let mut index: HashMap<[u8; 32], usize> = HashMap::new();
+    for cert in pool {
+        let pin = spki_pin(cert)?;
+        match index.get(&pin) {
+            Some(&i) => groups[i].certificates.push(cert),
+            None => {
+                index.insert(pin, groups.len());
+                groups.push(KeyGroup {
+                    pin,
+                    certificates: vec![cert],
+                });
+            }
+        }
+    }
+
+    Ok(groups)
+}
+
+/// the keys in `pool` that are used for more than one subject, see [`KeyGroup::is_reused`].
+/// after a key compromise these are all the certificates to revoke, and a reused key in
+/// unrelated certificates is a finding on its own.
+pub fn find_key_reuse<'c, 'a>(
+    pool: &'c [CertificateRef<'a>],
+) -> Result<Vec<KeyGroup<'c, 'a>>, Error> {
+    let mut groups = group_by_key(pool)?;
+    groups.retain(KeyGroup::is_reused);
+
+    Ok(groups)
+}
+
+/// the SHA-256 hash of the DER encoded SubjectPublicKeyInfo of `cert`
+pub fn spki_pin(cert: &CertificateRef) -> Result<[u8; 32], Error> {
+    let spki = cert.tbs_cert().subject_public_key_info().raw_data();
+    let hash = default_provider().digest(&oids::SHA256, spki)?;
+
+    Ok(hash.try_into().expect("SHA-256 hashes are 32 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cert(data: &'static [u8]) -> CertificateRef<'static> {
+        CertificateRef::from_slice(data).unwrap()
+    }
+
+    #[test]
+    fn test_pin_set() {
+        let leaf = cert(include_bytes!("../../certs/ocsp/leaf.der"));
+        let ca = cert(include_bytes!("../../certs/ocsp/ca.der"));
+
+        let mut pins = PinSet::default();
+        pins.add_certificate(&ca).unwrap();
+        assert!(pins.matches(&ca).unwrap());
+        assert!(!pins.matches(&leaf).unwrap());
+        assert!(pins.any_match(&[leaf, ca]).unwrap());
+
+        let leaf = cert(include_bytes!("../../certs/ocsp/leaf.der"));
+        assert!(!pins.any_match(&[leaf]).unwrap());
+    }
+
+    #[test]
+    fn test_add_base64() {
+        let ca = cert(include_bytes!("../../certs/ocsp/ca.der"));
+        // openssl x509 -pubkey -noout | openssl pkey -pubin -outform der | openssl dgst -sha256
+        let pin = "pmMkOiGtXptNV0s9zCdlBQ2Is5Ve4Qh/9Rs/QKTcX40=";
+
+        let mut pins = PinSet::default();
+        pins.add_base64(&format!("sha256/{}", pin)).unwrap();
+        pins.add_base64(pin).unwrap();
+        assert_eq!(pins.len(), 1);
+        assert!(pins.matches(&ca).unwrap());
+
+        assert!(matches!(
+            pins.add_base64("not base64!"),
+            Err(ParseError::StringEncoding)
+        ));
+        assert!(matches!(
+            pins.add_base64("AAAA"),
+            Err(ParseError::InvalidLength)
+        ));
+    }
+
+    #[test]
+    fn test_find_key_reuse() {
+        // the leaf, critical-ext and pss share a key, the two responders are the same subject
+        let pool = [
+            cert(include_bytes!("../../certs/ocsp/leaf.der")),
+            cert(include_bytes!("../../certs/ocsp/ca.der")),
+            cert(include_bytes!("../../certs/ocsp/responder.der")),
+            cert(include_bytes!("../../certs/ocsp/critical-ext.der")),
+            cert(include_bytes!("../../certs/ocsp/responder-nocheck.der")),
+            cert(include_bytes!("../../certs/identity/pss.der")),
+        ];
+
+        let groups = group_by_key(&pool).unwrap();
+        let sizes: Vec<_> = groups.iter().map(|g| g.certificates.len()).collect();
+        assert_eq!(sizes, vec![3, 1, 2]);
+        assert!(!groups[2].is_reused());
+
+        let reused = find_key_reuse(&pool).unwrap();
+        assert_eq!(reused.len(), 1);
+        assert_eq!(reused[0].pin, spki_pin(&pool[0]).unwrap());
+        let serials: Vec<_> = reused[0]
+            .certificates
+            .iter()
+            .map(|cert| cert.tbs_cert().serial_number().as_bytes())
+            .collect();
+        assert_eq!(serials[..2], [[0x10, 0x00], [0x10, 0x01]]);
+        assert!(std::ptr::eq(reused[0].certificates[2], &pool[5]));
+
+        assert!(find_key_reuse(&pool[1..3]).unwrap().is_empty());
+        assert!(find_key_reuse(&[]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,477 @@
+//! PKCS#12 key stores (RFC 7292), the .p12/.pfx files browsers and Windows export identities
+//! as.
+//!
+//! only the password integrity mode is supported, the MAC is always checked. encrypted bags
+//! are decrypted with the same password: PBES2 with AES-CBC, what OpenSSL 3 writes by default,
+//! and the legacy 3DES and RC2 schemes of `openssl pkcs12 -legacy` and older tools.
+
+use crate::identity::{Identity, IdentityError, PrivateKeyDer};
+use crate::pbe::{cbc_decrypt, pbes2_decrypt, PbeError};
+use crate::{digest, Error};
+use des::TdesEde3;
+use rc2::Rc2;
+use x509_core::{
+    oids,
+    parse::{
+        certificate::{expect_empty, parse_algorithm_identifier, AlgorithmidentifierRef},
+        der::{
+            expect_integer, expect_object_identifier, expect_octet_string, expect_sequence,
+            expect_set, try_get_explicit, try_get_implicit, AnyRef, ExplicitTag,
+            ObjectIdentifierRef,
+        },
+        error::ParseError,
+        pkcs8::{EncryptedPrivateKeyInfoRef, Pbes2ParamsRef},
+    },
+};
+
+#[derive(Debug)]
+pub enum Pkcs12Error {
+    Parse(ParseError),
+    Crypto(Error),
+    Identity(IdentityError),
+    /// the file is not protected by a password MAC, e.g. it uses public key integrity
+    UnsupportedIntegrity,
+    /// the MAC doesn't match, usually the password is wrong
+    MacMismatch,
+    /// a bag is encrypted with the algorithm that has this OID
+    UnsupportedEncryption(String),
+    /// the padding of a decrypted bag is wrong, it was encrypted with another password than
+    /// the MAC was computed with
+    DecryptionFailed,
+}
+
+impl From<ParseError> for Pkcs12Error {
+    fn from(e: ParseError) -> Self {
+        Pkcs12Error::Parse(e)
+    }
+}
+
+impl From<Error> for Pkcs12Error {
+    fn from(e: Error) -> Self {
+        Pkcs12Error::Crypto(e)
+    }
+}
+
+impl From<PbeError> for Pkcs12Error {
+    fn from(e: PbeError) -> Self {
+        match e {
+            PbeError::Parse(e) => Pkcs12Error::Parse(e),
+            PbeError::Crypto(e) => Pkcs12Error::Crypto(e),
+            PbeError::Unsupported(oid) => Pkcs12Error::UnsupportedEncryption(oid),
+            PbeError::BadPadding => Pkcs12Error::DecryptionFailed,
+        }
+    }
+}
+
+impl From<IdentityError> for Pkcs12Error {
+    fn from(e: IdentityError) -> Self {
+        Pkcs12Error::Identity(e)
+    }
+}
+
+/// decodes a DER encoded PFX protected with `password` into the private key and the chain.
+/// the certificate whose localKeyId matches the one of the key becomes the leaf, the other
+/// certificates follow in the order they are stored in.
+pub fn decode(data: &[u8], password: &str) -> Result<Identity, Pkcs12Error> {
+    let (rest, pfx) = expect_sequence(data)?;
+    expect_empty(rest)?;
+
+    let (pfx, version) = expect_integer(pfx)?;
+    if version.to_i64()? != 3 {
+        return Err(ParseError::InvalidVersion.into());
+    }
+    let (pfx, auth_safe) = expect_sequence(pfx)?;
+    let (auth_safe, content_type) = expect_object_identifier(auth_safe)?;
+    if content_type != oids::PKCS7_DATA || pfx.is_empty() {
+        return Err(Pkcs12Error::UnsupportedIntegrity);
+    }
+    let auth_safe = data_content(auth_safe)?;
+    let (pfx, mac_data) = expect_sequence(pfx)?;
+    expect_empty(pfx)?;
+    verify_mac(mac_data, password, auth_safe)?;
+
+    let mut bags = Bags::default();
+    let (rest, mut contents) = expect_sequence(auth_safe)?;
+    expect_empty(rest)?;
+    while !contents.is_empty() {
+        let (rest, content_info) = expect_sequence(contents)?;
+        contents = rest;
+
+        let (content, content_type) = expect_object_identifier(content_info)?;
+        match content_type {
+            oids::PKCS7_DATA => bags.add_safe_contents(data_content(content)?, password)?,
+            oids::PKCS7_ENCRYPTED_DATA => {
+                let (_, encrypted_data) = try_get_explicit(content, ExplicitTag::try_new(0)?)?;
+                let (_, encrypted_data) = expect_sequence(encrypted_data)?;
+                let (encrypted_data, _version) = expect_integer(encrypted_data)?;
+                let (_, encrypted_content_info) = expect_sequence(encrypted_data)?;
+                let (encrypted_content_info, _) = expect_object_identifier(encrypted_content_info)?;
+                let (encrypted_content_info, algorithm) =
+                    parse_algorithm_identifier(encrypted_content_info)?;
+                // encryptedContent [0] IMPLICIT OCTET STRING, optional in PKCS#7 but not here
+                let (rest, encrypted_content) = try_get_implicit(encrypted_content_info, 0)?;
+                expect_empty(rest)?;
+                let safe_contents = decrypt(&algorithm, password, encrypted_content)?;
+                bags.add_safe_contents(&safe_contents, password)?;
+            }
+            _ => return Err(ParseError::MalformedData.into()),
+        }
+    }
+
+    bags.into_identity()
+}
+
+/// the content of a ContentInfo of type data or of a CertBag, `data` starts after the type
+fn data_content(data: &[u8]) -> Result<&[u8], ParseError> {
+    let (rest, content) = try_get_explicit(data, ExplicitTag::try_new(0)?)?;
+    expect_empty(rest)?;
+    let (rest, content) = expect_octet_string(content)?;
+    expect_empty(rest)?;
+
+    Ok(content.as_bytes())
+}
+
+/// decrypts an encrypted bag or the content of an EncryptedData. PBES2 takes the password as
+/// UTF-8 like OpenSSL does, the PKCS#12 schemes derive key and IV from it with SHA-1.
+fn decrypt(
+    algorithm: &AlgorithmidentifierRef,
+    password: &str,
+    data: &[u8],
+) -> Result<Vec<u8>, Pkcs12Error> {
+    if let Some(params) = Pbes2ParamsRef::from_algorithm(algorithm)? {
+        return Ok(pbes2_decrypt(&params, password.as_bytes(), data)?);
+    }
+
+    let key_length = match *algorithm.algorithm_identifier() {
+        oids::PBE_WITH_SHA_AND_3KEY_TRIPLE_DES_CBC => 24,
+        oids::PBE_WITH_SHA_AND_128BIT_RC2_CBC => 16,
+        oids::PBE_WITH_SHA_AND_40BIT_RC2_CBC => 5,
+        ref oid => return Err(Pkcs12Error::UnsupportedEncryption(oid.to_string())),
+    };
+    // pkcs-12PbeParams
+    let params = match algorithm.parameters() {
+        Some(AnyRef::Sequence(params)) => params,
+        _ => return Err(ParseError::MalformedData.into()),
+    };
+    let (params, salt) = expect_octet_string(params)?;
+    let (params, iterations) = expect_integer(params)?;
+    expect_empty(params)?;
+    let iterations = iterations.to_i64()?;
+    if iterations < 1 {
+        return Err(ParseError::MalformedData.into());
+    }
+
+    let (salt, iterations) = (salt.as_bytes(), iterations as u64);
+    let key = derive_key(&oids::SHA1, 1, password, salt, iterations, key_length)?;
+    let iv = derive_key(&oids::SHA1, 2, password, salt, iterations, 8)?;
+    let plaintext = match key_length {
+        24 => cbc_decrypt::<TdesEde3>(&key, &iv, data)?,
+        _ => cbc_decrypt::<Rc2>(&key, &iv, data)?,
+    };
+
+    Ok(plaintext)
+}
+
+#[derive(Default)]
+struct Bags {
+    keys: Vec<(PrivateKeyDer, Option<Vec<u8>>)>,
+    certs: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
+impl Bags {
+    fn add_safe_contents(&mut self, data: &[u8], password: &str) -> Result<(), Pkcs12Error> {
+        let (rest, mut bags) = expect_sequence(data)?;
+        expect_empty(rest)?;
+        while !bags.is_empty() {
+            let (rest, bag) = expect_sequence(bags)?;
+            bags = rest;
+
+            let (bag, bag_id) = expect_object_identifier(bag)?;
+            let (attributes, value) = try_get_explicit(bag, ExplicitTag::try_new(0)?)?;
+            let local_key_id = local_key_id(attributes)?;
+            match bag_id {
+                oids::PKCS12_KEY_BAG => {
+                    // the value is a PrivateKeyInfo, keep its whole encoding
+                    let (rest, _) = expect_sequence(value)?;
+                    expect_empty(rest)?;
+                    self.keys
+                        .push((PrivateKeyDer::Pkcs8(value.to_vec()), local_key_id));
+                }
+                oids::PKCS12_SHROUDED_KEY_BAG => {
+                    let info = EncryptedPrivateKeyInfoRef::from_slice(value)?;
+                    let key = decrypt(info.algorithm(), password, info.encrypted_data())?;
+                    let (rest, _) = expect_sequence(&key)?;
+                    expect_empty(rest)?;
+                    self.keys.push((PrivateKeyDer::Pkcs8(key), local_key_id));
+                }
+                oids::PKCS12_CERT_BAG => {
+                    let (rest, cert_bag) = expect_sequence(value)?;
+                    expect_empty(rest)?;
+                    let (cert_bag, cert_id) = expect_object_identifier(cert_bag)?;
+                    // SDSI certificates are skipped
+                    if cert_id == oids::PKCS9_X509_CERTIFICATE {
+                        let cert = data_content(cert_bag)?;
+                        self.certs.push((cert.to_vec(), local_key_id));
+                    }
+                }
+                // CRLs, secrets and nested safe contents don't belong to an identity
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn into_identity(mut self) -> Result<Identity, Pkcs12Error> {
+        if self.keys.len() != 1 {
+            return Err(IdentityError::NoSinglePrivateKey.into());
+        }
+        let (key, key_id) = self.keys.remove(0);
+
+        let leaf = match key_id {
+            Some(key_id) => self
+                .certs
+                .iter()
+                .position(|(_, id)| id.as_ref() == Some(&key_id))
+                .unwrap_or(0),
+            None => 0,
+        };
+        let mut chain: Vec<Vec<u8>> = self.certs.into_iter().map(|(cert, _)| cert).collect();
+        if leaf < chain.len() {
+            let leaf = chain.remove(leaf);
+            chain.insert(0, leaf);
+        }
+
+        Ok(Identity::new(chain, key)?)
+    }
+}
+
+/// the localKeyId attribute from the optional bagAttributes
+fn local_key_id(attributes: &[u8]) -> Result<Option<Vec<u8>>, ParseError> {
+    if attributes.is_empty() {
+        return Ok(None);
+    }
+    let (rest, mut attributes) = expect_set(attributes)?;
+    expect_empty(rest)?;
+    while !attributes.is_empty() {
+        let (rest, attribute) = expect_sequence(attributes)?;
+        attributes = rest;
+
+        let (attribute, attribute_type) = expect_object_identifier(attribute)?;
+        if attribute_type == oids::PKCS9_LOCAL_KEY_ID {
+            let (_, values) = expect_set(attribute)?;
+            let (_, key_id) = expect_octet_string(values)?;
+            return Ok(Some(key_id.as_bytes().to_vec()));
+        }
+    }
+
+    Ok(None)
+}
+
+fn verify_mac(mac_data: &[u8], password: &str, auth_safe: &[u8]) -> Result<(), Pkcs12Error> {
+    let (mac_data, digest_info) = expect_sequence(mac_data)?;
+    let (digest_info, algorithm) = parse_algorithm_identifier(digest_info)?;
+    let (digest_info, expected) = expect_octet_string(digest_info)?;
+    expect_empty(digest_info)?;
+    let (mac_data, salt) = expect_octet_string(mac_data)?;
+    // iterations INTEGER DEFAULT 1
+    let iterations = if mac_data.is_empty() {
+        1
+    } else {
+        let (rest, iterations) = expect_integer(mac_data)?;
+        expect_empty(rest)?;
+        iterations.to_i64()?
+    };
+    if iterations < 1 {
+        return Err(ParseError::MalformedData.into());
+    }
+
+    let hash = algorithm.algorithm_identifier();
+    let key = derive_key(
+        hash,
+        3,
+        password,
+        salt.as_bytes(),
+        iterations as u64,
+        output_size(hash)?,
+    )?;
+    let mac = hmac(hash, &key, auth_safe)?;
+    if !constant_time_eq(&mac, expected.as_bytes()) {
+        return Err(Pkcs12Error::MacMismatch);
+    }
+
+    Ok(())
+}
+
+/// the input block size of `hash`, used by both the key derivation and HMAC
+fn block_size(hash: &ObjectIdentifierRef) -> Result<usize, Error> {
+    match *hash {
+        oids::SHA1 | oids::SHA256 => Ok(64),
+        oids::SHA384 | oids::SHA512 => Ok(128),
+        ref oid => Err(Error::UnsupportedAlgorithm(oid.to_string())),
+    }
+}
+
+/// the output size of `hash`, the length of a MAC key
+fn output_size(hash: &ObjectIdentifierRef) -> Result<usize, Error> {
+    match *hash {
+        oids::SHA1 => Ok(20),
+        oids::SHA256 => Ok(32),
+        oids::SHA384 => Ok(48),
+        oids::SHA512 => Ok(64),
+        ref oid => Err(Error::UnsupportedAlgorithm(oid.to_string())),
+    }
+}
+
+/// the key derivation of RFC 7292 appendix B.2, `id` is 1 for keys, 2 for IVs and 3 for MAC
+/// keys
+fn derive_key(
+    hash: &ObjectIdentifierRef,
+    id: u8,
+    password: &str,
+    salt: &[u8],
+    iterations: u64,
+    length: usize,
+) -> Result<Vec<u8>, Error> {
+    let v = block_size(hash)?;
+    // the password is a BMPString including the trailing NUL character
+    let password: Vec<u8> = password
+        .encode_utf16()
+        .chain(Some(0))
+        .flat_map(|c| c.to_be_bytes())
+        .collect();
+    // both are repeated to fill whole blocks
+    let repeat = |data: &[u8]| -> Vec<u8> {
+        let len = data.len().div_ceil(v) * v;
+        data.iter().cycle().take(len).copied().collect()
+    };
+    let mut i = repeat(salt);
+    i.extend(repeat(&password));
+
+    let mut key = Vec::with_capacity(length);
+    loop {
+        let mut input = vec![id; v];
+        input.extend_from_slice(&i);
+        let mut a = digest(hash, &input)?;
+        for _ in 1..iterations {
+            a = digest(hash, &a)?;
+        }
+        key.extend_from_slice(&a);
+        if key.len() >= length {
+            break;
+        }
+
+        // every v byte block of I becomes I_j + B + 1, with B the hash repeated to v bytes
+        let b: Vec<u8> = a.iter().cycle().take(v).copied().collect();
+        for block in i.chunks_mut(v) {
+            let mut carry = 1;
+            for (x, y) in block.iter_mut().zip(&b).rev() {
+                let sum = u16::from(*x) + u16::from(*y) + carry;
+                *x = sum as u8;
+                carry = sum >> 8;
+            }
+        }
+    }
+    key.truncate(length);
+
+    Ok(key)
+}
+
+fn hmac(hash: &ObjectIdentifierRef, key: &[u8], data: &[u8]) -> Result<Vec<u8>, Error> {
+    let v = block_size(hash)?;
+    let mut key = if key.len() > v {
+        digest(hash, key)?
+    } else {
+        key.to_vec()
+    };
+    key.resize(v, 0);
+
+    let mut inner: Vec<u8> = key.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(data);
+    let mut outer: Vec<u8> = key.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend(digest(hash, &inner)?);
+
+    digest(hash, &outer)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let data = include_bytes!("../../certs/pkcs12/leaf-plain.p12");
+        let identity = decode(data, "x509-rs").unwrap();
+
+        assert_eq!(
+            identity.chain_der(),
+            &[
+                include_bytes!("../../certs/ocsp/leaf.der").to_vec(),
+                include_bytes!("../../certs/ocsp/ca.der").to_vec(),
+            ]
+        );
+        let key_pem = include_bytes!("../../certs/identity/leaf.key");
+        let expected = Identity::from_pem(&identity.to_pem().0.into_bytes(), key_pem).unwrap();
+        assert_eq!(identity.private_key(), expected.private_key());
+    }
+
+    #[test]
+    fn test_wrong_password() {
+        let data = include_bytes!("../../certs/pkcs12/leaf-plain.p12");
+        assert!(matches!(
+            decode(data, "wrong"),
+            Err(Pkcs12Error::MacMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_encrypted_bags() {
+        let plain = decode(
+            include_bytes!("../../certs/pkcs12/leaf-plain.p12"),
+            "x509-rs",
+        )
+        .unwrap();
+
+        // openssl 3 defaults, PBES2 with AES-256-CBC for the certificates and the key
+        let aes = decode(include_bytes!("../../certs/pkcs12/leaf-aes.p12"), "x509-rs").unwrap();
+        // openssl pkcs12 -export -legacy, 40 bit RC2 for the certificates and 3DES for the key
+        let legacy = decode(
+            include_bytes!("../../certs/pkcs12/leaf-legacy.p12"),
+            "x509-rs",
+        )
+        .unwrap();
+
+        for identity in [aes, legacy] {
+            assert_eq!(identity.chain_der(), plain.chain_der());
+            assert_eq!(identity.private_key(), plain.private_key());
+        }
+        assert!(matches!(
+            decode(
+                include_bytes!("../../certs/pkcs12/leaf-legacy.p12"),
+                "wrong"
+            ),
+            Err(Pkcs12Error::MacMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_derive_key() {
+        // the vectors of the Bouncy Castle PKCS#12 tests, the 3DES key takes two SHA-1 blocks
+        let salt = [0x0a, 0x58, 0xcf, 0x64, 0x53, 0x0d, 0x82, 0x3f];
+        assert_eq!(
+            derive_key(&oids::SHA1, 1, "smeg", &salt, 1, 24).unwrap(),
+            [
+                0x8a, 0xaa, 0xe6, 0x29, 0x7b, 0x6c, 0xb0, 0x46, 0x42, 0xab, 0x5b, 0x07, 0x78, 0x51,
+                0x28, 0x4e, 0xb7, 0x12, 0x8f, 0x1a, 0x2a, 0x7f, 0xbc, 0xa3
+            ]
+        );
+        assert_eq!(
+            derive_key(&oids::SHA1, 2, "smeg", &salt, 1, 8).unwrap(),
+            [0x79, 0x99, 0x3d, 0xfe, 0x04, 0x8d, 0x3b, 0x76]
+        );
+    }
+}
@@ -0,0 +1,476 @@
+//! Many certificates in one blob, back to back in DER, indexed by fingerprint, subject and
+//! subject key identifier. the index can be saved next to the blob, so opening a pool of a few
+//! hundred MB neither parses nor hashes a single certificate. with the `mmap` feature the blob
+//! is memory-mapped instead of read.
+//!
+//! a saved pool is a directory with two files: `certificates.der`, the blob, and `index`:
+//!
+//! ```text
+//! "x509idx1" | blob length: u64 | entry count: u64 | entries
+//! entry: fingerprint: [u8; 32] | subject hash: [u8; 32] | offset: u64 | length: u32
+//!        | key identifier length: u8 | key identifier
+//! ```
+//!
+//! all integers are big endian. a key identifier length of 0 means there is none.
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use x509_core::{
+    oids,
+    parse::{
+        error::ParseError, extensions::SubjectKeyIdentifierRef, parsing::CertificateRef,
+        pem::classify_pem,
+    },
+};
+
+use crate::{provider::default_provider, trust_store::TrustStore, Error};
+
+const BLOB_FILE: &str = "certificates.der";
+const INDEX_FILE: &str = "index";
+/// the start of every index, the digit is the version of the format
+const INDEX_MAGIC: &[u8; 8] = b"x509idx1";
+
+#[derive(Debug)]
+pub enum PoolError {
+    Io(io::Error),
+    Parse(ParseError),
+    Crypto(Error),
+    /// the index is cut off, points outside of the blob or belongs to another blob
+    MalformedIndex,
+}
+
+impl From<io::Error> for PoolError {
+    fn from(e: io::Error) -> Self {
+        PoolError::Io(e)
+    }
+}
+
+impl From<ParseError> for PoolError {
+    fn from(e: ParseError) -> Self {
+        PoolError::Parse(e)
+    }
+}
+
+impl From<Error> for PoolError {
+    fn from(e: Error) -> Self {
+        PoolError::Crypto(e)
+    }
+}
+
+/// what the index knows about a certificate in a [`CertificatePool`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolEntry {
+    /// the SHA-256 hash of the certificate
+    pub fingerprint: [u8; 32],
+    /// the SHA-256 hash of the DER encoded subject
+    pub subject_hash: [u8; 32],
+    /// the subjectKeyIdentifier, if the certificate has one
+    pub key_identifier: Option<Vec<u8>>,
+    /// where the certificate starts in the blob
+    pub offset: usize,
+    pub len: usize,
+}
+
+#[derive(Debug)]
+enum Blob {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl Default for Blob {
+    fn default() -> Self {
+        Blob::Owned(Vec::new())
+    }
+}
+
+impl Blob {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Blob::Owned(blob) => blob,
+            #[cfg(feature = "mmap")]
+            Blob::Mapped(map) => map,
+        }
+    }
+
+    /// a mapped blob is copied into memory before it is changed
+    fn to_mut(&mut self) -> &mut Vec<u8> {
+        #[cfg(feature = "mmap")]
+        if let Blob::Mapped(map) = self {
+            *self = Blob::Owned(map.to_vec());
+        }
+        match self {
+            Blob::Owned(blob) => blob,
+            #[cfg(feature = "mmap")]
+            Blob::Mapped(_) => unreachable!("copied above"),
+        }
+    }
+}
+
+/// certificates kept as one blob with an index. a certificate added twice is kept once.
+///
+/// the pool is a [`TrustStore`], so the issuers of a certificate can be looked up in it like in
+/// a [`RootStore`](crate::trust_store::RootStore).
+#[derive(Debug, Default)]
+pub struct CertificatePool {
+    blob: Blob,
+    entries: Vec<PoolEntry>,
+    by_fingerprint: HashMap<[u8; 32], usize>,
+    by_subject: HashMap<[u8; 32], Vec<usize>>,
+    by_key_identifier: HashMap<Vec<u8>, Vec<usize>>,
+}
+
+impl CertificatePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// adds a DER encoded certificate. returns false if it is in the pool already.
+    pub fn add_der(&mut self, der: &[u8]) -> Result<bool, PoolError> {
+        let cert = CertificateRef::from_slice(der)?;
+        let der = cert.raw_data();
+        let fingerprint = sha256(der)?;
+        if self.by_fingerprint.contains_key(&fingerprint) {
+            return Ok(false);
+        }
+
+        let blob = self.blob.to_mut();
+        let entry = PoolEntry {
+            fingerprint,
+            subject_hash: sha256(cert.tbs_cert().subject().raw_data())?,
+            key_identifier: subject_key_identifier(&cert).map(|ki| ki.to_vec()),
+            offset: blob.len(),
+            len: der.len(),
+        };
+        blob.extend_from_slice(der);
+        self.insert(entry);
+
+        Ok(true)
+    }
+
+    /// adds every certificate in a PEM bundle and returns how many there were, including the
+    /// ones already in the pool. other PEM blocks are ignored.
+    pub fn add_pem_bundle(&mut self, pem: &[u8]) -> Result<usize, PoolError> {
+        let mut count = 0;
+        for item in classify_pem(pem)? {
+            if let Some(cert) = item.certificate() {
+                self.add_der(cert?.raw_data())?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// writes the blob and the index into `dir`, which is created if needed. both files are
+    /// replaced, not overwritten, so a pool memory-mapped from the same directory stays valid.
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<(), PoolError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        replace(&dir.join(BLOB_FILE), self.blob.as_slice())?;
+        replace(&dir.join(INDEX_FILE), &self.encode_index()?)?;
+
+        Ok(())
+    }
+
+    /// opens a pool saved with [`save`](Self::save). the certificates are not checked, only
+    /// that the index fits the blob.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, PoolError> {
+        let dir = dir.as_ref();
+        let blob = read_blob(&dir.join(BLOB_FILE))?;
+        let index = fs::read(dir.join(INDEX_FILE))?;
+
+        let mut pool = Self {
+            blob,
+            ..Self::default()
+        };
+        for entry in decode_index(&index, pool.blob.as_slice().len())? {
+            pool.insert(entry);
+        }
+
+        Ok(pool)
+    }
+
+    /// the certificate with the SHA-256 fingerprint `fingerprint`, DER encoded
+    pub fn get(&self, fingerprint: &[u8; 32]) -> Option<&[u8]> {
+        let index = *self.by_fingerprint.get(fingerprint)?;
+        Some(self.der(&self.entries[index]))
+    }
+
+    /// the DER encoded certificates whose subjectKeyIdentifier is `key_identifier`
+    pub fn find_by_key_identifier(&self, key_identifier: &[u8]) -> Vec<&[u8]> {
+        self.lookup(self.by_key_identifier.get(key_identifier))
+    }
+
+    /// the DER encoded certificates, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.entries.iter().map(move |entry| self.der(entry))
+    }
+
+    /// the index entries, in the order the certificates were added
+    pub fn entries(&self) -> &[PoolEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn insert(&mut self, entry: PoolEntry) {
+        let index = self.entries.len();
+        self.by_fingerprint.insert(entry.fingerprint, index);
+        self.by_subject
+            .entry(entry.subject_hash)
+            .or_default()
+            .push(index);
+        if let Some(key_identifier) = &entry.key_identifier {
+            self.by_key_identifier
+                .entry(key_identifier.clone())
+                .or_default()
+                .push(index);
+        }
+        self.entries.push(entry);
+    }
+
+    fn der(&self, entry: &PoolEntry) -> &[u8] {
+        &self.blob.as_slice()[entry.offset..entry.offset + entry.len]
+    }
+
+    fn lookup(&self, indices: Option<&Vec<usize>>) -> Vec<&[u8]> {
+        indices
+            .into_iter()
+            .flatten()
+            .map(|index| self.der(&self.entries[*index]))
+            .collect()
+    }
+
+    fn encode_index(&self) -> Result<Vec<u8>, PoolError> {
+        let mut index = INDEX_MAGIC.to_vec();
+        index.extend_from_slice(&(self.blob.as_slice().len() as u64).to_be_bytes());
+        index.extend_from_slice(&(self.entries.len() as u64).to_be_bytes());
+        for entry in &self.entries {
+            let key_identifier = entry.key_identifier.as_deref().unwrap_or_default();
+            // key identifiers are hashes, 20 bytes as a rule
+            let key_identifier_len: u8 = key_identifier
+                .len()
+                .try_into()
+                .map_err(|_| ParseError::InvalidLength)?;
+            let len: u32 = entry
+                .len
+                .try_into()
+                .map_err(|_| ParseError::InvalidLength)?;
+
+            index.extend_from_slice(&entry.fingerprint);
+            index.extend_from_slice(&entry.subject_hash);
+            index.extend_from_slice(&(entry.offset as u64).to_be_bytes());
+            index.extend_from_slice(&len.to_be_bytes());
+            index.push(key_identifier_len);
+            index.extend_from_slice(key_identifier);
+        }
+
+        Ok(index)
+    }
+}
+
+impl TrustStore for CertificatePool {
+    fn find_by_subject(&self, subject: &[u8]) -> Vec<&[u8]> {
+        match sha256(subject) {
+            Ok(subject_hash) => self.lookup(self.by_subject.get(&subject_hash)),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+fn decode_index(mut index: &[u8], blob_len: usize) -> Result<Vec<PoolEntry>, PoolError> {
+    if take(&mut index, INDEX_MAGIC.len())? != INDEX_MAGIC || take_u64(&mut index)? != blob_len {
+        return Err(PoolError::MalformedIndex);
+    }
+    let count = take_u64(&mut index)?;
+
+    // every entry takes at least 77 bytes, a bogus count can't make this allocate much
+    let mut entries = Vec::with_capacity(count.min(index.len() / 77));
+    for _ in 0..count {
+        let fingerprint = take(&mut index, 32)?.try_into().expect("32 bytes");
+        let subject_hash = take(&mut index, 32)?.try_into().expect("32 bytes");
+        let offset = take_u64(&mut index)?;
+        let len = u32::from_be_bytes(take(&mut index, 4)?.try_into().expect("4 bytes")) as usize;
+        let key_identifier_len = take(&mut index, 1)?[0] as usize;
+        let key_identifier = match key_identifier_len {
+            0 => None,
+            len => Some(take(&mut index, len)?.to_vec()),
+        };
+        match offset.checked_add(len) {
+            Some(end) if end <= blob_len => {}
+            _ => return Err(PoolError::MalformedIndex),
+        }
+
+        entries.push(PoolEntry {
+            fingerprint,
+            subject_hash,
+            key_identifier,
+            offset,
+            len,
+        });
+    }
+    if !index.is_empty() {
+        return Err(PoolError::MalformedIndex);
+    }
+
+    Ok(entries)
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], PoolError> {
+    if data.len() < len {
+        return Err(PoolError::MalformedIndex);
+    }
+    let (taken, rest) = data.split_at(len);
+    *data = rest;
+
+    Ok(taken)
+}
+
+fn take_u64(data: &mut &[u8]) -> Result<usize, PoolError> {
+    let value = u64::from_be_bytes(take(data, 8)?.try_into().expect("8 bytes"));
+    value.try_into().map_err(|_| PoolError::MalformedIndex)
+}
+
+fn sha256(data: &[u8]) -> Result<[u8; 32], Error> {
+    let hash = default_provider().digest(&oids::SHA256, data)?;
+
+    Ok(hash.try_into().expect("SHA-256 hashes are 32 bytes"))
+}
+
+fn subject_key_identifier<'c>(cert: &'c CertificateRef) -> Option<&'c [u8]> {
+    let ext = cert
+        .tbs_cert()
+        .extensions()?
+        .find(oids::SUBJECT_KEY_IDENTIFIER)
+        .ok()??;
+    SubjectKeyIdentifierRef::from_slice(ext.value().as_bytes())
+        .ok()
+        .map(|ski| ski.key_identifier())
+}
+
+/// writes `data` to a temporary file next to `path` and renames it to `path`
+fn replace(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(not(feature = "mmap"))]
+fn read_blob(path: &Path) -> io::Result<Blob> {
+    Ok(Blob::Owned(fs::read(path)?))
+}
+
+#[cfg(feature = "mmap")]
+fn read_blob(path: &Path) -> io::Result<Blob> {
+    let file = fs::File::open(path)?;
+    // SAFETY: the mapping stays valid as long as the file isn't changed in place, `save`
+    // replaces files instead of writing into them
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+
+    Ok(Blob::Mapped(map))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("x509-rs-pool-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_save_and_open() {
+        let leaf = include_bytes!("../../certs/path/leaf.der");
+        let intermediate = include_bytes!("../../certs/path/intermediate.der");
+        let decoy = include_bytes!("../../certs/path/decoy.der");
+        let ca = include_bytes!("../../certs/ocsp/ca.der");
+
+        let mut pool = CertificatePool::new();
+        for der in [&leaf[..], intermediate, decoy, ca] {
+            assert!(pool.add_der(der).unwrap());
+        }
+        assert!(!pool.add_der(ca).unwrap());
+        assert!(pool.add_der(&ca[1..]).is_err());
+        assert_eq!(pool.len(), 4);
+
+        let dir = temp_dir("save");
+        pool.save(&dir).unwrap();
+        let opened = CertificatePool::open(&dir).unwrap();
+        assert_eq!(opened.entries(), pool.entries());
+        assert_eq!(
+            opened.iter().collect::<Vec<_>>(),
+            pool.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(opened.get(&sha256(ca).unwrap()), Some(&ca[..]));
+        assert_eq!(opened.get(&[0; 32]), None);
+
+        // the decoy copies the subject and the key identifier of the intermediate
+        let intermediate_ref = CertificateRef::from_slice(intermediate).unwrap();
+        let subject = intermediate_ref.tbs_cert().subject().raw_data();
+        assert_eq!(
+            opened.find_by_subject(subject),
+            vec![&intermediate[..], decoy]
+        );
+        let key_identifier = subject_key_identifier(&intermediate_ref).unwrap();
+        assert_eq!(
+            opened.find_by_key_identifier(key_identifier),
+            vec![&intermediate[..], decoy]
+        );
+        assert!(opened.find_by_key_identifier(&[1, 2, 3]).is_empty());
+
+        let leaf = CertificateRef::from_slice(leaf).unwrap();
+        let issuers = opened.anchors_for(&leaf, &[]);
+        assert_eq!(issuers.len(), 2);
+
+        // adding to an opened pool doesn't touch the saved files
+        let mut opened = opened;
+        assert!(opened
+            .add_der(include_bytes!("../../certs/ocsp/responder.der"))
+            .unwrap());
+        assert_eq!(CertificatePool::open(&dir).unwrap().len(), 4);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_malformed_index() {
+        let mut pool = CertificatePool::new();
+        pool.add_der(include_bytes!("../../certs/ocsp/ca.der"))
+            .unwrap();
+        let index = pool.encode_index().unwrap();
+        let blob_len = pool.blob.as_slice().len();
+        assert_eq!(decode_index(&index, blob_len).unwrap(), pool.entries());
+
+        let malformed = |index: &[u8], blob_len| {
+            matches!(
+                decode_index(index, blob_len),
+                Err(PoolError::MalformedIndex)
+            )
+        };
+        assert!(malformed(&index[..index.len() - 1], blob_len));
+        assert!(malformed(&[&index[..], &[0]].concat(), blob_len));
+        assert!(malformed(&index, blob_len + 1));
+        let mut other_version = index.clone();
+        other_version[7] = b'2';
+        assert!(malformed(&other_version, blob_len));
+        // the certificate ends after the blob
+        let mut too_long = index.clone();
+        let len_at = INDEX_MAGIC.len() + 16 + 64 + 8;
+        too_long[len_at + 3] += 1;
+        assert!(malformed(&too_long, blob_len));
+
+        let dir = temp_dir("missing");
+        assert!(matches!(CertificatePool::open(&dir), Err(PoolError::Io(_))));
+    }
+}
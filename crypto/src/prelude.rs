@@ -0,0 +1,7 @@
+//! Re-exports the `x509_core` prelude together with the signing and verification traits.
+
+pub use crate::{
+    private_key::PrivateKey, provider::CryptoProvider, Algorithm, Digest, KeyType, SignCert,
+    SigningError, VerifySignature,
+};
+pub use x509_core::prelude::*;
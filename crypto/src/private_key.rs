@@ -0,0 +1,334 @@
+//! Private keys for signing, loaded from the PKCS#1, SEC1 and PKCS#8 encodings.
+
+use std::convert::TryFrom;
+
+use crate::{identity::PrivateKeyDer, Error, KeyType};
+use x509_core::{
+    generate::der::{encode_tlv, DataType},
+    oids,
+    parse::{
+        certificate::{expect_empty, PublicKeyParametersRef},
+        der::{expect_integer, expect_sequence, ObjectIdentifierRef},
+        error::ParseError,
+        pem::{classify_pem, PemItem},
+        pkcs8::PrivateKeyInfoRef,
+        sec1::EcPrivateKeyRef,
+    },
+};
+
+#[cfg(feature = "encrypted-keys")]
+use crate::pbe::{pbes2_decrypt, PbeError};
+#[cfg(feature = "encrypted-keys")]
+use x509_core::parse::pkcs8::EncryptedPrivateKeyInfoRef;
+
+/// an RSA, EC or Ed25519 private key. whatever format it was loaded from, the key is kept as a
+/// PKCS#8 PrivateKeyInfo, the format both backends load keys from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivateKey {
+    Rsa(Vec<u8>),
+    /// an EC key on a named curve, the ECPrivateKey always includes the public key
+    Ecdsa(Vec<u8>),
+    /// only available as PKCS#8, version 1 without the public key or version 2 with it
+    Ed25519(Vec<u8>),
+}
+
+#[derive(Debug)]
+pub enum PrivateKeyError {
+    Parse(ParseError),
+    /// a PKCS#8 key of an algorithm other than RSA, EC and Ed25519, e.g. X25519
+    UnsupportedAlgorithm(String),
+    /// an EC key that doesn't name its curve or lacks the public key, both are needed to sign
+    IncompleteEcKey,
+    /// the PEM input contains no private key, or more than one
+    NoSinglePrivateKey,
+    /// an encrypted key that doesn't decrypt with the passphrase, the padding or the decrypted
+    /// PrivateKeyInfo is wrong
+    WrongPassphrase,
+    /// an encrypted key whose encryption scheme, cipher or PRF has this OID, only PBES2 with
+    /// PBKDF2 and AES-CBC is supported
+    UnsupportedEncryption(String),
+    /// decrypting a key failed in the crypto backend
+    Crypto(Error),
+}
+
+impl From<ParseError> for PrivateKeyError {
+    fn from(e: ParseError) -> Self {
+        PrivateKeyError::Parse(e)
+    }
+}
+
+#[cfg(feature = "encrypted-keys")]
+impl From<PbeError> for PrivateKeyError {
+    fn from(e: PbeError) -> Self {
+        match e {
+            PbeError::Parse(e) => PrivateKeyError::Parse(e),
+            PbeError::Crypto(e) => PrivateKeyError::Crypto(e),
+            PbeError::Unsupported(oid) => PrivateKeyError::UnsupportedEncryption(oid),
+            PbeError::BadPadding => PrivateKeyError::WrongPassphrase,
+        }
+    }
+}
+
+impl PrivateKey {
+    /// loads an RSAPrivateKey, PEM label `RSA PRIVATE KEY`
+    pub fn from_pkcs1_der(der: &[u8]) -> Result<Self, PrivateKeyError> {
+        let (rest, key) = expect_sequence(der)?;
+        expect_empty(rest)?;
+        // two-prime keys are version 0, multi-prime keys version 1
+        let (_, version) = expect_integer(key)?;
+        if !matches!(version.to_i64()?, 0 | 1) {
+            return Err(ParseError::InvalidVersion.into());
+        }
+
+        Ok(PrivateKey::Rsa(private_key_info(
+            oids::RSA_ENCRYPTION,
+            &[0x05, 0x00],
+            der,
+        )))
+    }
+
+    /// loads an ECPrivateKey, PEM label `EC PRIVATE KEY`
+    pub fn from_sec1_der(der: &[u8]) -> Result<Self, PrivateKeyError> {
+        let key = EcPrivateKeyRef::from_slice(der)?;
+        let named_curve = key.named_curve().ok_or(PrivateKeyError::IncompleteEcKey)?;
+
+        ec_private_key_info(*named_curve, &key)
+    }
+
+    /// loads a PrivateKeyInfo, PEM label `PRIVATE KEY`
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self, PrivateKeyError> {
+        let info = PrivateKeyInfoRef::from_slice(der)?;
+        match *info.algorithm().algorithm_identifier() {
+            oids::RSA_ENCRYPTION => Ok(PrivateKey::Rsa(der.to_vec())),
+            oids::EC_PUBLIC_KEY => {
+                let named_curve = match info.algorithm().public_key_parameters() {
+                    PublicKeyParametersRef::NamedCurve(named_curve) => named_curve,
+                    _ => return Err(PrivateKeyError::IncompleteEcKey),
+                };
+                let key = EcPrivateKeyRef::from_slice(info.private_key())?;
+                if key.named_curve().is_some_and(|curve| curve != &named_curve) {
+                    return Err(ParseError::MalformedData.into());
+                }
+
+                ec_private_key_info(named_curve, &key)
+            }
+            oids::ED25519 => Ok(PrivateKey::Ed25519(der.to_vec())),
+            oid => Err(PrivateKeyError::UnsupportedAlgorithm(oid.to_string())),
+        }
+    }
+
+    /// decrypts an EncryptedPrivateKeyInfo, PEM label `ENCRYPTED PRIVATE KEY`, and loads the
+    /// PrivateKeyInfo inside. only PBES2 is supported, what `openssl pkcs8 -topk8 -v2 aes-256-cbc`
+    /// and OpenSSL 3 by default write.
+    #[cfg(feature = "encrypted-keys")]
+    pub fn from_encrypted_pkcs8_der(der: &[u8], passphrase: &str) -> Result<Self, PrivateKeyError> {
+        let info = EncryptedPrivateKeyInfoRef::from_slice(der)?;
+        let params = info.pbes2_params()?.ok_or_else(|| {
+            PrivateKeyError::UnsupportedEncryption(
+                info.algorithm().algorithm_identifier().to_string(),
+            )
+        })?;
+        let der = pbes2_decrypt(&params, passphrase.as_bytes(), info.encrypted_data())?;
+
+        // with a wrong passphrase the padding is still right once in 256 times
+        Self::from_pkcs8_der(&der).map_err(|e| match e {
+            PrivateKeyError::Parse(_) => PrivateKeyError::WrongPassphrase,
+            e => e,
+        })
+    }
+
+    /// loads the single private key in `pem`, a `PRIVATE KEY`, `RSA PRIVATE KEY` or
+    /// `EC PRIVATE KEY` block. other blocks are ignored.
+    pub fn from_pem(pem: &[u8]) -> Result<Self, PrivateKeyError> {
+        let mut keys: Vec<_> = classify_pem(pem)?
+            .into_iter()
+            .filter(|item| {
+                matches!(
+                    item,
+                    PemItem::Pkcs1PrivateKey(_)
+                        | PemItem::Sec1PrivateKey(_)
+                        | PemItem::Pkcs8PrivateKey(_)
+                )
+            })
+            .collect();
+        if keys.len() != 1 {
+            return Err(PrivateKeyError::NoSinglePrivateKey);
+        }
+
+        match keys.remove(0) {
+            PemItem::Pkcs1PrivateKey(der) => Self::from_pkcs1_der(&der),
+            PemItem::Sec1PrivateKey(der) => Self::from_sec1_der(&der),
+            PemItem::Pkcs8PrivateKey(der) => Self::from_pkcs8_der(&der),
+            _ => unreachable!("only private keys are kept"),
+        }
+    }
+
+    pub fn key_algorithm(&self) -> KeyType {
+        match self {
+            PrivateKey::Rsa(_) => KeyType::Rsa,
+            PrivateKey::Ecdsa(_) => KeyType::Ecdsa,
+            PrivateKey::Ed25519(_) => KeyType::Ed25519,
+        }
+    }
+
+    /// the key as a DER encoded PrivateKeyInfo
+    pub fn pkcs8_der(&self) -> &[u8] {
+        match self {
+            PrivateKey::Rsa(der) | PrivateKey::Ecdsa(der) | PrivateKey::Ed25519(der) => der,
+        }
+    }
+}
+
+impl TryFrom<&PrivateKeyDer> for PrivateKey {
+    type Error = PrivateKeyError;
+
+    fn try_from(key: &PrivateKeyDer) -> Result<Self, Self::Error> {
+        match key {
+            PrivateKeyDer::Pkcs1(der) => Self::from_pkcs1_der(der),
+            PrivateKeyDer::Sec1(der) => Self::from_sec1_der(der),
+            PrivateKeyDer::Pkcs8(der) => Self::from_pkcs8_der(der),
+        }
+    }
+}
+
+/// re-encodes `key` without its curve, which moves to the algorithm parameters of the
+/// PrivateKeyInfo, like `openssl pkcs8 -topk8` does
+fn ec_private_key_info(
+    named_curve: ObjectIdentifierRef,
+    key: &EcPrivateKeyRef,
+) -> Result<PrivateKey, PrivateKeyError> {
+    let public_key = key.public_key().ok_or(PrivateKeyError::IncompleteEcKey)?;
+    let (unused_bits, public_key) = public_key.data();
+    let public_key = [&[unused_bits], public_key].concat();
+
+    let mut ec_private_key = vec![0x02, 0x01, 0x01];
+    ec_private_key.extend_from_slice(&encode_tlv(DataType::OctetString.into(), key.private_key()));
+    ec_private_key.extend_from_slice(&encode_tlv(
+        0xa1,
+        &encode_tlv(DataType::BitString.into(), &public_key),
+    ));
+    let ec_private_key = encode_tlv(DataType::Sequence.constructed(), &ec_private_key);
+
+    let named_curve = encode_tlv(DataType::ObjectIdentifier.into(), named_curve.as_bytes());
+    Ok(PrivateKey::Ecdsa(private_key_info(
+        oids::EC_PUBLIC_KEY,
+        &named_curve,
+        &ec_private_key,
+    )))
+}
+
+/// a version 0 PrivateKeyInfo, `parameters` is the encoded parameters of the algorithm
+fn private_key_info(algorithm: ObjectIdentifierRef, parameters: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut algorithm_identifier =
+        encode_tlv(DataType::ObjectIdentifier.into(), algorithm.as_bytes());
+    algorithm_identifier.extend_from_slice(parameters);
+
+    let mut info = vec![0x02, 0x01, 0x00];
+    info.extend_from_slice(&encode_tlv(
+        DataType::Sequence.constructed(),
+        &algorithm_identifier,
+    ));
+    info.extend_from_slice(&encode_tlv(DataType::OctetString.into(), key));
+
+    encode_tlv(DataType::Sequence.constructed(), &info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sec1_to_pkcs8() {
+        let sec1 =
+            PrivateKey::from_sec1_der(include_bytes!("../../certs/identity/ec-sec1.der")).unwrap();
+        let pkcs8 = PrivateKey::from_pkcs8_der(include_bytes!("../../certs/identity/ec-pkcs8.der"))
+            .unwrap();
+
+        assert_eq!(sec1.key_algorithm(), KeyType::Ecdsa);
+        // byte for byte what openssl pkcs8 -topk8 produced
+        assert_eq!(sec1.pkcs8_der(), pkcs8.pkcs8_der());
+        assert_eq!(
+            pkcs8.pkcs8_der(),
+            &include_bytes!("../../certs/identity/ec-pkcs8.der")[..]
+        );
+    }
+
+    #[test]
+    fn test_rsa_pem() {
+        let key = PrivateKey::from_pem(include_bytes!("../../certs/identity/leaf.key")).unwrap();
+        assert_eq!(key.key_algorithm(), KeyType::Rsa);
+
+        let info = PrivateKeyInfoRef::from_slice(key.pkcs8_der()).unwrap();
+        let pkcs1 = PrivateKey::from_pkcs1_der(info.private_key()).unwrap();
+        assert_eq!(pkcs1, key);
+    }
+
+    #[test]
+    fn test_ed25519() {
+        // openssl genpkey -algorithm ed25519, a version 1 PrivateKeyInfo
+        let der = include_bytes!("../../certs/identity/ed25519.der");
+        let key = PrivateKey::from_pkcs8_der(der).unwrap();
+        assert_eq!(key.key_algorithm(), KeyType::Ed25519);
+        assert_eq!(key.pkcs8_der(), &der[..]);
+    }
+
+    #[test]
+    fn test_unsupported_keys() {
+        assert!(matches!(
+            PrivateKey::from_pem(b""),
+            Err(PrivateKeyError::NoSinglePrivateKey)
+        ));
+
+        // an X25519 PrivateKeyInfo (RFC 8410 section 10.3), a key agreement key
+        let x25519 = [
+            &[
+                0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x6e, 0x04, 0x22,
+                0x04, 0x20,
+            ][..],
+            &[0; 32],
+        ]
+        .concat();
+        match PrivateKey::from_pkcs8_der(&x25519) {
+            Err(PrivateKeyError::UnsupportedAlgorithm(oid)) => assert_eq!(oid, "1.3.101.110"),
+            r => panic!("unexpected result {:?}", r),
+        }
+
+        // an ECPrivateKey without the curve, as found inside PKCS#8
+        let info =
+            PrivateKeyInfoRef::from_slice(include_bytes!("../../certs/identity/ec-pkcs8.der"))
+                .unwrap();
+        assert!(matches!(
+            PrivateKey::from_sec1_der(info.private_key()),
+            Err(PrivateKeyError::IncompleteEcKey)
+        ));
+    }
+
+    #[cfg(feature = "encrypted-keys")]
+    #[test]
+    fn test_encrypted_pkcs8() {
+        // openssl pkcs8 -topk8 -v2 aes-256-cbc -in leaf.key -outform DER -passout pass:x509-rs
+        let der = include_bytes!("../../certs/identity/leaf-encrypted.der");
+        let key = PrivateKey::from_encrypted_pkcs8_der(der, "x509-rs").unwrap();
+        assert_eq!(
+            key,
+            PrivateKey::from_pem(include_bytes!("../../certs/identity/leaf.key")).unwrap()
+        );
+
+        assert!(matches!(
+            PrivateKey::from_encrypted_pkcs8_der(der, "x509-rs!"),
+            Err(PrivateKeyError::WrongPassphrase)
+        ));
+
+        // aes-256-cbc turned into aes-256-ecb
+        let mut ecb = der.to_vec();
+        let cipher = [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2a];
+        let pos = ecb.windows(9).position(|window| window == cipher).unwrap();
+        ecb[pos + 8] = 0x29;
+        match PrivateKey::from_encrypted_pkcs8_der(&ecb, "x509-rs") {
+            Err(PrivateKeyError::UnsupportedEncryption(oid)) => {
+                assert_eq!(oid, "2.16.840.1.101.3.4.1.41")
+            }
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! The cryptographic operations behind signing and verification as a trait, so an application
+//! can pick the implementation at runtime, e.g. one that keeps its keys in an HSM, instead of
+//! only at compile time through the `use-ring`, `use-aws-lc-rs` and `use-rust-crypto` features.
+
+use x509_core::parse::{
+    certificate::{AlgorithmidentifierRef, SubjectPublicKeyInfoRef},
+    der::ObjectIdentifierRef,
+    parsing::CertificateRef,
+};
+
+use crate::{private_key::PrivateKey, Algorithm, Error, Signer};
+
+/// verifies, signs and hashes. the free functions of this crate, like
+/// [`verify_signed_data`](crate::verify_signed_data), are the ones of the backend the crate is
+/// built with, [`default_provider`] returns it as a `CryptoProvider`.
+pub trait CryptoProvider: Send + Sync {
+    /// verifies `signature` over `data` with the public key `public_key`
+    fn verify_signed_data(
+        &self,
+        signature_algorithm: &AlgorithmidentifierRef,
+        public_key: &SubjectPublicKeyInfoRef,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, Error>;
+
+    /// hashes `data` with the hash algorithm identified by `algorithm`
+    fn digest(&self, algorithm: &ObjectIdentifierRef, data: &[u8]) -> Result<Vec<u8>, Error>;
+
+    /// signs `data` with `private_key`
+    fn sign(
+        &self,
+        algorithm: Algorithm,
+        private_key: &PrivateKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Error>;
+
+    /// whether `subject` is signed by the key of `issuer`
+    fn check_signature(
+        &self,
+        subject: &CertificateRef,
+        issuer: &CertificateRef,
+    ) -> Result<bool, Error> {
+        let (_, signature) = subject.signature().data();
+
+        self.verify_signed_data(
+            subject.signature_algorithm(),
+            issuer.tbs_cert().subject_public_key_info(),
+            subject.tbs_cert().raw_data(),
+            signature,
+        )
+    }
+}
+
+/// the backend the crate is built with
+pub fn default_provider() -> &'static dyn CryptoProvider {
+    #[cfg(feature = "use-ring")]
+    return &crate::ring::RingProvider;
+    #[cfg(feature = "use-aws-lc-rs")]
+    return &crate::aws_lc::AwsLcProvider;
+    #[cfg(feature = "use-rust-crypto")]
+    return &crate::rust_crypto::RustCryptoProvider;
+}
+
+/// a [`Signer`] that signs with a private key through a provider, e.g. to sign a certificate
+/// with [`SignCert::sign_with`](crate::SignCert::sign_with)
+pub struct ProviderSigner<'p> {
+    provider: &'p dyn CryptoProvider,
+    algorithm: Algorithm,
+    private_key: PrivateKey,
+}
+
+impl<'p> ProviderSigner<'p> {
+    pub fn new(
+        provider: &'p dyn CryptoProvider,
+        algorithm: Algorithm,
+        private_key: PrivateKey,
+    ) -> Self {
+        Self {
+            provider,
+            algorithm,
+            private_key,
+        }
+    }
+}
+
+impl Signer for ProviderSigner<'_> {
+    type Input = Vec<u8>;
+
+    fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    fn begin(&self, len: usize) -> Self::Input {
+        Vec::with_capacity(len)
+    }
+
+    fn finish(&self, input: Self::Input) -> Result<Vec<u8>, Error> {
+        self.provider
+            .sign(self.algorithm, &self.private_key, &input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn cert(data: &'static [u8]) -> CertificateRef<'static> {
+        CertificateRef::from_slice(data).unwrap()
+    }
+
+    /// counts the signatures it verifies and leaves the rest to the default provider
+    #[derive(Default)]
+    struct CountingProvider(AtomicUsize);
+
+    impl CryptoProvider for CountingProvider {
+        fn verify_signed_data(
+            &self,
+            signature_algorithm: &AlgorithmidentifierRef,
+            public_key: &SubjectPublicKeyInfoRef,
+            data: &[u8],
+            signature: &[u8],
+        ) -> Result<bool, Error> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            default_provider().verify_signed_data(signature_algorithm, public_key, data, signature)
+        }
+
+        fn digest(&self, algorithm: &ObjectIdentifierRef, data: &[u8]) -> Result<Vec<u8>, Error> {
+            default_provider().digest(algorithm, data)
+        }
+
+        fn sign(
+            &self,
+            algorithm: Algorithm,
+            private_key: &PrivateKey,
+            data: &[u8],
+        ) -> Result<Vec<u8>, Error> {
+            default_provider().sign(algorithm, private_key, data)
+        }
+    }
+
+    #[test]
+    fn test_default_provider() {
+        let leaf = cert(include_bytes!("../../certs/ocsp/leaf.der"));
+        let ca = cert(include_bytes!("../../certs/ocsp/ca.der"));
+        let provider = default_provider();
+
+        assert!(provider.check_signature(&leaf, &ca).unwrap());
+        assert!(!provider.check_signature(&ca, &leaf).unwrap());
+        assert_eq!(
+            provider.digest(&x509_core::oids::SHA256, b"abc").unwrap()[..4],
+            [0xba, 0x78, 0x16, 0xbf]
+        );
+    }
+
+    #[test]
+    fn test_custom_provider() {
+        let leaf = cert(include_bytes!("../../certs/ocsp/leaf.der"));
+        let key = PrivateKey::from_pem(include_bytes!("../../certs/identity/leaf.key")).unwrap();
+        let counting = CountingProvider::default();
+        let providers: [&dyn CryptoProvider; 2] = [default_provider(), &counting];
+
+        for provider in providers {
+            let signer = ProviderSigner::new(provider, Algorithm::RSA_SHA256, key.clone());
+            let signature = signer.finish(b"data".to_vec()).unwrap();
+            assert!(provider
+                .verify_signed_data(
+                    leaf.signature_algorithm(),
+                    leaf.tbs_cert().subject_public_key_info(),
+                    b"data",
+                    &signature,
+                )
+                .unwrap());
+        }
+        assert_eq!(counting.0.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_verification_uses_provider() {
+        use crate::{
+            ocsp::validate_stapled_ocsp_with_provider,
+            revocation::{CrlChecker, RevocationStatus},
+            verify::{verify_chain_with_provider, VerifyPolicy},
+        };
+        use x509_core::{common::time::UtcDateTime, parse::crl::CertificateListRef};
+
+        let chain = [
+            cert(include_bytes!("../../certs/ocsp/leaf.der")),
+            cert(include_bytes!("../../certs/ocsp/ca.der")),
+        ];
+        let crls =
+            [CertificateListRef::from_slice(include_bytes!("../../certs/crl/ca.crl")).unwrap()];
+        let counting = CountingProvider::default();
+
+        // both certificates and the CRL
+        let now = UtcDateTime::from_ymd_hms(2026, 10, 20, 0, 0, 0).unwrap();
+        let report = verify_chain_with_provider(
+            &chain,
+            &CrlChecker::new(&crls),
+            now,
+            &VerifyPolicy::default(),
+            &counting,
+        );
+        assert!(!report.is_valid());
+        assert_eq!(counting.0.load(Ordering::Relaxed), 3);
+
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let status = validate_stapled_ocsp_with_provider(
+            &chain[0],
+            &chain[1],
+            include_bytes!("../../certs/ocsp/good-ca.der"),
+            now,
+            &counting,
+        );
+        assert_eq!(status.unwrap(), RevocationStatus::Good);
+        assert_eq!(counting.0.load(Ordering::Relaxed), 4);
+    }
+}
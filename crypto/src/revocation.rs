@@ -0,0 +1,318 @@
+//! The revocation status of a certificate, the same whether it was looked up in a CRL or an OCSP
+//! response, so policy code doesn't have to care where it came from.
+
+use crate::ocsp::OcspError;
+use crate::provider::{default_provider, CryptoProvider};
+use crate::VerifySignature;
+use x509_core::{
+    common::{certificate::CRLReason, time::UtcDateTime},
+    oids,
+    parse::{
+        crl::CertificateListRef,
+        error::ParseError,
+        extensions::{
+            BasicConstraints, CrlDistributionPointsRef, DistributionPointNameRef,
+            IssuingDistributionPointRef,
+        },
+        parsing::CertificateRef,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevocationStatus {
+    Good,
+    Revoked {
+        revocation_time: UtcDateTime,
+        reason: Option<CRLReason>,
+    },
+    /// the source was asked but doesn't know the certificate, e.g. an OCSP responder answering
+    /// unknown
+    Unknown,
+    /// there was nothing to ask, e.g. no current CRL of the issuer
+    NotChecked,
+}
+
+impl RevocationStatus {
+    pub fn is_revoked(&self) -> bool {
+        matches!(self, RevocationStatus::Revoked { .. })
+    }
+}
+
+#[derive(Debug)]
+pub enum RevocationError {
+    /// a CRL that covers the certificate can't be read
+    MalformedCrl(ParseError),
+    /// the OCSP response for the certificate can't be used
+    Ocsp(OcspError),
+}
+
+impl From<ParseError> for RevocationError {
+    fn from(e: ParseError) -> Self {
+        RevocationError::MalformedCrl(e)
+    }
+}
+
+impl From<OcspError> for RevocationError {
+    fn from(e: OcspError) -> Self {
+        RevocationError::Ocsp(e)
+    }
+}
+
+/// a source of revocation information for the path validation, see
+/// [`verify_chain_with_revocation`](crate::verify::verify_chain_with_revocation)
+pub trait RevocationChecker {
+    /// the status of `cert`, issued by `issuer`, at the time `now`. signatures on the revocation
+    /// information are checked with `provider`, the one the chain is verified with. an error
+    /// means the revocation information can't be used, it fails the revocation check.
+    fn check(
+        &self,
+        cert: &CertificateRef,
+        issuer: &CertificateRef,
+        now: UtcDateTime,
+        provider: &dyn CryptoProvider,
+    ) -> Result<RevocationStatus, RevocationError>;
+}
+
+/// checks the serial numbers of certificates against a set of CRLs with [`crl_status`]
+#[derive(Debug, Clone, Copy)]
+pub struct CrlChecker<'c, 'a> {
+    crls: &'c [CertificateListRef<'a>],
+}
+
+impl<'c, 'a> CrlChecker<'c, 'a> {
+    pub fn new(crls: &'c [CertificateListRef<'a>]) -> Self {
+        Self { crls }
+    }
+}
+
+impl RevocationChecker for CrlChecker<'_, '_> {
+    fn check(
+        &self,
+        cert: &CertificateRef,
+        issuer: &CertificateRef,
+        now: UtcDateTime,
+        provider: &dyn CryptoProvider,
+    ) -> Result<RevocationStatus, RevocationError> {
+        Ok(crl_status_with_provider(
+            cert, issuer, self.crls, now, provider,
+        )?)
+    }
+}
+
+/// the status of `cert` at the time `now` according to the newest of `crls` that was issued by
+/// `issuer`, has a valid signature, is current at `now` and covers `cert`. a certificate revoked
+/// after `now` is still good.
+///
+/// a CRL with an issuingDistributionPoint extension covers only the certificates it names: only
+/// end entity or only CA certificates, and only the ones with a cRLDistributionPoints entry with
+/// the same full name if it is for a distribution point. indirect CRLs, CRLs for a subset of the
+/// reasons and CRLs for attribute certificates are not used.
+pub fn crl_status(
+    cert: &CertificateRef,
+    issuer: &CertificateRef,
+    crls: &[CertificateListRef],
+    now: UtcDateTime,
+) -> Result<RevocationStatus, ParseError> {
+    crl_status_with_provider(cert, issuer, crls, now, default_provider())
+}
+
+/// like [`crl_status`], with the signatures of the CRLs checked by `provider`
+pub fn crl_status_with_provider(
+    cert: &CertificateRef,
+    issuer: &CertificateRef,
+    crls: &[CertificateListRef],
+    now: UtcDateTime,
+    provider: &dyn CryptoProvider,
+) -> Result<RevocationStatus, ParseError> {
+    let crl = crls
+        .iter()
+        .filter(|crl| crl.tbs_cert_list().issuer() == cert.tbs_cert().issuer())
+        .filter(|crl| matches!(crl.verify_signature_with(provider, issuer), Ok(true)))
+        .filter(|crl| crl_covers(crl, cert).unwrap_or(false))
+        .filter_map(|crl| Some((current_crl_update(crl, now)?, crl)))
+        .max_by_key(|(this_update, _)| *this_update)
+        .map(|(_, crl)| crl);
+    let crl = match crl {
+        Some(crl) => crl,
+        None => return Ok(RevocationStatus::NotChecked),
+    };
+
+    let entry = match crl
+        .tbs_cert_list()
+        .find_revoked(cert.tbs_cert().serial_number())?
+    {
+        Some(entry) => entry,
+        None => return Ok(RevocationStatus::Good),
+    };
+    let revocation_time = entry
+        .revocation_date()
+        .map_err(|_| ParseError::MalformedData)?;
+    let reason = entry.reason()?;
+
+    if revocation_time > now {
+        Ok(RevocationStatus::Good)
+    } else {
+        Ok(RevocationStatus::Revoked {
+            revocation_time,
+            reason,
+        })
+    }
+}
+
+/// whether the scope of `crl` set by its issuingDistributionPoint extension includes `cert`
+fn crl_covers(crl: &CertificateListRef, cert: &CertificateRef) -> Result<bool, ParseError> {
+    let ext = match crl.tbs_cert_list().crl_extensions() {
+        Some(extensions) => extensions.find(oids::ISSUING_DISTRIBUTION_POINT)?,
+        None => None,
+    };
+    let idp = match ext {
+        Some(ext) => IssuingDistributionPointRef::from_slice(ext.value().as_bytes())?,
+        None => return Ok(true),
+    };
+    if idp.indirect_crl()
+        || idp.only_some_reasons().is_some()
+        || idp.only_contains_attribute_certs()
+    {
+        return Ok(false);
+    }
+
+    let extensions = cert.tbs_cert().extensions();
+    let is_ca = match extensions.map(|e| e.find(oids::BASIC_CONSTRAINTS)) {
+        Some(Ok(Some(ext))) => BasicConstraints::from_slice(ext.value().as_bytes())?.is_ca(),
+        Some(Err(e)) => return Err(e),
+        _ => false,
+    };
+    if (idp.only_contains_user_certs() && is_ca) || (idp.only_contains_ca_certs() && !is_ca) {
+        return Ok(false);
+    }
+
+    let crl_names = match idp.distribution_point() {
+        Some(DistributionPointNameRef::FullName(names)) => names,
+        // a name relative to the issuer would have to be resolved first
+        Some(_) => return Ok(false),
+        None => return Ok(true),
+    };
+    let ext = match extensions {
+        Some(extensions) => extensions.find(oids::CRL_DISTRIBUTION_POINTS)?,
+        None => None,
+    };
+    let points = match ext {
+        Some(ext) => CrlDistributionPointsRef::from_slice(ext.value().as_bytes())?,
+        None => return Ok(false),
+    };
+    for point in points.distribution_points() {
+        if let Some(DistributionPointNameRef::FullName(names)) = point?.distribution_point() {
+            for name in names.iter() {
+                let name = name?;
+                for crl_name in crl_names.iter() {
+                    if crl_name? == name {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// thisUpdate of `crl` if it is valid at `now`
+fn current_crl_update(crl: &CertificateListRef, now: UtcDateTime) -> Option<UtcDateTime> {
+    let tbs = crl.tbs_cert_list();
+    let this_update = tbs.this_update().ok()?;
+    let next_update = tbs.next_update().ok()?;
+
+    if this_update > now || matches!(next_update, Some(next_update) if next_update < now) {
+        return None;
+    }
+
+    Some(this_update)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocsp::validate_stapled_ocsp;
+
+    fn cert(data: &'static [u8]) -> CertificateRef<'static> {
+        CertificateRef::from_slice(data).unwrap()
+    }
+
+    #[test]
+    fn test_crl_status() {
+        let leaf = cert(include_bytes!("../../certs/ocsp/leaf.der"));
+        let issuer = cert(include_bytes!("../../certs/ocsp/ca.der"));
+        let crls = [
+            CertificateListRef::from_slice(include_bytes!("../../certs/crl/ca-old.crl")).unwrap(),
+            CertificateListRef::from_slice(include_bytes!("../../certs/crl/ca.crl")).unwrap(),
+        ];
+
+        let before_newer_crl = UtcDateTime::from_ymd_hms(2026, 10, 16, 14, 20, 0).unwrap();
+        assert_eq!(
+            crl_status(&leaf, &issuer, &crls, before_newer_crl).unwrap(),
+            RevocationStatus::Good
+        );
+        let before_any_crl = UtcDateTime::from_ymd_hms(2026, 10, 16, 14, 18, 30).unwrap();
+        assert_eq!(
+            crl_status(&leaf, &issuer, &crls, before_any_crl).unwrap(),
+            RevocationStatus::NotChecked
+        );
+        // the issuer didn't sign the CRLs of itself as a leaf
+        let now = UtcDateTime::from_ymd_hms(2026, 10, 20, 0, 0, 0).unwrap();
+        assert_eq!(
+            crl_status(&leaf, &leaf, &crls, now).unwrap(),
+            RevocationStatus::NotChecked
+        );
+
+        // the CRL and the OCSP responder agree
+        let status = crl_status(&leaf, &issuer, &crls, now).unwrap();
+        assert!(status.is_revoked());
+        assert_eq!(
+            status,
+            RevocationStatus::Revoked {
+                revocation_time: UtcDateTime::from_ymd_hms(2026, 10, 1, 12, 0, 0).unwrap(),
+                reason: Some(CRLReason::KeyCompromise),
+            }
+        );
+        let ocsp = include_bytes!("../../certs/ocsp/revoked.der");
+        assert_eq!(
+            validate_stapled_ocsp(&leaf, &issuer, ocsp, now).unwrap(),
+            status
+        );
+    }
+    #[test]
+    fn test_crl_scope() {
+        let leaf = cert(include_bytes!("../../certs/ocsp/leaf.der"));
+        let issuer = cert(include_bytes!("../../certs/ocsp/ca.der"));
+        let crl = |data| [CertificateListRef::from_slice(data).unwrap()];
+        let now = UtcDateTime::from_ymd_hms(2026, 10, 20, 0, 0, 0).unwrap();
+
+        // all three list the leaf
+        let user = crl(include_bytes!("../../certs/crl/idp-user.crl"));
+        assert!(crl_status(&leaf, &issuer, &user, now).unwrap().is_revoked());
+        let ca_only = crl(include_bytes!("../../certs/crl/idp-ca-only.crl"));
+        assert_eq!(
+            crl_status(&leaf, &issuer, &ca_only, now).unwrap(),
+            RevocationStatus::NotChecked
+        );
+        // the leaf has no cRLDistributionPoints pointing to this partition
+        let partition = crl(include_bytes!("../../certs/crl/idp-partition.crl"));
+        assert_eq!(
+            CrlChecker::new(&partition)
+                .check(&leaf, &issuer, now, default_provider())
+                .unwrap(),
+            RevocationStatus::NotChecked
+        );
+
+        // a newer CRL out of scope doesn't shadow an older one that covers the leaf
+        let crls = [
+            CertificateListRef::from_slice(include_bytes!("../../certs/crl/ca-old.crl")).unwrap(),
+            CertificateListRef::from_slice(include_bytes!("../../certs/crl/idp-ca-only.crl"))
+                .unwrap(),
+        ];
+        assert_eq!(
+            crl_status(&leaf, &issuer, &crls, now).unwrap(),
+            RevocationStatus::Good
+        );
+    }
+}
@@ -1,91 +1,472 @@
 use crate::{
-    ECDSA_SHA256_OID, RSA_MD5_OID, RSA_SHA1_OID, RSA_SHA256_OID, RSA_SHA384_OID, RSA_SHA512_OID,
+    private_key::PrivateKey, provider::CryptoProvider, Algorithm, Digest as Hash, KeyType, Signer,
+};
+use p256::{ecdsa::Signature, pkcs8::DecodePrivateKey};
+use rsa::{
+    pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey, EncodeRsaPublicKey},
+    rand_core::OsRng,
+    traits::SignatureScheme,
+    Pkcs1v15Sign, Pss, RsaPrivateKey, RsaPublicKey,
 };
-use p256::ecdsa::Signature;
-use rsa::{pkcs1::FromRsaPublicKey, Hash, PublicKey, RsaPublicKey};
 use sha2::Digest;
-use x509_core::parse::{
-    certificate::SubjectPublicKeyInfoRef, der::ObjectIdentifierRef, parsing::CertificateRef,
+use std::num::NonZeroU32;
+use x509_core::{
+    oids,
+    parse::{
+        certificate::{AlgorithmidentifierRef, SignatureParametersRef, SubjectPublicKeyInfoRef},
+        der::ObjectIdentifierRef,
+        parsing::CertificateRef,
+        pkcs8::PrivateKeyInfoRef,
+    },
 };
 
 #[derive(Debug)]
 pub enum Error {
-    UnsupportedAlgorighm(String),
+    UnsupportedAlgorithm(String),
+    UnsupportedPublicKey(String),
+    InvalidPrivateKey,
     Pkcs1(rsa::pkcs1::Error),
     Signature(ecdsa::signature::Error),
 }
 
 pub fn check_signature(subject: &CertificateRef, issuer: &CertificateRef) -> Result<bool, Error> {
-    let sig_algo = subject.signature_algorithm().algorithm_identifier();
-
     let pub_key = issuer.tbs_cert().subject_public_key_info();
     let raw_tbs = subject.tbs_cert().raw_data();
     let (_, signature) = subject.signature().data();
 
-    match sig_algo.as_bytes() {
-        RSA_MD5_OID => validate_rsa(
+    verify_signed_data(subject.signature_algorithm(), pub_key, raw_tbs, signature)
+}
+
+/// verifies `signature` over `raw_tbs` with the public key `pub_key`. this is the algorithm
+/// dispatch shared by everything that is signed by a certificate's key.
+pub fn verify_signed_data(
+    signature_algorithm: &AlgorithmidentifierRef,
+    pub_key: &SubjectPublicKeyInfoRef,
+    raw_tbs: &[u8],
+    signature: &[u8],
+) -> Result<bool, Error> {
+    let sig_algo = signature_algorithm.algorithm_identifier();
+
+    match *sig_algo {
+        oids::MD5_WITH_RSA_ENCRYPTION => validate_rsa(
             pub_key,
-            Hash::MD5,
-            &md5::Md5::digest(raw_tbs).to_vec(),
+            Pkcs1v15Sign::new::<md5::Md5>(),
+            &md5::Md5::digest(raw_tbs),
             signature,
         ),
-        RSA_SHA1_OID => validate_rsa(
+        oids::SHA1_WITH_RSA_ENCRYPTION => validate_rsa(
             pub_key,
-            Hash::SHA1,
-            &sha1::Sha1::digest(raw_tbs).to_vec(),
+            Pkcs1v15Sign::new::<sha1::Sha1>(),
+            &sha1::Sha1::digest(raw_tbs),
             signature,
         ),
-        RSA_SHA256_OID => validate_rsa(
+        oids::SHA256_WITH_RSA_ENCRYPTION => validate_rsa(
             pub_key,
-            Hash::SHA2_256,
-            &sha2::Sha256::digest(raw_tbs).to_vec(),
+            Pkcs1v15Sign::new::<sha2::Sha256>(),
+            &sha2::Sha256::digest(raw_tbs),
             signature,
         ),
-        RSA_SHA384_OID => validate_rsa(
+        oids::SHA384_WITH_RSA_ENCRYPTION => validate_rsa(
             pub_key,
-            Hash::SHA2_384,
-            &sha2::Sha384::digest(raw_tbs).to_vec(),
+            Pkcs1v15Sign::new::<sha2::Sha384>(),
+            &sha2::Sha384::digest(raw_tbs),
             signature,
         ),
-        RSA_SHA512_OID => validate_rsa(
+        oids::SHA512_WITH_RSA_ENCRYPTION => validate_rsa(
             pub_key,
-            Hash::SHA2_512,
-            &sha2::Sha512::digest(raw_tbs).to_vec(),
+            Pkcs1v15Sign::new::<sha2::Sha512>(),
+            &sha2::Sha512::digest(raw_tbs),
             signature,
         ),
-        ECDSA_SHA256_OID => {
+        oids::RSASSA_PSS => {
+            let params = match signature_algorithm.signature_parameters() {
+                Ok(SignatureParametersRef::RsassaPss(params)) => params,
+                _ => return Err(Error::UnsupportedAlgorithm(sig_algo.to_string())),
+            };
+            if params.mask_gen_algorithm() != &oids::MGF1
+                || params.mask_gen_hash_algorithm() != params.hash_algorithm()
+                || params.trailer_field() != 1
+            {
+                return Err(Error::UnsupportedAlgorithm(sig_algo.to_string()));
+            }
+
+            let salt_len = params.salt_length() as usize;
+            let (padding, hashed) = match *params.hash_algorithm() {
+                oids::SHA1 => (
+                    Pss::new_with_salt::<sha1::Sha1>(salt_len),
+                    sha1::Sha1::digest(raw_tbs).to_vec(),
+                ),
+                oids::SHA256 => (
+                    Pss::new_with_salt::<sha2::Sha256>(salt_len),
+                    sha2::Sha256::digest(raw_tbs).to_vec(),
+                ),
+                oids::SHA384 => (
+                    Pss::new_with_salt::<sha2::Sha384>(salt_len),
+                    sha2::Sha384::digest(raw_tbs).to_vec(),
+                ),
+                oids::SHA512 => (
+                    Pss::new_with_salt::<sha2::Sha512>(salt_len),
+                    sha2::Sha512::digest(raw_tbs).to_vec(),
+                ),
+                _ => return Err(Error::UnsupportedAlgorithm(sig_algo.to_string())),
+            };
+            validate_rsa_padding(pub_key, padding, &hashed, signature)
+        }
+        oids::ECDSA_WITH_SHA256 => {
             use p256::ecdsa::signature::Verifier;
 
             let (_padding, key) = pub_key.subject_public_key().data();
-            let sig = Signature::from_der(signature).map_err(|e| Error::Signature(e))?;
+            let sig = Signature::from_der(signature).map_err(Error::Signature)?;
+            let verify_key =
+                p256::ecdsa::VerifyingKey::from_sec1_bytes(key).map_err(Error::Signature)?;
+            Ok(verify_key.verify(raw_tbs, &sig).is_ok())
+        }
+        oids::ED25519 => {
+            use ed25519_dalek::Verifier;
+
+            let (_padding, key) = pub_key.subject_public_key().data();
+            let sig = ed25519_dalek::Signature::from_slice(signature).map_err(Error::Signature)?;
             let verify_key =
-                p256::ecdsa::VerifyingKey::from_sec1_bytes(key).map_err(|e| Error::Signature(e))?;
+                ed25519_dalek::VerifyingKey::try_from(key).map_err(Error::Signature)?;
             Ok(verify_key.verify(raw_tbs, &sig).is_ok())
         }
-        s => Err(Error::UnsupportedAlgorighm(
-            ObjectIdentifierRef::new(s).to_string(),
-        )),
+        oid => Err(Error::UnsupportedAlgorithm(oid.to_string())),
+    }
+}
+
+/// hashes `data` with the hash algorithm identified by `algorithm`
+pub fn digest(algorithm: &ObjectIdentifierRef, data: &[u8]) -> Result<Vec<u8>, Error> {
+    match *algorithm {
+        oids::SHA1 => Ok(sha1::Sha1::digest(data).to_vec()),
+        oids::SHA256 => Ok(sha2::Sha256::digest(data).to_vec()),
+        oids::SHA384 => Ok(sha2::Sha384::digest(data).to_vec()),
+        oids::SHA512 => Ok(sha2::Sha512::digest(data).to_vec()),
+        oid => Err(Error::UnsupportedAlgorithm(oid.to_string())),
+    }
+}
+
+/// fills `out` with a key derived from `password` with PBKDF2 (RFC 8018, 5.2), `prf` is the
+/// HMAC algorithm of the PBKDF2-params, e.g. [`oids::HMAC_WITH_SHA256`]
+pub fn pbkdf2(
+    prf: &ObjectIdentifierRef,
+    salt: &[u8],
+    iterations: NonZeroU32,
+    password: &[u8],
+    out: &mut [u8],
+) -> Result<(), Error> {
+    let iterations = iterations.get();
+    match *prf {
+        oids::HMAC_WITH_SHA1 => pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password, salt, iterations, out),
+        oids::HMAC_WITH_SHA256 => {
+            pbkdf2::pbkdf2_hmac::<sha2::Sha256>(password, salt, iterations, out)
+        }
+        oids::HMAC_WITH_SHA384 => {
+            pbkdf2::pbkdf2_hmac::<sha2::Sha384>(password, salt, iterations, out)
+        }
+        oids::HMAC_WITH_SHA512 => {
+            pbkdf2::pbkdf2_hmac::<sha2::Sha512>(password, salt, iterations, out)
+        }
+        oid => return Err(Error::UnsupportedAlgorithm(oid.to_string())),
+    }
+
+    Ok(())
+}
+
+/// loads an RSA key from a PKCS#8 PrivateKeyInfo or a PKCS#1 RSAPrivateKey. PKCS#8 keys of
+/// other algorithms fail with `UnsupportedPublicKey`.
+pub fn rsa_key_pair(private_key: &[u8]) -> Result<RsaPrivateKey, Error> {
+    match PrivateKeyInfoRef::from_slice(private_key) {
+        Ok(info) => match *info.algorithm().algorithm_identifier() {
+            oids::RSA_ENCRYPTION => {
+                RsaPrivateKey::from_pkcs8_der(private_key).map_err(|_| Error::InvalidPrivateKey)
+            }
+            oid => Err(Error::UnsupportedPublicKey(oid.to_string())),
+        },
+        // an RSAPrivateKey starts with two INTEGERs, so it never parses as PKCS#8
+        Err(_) => RsaPrivateKey::from_pkcs1_der(private_key).map_err(|_| Error::InvalidPrivateKey),
+    }
+}
+
+/// signs `tbs` with an RSA key using PKCS#1 v1.5 or PSS padding, depending on the algorithm
+pub fn sign(tbs: &[u8], key_pair: &RsaPrivateKey, algorithm: Algorithm) -> Result<Vec<u8>, Error> {
+    let hashed = hash(algorithm.digest(), tbs);
+    let signature = if algorithm.oid() == oids::RSASSA_PSS {
+        // the salt is as long as the hash, like the parameters `Algorithm` encodes
+        match algorithm.digest() {
+            Hash::Sha256 => key_pair.sign_with_rng(&mut OsRng, Pss::new::<sha2::Sha256>(), &hashed),
+            Hash::Sha384 => key_pair.sign_with_rng(&mut OsRng, Pss::new::<sha2::Sha384>(), &hashed),
+            Hash::Sha512 => key_pair.sign_with_rng(&mut OsRng, Pss::new::<sha2::Sha512>(), &hashed),
+            _ => return Err(Error::UnsupportedAlgorithm(algorithm.oid().to_string())),
+        }
+    } else {
+        let padding = match algorithm.digest() {
+            Hash::Md5 => Pkcs1v15Sign::new::<md5::Md5>(),
+            Hash::Sha1 => Pkcs1v15Sign::new::<sha1::Sha1>(),
+            Hash::Sha256 => Pkcs1v15Sign::new::<sha2::Sha256>(),
+            Hash::Sha384 => Pkcs1v15Sign::new::<sha2::Sha384>(),
+            Hash::Sha512 => Pkcs1v15Sign::new::<sha2::Sha512>(),
+        };
+        key_pair.sign(padding, &hashed)
+    };
+
+    signature.map_err(|_| Error::InvalidPrivateKey)
+}
+
+fn hash(digest: Hash, data: &[u8]) -> Vec<u8> {
+    match digest {
+        Hash::Md5 => md5::Md5::digest(data).to_vec(),
+        Hash::Sha1 => sha1::Sha1::digest(data).to_vec(),
+        Hash::Sha256 => sha2::Sha256::digest(data).to_vec(),
+        Hash::Sha384 => sha2::Sha384::digest(data).to_vec(),
+        Hash::Sha512 => sha2::Sha512::digest(data).to_vec(),
+    }
+}
+
+/// signs with an RSA key using PKCS#1 v1.5 or PSS padding, depending on the algorithm.
+///
+/// the chunks are collected into a buffer of the announced length, the same as with the ring
+/// backend.
+pub struct RsaSigner {
+    key_pair: RsaPrivateKey,
+    public_key: Vec<u8>,
+    algorithm: Algorithm,
+}
+
+impl RsaSigner {
+    /// fails if `algorithm` is not an RSA algorithm
+    pub fn new(key_pair: RsaPrivateKey, algorithm: Algorithm) -> Result<Self, Error> {
+        if algorithm.key_type() != KeyType::Rsa {
+            return Err(Error::UnsupportedAlgorithm(algorithm.oid().to_string()));
+        }
+        let public_key = key_pair
+            .to_public_key()
+            .to_pkcs1_der()
+            .map_err(|_| Error::InvalidPrivateKey)?
+            .into_vec();
+
+        Ok(Self {
+            key_pair,
+            public_key,
+            algorithm,
+        })
+    }
+
+    /// the public key, encoded as an RSAPublicKey structure
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+}
+
+impl Signer for RsaSigner {
+    type Input = Vec<u8>;
+
+    fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    fn begin(&self, len: usize) -> Self::Input {
+        Vec::with_capacity(len)
+    }
+
+    fn finish(&self, input: Self::Input) -> Result<Vec<u8>, Error> {
+        sign(&input, &self.key_pair, self.algorithm)
+    }
+}
+
+/// signs with an EC key on P-256, the only curve this backend implements. signatures are
+/// deterministic, as described in RFC 6979.
+pub struct EcdsaSigner {
+    key: p256::ecdsa::SigningKey,
+    public_key: Vec<u8>,
+    algorithm: Algorithm,
+}
+
+impl EcdsaSigner {
+    /// `private_key` is a PKCS#8 PrivateKeyInfo. fails with `UnsupportedAlgorithm` if
+    /// `algorithm` is not ECDSA with SHA-256, and with `InvalidPrivateKey` if the key is not on
+    /// P-256.
+    pub fn new(private_key: &[u8], algorithm: Algorithm) -> Result<Self, Error> {
+        if algorithm != Algorithm::ECDSA_SHA256 {
+            return Err(Error::UnsupportedAlgorithm(algorithm.oid().to_string()));
+        }
+        let key = p256::ecdsa::SigningKey::from_pkcs8_der(private_key)
+            .map_err(|_| Error::InvalidPrivateKey)?;
+        let public_key = key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec();
+
+        Ok(Self {
+            key,
+            public_key,
+            algorithm,
+        })
+    }
+
+    /// the public key, an uncompressed EC point
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    /// the curve of the key, always `oids::SECP256R1`
+    pub fn named_curve(&self) -> ObjectIdentifierRef<'static> {
+        oids::SECP256R1
+    }
+}
+
+impl Signer for EcdsaSigner {
+    type Input = Vec<u8>;
+
+    fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    fn begin(&self, len: usize) -> Self::Input {
+        Vec::with_capacity(len)
+    }
+
+    fn finish(&self, input: Self::Input) -> Result<Vec<u8>, Error> {
+        use p256::ecdsa::signature::Signer;
+
+        let signature: Signature = self.key.try_sign(&input).map_err(Error::Signature)?;
+
+        Ok(signature.to_der().as_bytes().to_vec())
+    }
+}
+
+/// signs with an Ed25519 key. Ed25519 signatures are deterministic, the same key and data always
+/// give the same signature.
+pub struct Ed25519Signer {
+    key: ed25519_dalek::SigningKey,
+}
+
+impl Ed25519Signer {
+    /// `private_key` is a PKCS#8 PrivateKeyInfo, with or without the public key. fails with
+    /// `UnsupportedAlgorithm` if `algorithm` is not Ed25519.
+    pub fn new(private_key: &[u8], algorithm: Algorithm) -> Result<Self, Error> {
+        if algorithm != Algorithm::ED25519 {
+            return Err(Error::UnsupportedAlgorithm(algorithm.oid().to_string()));
+        }
+        let key = ed25519_dalek::SigningKey::from_pkcs8_der(private_key)
+            .map_err(|_| Error::InvalidPrivateKey)?;
+
+        Ok(Self { key })
+    }
+
+    /// the public key, 32 bytes
+    pub fn public_key(&self) -> &[u8] {
+        self.key.as_ref().as_bytes()
+    }
+}
+
+impl Signer for Ed25519Signer {
+    type Input = Vec<u8>;
+
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::ED25519
+    }
+
+    fn begin(&self, len: usize) -> Self::Input {
+        Vec::with_capacity(len)
+    }
+
+    fn finish(&self, input: Self::Input) -> Result<Vec<u8>, Error> {
+        use ed25519_dalek::Signer;
+
+        Ok(self.key.sign(&input).to_bytes().to_vec())
+    }
+}
+
+/// the RustCrypto backend as a [`CryptoProvider`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RustCryptoProvider;
+
+impl CryptoProvider for RustCryptoProvider {
+    fn verify_signed_data(
+        &self,
+        signature_algorithm: &AlgorithmidentifierRef,
+        public_key: &SubjectPublicKeyInfoRef,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, Error> {
+        verify_signed_data(signature_algorithm, public_key, data, signature)
+    }
+
+    fn digest(&self, algorithm: &ObjectIdentifierRef, data: &[u8]) -> Result<Vec<u8>, Error> {
+        digest(algorithm, data)
+    }
+
+    fn sign(
+        &self,
+        algorithm: Algorithm,
+        private_key: &PrivateKey,
+        data: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        // all signers collect the data into a buffer anyway
+        match private_key {
+            PrivateKey::Rsa(der) => {
+                RsaSigner::new(rsa_key_pair(der)?, algorithm)?.finish(data.to_vec())
+            }
+            PrivateKey::Ecdsa(der) => EcdsaSigner::new(der, algorithm)?.finish(data.to_vec()),
+            PrivateKey::Ed25519(der) => Ed25519Signer::new(der, algorithm)?.finish(data.to_vec()),
+        }
     }
 }
 
 fn validate_rsa(
     pub_key: &SubjectPublicKeyInfoRef,
-    hash_method: Hash,
+    padding: Pkcs1v15Sign,
+    hashed: &[u8],
+    signature: &[u8],
+) -> Result<bool, Error> {
+    validate_rsa_padding(pub_key, padding, hashed, signature)
+}
+
+fn validate_rsa_padding<S: SignatureScheme>(
+    pub_key: &SubjectPublicKeyInfoRef,
+    padding: S,
     hashed: &[u8],
     signature: &[u8],
 ) -> Result<bool, Error> {
     let (padding_bits, key_data) = pub_key.subject_public_key().data();
-    assert!(padding_bits == 0);
+    if padding_bits != 0 {
+        return Err(Error::UnsupportedPublicKey(
+            pub_key
+                .algorithm_identifier()
+                .algorithm_identifier()
+                .to_string(),
+        ));
+    }
 
-    let public_key = RsaPublicKey::from_pkcs1_der(key_data).map_err(|e| Error::Pkcs1(e))?;
+    let public_key = RsaPublicKey::from_pkcs1_der(key_data).map_err(Error::Pkcs1)?;
 
-    if let Ok(_) = public_key.verify(
-        rsa::PaddingScheme::new_pkcs1v15_sign(Some(hash_method)),
-        hashed,
-        signature,
-    ) {
-        Ok(true)
-    } else {
-        Ok(false)
+    Ok(public_key.verify(padding, hashed, signature).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cert(data: &[u8]) -> CertificateRef<'_> {
+        CertificateRef::from_slice(data).unwrap()
+    }
+
+    #[test]
+    fn test_pss_salt_length() {
+        // SHA-256 with a 20 byte salt, which ring can't verify
+        let salt20 = cert(include_bytes!("../../certs/identity/pss-salt20.der"));
+        assert!(check_signature(&salt20, &salt20).unwrap());
+
+        // the outer signatureAlgorithm isn't signed, claiming a 33 byte salt instead of 32 has
+        // to make the signature fail
+        let mut data = include_bytes!("../../certs/identity/pss.der").to_vec();
+        let pss = cert(&data);
+        assert!(check_signature(&pss, &pss).unwrap());
+        let salt = [0xa2, 0x03, 0x02, 0x01, 0x20];
+        let pos = data.windows(5).rposition(|w| w == salt).unwrap();
+        data[pos + 4] = 0x21;
+        let tampered = cert(&data);
+        assert!(!check_signature(&tampered, &tampered).unwrap());
     }
 }
@@ -0,0 +1,258 @@
+//! Verification of the signed certificate timestamps embedded in certificates (RFC 6962, 3.3)
+//! against the logs of a log list.
+
+use x509_core::{
+    common::time::UtcDateTime,
+    oids,
+    parse::{
+        certificate::{parse_algorithm_identifier, SubjectPublicKeyInfoRef},
+        ct::{parse_sct_list, precert_tbs, SignedCertificateTimestampRef},
+        error::ParseError,
+        parsing::CertificateRef,
+    },
+};
+
+use crate::ct_logs::CtLogs;
+use crate::provider::CryptoProvider;
+use crate::Error;
+
+/// the AlgorithmIdentifiers of the two TLS signature algorithms logs sign with (RFC 6962,
+/// 2.1.4): ecdsa-with-SHA256 and sha256WithRSAEncryption
+const ECDSA_WITH_SHA256: &[u8] = &[
+    0x30, 0x0a, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02,
+];
+const SHA256_WITH_RSA_ENCRYPTION: &[u8] = &[
+    0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b, 0x05, 0x00,
+];
+
+/// whether an SCT counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SctStatus {
+    Valid,
+    /// the log is not in the log list
+    UnknownLog,
+    /// the log is in the list but its SCT doesn't count: the log wasn't usable at the time of
+    /// the SCT, the SCT is from the future or the log is a shard for certificates that expire
+    /// at another time
+    NotAccepted,
+    /// neither ECDSA nor RSA with SHA-256
+    UnsupportedAlgorithm,
+    InvalidSignature,
+}
+
+#[derive(Debug)]
+pub enum SctError {
+    /// the SCT list, the certificate or the key of a log can't be read
+    Parse(ParseError),
+    Crypto(Error),
+}
+
+impl From<ParseError> for SctError {
+    fn from(e: ParseError) -> Self {
+        SctError::Parse(e)
+    }
+}
+
+impl From<Error> for SctError {
+    fn from(e: Error) -> Self {
+        SctError::Crypto(e)
+    }
+}
+
+/// checks the SCTs embedded in `cert`, which was issued by `issuer`, against `logs` at the time
+/// `now`. every SCT is returned with its status, a certificate without SCTs has none.
+///
+/// the SCTs are checked as signatures over the precertificate the issuer logged, precertificates
+/// issued by a Precertificate Signing Certificate are not supported.
+pub fn check_embedded_scts<'c>(
+    cert: &'c CertificateRef,
+    issuer: &CertificateRef,
+    logs: &CtLogs,
+    now: UtcDateTime,
+    provider: &dyn CryptoProvider,
+) -> Result<Vec<(SignedCertificateTimestampRef<'c>, SctStatus)>, SctError> {
+    let ext = match cert.tbs_cert().extensions() {
+        Some(extensions) => extensions.find(oids::CT_PRECERT_SCTS)?,
+        None => None,
+    };
+    let scts = match ext {
+        Some(ext) => parse_sct_list(ext.value().as_bytes())?,
+        None => return Ok(Vec::new()),
+    };
+    let not_after = cert
+        .tbs_cert()
+        .validity()
+        .not_after()
+        .map_err(|_| ParseError::MalformedData)?;
+
+    // the PreCert entry: the hash of the issuer key and the TBSCertificate without the SCTs
+    let issuer_key = issuer.tbs_cert().subject_public_key_info().raw_data();
+    let mut entry = provider.digest(&oids::SHA256, issuer_key)?;
+    let tbs = precert_tbs(cert)?;
+    let tbs_len = u32::try_from(tbs.len()).map_err(|_| ParseError::TooLarge)?;
+    entry.extend_from_slice(&tbs_len.to_be_bytes()[1..]);
+    entry.extend(tbs);
+
+    let mut checked = Vec::with_capacity(scts.len());
+    for sct in scts {
+        let status = check_sct(&sct, &entry, not_after, logs, now, provider)?;
+        checked.push((sct, status));
+    }
+
+    Ok(checked)
+}
+
+fn check_sct(
+    sct: &SignedCertificateTimestampRef,
+    entry: &[u8],
+    not_after: UtcDateTime,
+    logs: &CtLogs,
+    now: UtcDateTime,
+    provider: &dyn CryptoProvider,
+) -> Result<SctStatus, SctError> {
+    let log = match logs.find(sct.log_id()) {
+        Some(log) => log,
+        None => return Ok(SctStatus::UnknownLog),
+    };
+    let accepted = match sct.time() {
+        Some(time) => time <= now && log.accepts_sct_at(time),
+        None => false,
+    };
+    let in_shard = match log.temporal_interval {
+        Some((start, end)) => start <= not_after && not_after < end,
+        None => true,
+    };
+    if !accepted || !in_shard {
+        return Ok(SctStatus::NotAccepted);
+    }
+
+    let algorithm = match (sct.hash_algorithm(), sct.signature_algorithm()) {
+        (4, 3) => ECDSA_WITH_SHA256,
+        (4, 1) => SHA256_WITH_RSA_ENCRYPTION,
+        _ => return Ok(SctStatus::UnsupportedAlgorithm),
+    };
+    let (_, algorithm) = parse_algorithm_identifier(algorithm)?;
+    let key = SubjectPublicKeyInfoRef::from_slice(&log.key)?;
+
+    // version v1, signature type certificate_timestamp, entry type precert_entry
+    let mut signed = Vec::with_capacity(entry.len() + 16 + sct.extensions().len());
+    signed.extend_from_slice(&[0, 0]);
+    signed.extend_from_slice(&sct.timestamp().to_be_bytes());
+    signed.extend_from_slice(&[0, 1]);
+    signed.extend_from_slice(entry);
+    signed.extend_from_slice(&(sct.extensions().len() as u16).to_be_bytes());
+    signed.extend_from_slice(sct.extensions());
+
+    if provider.verify_signed_data(&algorithm, &key, &signed, sct.signature())? {
+        Ok(SctStatus::Valid)
+    } else {
+        Ok(SctStatus::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::default_provider;
+
+    fn cert(data: &'static [u8]) -> CertificateRef<'static> {
+        CertificateRef::from_slice(data).unwrap()
+    }
+
+    fn logs() -> CtLogs {
+        CtLogs::from_log_list_json(include_bytes!("../../certs/ct/log_list.json")).unwrap()
+    }
+
+    fn statuses(
+        cert: &CertificateRef,
+        issuer: &CertificateRef,
+        logs: &CtLogs,
+        now: UtcDateTime,
+    ) -> Vec<SctStatus> {
+        check_embedded_scts(cert, issuer, logs, now, default_provider())
+            .unwrap()
+            .into_iter()
+            .map(|(_, status)| status)
+            .collect()
+    }
+
+    #[test]
+    fn test_check_embedded_scts() {
+        let leaf = cert(include_bytes!("../../certs/ct/scts.der"));
+        let ca = cert(include_bytes!("../../certs/ocsp/ca.der"));
+        let now = UtcDateTime::from_ymd_hms(2026, 10, 16, 0, 0, 0).unwrap();
+
+        let scts = check_embedded_scts(&leaf, &ca, &logs(), now, default_provider()).unwrap();
+        assert_eq!(scts.len(), 3);
+        assert!(scts.iter().all(|(_, status)| *status == SctStatus::Valid));
+
+        // signed over another issuer key
+        assert_eq!(
+            statuses(&leaf, &leaf, &logs(), now),
+            [SctStatus::InvalidSignature; 3]
+        );
+        assert_eq!(
+            statuses(&leaf, &ca, &CtLogs::default(), now),
+            [SctStatus::UnknownLog; 3]
+        );
+        // no SCTs at all
+        assert!(statuses(&ca, &ca, &logs(), now).is_empty());
+    }
+
+    #[test]
+    fn test_sct_acceptance() {
+        let leaf = cert(include_bytes!("../../certs/ct/scts.der"));
+        let ca = cert(include_bytes!("../../certs/ocsp/ca.der"));
+
+        // only the SCT of the retired log, from 2026-01-15, was issued by then
+        let now = UtcDateTime::from_ymd_hms(2026, 2, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            statuses(&leaf, &ca, &logs(), now),
+            [
+                SctStatus::NotAccepted,
+                SctStatus::Valid,
+                SctStatus::NotAccepted
+            ]
+        );
+
+        // the certificate expires after the shard of Aurora2026
+        let json = std::str::from_utf8(include_bytes!("../../certs/ct/log_list.json"))
+            .unwrap()
+            .replace("2027-01-01T00:00:00Z", "2026-07-01T00:00:00Z");
+        let logs = CtLogs::from_log_list_json(json.as_bytes()).unwrap();
+        let now = UtcDateTime::from_ymd_hms(2026, 10, 16, 0, 0, 0).unwrap();
+        assert_eq!(
+            statuses(&leaf, &ca, &logs, now),
+            [SctStatus::NotAccepted, SctStatus::Valid, SctStatus::Valid]
+        );
+    }
+
+    #[test]
+    fn test_tampered_sct() {
+        let mut der = include_bytes!("../../certs/ct/scts.der").to_vec();
+        let ca = cert(include_bytes!("../../certs/ocsp/ca.der"));
+        let now = UtcDateTime::from_ymd_hms(2026, 10, 16, 0, 0, 0).unwrap();
+
+        let signature = {
+            let leaf = CertificateRef::from_slice(&der).unwrap();
+            let scts = check_embedded_scts(&leaf, &ca, &logs(), now, default_provider()).unwrap();
+            scts[2].0.signature().to_vec()
+        };
+        let pos = der
+            .windows(signature.len())
+            .position(|window| window == signature)
+            .unwrap();
+        // the last byte of s, the signature stays well formed
+        der[pos + signature.len() - 1] ^= 1;
+
+        let leaf = CertificateRef::from_slice(&der).unwrap();
+        assert_eq!(
+            statuses(&leaf, &ca, &logs(), now),
+            [
+                SctStatus::Valid,
+                SctStatus::Valid,
+                SctStatus::InvalidSignature
+            ]
+        );
+    }
+}
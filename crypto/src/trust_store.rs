@@ -0,0 +1,300 @@
+//! Trust anchors for chain verification, from PEM bundles, certificate directories or, with the
+//! `native-roots` feature, the operating system. the `webpki-roots` feature compiles in the
+//! Mozilla root set instead.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use x509_core::parse::{error::ParseError, parsing::CertificateRef, pem::classify_pem};
+
+/// where the verification looks up trust anchors
+pub trait TrustStore {
+    /// the DER encoded trust anchors whose subject is `subject`, the DER encoding of a Name
+    fn find_by_subject(&self, subject: &[u8]) -> Vec<&[u8]>;
+
+    /// the trust anchors that can be at the top of a chain from `leaf` through
+    /// `intermediates`, to be passed to [`verify_from_pool`](crate::verify::verify_from_pool)
+    fn anchors_for(
+        &self,
+        leaf: &CertificateRef,
+        intermediates: &[CertificateRef],
+    ) -> Vec<CertificateRef<'_>> {
+        let mut anchors: Vec<CertificateRef> = Vec::new();
+        for cert in std::iter::once(leaf).chain(intermediates) {
+            for der in self.find_by_subject(cert.tbs_cert().issuer().raw_data()) {
+                if anchors.iter().any(|a| a.raw_data() == der) {
+                    continue;
+                }
+                if let Ok(anchor) = CertificateRef::from_slice(der) {
+                    anchors.push(anchor);
+                }
+            }
+        }
+
+        anchors
+    }
+}
+
+#[derive(Debug)]
+pub enum TrustStoreError {
+    Io(io::Error),
+    /// the operating system store couldn't be read, with the reasons
+    NativeRoots(Vec<String>),
+    /// some files of a directory hold certificates that couldn't be parsed. the certificates of
+    /// the other files were added.
+    Files(Vec<(PathBuf, ParseError)>),
+}
+
+impl From<io::Error> for TrustStoreError {
+    fn from(e: io::Error) -> Self {
+        TrustStoreError::Io(e)
+    }
+}
+
+/// a [`TrustStore`] held in memory, indexed by subject. adding a certificate twice keeps one
+/// copy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RootStore {
+    by_subject: HashMap<Vec<u8>, Vec<Vec<u8>>>,
+    len: usize,
+}
+
+impl RootStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// adds a DER encoded certificate
+    pub fn add_der(&mut self, der: &[u8]) -> Result<(), ParseError> {
+        let cert = CertificateRef::from_slice(der)?;
+        let anchors = self
+            .by_subject
+            .entry(cert.tbs_cert().subject().raw_data().to_vec())
+            .or_default();
+        if !anchors.iter().any(|a| a == der) {
+            anchors.push(der.to_vec());
+            self.len += 1;
+        }
+
+        Ok(())
+    }
+
+    /// adds every certificate in a PEM bundle like `/etc/ssl/certs/ca-certificates.crt` and
+    /// returns how many there were. other PEM blocks are ignored.
+    pub fn add_pem_bundle(&mut self, pem: &[u8]) -> Result<usize, ParseError> {
+        let mut count = 0;
+        for item in classify_pem(pem)? {
+            if let Some(cert) = item.certificate() {
+                self.add_der(cert?.raw_data())?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// adds the certificates in the files of `dir`, PEM or DER encoded, like the hashed
+    /// directories of OpenSSL. files that neither contain a PEM block nor start like a DER
+    /// SEQUENCE are skipped, subdirectories are not read. returns the number of certificates
+    /// found, or [`TrustStoreError::Files`] with every file that couldn't be parsed once the
+    /// whole directory was read.
+    pub fn add_directory(&mut self, dir: impl AsRef<Path>) -> Result<usize, TrustStoreError> {
+        let mut count = 0;
+        let mut errors = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_file() {
+                continue;
+            }
+            let data = fs::read(&path)?;
+            // PEM files may start with explanatory text, like the bundles of some distributions
+            let result = if data.windows(10).any(|w| w == b"-----BEGIN") {
+                self.add_pem_bundle(&data)
+            } else if data.first() == Some(&0x30) {
+                self.add_der(&data).map(|_| 1)
+            } else {
+                continue;
+            };
+            match result {
+                Ok(n) => count += n,
+                Err(e) => errors.push((path, e)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(TrustStoreError::Files(errors));
+        }
+
+        Ok(count)
+    }
+
+    /// the trust anchors of the operating system. certificates that can't be parsed are
+    /// skipped; it's only an error if no certificate could be loaded at all.
+    #[cfg(feature = "native-roots")]
+    pub fn native_roots() -> Result<Self, TrustStoreError> {
+        let result = rustls_native_certs::load_native_certs();
+
+        let mut store = Self::new();
+        for cert in &result.certs {
+            let _ = store.add_der(cert.as_ref());
+        }
+        if store.is_empty() {
+            let errors = result.errors.iter().map(|e| e.to_string()).collect();
+            return Err(TrustStoreError::NativeRoots(errors));
+        }
+
+        Ok(store)
+    }
+
+    /// the roots Mozilla trusts for TLS servers, as shipped by the webpki-root-certs crate. this
+    /// doesn't touch the operating system, so it works the same everywhere.
+    #[cfg(feature = "webpki-roots")]
+    pub fn webpki_roots() -> Self {
+        let mut store = Self::new();
+        for cert in webpki_root_certs::TLS_SERVER_ROOT_CERTS {
+            let _ = store.add_der(cert.as_ref());
+        }
+
+        store
+    }
+
+    /// whether `cert` itself is one of the trust anchors
+    pub fn contains(&self, cert: &CertificateRef) -> bool {
+        self.find_by_subject(cert.tbs_cert().subject().raw_data())
+            .contains(&cert.raw_data())
+    }
+
+    /// the DER encoded trust anchors
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.by_subject.values().flatten().map(|der| der.as_slice())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl TrustStore for RootStore {
+    fn find_by_subject(&self, subject: &[u8]) -> Vec<&[u8]> {
+        match self.by_subject.get(subject) {
+            Some(anchors) => anchors.iter().map(|der| der.as_slice()).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::{verify_from_pool, VerifyPolicy};
+    use x509_core::common::time::UtcDateTime;
+
+    #[test]
+    fn test_root_store() {
+        let ca = include_bytes!("../../certs/ocsp/ca.der");
+        let mut store = RootStore::new();
+        store.add_der(ca).unwrap();
+        store.add_der(ca).unwrap();
+        assert_eq!(store.len(), 1);
+        assert!(store.add_der(&ca[1..]).is_err());
+
+        let leaf = CertificateRef::from_slice(include_bytes!("../../certs/path/leaf.der")).unwrap();
+        let intermediate = include_bytes!("../../certs/path/intermediate.der");
+        let intermediates = [CertificateRef::from_slice(intermediate).unwrap()];
+        assert!(store.anchors_for(&leaf, &[]).is_empty());
+
+        let anchors = store.anchors_for(&leaf, &intermediates);
+        assert_eq!(anchors.len(), 1);
+        assert!(store.contains(&anchors[0]));
+        assert!(!store.contains(&intermediates[0]));
+
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let built = verify_from_pool(
+            &leaf,
+            &intermediates,
+            &anchors,
+            &[],
+            now,
+            &VerifyPolicy::default(),
+        )
+        .unwrap();
+        assert!(built.report.is_valid(), "{}", built.report);
+    }
+
+    #[test]
+    fn test_pem_bundle_and_directory() {
+        let dir = std::env::temp_dir().join(format!("x509-rs-trust-store-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let pem = |der: &[u8]| {
+            format!(
+                "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+                base64::encode(der)
+            )
+        };
+        let ca = include_bytes!("../../certs/ocsp/ca.der");
+        let intermediate = include_bytes!("../../certs/path/intermediate.der");
+        let bundle = pem(ca) + &pem(intermediate);
+
+        let mut store = RootStore::new();
+        assert_eq!(store.add_pem_bundle(bundle.as_bytes()).unwrap(), 2);
+        assert_eq!(store.len(), 2);
+
+        fs::write(dir.join("bundle.pem"), &bundle).unwrap();
+        fs::write(dir.join("ca.der"), &ca[..]).unwrap();
+        fs::write(dir.join("README"), "not a certificate").unwrap();
+        let mut store = RootStore::new();
+        assert_eq!(store.add_directory(&dir).unwrap(), 3);
+        assert_eq!(store.len(), 2);
+
+        fs::write(
+            dir.join("commented.pem"),
+            format!("# Issuer: CN=x509-rs Intermediate\n{}", pem(intermediate)),
+        )
+        .unwrap();
+        fs::write(dir.join("truncated.der"), &ca[..ca.len() - 1]).unwrap();
+        fs::write(
+            dir.join("broken.pem"),
+            "-----BEGIN CERTIFICATE-----\nAQID\n",
+        )
+        .unwrap();
+        let mut store = RootStore::new();
+        match store.add_directory(&dir) {
+            Err(TrustStoreError::Files(errors)) => {
+                let mut names: Vec<_> = errors
+                    .iter()
+                    .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+                    .collect();
+                names.sort_unstable();
+                assert_eq!(names, ["broken.pem", "truncated.der"]);
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert_eq!(store.len(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(
+            store.add_directory(dir),
+            Err(TrustStoreError::Io(_))
+        ));
+    }
+
+    #[cfg(feature = "webpki-roots")]
+    #[test]
+    fn test_webpki_roots() {
+        let store = RootStore::webpki_roots();
+        assert_eq!(store.len(), webpki_root_certs::TLS_SERVER_ROOT_CERTS.len());
+
+        // ISRG Root X1, the root of Let's Encrypt
+        let isrg = store.iter().any(|der| {
+            let cert = CertificateRef::from_slice(der).unwrap();
+            let subject = cert.tbs_cert().subject().raw_data();
+            subject.windows(12).any(|w| w == b"ISRG Root X1")
+        });
+        assert!(isrg);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use x509_core::{oids, parse::parsing::CertificateRef};
+
+use crate::provider::{default_provider, CryptoProvider};
+use crate::{Error, VerifySignature};
+
+type Fingerprint = Vec<u8>;
+
+/// memoizes signature verification results, keyed by the SHA-256 fingerprints of the subject
+/// and issuer certificate. scanning workloads see the same intermediates over and over, and
+/// hashing them is much cheaper than checking their signatures again.
+///
+/// the cache can be shared between threads. once it holds `capacity` results an arbitrary one
+/// is dropped for every new one.
+#[derive(Debug)]
+pub struct SignatureCache {
+    results: Mutex<HashMap<(Fingerprint, Fingerprint), bool>>,
+    capacity: usize,
+}
+
+impl Default for SignatureCache {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl SignatureCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            results: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// like [`VerifySignature::verify_signature`], looking the result up first. errors are not
+    /// cached.
+    pub fn verify(&self, subject: &CertificateRef, issuer: &CertificateRef) -> Result<bool, Error> {
+        self.verify_with_provider(subject, issuer, default_provider())
+    }
+
+    /// like [`verify`](Self::verify), with the fingerprints and the signature computed by
+    /// `provider`. the results are not kept apart by provider, a cache should only be used with
+    /// one.
+    pub fn verify_with_provider(
+        &self,
+        subject: &CertificateRef,
+        issuer: &CertificateRef,
+        provider: &dyn CryptoProvider,
+    ) -> Result<bool, Error> {
+        let key = (
+            fingerprint(subject, provider)?,
+            fingerprint(issuer, provider)?,
+        );
+        if let Some(valid) = self.lock().get(&key) {
+            return Ok(*valid);
+        }
+
+        let valid = subject.verify_signature_with(provider, issuer)?;
+        let mut results = self.lock();
+        if results.len() >= self.capacity {
+            let evicted = results.keys().next().cloned();
+            if let Some(evicted) = evicted {
+                results.remove(&evicted);
+            }
+        }
+        if self.capacity > 0 {
+            results.insert(key, valid);
+        }
+
+        Ok(valid)
+    }
+
+    /// the number of cached results
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(Fingerprint, Fingerprint), bool>> {
+        // the map is never left half updated, so a panic in another thread doesn't matter
+        self.results
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn fingerprint(cert: &CertificateRef, provider: &dyn CryptoProvider) -> Result<Fingerprint, Error> {
+    provider.digest(&oids::SHA256, cert.raw_data())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cert(data: &'static [u8]) -> CertificateRef<'static> {
+        CertificateRef::from_slice(data).unwrap()
+    }
+
+    #[test]
+    fn test_results_are_cached() {
+        let leaf = cert(include_bytes!("../../../certs/ocsp/leaf.der"));
+        let ca = cert(include_bytes!("../../../certs/ocsp/ca.der"));
+        let responder = cert(include_bytes!("../../../certs/ocsp/responder.der"));
+        let cache = SignatureCache::default();
+
+        assert!(cache.verify(&leaf, &ca).unwrap());
+        assert!(cache.verify(&leaf, &ca).unwrap());
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.verify(&leaf, &responder).unwrap());
+        assert!(!cache.verify(&leaf, &responder).unwrap());
+        assert_eq!(cache.len(), 2);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let leaf = cert(include_bytes!("../../../certs/ocsp/leaf.der"));
+        let ca = cert(include_bytes!("../../../certs/ocsp/ca.der"));
+        let responder = cert(include_bytes!("../../../certs/ocsp/responder.der"));
+
+        let cache = SignatureCache::new(1);
+        assert!(cache.verify(&leaf, &ca).unwrap());
+        assert!(!cache.verify(&leaf, &responder).unwrap());
+        assert_eq!(cache.len(), 1);
+
+        let cache = SignatureCache::new(0);
+        assert!(cache.verify(&leaf, &ca).unwrap());
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,25 @@
+use x509_core::{common::time::UtcDateTime, error::Error, parse::parsing::CertificateRef};
+
+/// the certificate of a chain that expires first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainExpiry {
+    /// position in the chain, the leaf is 0
+    pub index: usize,
+    pub not_after: UtcDateTime,
+}
+
+/// finds the earliest notAfter in `chain`, the chain as a whole stops being valid at that time.
+/// if several certificates expire at the same time the one closest to the leaf is returned.
+/// returns None for an empty chain.
+pub fn earliest_expiry(chain: &[CertificateRef]) -> Result<Option<ChainExpiry>, Error> {
+    let mut earliest: Option<ChainExpiry> = None;
+
+    for (index, cert) in chain.iter().enumerate() {
+        let not_after = cert.tbs_cert().validity().not_after()?;
+        if !matches!(earliest, Some(e) if e.not_after <= not_after) {
+            earliest = Some(ChainExpiry { index, not_after });
+        }
+    }
+
+    Ok(earliest)
+}
@@ -0,0 +1,832 @@
+//! Certificate chain verification with a per-check report.
+//!
+//! Every check is done "as of" the time passed in, which doesn't have to be the current time:
+//! validating at a past time, together with the CRLs that were current back then, tells
+//! whether a chain was acceptable when e.g. a signature was made.
+
+mod cache;
+mod expiry;
+mod observer;
+mod path;
+mod policy;
+mod render;
+mod report;
+mod signature;
+mod subject_name;
+mod uri;
+
+pub use cache::SignatureCache;
+pub use expiry::{earliest_expiry, ChainExpiry};
+pub use observer::VerifyObserver;
+pub use path::{build_paths, verify_from_pool, BuiltPath};
+pub use policy::VerifyPolicy;
+pub(crate) use render::format_name;
+pub use render::{render_chain_dot, render_chain_tree};
+pub use report::{CertificateReport, Check, CheckOutcome, CheckResult, Reason, VerificationReport};
+pub use signature::{
+    check_signature_detailed, check_signature_detailed_with_provider, verify_signed_data_detailed,
+    verify_signed_data_detailed_with_provider, SignatureFailure,
+};
+pub use subject_name::{matches_subject_name, SubjectName};
+pub use uri::{subject_uris, Uri};
+
+use crate::ocsp::StapledOcsp;
+use crate::provider::{default_provider, CryptoProvider};
+use crate::revocation::{CrlChecker, RevocationChecker, RevocationError, RevocationStatus};
+#[cfg(feature = "serde")]
+use crate::sct::{check_embedded_scts, SctError, SctStatus};
+use crate::VerifySignature;
+use x509_core::{
+    common::time::UtcDateTime,
+    oids,
+    parse::{
+        certificate::SignatureParametersRef,
+        crl::CertificateListRef,
+        extensions::{BasicConstraints, KeyUsageRef},
+        parsing::CertificateRef,
+    },
+};
+
+/// verifies `chain` at the time `now` with the default [`VerifyPolicy`].
+///
+/// the chain starts with the leaf, every certificate is followed by its issuer and the last one
+/// is the trust anchor, whose signature is checked against itself. whether the trust anchor is
+/// actually trusted is up to the caller.
+pub fn verify_chain(chain: &[CertificateRef], now: UtcDateTime) -> VerificationReport {
+    verify_chain_with_policy(chain, now, &VerifyPolicy::default())
+}
+
+/// like [`verify_chain`], with the checks tuned by `policy`
+pub fn verify_chain_with_policy(
+    chain: &[CertificateRef],
+    now: UtcDateTime,
+    policy: &VerifyPolicy,
+) -> VerificationReport {
+    verify_chain_with_crls(chain, &[], now, policy)
+}
+
+/// like [`verify_chain_with_policy`], checking revocation against `crls`.
+///
+/// for every certificate the newest CRL from its issuer that is valid at `now` and covers the
+/// certificate is used, CRLs that aren't signed by the issuer are ignored. a certificate only
+/// counts as revoked if its revocation date is not after `now`.
+pub fn verify_chain_with_crls(
+    chain: &[CertificateRef],
+    crls: &[CertificateListRef],
+    now: UtcDateTime,
+    policy: &VerifyPolicy,
+) -> VerificationReport {
+    verify_chain_with_revocation(chain, &CrlChecker::new(crls), now, policy)
+}
+
+/// like [`verify_chain_with_policy`], asking `checker` for the revocation status of every
+/// certificate but the trust anchor
+pub fn verify_chain_with_revocation(
+    chain: &[CertificateRef],
+    checker: &dyn RevocationChecker,
+    now: UtcDateTime,
+    policy: &VerifyPolicy,
+) -> VerificationReport {
+    verify_chain_with_provider(chain, checker, now, policy, default_provider())
+}
+
+/// like [`verify_chain_with_revocation`], with the signatures checked and the hashes computed
+/// by `provider` instead of the backend the crate is built with. `checker` is handed the
+/// provider as well.
+pub fn verify_chain_with_provider(
+    chain: &[CertificateRef],
+    checker: &dyn RevocationChecker,
+    now: UtcDateTime,
+    policy: &VerifyPolicy,
+    provider: &dyn CryptoProvider,
+) -> VerificationReport {
+    let chain: Vec<&CertificateRef> = chain.iter().collect();
+    verify(&chain, checker, now, policy, provider, None, None)
+}
+
+/// like [`verify_chain_with_policy`], checking revocation against the DER encoded OCSP
+/// responses stapled for the certificates, `responses[i]` belongs to `chain[i]`.
+///
+/// a response has to be signed by the issuer or a responder it delegated to, has to be about
+/// the certificate and current at `now`. certificates without a response are treated like ones
+/// without a CRL.
+pub fn verify_chain_with_ocsp(
+    chain: &[CertificateRef],
+    responses: &[Option<&[u8]>],
+    now: UtcDateTime,
+    policy: &VerifyPolicy,
+) -> VerificationReport {
+    verify_chain_with_revocation(chain, &StapledOcsp::new(chain, responses), now, policy)
+}
+
+/// like [`verify_chain_with_crls`], looking signature checks up in `cache` and remembering
+/// their results there
+pub fn verify_chain_with_cache(
+    chain: &[CertificateRef],
+    crls: &[CertificateListRef],
+    now: UtcDateTime,
+    policy: &VerifyPolicy,
+    cache: &SignatureCache,
+) -> VerificationReport {
+    let chain: Vec<&CertificateRef> = chain.iter().collect();
+    let crls = CrlChecker::new(crls);
+    verify(
+        &chain,
+        &crls,
+        now,
+        policy,
+        default_provider(),
+        Some(cache),
+        None,
+    )
+}
+
+/// like [`verify_chain_with_crls`], reporting every step to `observer`, which may also change
+/// the result of single checks
+pub fn verify_chain_with_observer(
+    chain: &[CertificateRef],
+    crls: &[CertificateListRef],
+    now: UtcDateTime,
+    policy: &VerifyPolicy,
+    observer: &mut dyn VerifyObserver,
+) -> VerificationReport {
+    let chain: Vec<&CertificateRef> = chain.iter().collect();
+    let crls = CrlChecker::new(crls);
+    verify(
+        &chain,
+        &crls,
+        now,
+        policy,
+        default_provider(),
+        None,
+        Some(observer),
+    )
+}
+
+fn verify(
+    chain: &[&CertificateRef],
+    revocation: &dyn RevocationChecker,
+    now: UtcDateTime,
+    policy: &VerifyPolicy,
+    provider: &dyn CryptoProvider,
+    cache: Option<&SignatureCache>,
+    mut observer: Option<&mut dyn VerifyObserver>,
+) -> VerificationReport {
+    let mut report = VerificationReport::default();
+
+    for (index, cert) in chain.iter().enumerate() {
+        let issuer = chain.get(index + 1).copied().unwrap_or(cert);
+        if let Some(observer) = observer.as_mut() {
+            observer.on_certificate(index, cert);
+        }
+        let mut cert_report = CertificateReport::new(index);
+        let mut record = |check, result| {
+            let result = match observer.as_mut() {
+                Some(observer) => observer.on_check(index, cert, check, result),
+                None => result,
+            };
+            cert_report.record(check, result);
+        };
+
+        record(
+            Check::Signature,
+            check_signature(cert, issuer, provider, cache),
+        );
+        record(Check::IssuerName, check_issuer_name(cert, issuer));
+        record(Check::Validity, check_validity(cert, now));
+        record(Check::KeySize, check_key_size(cert, policy));
+        if index + 1 < chain.len() {
+            // the self-signature of the trust anchor doesn't protect anything
+            record(
+                Check::SignatureAlgorithm,
+                check_signature_algorithm(cert, policy),
+            );
+            // the extensions of the trust anchor are not processed (RFC 5280 section 6.1)
+            record(
+                Check::CriticalExtensions,
+                check_critical_extensions(cert, policy),
+            );
+        }
+        #[cfg(feature = "serde")]
+        if let (0, Some((required, logs))) = (index, policy.requires_scts()) {
+            record(
+                Check::CertificateTransparency,
+                check_scts(cert, issuer, now, required, logs, provider),
+            );
+        }
+        if index >= policy.maximum_chain_depth() {
+            record(Check::ChainDepth, CheckResult::Failed(Reason::ChainTooLong));
+        }
+        if index > 0 && index + 1 < chain.len() {
+            // everything between the leaf and the trust anchor issues certificates
+            let intermediates_below = chain[1..index]
+                .iter()
+                .filter(|c| !is_self_issued(c))
+                .count();
+            record(
+                Check::BasicConstraints,
+                check_basic_constraints(cert, intermediates_below),
+            );
+            record(Check::KeyUsage, check_key_usage(cert));
+        }
+        if index + 1 < chain.len() {
+            // trust anchors are not checked for revocation
+            record(
+                Check::Revocation,
+                check_revocation(cert, issuer, revocation, now, policy, provider),
+            );
+        }
+
+        if let Some(observer) = observer.as_mut() {
+            observer.on_certificate_done(&cert_report);
+        }
+        report.certificates.push(cert_report);
+    }
+
+    report
+}
+
+fn is_self_issued(cert: &CertificateRef) -> bool {
+    cert.tbs_cert().issuer() == cert.tbs_cert().subject()
+}
+
+fn check_signature(
+    cert: &CertificateRef,
+    issuer: &CertificateRef,
+    provider: &dyn CryptoProvider,
+    cache: Option<&SignatureCache>,
+) -> CheckResult {
+    let valid = match cache {
+        Some(cache) => cache.verify_with_provider(cert, issuer, provider),
+        None => cert.verify_signature_with(provider, issuer),
+    };
+    if let Ok(true) = valid {
+        return CheckResult::Passed;
+    }
+
+    // the backends don't say why, look for a reason before blaming the signature
+    match check_signature_detailed_with_provider(cert, issuer, provider) {
+        Ok(()) | Err(SignatureFailure::SignatureMismatch) => {
+            CheckResult::Failed(Reason::InvalidSignature)
+        }
+        Err(SignatureFailure::MalformedPublicKey) => {
+            CheckResult::Failed(Reason::MalformedPublicKey)
+        }
+        Err(SignatureFailure::Backend(e)) => {
+            CheckResult::Failed(Reason::SignatureError(format!("{:?}", e)))
+        }
+        Err(e) => CheckResult::Failed(Reason::SignatureError(e.to_string())),
+    }
+}
+
+fn check_issuer_name(cert: &CertificateRef, issuer: &CertificateRef) -> CheckResult {
+    if cert.tbs_cert().issuer() == issuer.tbs_cert().subject() {
+        CheckResult::Passed
+    } else {
+        CheckResult::Failed(Reason::IssuerMismatch)
+    }
+}
+
+fn check_validity(cert: &CertificateRef, now: UtcDateTime) -> CheckResult {
+    let validity = cert.tbs_cert().validity();
+    let (not_before, not_after) = match (validity.not_before(), validity.not_after()) {
+        (Ok(not_before), Ok(not_after)) => (not_before, not_after),
+        _ => return CheckResult::Failed(Reason::MalformedTime),
+    };
+
+    if now < not_before {
+        CheckResult::Failed(Reason::NotYetValid)
+    } else if now > not_after {
+        CheckResult::Failed(Reason::Expired)
+    } else {
+        CheckResult::Passed
+    }
+}
+
+fn check_basic_constraints(cert: &CertificateRef, intermediates_below: usize) -> CheckResult {
+    let ext = match cert
+        .tbs_cert()
+        .extensions()
+        .map(|e| e.find(oids::BASIC_CONSTRAINTS))
+    {
+        Some(Ok(Some(ext))) => ext,
+        Some(Err(_)) => return CheckResult::Failed(Reason::MalformedExtension),
+        _ => return CheckResult::Failed(Reason::NotCA),
+    };
+    let constraints = match BasicConstraints::from_slice(ext.value().as_bytes()) {
+        Ok(constraints) => constraints,
+        Err(_) => return CheckResult::Failed(Reason::MalformedExtension),
+    };
+
+    if !constraints.is_ca() {
+        return CheckResult::Failed(Reason::NotCA);
+    }
+    match constraints.path_len_constraint() {
+        Some(max) if intermediates_below as u64 > max => {
+            CheckResult::Failed(Reason::PathLenExceeded)
+        }
+        _ => CheckResult::Passed,
+    }
+}
+
+fn check_key_usage(cert: &CertificateRef) -> CheckResult {
+    let ext = match cert
+        .tbs_cert()
+        .extensions()
+        .map(|e| e.find(oids::KEY_USAGE))
+    {
+        Some(Ok(Some(ext))) => ext,
+        Some(Err(_)) => return CheckResult::Failed(Reason::MalformedExtension),
+        // without the extension the key may be used for anything
+        _ => return CheckResult::Passed,
+    };
+
+    match KeyUsageRef::from_slice(ext.value().as_bytes()) {
+        Ok(usage) if usage.key_cert_sign() => CheckResult::Passed,
+        Ok(_) => CheckResult::Failed(Reason::KeyCertSignNotAllowed),
+        Err(_) => CheckResult::Failed(Reason::MalformedExtension),
+    }
+}
+
+fn check_critical_extensions(cert: &CertificateRef, policy: &VerifyPolicy) -> CheckResult {
+    let extensions = match cert.tbs_cert().extensions() {
+        Some(extensions) => extensions,
+        None => return CheckResult::Passed,
+    };
+
+    for ext in extensions {
+        let ext = match ext {
+            Ok(ext) => ext,
+            Err(_) => return CheckResult::Failed(Reason::MalformedExtension),
+        };
+        if ext.critical() && !policy.handles_critical_extension(ext.extension_id()) {
+            return CheckResult::Failed(Reason::UnhandledCriticalExtension(
+                ext.extension_id().to_string(),
+            ));
+        }
+    }
+
+    CheckResult::Passed
+}
+
+fn check_revocation(
+    cert: &CertificateRef,
+    issuer: &CertificateRef,
+    revocation: &dyn RevocationChecker,
+    now: UtcDateTime,
+    policy: &VerifyPolicy,
+    provider: &dyn CryptoProvider,
+) -> CheckResult {
+    match revocation.check(cert, issuer, now, provider) {
+        Ok(RevocationStatus::Good) => CheckResult::Passed,
+        Ok(RevocationStatus::Revoked {
+            revocation_time,
+            reason,
+        }) => CheckResult::Failed(Reason::Revoked {
+            revocation_time,
+            reason,
+        }),
+        // the responder was asked and couldn't vouch for the certificate
+        Ok(RevocationStatus::Unknown) => CheckResult::Failed(Reason::RevocationStatusUnknown),
+        Ok(RevocationStatus::NotChecked) if policy.checks_revocation() => {
+            CheckResult::Failed(Reason::NoRevocationInfo)
+        }
+        Ok(RevocationStatus::NotChecked) => CheckResult::Skipped(Reason::NoRevocationInfo),
+        Err(RevocationError::MalformedCrl(_)) => CheckResult::Failed(Reason::MalformedCrl),
+        Err(RevocationError::Ocsp(e)) => {
+            CheckResult::Failed(Reason::InvalidOcspResponse(format!("{:?}", e)))
+        }
+    }
+}
+
+fn check_signature_algorithm(cert: &CertificateRef, policy: &VerifyPolicy) -> CheckResult {
+    let algorithm = cert.signature_algorithm().algorithm_identifier();
+
+    if oids::is(algorithm, oids::MD5_WITH_RSA_ENCRYPTION) {
+        return CheckResult::Failed(Reason::WeakSignatureAlgorithm);
+    }
+    // RSASSA-PSS names its hash in the parameters, SHA-1 if they are left out
+    let is_pss_sha1 = oids::is(algorithm, oids::RSASSA_PSS)
+        && matches!(
+            cert.signature_algorithm().signature_parameters(),
+            Ok(SignatureParametersRef::RsassaPss(params)) if *params.hash_algorithm() == oids::SHA1
+        );
+    let is_sha1 = oids::is(algorithm, oids::SHA1_WITH_RSA_ENCRYPTION)
+        || oids::is(algorithm, oids::ECDSA_WITH_SHA1)
+        || is_pss_sha1;
+    if is_sha1 && !policy.allows_sha1() {
+        return CheckResult::Failed(Reason::WeakSignatureAlgorithm);
+    }
+
+    CheckResult::Passed
+}
+
+fn check_key_size(cert: &CertificateRef, policy: &VerifyPolicy) -> CheckResult {
+    let spki = cert.tbs_cert().subject_public_key_info();
+    if !oids::is(
+        spki.algorithm_identifier().algorithm_identifier(),
+        oids::RSA_ENCRYPTION,
+    ) {
+        return CheckResult::Passed;
+    }
+
+    let (_, key) = spki.subject_public_key().data();
+    match signature::rsa_modulus_bits(key) {
+        Ok(bits) if bits < policy.minimum_rsa_bits() => {
+            CheckResult::Failed(Reason::KeyTooSmall(bits))
+        }
+        Ok(_) => CheckResult::Passed,
+        Err(_) => CheckResult::Failed(Reason::MalformedPublicKey),
+    }
+}
+
+#[cfg(feature = "serde")]
+fn check_scts(
+    cert: &CertificateRef,
+    issuer: &CertificateRef,
+    now: UtcDateTime,
+    required: usize,
+    logs: &crate::ct_logs::CtLogs,
+    provider: &dyn CryptoProvider,
+) -> CheckResult {
+    let scts = match check_embedded_scts(cert, issuer, logs, now, provider) {
+        Ok(scts) if scts.is_empty() => return CheckResult::Failed(Reason::MissingScts),
+        Ok(scts) => scts,
+        Err(SctError::Parse(_)) => return CheckResult::Failed(Reason::MalformedExtension),
+        Err(SctError::Crypto(e)) => {
+            return CheckResult::Failed(Reason::SignatureError(format!("{:?}", e)))
+        }
+    };
+
+    // several SCTs of the same log count once
+    let mut valid: Vec<&[u8]> = scts
+        .iter()
+        .filter(|(_, status)| *status == SctStatus::Valid)
+        .map(|(sct, _)| sct.log_id())
+        .collect();
+    valid.sort_unstable();
+    valid.dedup();
+
+    if valid.len() < required {
+        CheckResult::Failed(Reason::TooFewScts {
+            valid: valid.len(),
+            required,
+        })
+    } else {
+        CheckResult::Passed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x509_core::common::certificate::CRLReason;
+
+    fn cert(data: &'static [u8]) -> CertificateRef<'static> {
+        CertificateRef::from_slice(data).unwrap()
+    }
+
+    #[test]
+    fn test_valid_chain() {
+        let chain = [
+            cert(include_bytes!("../../../certs/ocsp/leaf.der")),
+            cert(include_bytes!("../../../certs/ocsp/ca.der")),
+        ];
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let report = verify_chain(&chain, now);
+
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(report.certificates.len(), 2);
+
+        let expiry = earliest_expiry(&chain).unwrap().unwrap();
+        assert_eq!(expiry.index, 0);
+        assert_eq!(
+            expiry.not_after,
+            chain[0].tbs_cert().validity().not_after().unwrap()
+        );
+        assert_eq!(
+            report.certificates[0].checks.last(),
+            Some(&CheckOutcome {
+                check: Check::Revocation,
+                result: CheckResult::Skipped(Reason::NoRevocationInfo),
+            })
+        );
+    }
+
+    #[test]
+    fn test_v1_trust_anchor() {
+        // neither certificate has extensions, the root is trusted as it is
+        let chain = [
+            cert(include_bytes!("../../../certs/path/v1-leaf.der")),
+            cert(include_bytes!("../../../certs/path/v1-root.der")),
+        ];
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let report = verify_chain(&chain, now);
+        assert!(report.is_valid(), "{}", report);
+    }
+
+    #[test]
+    fn test_failures_are_explained() {
+        // the responder certificate did not issue the leaf and is not a CA
+        let chain = [
+            cert(include_bytes!("../../../certs/ocsp/leaf.der")),
+            cert(include_bytes!("../../../certs/ocsp/responder.der")),
+            cert(include_bytes!("../../../certs/ocsp/ca.der")),
+        ];
+        let now = UtcDateTime::from_ymd_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        let report = verify_chain(&chain, now);
+        assert!(!report.is_valid());
+
+        let failures: Vec<(usize, Check)> = report.failures().map(|(i, f)| (i, f.check)).collect();
+        assert!(failures.contains(&(0, Check::Signature)));
+        assert!(failures.contains(&(0, Check::IssuerName)));
+        assert!(failures.contains(&(0, Check::Validity)));
+        assert!(failures.contains(&(1, Check::BasicConstraints)));
+        assert!(failures.contains(&(1, Check::KeyUsage)));
+        assert!(!failures.contains(&(1, Check::Signature)));
+    }
+
+    #[test]
+    fn test_cached_signatures() {
+        let chain = [
+            cert(include_bytes!("../../../certs/ocsp/leaf.der")),
+            cert(include_bytes!("../../../certs/ocsp/ca.der")),
+        ];
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let policy = VerifyPolicy::default();
+        let cache = SignatureCache::default();
+
+        let uncached = verify_chain_with_crls(&chain, &[], now, &policy);
+        for _ in 0..2 {
+            let report = verify_chain_with_cache(&chain, &[], now, &policy, &cache);
+            assert_eq!(report.to_string(), uncached.to_string());
+        }
+        // the leaf and the self-signed trust anchor
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_policy() {
+        let chain = [
+            cert(include_bytes!("../../../certs/ocsp/leaf.der")),
+            cert(include_bytes!("../../../certs/ocsp/ca.der")),
+        ];
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let policy = VerifyPolicy::default()
+            .min_rsa_bits(4096)
+            .check_revocation(true)
+            .max_chain_depth(1);
+        let report = verify_chain_with_policy(&chain, now, &policy);
+
+        let failures: Vec<(usize, &CheckResult)> =
+            report.failures().map(|(i, f)| (i, &f.result)).collect();
+        assert_eq!(
+            failures,
+            vec![
+                (0, &CheckResult::Failed(Reason::KeyTooSmall(2048))),
+                (0, &CheckResult::Failed(Reason::NoRevocationInfo)),
+                (1, &CheckResult::Failed(Reason::KeyTooSmall(2048))),
+                (1, &CheckResult::Failed(Reason::ChainTooLong)),
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_scts() {
+        use crate::ct_logs::CtLogs;
+        use std::sync::Arc;
+
+        let logs = Arc::new(
+            CtLogs::from_log_list_json(include_bytes!("../../../certs/ct/log_list.json")).unwrap(),
+        );
+        let now = UtcDateTime::from_ymd_hms(2026, 10, 16, 0, 0, 0).unwrap();
+        let ct_check = |leaf: &'static [u8], required| {
+            let chain = [
+                cert(leaf),
+                cert(include_bytes!("../../../certs/ocsp/ca.der")),
+            ];
+            let policy = VerifyPolicy::default().require_scts(required, logs.clone());
+            let report = verify_chain_with_policy(&chain, now, &policy);
+            report.certificates[0]
+                .checks
+                .iter()
+                .find(|c| c.check == Check::CertificateTransparency)
+                .map(|c| c.result.clone())
+        };
+        let scts = include_bytes!("../../../certs/ct/scts.der");
+
+        assert_eq!(ct_check(scts, 3), Some(CheckResult::Passed));
+        assert_eq!(
+            ct_check(scts, 4),
+            Some(CheckResult::Failed(Reason::TooFewScts {
+                valid: 3,
+                required: 4
+            }))
+        );
+        assert_eq!(
+            ct_check(include_bytes!("../../../certs/ocsp/leaf.der"), 1),
+            Some(CheckResult::Failed(Reason::MissingScts))
+        );
+        // the dummy SCT list of final.der is cut short
+        assert_eq!(
+            ct_check(include_bytes!("../../../certs/ct/final.der"), 1),
+            Some(CheckResult::Failed(Reason::MalformedExtension))
+        );
+        // not required
+        let chain = [
+            cert(scts),
+            cert(include_bytes!("../../../certs/ocsp/ca.der")),
+        ];
+        let report = verify_chain_with_policy(&chain, now, &VerifyPolicy::default());
+        assert!(report.certificates[0]
+            .checks
+            .iter()
+            .all(|c| c.check != Check::CertificateTransparency));
+    }
+
+    #[test]
+    fn test_pss_sha1() {
+        // RSASSA-PSS with the default parameters, i.e. SHA-1. the certificate is self-signed, as
+        // its own trust anchor it wouldn't get the algorithm checked
+        let pss = include_bytes!("../../../certs/identity/pss-sha1.der");
+        let chain = [cert(pss), cert(pss)];
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let algorithm_check = |policy| {
+            let report = verify_chain_with_policy(&chain, now, &policy);
+            report.certificates[0]
+                .checks
+                .iter()
+                .find(|c| c.check == Check::SignatureAlgorithm)
+                .map(|c| c.result.clone())
+        };
+
+        assert_eq!(
+            algorithm_check(VerifyPolicy::default()),
+            Some(CheckResult::Failed(Reason::WeakSignatureAlgorithm))
+        );
+        assert_eq!(
+            algorithm_check(VerifyPolicy::default().allow_sha1(true)),
+            Some(CheckResult::Passed)
+        );
+    }
+
+    #[test]
+    fn test_critical_extensions() {
+        // a leaf with the critical private extension 1.3.6.1.4.1.55555.1 and a critical SAN
+        let chain = [
+            cert(include_bytes!("../../../certs/ocsp/critical-ext.der")),
+            cert(include_bytes!("../../../certs/ocsp/ca.der")),
+        ];
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+
+        let report = verify_chain(&chain, now);
+        let failures: Vec<(usize, &CheckResult)> =
+            report.failures().map(|(i, f)| (i, &f.result)).collect();
+        assert_eq!(
+            failures,
+            vec![(
+                0,
+                &CheckResult::Failed(Reason::UnhandledCriticalExtension(
+                    "1.3.6.1.4.1.55555.1".to_string()
+                ))
+            )]
+        );
+
+        let oid = [0x2b, 0x06, 0x01, 0x04, 0x01, 0x83, 0xb2, 0x03, 0x01];
+        let policy = VerifyPolicy::default()
+            .handle_critical_extension(x509_core::parse::der::ObjectIdentifierRef::new(&oid));
+        assert!(policy.handles_critical_extension(&oids::SUBJECT_ALT_NAME));
+        let report = verify_chain_with_policy(&chain, now, &policy);
+        assert!(report.is_valid(), "{}", report);
+    }
+
+    #[test]
+    fn test_revocation_as_of() {
+        let chain = [
+            cert(include_bytes!("../../../certs/ocsp/leaf.der")),
+            cert(include_bytes!("../../../certs/ocsp/ca.der")),
+        ];
+        // the older CRL doesn't list the leaf yet, the newer one does
+        let crls = [
+            CertificateListRef::from_slice(include_bytes!("../../../certs/crl/ca-old.crl"))
+                .unwrap(),
+            CertificateListRef::from_slice(include_bytes!("../../../certs/crl/ca.crl")).unwrap(),
+        ];
+        let policy = VerifyPolicy::default();
+        let revocation = |now| {
+            let report = verify_chain_with_crls(&chain, &crls, now, &policy);
+            report.certificates[0]
+                .checks
+                .iter()
+                .find(|c| c.check == Check::Revocation)
+                .map(|c| c.result.clone())
+                .unwrap()
+        };
+
+        let before_newer_crl = UtcDateTime::from_ymd_hms(2026, 10, 16, 14, 20, 0).unwrap();
+        assert_eq!(revocation(before_newer_crl), CheckResult::Passed);
+
+        let revoked = CheckResult::Failed(Reason::Revoked {
+            revocation_time: UtcDateTime::from_ymd_hms(2026, 10, 1, 12, 0, 0).unwrap(),
+            reason: Some(CRLReason::KeyCompromise),
+        });
+        // both CRLs are valid, the newer one wins
+        let both_valid = UtcDateTime::from_ymd_hms(2026, 10, 20, 0, 0, 0).unwrap();
+        assert_eq!(revocation(both_valid), revoked);
+
+        let before_any_crl = UtcDateTime::from_ymd_hms(2026, 10, 16, 14, 18, 30).unwrap();
+        assert_eq!(
+            revocation(before_any_crl),
+            CheckResult::Skipped(Reason::NoRevocationInfo)
+        );
+    }
+    /// knows that everything but the certificates issued by the test CA is revoked
+    struct OnlyTestCa<'a>(&'a CertificateRef<'a>);
+
+    impl RevocationChecker for OnlyTestCa<'_> {
+        fn check(
+            &self,
+            _cert: &CertificateRef,
+            issuer: &CertificateRef,
+            now: UtcDateTime,
+            _provider: &dyn CryptoProvider,
+        ) -> Result<RevocationStatus, RevocationError> {
+            if issuer.raw_data() == self.0.raw_data() {
+                Ok(RevocationStatus::Good)
+            } else {
+                Ok(RevocationStatus::Revoked {
+                    revocation_time: now,
+                    reason: None,
+                })
+            }
+        }
+    }
+
+    #[test]
+    fn test_revocation_checker() {
+        let ca = cert(include_bytes!("../../../certs/ocsp/ca.der"));
+        let chain = [
+            cert(include_bytes!("../../../certs/path/leaf.der")),
+            cert(include_bytes!("../../../certs/path/intermediate.der")),
+            cert(include_bytes!("../../../certs/ocsp/ca.der")),
+        ];
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let policy = VerifyPolicy::default();
+
+        let report = verify_chain_with_revocation(&chain, &OnlyTestCa(&ca), now, &policy);
+        let failures: Vec<_> = report.failures().map(|(i, f)| (i, f.check)).collect();
+        // the intermediate is good, the leaf it issued is not and the anchor is not asked
+        assert_eq!(failures, vec![(0, Check::Revocation)]);
+        assert!(report.certificates[1].checks.contains(&CheckOutcome {
+            check: Check::Revocation,
+            result: CheckResult::Passed,
+        }));
+    }
+
+    #[test]
+    fn test_stapled_ocsp() {
+        let chain = [
+            cert(include_bytes!("../../../certs/ocsp/leaf.der")),
+            cert(include_bytes!("../../../certs/ocsp/ca.der")),
+        ];
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let policy = VerifyPolicy::default().check_revocation(true);
+        let revocation = |response: &'static [u8]| {
+            let report = verify_chain_with_ocsp(&chain, &[Some(response), None], now, &policy);
+            report.certificates[0]
+                .checks
+                .iter()
+                .find(|c| c.check == Check::Revocation)
+                .map(|c| c.result.clone())
+                .unwrap()
+        };
+
+        assert_eq!(
+            revocation(include_bytes!("../../../certs/ocsp/good-delegated.der")),
+            CheckResult::Passed
+        );
+        assert_eq!(
+            revocation(include_bytes!("../../../certs/ocsp/revoked.der")),
+            CheckResult::Failed(Reason::Revoked {
+                revocation_time: UtcDateTime::from_ymd_hms(2026, 10, 1, 12, 0, 0).unwrap(),
+                reason: Some(CRLReason::KeyCompromise),
+            })
+        );
+        assert_eq!(
+            revocation(include_bytes!("../../../certs/ocsp/unknown.der")),
+            CheckResult::Failed(Reason::RevocationStatusUnknown)
+        );
+        assert_eq!(
+            revocation(include_bytes!("../../../certs/ocsp/good-unauthorized.der")),
+            CheckResult::Failed(Reason::InvalidOcspResponse(
+                "UnauthorizedResponder".to_string()
+            ))
+        );
+
+        // without a response it's up to the policy
+        let report = verify_chain_with_ocsp(&chain, &[], now, &policy);
+        let failures: Vec<_> = report.failures().map(|(i, f)| (i, f.check)).collect();
+        assert_eq!(failures, vec![(0, Check::Revocation)]);
+    }
+}
@@ -0,0 +1,88 @@
+use x509_core::parse::parsing::CertificateRef;
+
+use super::{CertificateReport, Check, CheckResult};
+
+/// is told about every step of [`verify_chain_with_observer`](super::verify_chain_with_observer),
+/// to log, collect metrics or override single results without changing the validator. all
+/// methods do nothing by default.
+pub trait VerifyObserver {
+    /// called before the certificate at `index` is checked, the leaf is 0
+    fn on_certificate(&mut self, _index: usize, _cert: &CertificateRef) {}
+
+    /// called with the result of every check. the returned result is the one that goes into the
+    /// report, so an observer can e.g. pass the validity check of a known expired internal root.
+    fn on_check(
+        &mut self,
+        _index: usize,
+        _cert: &CertificateRef,
+        _check: Check,
+        result: CheckResult,
+    ) -> CheckResult {
+        result
+    }
+
+    /// called once all checks of a certificate are done
+    fn on_certificate_done(&mut self, _report: &CertificateReport) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::{verify_chain_with_observer, Reason, VerifyPolicy};
+    use x509_core::common::time::UtcDateTime;
+
+    /// counts the checks and lets the trust anchor be expired
+    #[derive(Default)]
+    struct ExpiredRoot {
+        certificates: Vec<usize>,
+        checks: usize,
+        done: usize,
+    }
+
+    impl VerifyObserver for ExpiredRoot {
+        fn on_certificate(&mut self, index: usize, _cert: &CertificateRef) {
+            self.certificates.push(index);
+        }
+
+        fn on_check(
+            &mut self,
+            index: usize,
+            _cert: &CertificateRef,
+            check: Check,
+            result: CheckResult,
+        ) -> CheckResult {
+            self.checks += 1;
+            match (index, check, result) {
+                (1, Check::Validity, CheckResult::Failed(Reason::Expired)) => CheckResult::Passed,
+                (_, _, result) => result,
+            }
+        }
+
+        fn on_certificate_done(&mut self, report: &CertificateReport) {
+            self.done += report.checks.len();
+        }
+    }
+
+    #[test]
+    fn test_observer() {
+        let chain = [
+            CertificateRef::from_slice(include_bytes!("../../../certs/ocsp/leaf.der")).unwrap(),
+            CertificateRef::from_slice(include_bytes!("../../../certs/ocsp/ca.der")).unwrap(),
+        ];
+        let policy = VerifyPolicy::default();
+        let mut observer = ExpiredRoot::default();
+
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let report = verify_chain_with_observer(&chain, &[], now, &policy, &mut observer);
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(observer.certificates, vec![0, 1]);
+        assert!(observer.checks > 0);
+        assert_eq!(observer.checks, observer.done);
+
+        // both are expired, only the leaf fails
+        let now = UtcDateTime::from_ymd_hms(2200, 1, 1, 0, 0, 0).unwrap();
+        let report = verify_chain_with_observer(&chain, &[], now, &policy, &mut observer);
+        let failures: Vec<_> = report.failures().map(|(i, f)| (i, f.check)).collect();
+        assert_eq!(failures, vec![(0, Check::Validity)]);
+    }
+}
@@ -0,0 +1,261 @@
+use x509_core::{
+    common::time::UtcDateTime,
+    oids,
+    parse::{
+        crl::CertificateListRef,
+        extensions::{AuthorityKeyIdentifierRef, SubjectKeyIdentifierRef},
+        parsing::CertificateRef,
+    },
+};
+
+use super::{verify, SignatureCache, VerificationReport, VerifyPolicy};
+use crate::provider::default_provider;
+use crate::revocation::CrlChecker;
+
+/// no more candidates than this are built, so a pool full of cross-certificates with the same
+/// names can't make the search explode
+const MAX_PATHS: usize = 32;
+
+/// a chain built by [`verify_from_pool`] together with its report
+#[derive(Debug)]
+pub struct BuiltPath<'c, 'a> {
+    /// the certificates from the leaf to the trust anchor
+    pub chain: Vec<&'c CertificateRef<'a>>,
+    pub report: VerificationReport,
+}
+
+/// builds the candidate chains from `leaf` to one of `anchors` through the unordered pool
+/// `intermediates`, leaf first and trust anchor last like [`verify_chain`](super::verify_chain)
+/// expects them.
+///
+/// issuers are found by matching the issuer of a certificate with the subject of the
+/// candidates. if the authority key identifier of the certificate is known, candidates with
+/// that subject key identifier come first and candidates with a different one last; otherwise
+/// the order of the pool is kept. chains are at most `max_depth` certificates long and don't
+/// contain a certificate twice.
+pub fn build_paths<'c, 'a>(
+    leaf: &'c CertificateRef<'a>,
+    intermediates: &'c [CertificateRef<'a>],
+    anchors: &'c [CertificateRef<'a>],
+    max_depth: usize,
+) -> Vec<Vec<&'c CertificateRef<'a>>> {
+    if anchors.iter().any(|a| a.raw_data() == leaf.raw_data()) {
+        // the leaf is trusted itself
+        return vec![vec![leaf]];
+    }
+
+    let mut paths = Vec::new();
+    let mut path = vec![leaf];
+    extend_paths(&mut path, intermediates, anchors, max_depth, &mut paths);
+
+    paths
+}
+
+/// like [`verify_chain_with_crls`](super::verify_chain_with_crls), but the chain is built
+/// with [`build_paths`]. the candidates are verified one after the other until one is valid.
+/// if none is, the first candidate is returned, since its failures are the most telling.
+///
+/// returns None if no chain leads to any of the anchors.
+pub fn verify_from_pool<'c, 'a>(
+    leaf: &'c CertificateRef<'a>,
+    intermediates: &'c [CertificateRef<'a>],
+    anchors: &'c [CertificateRef<'a>],
+    crls: &[CertificateListRef],
+    now: UtcDateTime,
+    policy: &VerifyPolicy,
+) -> Option<BuiltPath<'c, 'a>> {
+    // candidates share their upper parts, their signatures only need to be checked once
+    let cache = SignatureCache::default();
+    let crls = CrlChecker::new(crls);
+    let mut first = None;
+
+    for chain in build_paths(leaf, intermediates, anchors, policy.maximum_chain_depth()) {
+        let report = verify(
+            &chain,
+            &crls,
+            now,
+            policy,
+            default_provider(),
+            Some(&cache),
+            None,
+        );
+        if report.is_valid() {
+            return Some(BuiltPath { chain, report });
+        }
+        if first.is_none() {
+            first = Some(BuiltPath { chain, report });
+        }
+    }
+
+    first
+}
+
+fn extend_paths<'c, 'a>(
+    path: &mut Vec<&'c CertificateRef<'a>>,
+    intermediates: &'c [CertificateRef<'a>],
+    anchors: &'c [CertificateRef<'a>],
+    max_depth: usize,
+    paths: &mut Vec<Vec<&'c CertificateRef<'a>>>,
+) {
+    let cert = path[path.len() - 1];
+
+    if path.len() >= max_depth {
+        return;
+    }
+    for anchor in issuer_candidates(cert, anchors) {
+        if paths.len() >= MAX_PATHS {
+            return;
+        }
+        let mut complete = path.clone();
+        complete.push(anchor);
+        paths.push(complete);
+    }
+
+    // an intermediate needs room for itself and a trust anchor above it
+    if path.len() + 2 > max_depth {
+        return;
+    }
+    for issuer in issuer_candidates(cert, intermediates) {
+        if paths.len() >= MAX_PATHS {
+            return;
+        }
+        // going on from a trust anchor leads nowhere new
+        let seen = path
+            .iter()
+            .copied()
+            .chain(anchors)
+            .any(|c| c.raw_data() == issuer.raw_data());
+        if seen {
+            continue;
+        }
+        path.push(issuer);
+        extend_paths(path, intermediates, anchors, max_depth, paths);
+        path.pop();
+    }
+}
+
+/// the certificates in `pool` that may have issued `cert`, the likeliest first
+fn issuer_candidates<'c, 'a>(
+    cert: &CertificateRef,
+    pool: &'c [CertificateRef<'a>],
+) -> Vec<&'c CertificateRef<'a>> {
+    let authority_key_id = authority_key_identifier(cert);
+    let mut candidates: Vec<_> = pool
+        .iter()
+        .filter(|c| c.tbs_cert().subject() == cert.tbs_cert().issuer())
+        .collect();
+    // the sort is stable, so the order of the pool is kept within each group
+    candidates.sort_by_key(|c| match (authority_key_id, subject_key_identifier(c)) {
+        (Some(aki), Some(ski)) if aki == ski => 0,
+        (Some(_), Some(_)) => 2,
+        _ => 1,
+    });
+
+    candidates
+}
+
+fn authority_key_identifier<'c>(cert: &'c CertificateRef) -> Option<&'c [u8]> {
+    let ext = cert
+        .tbs_cert()
+        .extensions()?
+        .find(oids::AUTHORITY_KEY_IDENTIFIER)
+        .ok()??;
+    AuthorityKeyIdentifierRef::from_slice(ext.value().as_bytes())
+        .ok()?
+        .key_identifier()
+}
+
+fn subject_key_identifier<'c>(cert: &'c CertificateRef) -> Option<&'c [u8]> {
+    let ext = cert
+        .tbs_cert()
+        .extensions()?
+        .find(oids::SUBJECT_KEY_IDENTIFIER)
+        .ok()??;
+    SubjectKeyIdentifierRef::from_slice(ext.value().as_bytes())
+        .ok()
+        .map(|ski| ski.key_identifier())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify::{Check, CheckResult, Reason};
+
+    fn cert(data: &'static [u8]) -> CertificateRef<'static> {
+        CertificateRef::from_slice(data).unwrap()
+    }
+
+    #[test]
+    fn test_build_paths() {
+        // the decoy has the subject and key identifier of the intermediate, but another key
+        let leaf = cert(include_bytes!("../../../certs/path/leaf.der"));
+        let intermediates = [
+            cert(include_bytes!("../../../certs/path/decoy.der")),
+            cert(include_bytes!("../../../certs/ocsp/leaf.der")),
+            cert(include_bytes!("../../../certs/path/intermediate.der")),
+        ];
+        let anchors = [cert(include_bytes!("../../../certs/ocsp/ca.der"))];
+
+        let paths = build_paths(&leaf, &intermediates, &anchors, 8);
+        let paths: Vec<Vec<&[u8]>> = paths
+            .iter()
+            .map(|p| p.iter().map(|c| c.raw_data()).collect())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                vec![
+                    leaf.raw_data(),
+                    intermediates[0].raw_data(),
+                    anchors[0].raw_data()
+                ],
+                vec![
+                    leaf.raw_data(),
+                    intermediates[2].raw_data(),
+                    anchors[0].raw_data()
+                ],
+            ]
+        );
+
+        // no room for the intermediate
+        assert!(build_paths(&leaf, &intermediates, &anchors, 2).is_empty());
+        assert!(build_paths(&leaf, &intermediates, &[], 8).is_empty());
+        // a trusted leaf
+        assert_eq!(build_paths(&anchors[0], &[], &anchors, 8).len(), 1);
+    }
+
+    #[test]
+    fn test_verify_from_pool() {
+        let leaf = cert(include_bytes!("../../../certs/path/leaf.der"));
+        let pool = [
+            cert(include_bytes!("../../../certs/path/decoy.der")),
+            cert(include_bytes!("../../../certs/path/intermediate.der")),
+        ];
+        let anchors = [cert(include_bytes!("../../../certs/ocsp/ca.der"))];
+        let now = UtcDateTime::from_ymd_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let policy = VerifyPolicy::default();
+
+        // the decoy comes first and fails, the intermediate is tried next
+        let built = verify_from_pool(&leaf, &pool, &anchors, &[], now, &policy).unwrap();
+        assert!(built.report.is_valid(), "{}", built.report);
+        assert_eq!(built.chain[1].raw_data(), pool[1].raw_data());
+
+        // with only the decoy, its failed chain is reported
+        let built = verify_from_pool(&leaf, &pool[..1], &anchors, &[], now, &policy).unwrap();
+        let failures: Vec<(usize, Check, &CheckResult)> = built
+            .report
+            .failures()
+            .map(|(i, f)| (i, f.check, &f.result))
+            .collect();
+        assert_eq!(
+            failures,
+            vec![(
+                0,
+                Check::Signature,
+                &CheckResult::Failed(Reason::InvalidSignature)
+            )]
+        );
+
+        assert!(verify_from_pool(&leaf, &[], &anchors, &[], now, &policy).is_none());
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(feature = "serde")]
+use std::sync::Arc;
+
+use x509_core::{oids, parse::der::ObjectIdentifierRef};
+
+#[cfg(feature = "serde")]
+use crate::ct_logs::CtLogs;
+
+/// the critical extensions that don't fail the chain without being added to the policy.
+/// basicConstraints and keyUsage are checked by the validator, subjectAltName and extKeyUsage
+/// are left to the host name and purpose checks of the application.
+const HANDLED_CRITICAL_EXTENSIONS: [ObjectIdentifierRef<'static>; 4] = [
+    oids::BASIC_CONSTRAINTS,
+    oids::KEY_USAGE,
+    oids::SUBJECT_ALT_NAME,
+    oids::EXT_KEY_USAGE,
+];
+
+/// tunes what the chain verification accepts. `VerifyPolicy::default()` is a reasonable policy
+/// for web PKI certificates; the setters return the policy so they can be chained:
+///
+/// ```
+/// use x509_crypto::verify::VerifyPolicy;
+///
+/// let policy = VerifyPolicy::default().allow_sha1(true).min_rsa_bits(1024);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyPolicy {
+    allow_sha1: bool,
+    min_rsa_bits: usize,
+    #[cfg(feature = "serde")]
+    require_scts: Option<(usize, Arc<CtLogs>)>,
+    check_revocation: bool,
+    max_chain_depth: usize,
+    handled_critical_extensions: Vec<Vec<u8>>,
+}
+
+impl Default for VerifyPolicy {
+    fn default() -> Self {
+        Self {
+            allow_sha1: false,
+            min_rsa_bits: 2048,
+            #[cfg(feature = "serde")]
+            require_scts: None,
+            check_revocation: false,
+            max_chain_depth: 8,
+            handled_critical_extensions: Vec::new(),
+        }
+    }
+}
+
+impl VerifyPolicy {
+    /// accept certificates signed with SHA-1. MD5 signatures are never accepted.
+    pub fn allow_sha1(mut self, allow: bool) -> Self {
+        self.allow_sha1 = allow;
+        self
+    }
+
+    /// the minimum modulus size of RSA keys anywhere in the chain
+    pub fn min_rsa_bits(mut self, bits: usize) -> Self {
+        self.min_rsa_bits = bits;
+        self
+    }
+
+    /// require the leaf to carry valid embedded signed certificate timestamps of at least
+    /// `count` different logs of `logs`, see
+    /// [`check_embedded_scts`](crate::sct::check_embedded_scts)
+    #[cfg(feature = "serde")]
+    pub fn require_scts(mut self, count: usize, logs: impl Into<Arc<CtLogs>>) -> Self {
+        self.require_scts = Some((count, logs.into()));
+        self
+    }
+
+    /// fail instead of skip when the revocation status of a certificate can't be determined
+    pub fn check_revocation(mut self, check: bool) -> Self {
+        self.check_revocation = check;
+        self
+    }
+
+    /// the maximum number of certificates in a chain, including leaf and trust anchor
+    pub fn max_chain_depth(mut self, depth: usize) -> Self {
+        self.max_chain_depth = depth;
+        self
+    }
+
+    /// accept certificates with the critical extension `oid`, because the application processes
+    /// it itself. without this, a critical extension the validator doesn't know fails the chain
+    /// as RFC 5280 requires.
+    pub fn handle_critical_extension(mut self, oid: ObjectIdentifierRef) -> Self {
+        self.handled_critical_extensions
+            .push(oid.as_bytes().to_vec());
+        self
+    }
+
+    pub fn allows_sha1(&self) -> bool {
+        self.allow_sha1
+    }
+
+    pub fn minimum_rsa_bits(&self) -> usize {
+        self.min_rsa_bits
+    }
+
+    /// the number of logs the leaf needs valid SCTs of and the logs that count
+    #[cfg(feature = "serde")]
+    pub fn requires_scts(&self) -> Option<(usize, &CtLogs)> {
+        self.require_scts
+            .as_ref()
+            .map(|(count, logs)| (*count, &**logs))
+    }
+
+    pub fn checks_revocation(&self) -> bool {
+        self.check_revocation
+    }
+
+    pub fn maximum_chain_depth(&self) -> usize {
+        self.max_chain_depth
+    }
+
+    /// whether a certificate may carry `oid` as a critical extension
+    pub fn handles_critical_extension(&self, oid: &ObjectIdentifierRef) -> bool {
+        HANDLED_CRITICAL_EXTENSIONS.contains(oid)
+            || self
+                .handled_critical_extensions
+                .iter()
+                .any(|handled| oid.eq_oid(handled))
+    }
+}
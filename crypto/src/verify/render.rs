@@ -0,0 +1,206 @@
+use std::fmt::Write;
+
+use x509_core::{
+    oids,
+    parse::{
+        certificate::NameRef,
+        der::{expect_octet_string, ObjectIdentifierRef},
+        parsing::CertificateRef,
+    },
+};
+
+/// renders `chain` as an indented tree with the trust anchor at the top, one certificate per
+/// level. the chain is ordered like for [`verify_chain`](super::verify_chain), leaf first.
+/// parts that can't be decoded are shown as `<malformed>`.
+pub fn render_chain_tree(chain: &[CertificateRef]) -> String {
+    let mut out = String::new();
+
+    for (depth, cert) in chain.iter().rev().enumerate() {
+        let indent = "   ".repeat(depth);
+        let (branch, detail) = if depth == 0 { ("", "") } else { ("`- ", "   ") };
+        let summary = CertificateSummary::new(cert);
+        let _ = writeln!(out, "{}{}{}", indent, branch, summary.subject);
+        let _ = writeln!(out, "{}{}key id: {}", indent, detail, summary.key_id);
+        let _ = writeln!(
+            out,
+            "{}{}valid: {} - {}",
+            indent, detail, summary.not_before, summary.not_after
+        );
+    }
+
+    out
+}
+
+/// renders `chain` as a Graphviz DOT digraph with an edge from every issuer to the certificate
+/// it issued, e.g. for `dot -Tsvg`. the chain is ordered leaf first.
+pub fn render_chain_dot(chain: &[CertificateRef]) -> String {
+    let mut out = String::from("digraph chain {\n    node [shape=box];\n");
+
+    for (index, cert) in chain.iter().enumerate() {
+        let summary = CertificateSummary::new(cert);
+        let label = format!(
+            "{}\nkey id: {}\nnot before: {}\nnot after: {}",
+            summary.subject, summary.key_id, summary.not_before, summary.not_after
+        );
+        let _ = writeln!(out, "    c{} [label=\"{}\"];", index, escape_dot(&label));
+    }
+    for index in 1..chain.len() {
+        let _ = writeln!(out, "    c{} -> c{};", index, index - 1);
+    }
+    out.push_str("}\n");
+
+    out
+}
+
+struct CertificateSummary {
+    subject: String,
+    key_id: String,
+    not_before: String,
+    not_after: String,
+}
+
+impl CertificateSummary {
+    fn new(cert: &CertificateRef) -> Self {
+        let tbs = cert.tbs_cert();
+        let validity = tbs.validity();
+
+        Self {
+            subject: format_name(tbs.subject()),
+            key_id: subject_key_id(cert),
+            not_before: validity
+                .not_before()
+                .map(|t| t.to_string())
+                .unwrap_or_else(|_| MALFORMED.to_string()),
+            not_after: validity
+                .not_after()
+                .map(|t| t.to_string())
+                .unwrap_or_else(|_| MALFORMED.to_string()),
+        }
+    }
+}
+
+const MALFORMED: &str = "<malformed>";
+
+/// the subject key identifier as colon separated hex, `-` if the extension is absent
+fn subject_key_id(cert: &CertificateRef) -> String {
+    let ext = match cert.tbs_cert().extensions() {
+        Some(extensions) => match extensions.find(oids::SUBJECT_KEY_IDENTIFIER) {
+            Ok(Some(ext)) => ext,
+            Ok(None) => return "-".to_string(),
+            Err(_) => return MALFORMED.to_string(),
+        },
+        None => return "-".to_string(),
+    };
+
+    match expect_octet_string(ext.value().as_bytes()) {
+        Ok(([], key_id)) => key_id
+            .as_bytes()
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(":"),
+        _ => MALFORMED.to_string(),
+    }
+}
+
+/// formats a name like `CN=example.com, O=Example, C=US`, starting with the last RDN
+pub(crate) fn format_name(name: &NameRef) -> String {
+    let NameRef::DistinguishedNameRef(dn) = name;
+    let mut rdns = Vec::new();
+
+    for rdn in dn.iter() {
+        let rdn = match rdn {
+            Ok(rdn) => rdn,
+            Err(_) => return MALFORMED.to_string(),
+        };
+        let mut attributes = Vec::new();
+        for atv in rdn.iter() {
+            let atv = match atv {
+                Ok(atv) => atv,
+                Err(_) => return MALFORMED.to_string(),
+            };
+            let value = atv
+                .value_as_str()
+                .map(|v| v.into_owned())
+                .unwrap_or_else(|_| MALFORMED.to_string());
+            attributes.push(format!("{}={}", short_name(atv.attribute_type()), value));
+        }
+        rdns.push(attributes.join("+"));
+    }
+    if rdns.is_empty() {
+        return "<empty>".to_string();
+    }
+
+    rdns.reverse();
+    rdns.join(", ")
+}
+
+fn short_name(oid: &ObjectIdentifierRef) -> String {
+    let name = match *oid {
+        oids::COMMON_NAME => "CN",
+        oids::COUNTRY_NAME => "C",
+        oids::LOCALITY_NAME => "L",
+        oids::STATE_OR_PROVINCE_NAME => "ST",
+        oids::ORGANIZATION_NAME => "O",
+        oids::ORGANIZATIONAL_UNIT_NAME => "OU",
+        oids::DOMAIN_COMPONENT => "DC",
+        oids::EMAIL_ADDRESS => "emailAddress",
+        _ => return oid.to_string(),
+    };
+
+    name.to_string()
+}
+
+fn escape_dot(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> Vec<CertificateRef<'static>> {
+        vec![
+            CertificateRef::from_slice(include_bytes!("../../../certs/ocsp/leaf.der")).unwrap(),
+            CertificateRef::from_slice(include_bytes!("../../../certs/ocsp/ca.der")).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_render_chain_tree() {
+        let tree = render_chain_tree(&chain());
+        let lines: Vec<&str> = tree.lines().collect();
+
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "CN=x509-rs Test CA, O=x509-rs, C=DE");
+        assert!(lines[1].starts_with("key id: "));
+        assert!(lines[2].starts_with("valid: 2026-10-16T14:18:17+00:00 - "));
+        assert_eq!(lines[3], "   `- CN=leaf.example.com");
+        assert!(lines[4].starts_with("      key id: "));
+    }
+
+    #[test]
+    fn test_render_malformed_validity() {
+        // notBefore without the 'Z', the UTCTime keeps its length
+        let mut der = include_bytes!("../../../certs/ocsp/leaf.der").to_vec();
+        let pos = der.windows(13).position(|w| w == b"261016141817Z").unwrap();
+        der[pos + 12] = b'0';
+        let leaf = CertificateRef::from_slice(&der).unwrap();
+
+        let tree = render_chain_tree(&[leaf]);
+        assert!(tree.contains("valid: <malformed> - "), "{}", tree);
+    }
+
+    #[test]
+    fn test_render_chain_dot() {
+        let dot = render_chain_dot(&chain());
+
+        assert!(dot.starts_with("digraph chain {\n"));
+        assert!(dot.contains("c0 [label=\"CN=leaf.example.com\\nkey id: "));
+        assert!(dot.contains("c1 -> c0;"));
+        assert!(dot.ends_with("}\n"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt::{self, Display, Formatter};
+use x509_core::common::{certificate::CRLReason, time::UtcDateTime};
+
+/// a single check the validator performs on a certificate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Check {
+    /// the certificate is signed by the next certificate in the chain
+    Signature,
+    /// the issuer name matches the subject of the next certificate in the chain
+    IssuerName,
+    /// the verification time is within notBefore and notAfter
+    Validity,
+    /// issuing certificates are CAs and the path length constraint holds
+    BasicConstraints,
+    /// issuing certificates may sign certificates
+    KeyUsage,
+    Revocation,
+    /// the signature algorithm is acceptable under the policy
+    SignatureAlgorithm,
+    /// the public key is large enough under the policy
+    KeySize,
+    /// the leaf carries signed certificate timestamps
+    CertificateTransparency,
+    /// the certificate is within the maximum chain depth
+    ChainDepth,
+    /// every critical extension is processed by the validator or, per the policy, the
+    /// application
+    CriticalExtensions,
+}
+
+/// why a check failed or was skipped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    InvalidSignature,
+    /// the signature could not be checked, e.g. because the algorithm is not supported
+    SignatureError(String),
+    IssuerMismatch,
+    NotYetValid,
+    Expired,
+    MalformedTime,
+    MalformedExtension,
+    NotCA,
+    PathLenExceeded,
+    KeyCertSignNotAllowed,
+    NoRevocationInfo,
+    /// MD5, or SHA-1 when the policy doesn't allow it
+    WeakSignatureAlgorithm,
+    /// the RSA modulus has fewer bits than the policy requires
+    KeyTooSmall(usize),
+    MalformedPublicKey,
+    /// the leaf has no embedded SCTs
+    MissingScts,
+    /// fewer logs than the policy requires issued a valid SCT for the leaf
+    TooFewScts {
+        valid: usize,
+        required: usize,
+    },
+    ChainTooLong,
+    Revoked {
+        revocation_time: UtcDateTime,
+        reason: Option<CRLReason>,
+    },
+    MalformedCrl,
+    /// the OCSP response for the certificate can't be used, with the reason
+    InvalidOcspResponse(String),
+    /// the OCSP responder doesn't know the certificate
+    RevocationStatusUnknown,
+    /// a critical extension nobody processes, with its OID in dotted form
+    UnhandledCriticalExtension(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckResult {
+    Passed,
+    Failed(Reason),
+    Skipped(Reason),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckOutcome {
+    pub check: Check,
+    pub result: CheckResult,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CertificateReport {
+    /// position in the chain, the leaf is 0
+    pub index: usize,
+    pub checks: Vec<CheckOutcome>,
+}
+
+impl CertificateReport {
+    pub(crate) fn new(index: usize) -> Self {
+        Self {
+            index,
+            checks: Vec::new(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, check: Check, result: CheckResult) {
+        self.checks.push(CheckOutcome { check, result });
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CheckOutcome> {
+        self.checks
+            .iter()
+            .filter(|c| matches!(c.result, CheckResult::Failed(_)))
+    }
+}
+
+/// everything the validator checked, per certificate in the chain
+#[derive(Debug, Clone, Default)]
+pub struct VerificationReport {
+    pub certificates: Vec<CertificateReport>,
+}
+
+impl VerificationReport {
+    /// true if the chain is not empty and no check failed. skipped checks don't count as failures.
+    pub fn is_valid(&self) -> bool {
+        !self.certificates.is_empty() && self.certificates.iter().all(CertificateReport::is_valid)
+    }
+
+    /// all failed checks together with the index of the certificate they belong to
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &CheckOutcome)> {
+        self.certificates
+            .iter()
+            .flat_map(|c| c.failures().map(move |f| (c.index, f)))
+    }
+}
+
+impl Display for CheckResult {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            CheckResult::Passed => write!(f, "passed"),
+            CheckResult::Failed(reason) => write!(f, "failed ({:?})", reason),
+            CheckResult::Skipped(reason) => write!(f, "skipped ({:?})", reason),
+        }
+    }
+}
+
+impl Display for VerificationReport {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        for cert in &self.certificates {
+            writeln!(f, "certificate {}:", cert.index)?;
+            for outcome in &cert.checks {
+                writeln!(f, "  {:?}: {}", outcome.check, outcome.result)?;
+            }
+        }
+
+        Ok(())
+    }
+}
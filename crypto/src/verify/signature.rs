@@ -0,0 +1,288 @@
+use std::fmt::{self, Display, Formatter};
+
+use x509_core::{
+    oids,
+    parse::{
+        certificate::{AlgorithmidentifierRef, SubjectPublicKeyInfoRef},
+        der::{expect_integer, expect_sequence},
+        error::ParseError,
+        parsing::CertificateRef,
+    },
+};
+
+use crate::provider::{default_provider, CryptoProvider};
+use crate::{Algorithm, Error, KeyType};
+
+/// why a signature doesn't verify. the backends only tell that it doesn't, so the public key
+/// is checked against the algorithm first, anything the backend rejects after that counts as
+/// a signature that doesn't match.
+#[derive(Debug)]
+pub enum SignatureFailure {
+    /// the public key can't be decoded, e.g. an RSA key that is no RSAPublicKey or an EC point
+    /// of the wrong length for its curve
+    MalformedPublicKey,
+    /// the backend doesn't verify with an RSA key of this many bits
+    UnsupportedKeySize(usize),
+    /// the signature algorithm needs another type of key, e.g. ECDSA with an RSA key. both
+    /// OIDs are in dotted form.
+    KeyMismatch {
+        algorithm: String,
+        public_key: String,
+    },
+    /// the key fits the algorithm, but the signature is not the one of the data
+    SignatureMismatch,
+    /// the signature could not be checked, e.g. because the algorithm is not supported
+    Backend(Error),
+}
+
+impl Display for SignatureFailure {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            SignatureFailure::MalformedPublicKey => write!(f, "malformed public key"),
+            SignatureFailure::UnsupportedKeySize(bits) => {
+                write!(f, "unsupported {} bit RSA key", bits)
+            }
+            SignatureFailure::KeyMismatch {
+                algorithm,
+                public_key,
+            } => write!(f, "a {} key can't verify {}", public_key, algorithm),
+            SignatureFailure::SignatureMismatch => write!(f, "signature mismatch"),
+            SignatureFailure::Backend(e) => write!(f, "{:?}", e),
+        }
+    }
+}
+
+impl From<Error> for SignatureFailure {
+    fn from(e: Error) -> Self {
+        SignatureFailure::Backend(e)
+    }
+}
+
+/// like [`check_signature`](crate::check_signature), with the reason if `subject` is not
+/// signed by the key of `issuer`
+pub fn check_signature_detailed(
+    subject: &CertificateRef,
+    issuer: &CertificateRef,
+) -> Result<(), SignatureFailure> {
+    check_signature_detailed_with_provider(subject, issuer, default_provider())
+}
+
+/// like [`check_signature_detailed`], with the signature checked by `provider`
+pub fn check_signature_detailed_with_provider(
+    subject: &CertificateRef,
+    issuer: &CertificateRef,
+    provider: &dyn CryptoProvider,
+) -> Result<(), SignatureFailure> {
+    let pub_key = issuer.tbs_cert().subject_public_key_info();
+    let raw_tbs = subject.tbs_cert().raw_data();
+    let (_, signature) = subject.signature().data();
+
+    verify_signed_data_detailed_with_provider(
+        subject.signature_algorithm(),
+        pub_key,
+        raw_tbs,
+        signature,
+        provider,
+    )
+}
+
+/// like [`verify_signed_data`](crate::verify_signed_data), with the reason if `signature`
+/// doesn't verify
+pub fn verify_signed_data_detailed(
+    signature_algorithm: &AlgorithmidentifierRef,
+    pub_key: &SubjectPublicKeyInfoRef,
+    raw_tbs: &[u8],
+    signature: &[u8],
+) -> Result<(), SignatureFailure> {
+    verify_signed_data_detailed_with_provider(
+        signature_algorithm,
+        pub_key,
+        raw_tbs,
+        signature,
+        default_provider(),
+    )
+}
+
+/// like [`verify_signed_data_detailed`], with the signature checked by `provider`
+pub fn verify_signed_data_detailed_with_provider(
+    signature_algorithm: &AlgorithmidentifierRef,
+    pub_key: &SubjectPublicKeyInfoRef,
+    raw_tbs: &[u8],
+    signature: &[u8],
+    provider: &dyn CryptoProvider,
+) -> Result<(), SignatureFailure> {
+    // algorithms that are not supported are left to the backend to report
+    if let Ok(algorithm) = Algorithm::try_from(signature_algorithm) {
+        check_public_key(algorithm, pub_key)?;
+    }
+
+    if provider.verify_signed_data(signature_algorithm, pub_key, raw_tbs, signature)? {
+        Ok(())
+    } else {
+        Err(SignatureFailure::SignatureMismatch)
+    }
+}
+
+/// whether `pub_key` can verify signatures of `algorithm`
+fn check_public_key(
+    algorithm: Algorithm,
+    pub_key: &SubjectPublicKeyInfoRef,
+) -> Result<(), SignatureFailure> {
+    let key_algorithm = pub_key.algorithm_identifier().algorithm_identifier();
+    let fits = match algorithm.key_type() {
+        // RFC 4055 keys restricted to RSASSA-PSS are RSA keys as well
+        KeyType::Rsa => {
+            oids::is(key_algorithm, oids::RSA_ENCRYPTION)
+                || oids::is(key_algorithm, oids::RSASSA_PSS)
+        }
+        KeyType::Ecdsa => oids::is(key_algorithm, oids::EC_PUBLIC_KEY),
+        KeyType::Ed25519 => oids::is(key_algorithm, oids::ED25519),
+    };
+    if !fits {
+        return Err(SignatureFailure::KeyMismatch {
+            algorithm: algorithm.oid().to_string(),
+            public_key: key_algorithm.to_string(),
+        });
+    }
+
+    let (_, key) = pub_key.subject_public_key().data();
+    match algorithm.key_type() {
+        KeyType::Rsa => {
+            let bits = rsa_modulus_bits(key).map_err(|_| SignatureFailure::MalformedPublicKey)?;
+            #[cfg(feature = "use-ring")]
+            if !crate::ring::rsa_modulus_sizes(algorithm).contains(&bits) {
+                return Err(SignatureFailure::UnsupportedKeySize(bits));
+            }
+            #[cfg(feature = "use-aws-lc-rs")]
+            if !crate::aws_lc::rsa_modulus_sizes(algorithm).contains(&bits) {
+                return Err(SignatureFailure::UnsupportedKeySize(bits));
+            }
+            #[cfg(feature = "use-rust-crypto")]
+            let _ = bits;
+        }
+        KeyType::Ecdsa => {
+            // the size of a coordinate, other curves are left to the backend
+            let coordinate_len = match pub_key.named_curve() {
+                Some(oids::SECP256R1) => 32,
+                Some(oids::SECP384R1) => 48,
+                _ => return Ok(()),
+            };
+            let valid = match key.first() {
+                Some(0x04) => key.len() == 1 + 2 * coordinate_len,
+                Some(0x02 | 0x03) => key.len() == 1 + coordinate_len,
+                _ => false,
+            };
+            if !valid {
+                return Err(SignatureFailure::MalformedPublicKey);
+            }
+        }
+        KeyType::Ed25519 if key.len() != 32 => return Err(SignatureFailure::MalformedPublicKey),
+        KeyType::Ed25519 => {}
+    }
+
+    Ok(())
+}
+
+/// the size of the modulus of a DER encoded RSAPublicKey
+pub(super) fn rsa_modulus_bits(key: &[u8]) -> Result<usize, ParseError> {
+    let (_, key) = expect_sequence(key)?;
+    let (_, modulus) = expect_integer(key)?;
+    let modulus = modulus.as_bytes();
+    let leading_zeros = modulus.iter().take_while(|b| **b == 0).count();
+
+    Ok(match modulus.get(leading_zeros) {
+        Some(first) => (modulus.len() - leading_zeros) * 8 - first.leading_zeros() as usize,
+        None => 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use x509_core::parse::der::encode_tlv;
+
+    fn cert(data: &'static [u8]) -> CertificateRef<'static> {
+        CertificateRef::from_slice(data).unwrap()
+    }
+
+    /// a certificate with `key` as its RSA public key, only used as an issuer
+    fn with_rsa_key(key: Vec<u8>) -> Vec<u8> {
+        let key_bits = key.len() * 8;
+        let tbs = TBSCertificateBuilder::default()
+            .serial_number(Integer::from_i64(1))
+            .signature(Algorithm::RSA_SHA256.into())
+            .issuer(Name::DistinguishedName(DistinguishedName::default()))
+            .validity(Validity::new(
+                UtcDateTime::from_ymd_hms(2021, 7, 31, 12, 33, 53).unwrap(),
+                UtcDateTime::from_ymd_hms(2022, 7, 31, 12, 33, 53).unwrap(),
+            ))
+            .subject(Name::DistinguishedName(DistinguishedName::default()))
+            .subject_public_key_info(SubjectPublicKeyInfo::new(
+                AlgorithmIdentifier::new(oids::RSA_ENCRYPTION.into(), Data::Null(Null())),
+                BitString::new(key, key_bits),
+            ))
+            .extensions(None)
+            .build()
+            .unwrap();
+        let signature = BitString::new(vec![0], 8);
+        Certificate::new(tbs, Algorithm::RSA_SHA256.into(), signature)
+            .to_der()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_check_signature_detailed() {
+        let leaf = cert(include_bytes!("../../../certs/ocsp/leaf.der"));
+        let ca = cert(include_bytes!("../../../certs/ocsp/ca.der"));
+        let responder = cert(include_bytes!("../../../certs/ocsp/responder.der"));
+        let ec = cert(include_bytes!("../../../certs/identity/ec-cert.der"));
+
+        assert!(check_signature_detailed(&leaf, &ca).is_ok());
+        assert!(check_signature_detailed(&ec, &ec).is_ok());
+        assert!(matches!(
+            check_signature_detailed(&leaf, &responder),
+            Err(SignatureFailure::SignatureMismatch)
+        ));
+        match check_signature_detailed(&leaf, &ec) {
+            Err(SignatureFailure::KeyMismatch {
+                algorithm,
+                public_key,
+            }) => {
+                assert_eq!(algorithm, "1.2.840.113549.1.1.11");
+                assert_eq!(public_key, "1.2.840.10045.2.1");
+            }
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_rsa_key_problems() {
+        let leaf = cert(include_bytes!("../../../certs/ocsp/leaf.der"));
+
+        let garbage = with_rsa_key(vec![1, 2, 3]);
+        assert!(matches!(
+            check_signature_detailed(&leaf, &CertificateRef::from_slice(&garbage).unwrap()),
+            Err(SignatureFailure::MalformedPublicKey)
+        ));
+
+        // a 512 bit modulus and the exponent 65537
+        let mut modulus = vec![0x00, 0xc3];
+        modulus.extend_from_slice(&[0x5a; 63]);
+        let mut key = encode_tlv(0x02, &modulus);
+        key.extend_from_slice(&encode_tlv(0x02, &[0x01, 0x00, 0x01]));
+        let small = with_rsa_key(encode_tlv(0x30, &key));
+        let small = CertificateRef::from_slice(&small).unwrap();
+        #[cfg(any(feature = "use-ring", feature = "use-aws-lc-rs"))]
+        assert!(matches!(
+            check_signature_detailed(&leaf, &small),
+            Err(SignatureFailure::UnsupportedKeySize(512))
+        ));
+        // RustCrypto refuses to load the key at all
+        #[cfg(feature = "use-rust-crypto")]
+        assert!(matches!(
+            check_signature_detailed(&leaf, &small),
+            Err(SignatureFailure::Backend(Error::Pkcs1(_)))
+        ));
+    }
+}
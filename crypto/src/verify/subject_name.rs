@@ -0,0 +1,242 @@
+use std::net::IpAddr;
+
+use super::uri::Uri;
+use x509_core::{
+    oids,
+    parse::{
+        error::ParseError,
+        extensions::{GeneralNameRef, GeneralNamesRef},
+        parsing::CertificateRef,
+    },
+};
+
+/// a name a certificate can be valid for, the reference identity of RFC 6125
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubjectName<'n> {
+    /// a host name, matched against the dNSName entries. Unicode labels are converted to
+    /// A-labels first, since certificates only carry those.
+    Dns(&'n str),
+    /// matched against the iPAddress entries
+    IpAddress(IpAddr),
+    /// a mailbox like `alice@example.com`, matched against the rfc822Name entries
+    Email(&'n str),
+    /// an absolute URI like a SPIFFE ID, matched against the uniformResourceIdentifier entries
+    /// after normalizing both with [`Uri::parse`]
+    Uri(&'n str),
+}
+
+impl<'n> SubjectName<'n> {
+    /// an IP address if `name` is one, IPv6 optionally in brackets like in URLs, a URI if it
+    /// contains `://`, an email address if it contains an `@` and a host name otherwise
+    pub fn parse(name: &'n str) -> Self {
+        let unbracketed = name
+            .strip_prefix('[')
+            .and_then(|n| n.strip_suffix(']'))
+            .unwrap_or(name);
+        if let Ok(ip) = unbracketed.parse() {
+            SubjectName::IpAddress(ip)
+        } else if name.contains("://") {
+            SubjectName::Uri(name)
+        } else if name.contains('@') {
+            SubjectName::Email(name)
+        } else {
+            SubjectName::Dns(name)
+        }
+    }
+}
+
+/// whether `cert` is valid for `name` according to its subjectAltName extension.
+///
+/// host names are converted to A-labels (punycode) with the IDNA mapping of UTS #46 if they
+/// contain non-ASCII characters, a host name that can't be converted matches nothing. they are
+/// compared case insensitively, a wildcard is only allowed as the whole leftmost
+/// label and stands for exactly one label. IP addresses have to be the same octets, an IPv4
+/// address doesn't match its IPv4-mapped IPv6 form. the local part of an email address is
+/// compared exactly, the domain case insensitively. URIs are equal if they are after
+/// normalization. the common name of the subject is not looked at, certificates without the
+/// extension match nothing.
+pub fn matches_subject_name(cert: &CertificateRef, name: SubjectName) -> Result<bool, ParseError> {
+    let ext = match cert.tbs_cert().extensions() {
+        Some(extensions) => extensions.find(oids::SUBJECT_ALT_NAME)?,
+        None => None,
+    };
+    let ext = match ext {
+        Some(ext) => ext,
+        None => return Ok(false),
+    };
+    let ascii_host;
+    let name = match name {
+        SubjectName::Dns(host) if !host.is_ascii() => match idna::domain_to_ascii(host) {
+            Ok(host) => {
+                ascii_host = host;
+                SubjectName::Dns(&ascii_host)
+            }
+            Err(_) => return Ok(false),
+        },
+        name => name,
+    };
+    let uri = match name {
+        SubjectName::Uri(uri) => match Uri::parse(uri) {
+            Some(uri) => Some(uri),
+            None => return Ok(false),
+        },
+        _ => None,
+    };
+
+    for entry in GeneralNamesRef::from_slice(ext.value().as_bytes())?.iter() {
+        let entry = entry?;
+        let matches = match (&entry, name) {
+            (GeneralNameRef::DnsName(pattern), SubjectName::Dns(host)) => {
+                dns_name_matches(&pattern.to_str()?, host)
+            }
+            (GeneralNameRef::IpAddress(_), SubjectName::IpAddress(ip)) => {
+                entry.ip_address() == Some(ip)
+            }
+            (GeneralNameRef::Rfc822Name(mailbox), SubjectName::Email(email)) => {
+                email_matches(&mailbox.to_str()?, email)
+            }
+            (GeneralNameRef::UniformResourceIdentifier(entry), SubjectName::Uri(_)) => {
+                Uri::parse(&entry.to_str()?) == uri
+            }
+            _ => false,
+        };
+        if matches {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+fn dns_name_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.strip_suffix('.').unwrap_or(pattern);
+    let host = host.strip_suffix('.').unwrap_or(host);
+
+    match pattern.strip_prefix("*.") {
+        // *.com or *.localhost would cover too much
+        Some(parent) if parent.contains('.') => match host.split_once('.') {
+            Some((label, rest)) => !label.is_empty() && rest.eq_ignore_ascii_case(parent),
+            None => false,
+        },
+        Some(_) => false,
+        None => !pattern.is_empty() && pattern.eq_ignore_ascii_case(host),
+    }
+}
+
+fn email_matches(mailbox: &str, email: &str) -> bool {
+    match (mailbox.rsplit_once('@'), email.rsplit_once('@')) {
+        (Some((local, domain)), Some((email_local, email_domain))) => {
+            local == email_local && domain.eq_ignore_ascii_case(email_domain)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cert(data: &'static [u8]) -> CertificateRef<'static> {
+        CertificateRef::from_slice(data).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            SubjectName::parse("192.0.2.1"),
+            SubjectName::IpAddress([192, 0, 2, 1].into())
+        );
+        assert_eq!(
+            SubjectName::parse("[2001:db8::1]"),
+            SubjectName::IpAddress("2001:db8::1".parse().unwrap())
+        );
+        assert_eq!(
+            SubjectName::parse("alice@example.com"),
+            SubjectName::Email("alice@example.com")
+        );
+        assert_eq!(
+            SubjectName::parse("spiffe://example.org/ns/default/sa/web"),
+            SubjectName::Uri("spiffe://example.org/ns/default/sa/web")
+        );
+        assert_eq!(
+            SubjectName::parse("https://alice@example.com/"),
+            SubjectName::Uri("https://alice@example.com/")
+        );
+        assert_eq!(
+            SubjectName::parse("www.example.com"),
+            SubjectName::Dns("www.example.com")
+        );
+    }
+
+    #[test]
+    fn test_matches_subject_name() {
+        // DNS:www.example.com, DNS:*.api.example.com, IP:192.0.2.1, IP:2001:db8::1,
+        // email:alice@Example.COM
+        let names = cert(include_bytes!("../../../certs/names/names.der"));
+        let matches = |name| matches_subject_name(&names, SubjectName::parse(name)).unwrap();
+
+        assert!(matches("www.example.com"));
+        assert!(matches("WWW.Example.com."));
+        assert!(matches("v1.api.example.com"));
+        assert!(!matches("api.example.com"));
+        assert!(!matches("a.v1.api.example.com"));
+        assert!(!matches("example.com"));
+        // A-labels in the certificate, U-labels in the reference identity
+        assert!(!matches("bücher.example.com"));
+
+        assert!(matches("192.0.2.1"));
+        assert!(matches("2001:db8:0::1"));
+        assert!(!matches("192.0.2.2"));
+        assert!(!matches("::ffff:192.0.2.1"));
+
+        assert!(matches("alice@example.com"));
+        assert!(!matches("Alice@example.com"));
+        assert!(!matches("bob@example.com"));
+
+        // the CA has no subjectAltName, its common name doesn't count
+        let other = cert(include_bytes!("../../../certs/ocsp/ca.der"));
+        assert!(!matches_subject_name(&other, SubjectName::parse("x509-rs Test CA")).unwrap());
+    }
+
+    #[test]
+    fn test_unicode_host_names() {
+        // DNS:xn--bcher-kva.example, DNS:*.xn--caf-dma.example
+        let idn = cert(include_bytes!("../../../certs/names/idn.der"));
+        let matches = |name| matches_subject_name(&idn, SubjectName::parse(name)).unwrap();
+
+        assert!(matches("bücher.example"));
+        assert!(matches("BÜCHER.example"));
+        assert!(matches("xn--bcher-kva.example"));
+        assert!(matches("menu.café.example"));
+        assert!(!matches("bucher.example"));
+        assert!(!matches("b\u{fffd}cher.example"));
+    }
+
+    #[test]
+    fn test_uris() {
+        // URI:spiffe://Prod.Example/ns/default/sa/web, URI:HTTPS://API.Example.com:443/v1?x=1
+        let uri = cert(include_bytes!("../../../certs/names/uri.der"));
+        let matches = |name| matches_subject_name(&uri, SubjectName::parse(name)).unwrap();
+
+        assert!(matches("spiffe://prod.example/ns/default/sa/web"));
+        assert!(matches("SPIFFE://PROD.EXAMPLE/ns/default/sa/web"));
+        assert!(!matches("spiffe://prod.example/ns/default/sa/Web"));
+        assert!(!matches("spiffe://prod.example/ns/default/sa/web/"));
+        assert!(matches("https://api.example.com/v1?x=1"));
+        assert!(matches("https://api.example.com:443/v1?x=1"));
+        assert!(!matches("https://api.example.com:8443/v1?x=1"));
+        assert!(!matches("http://api.example.com/v1?x=1"));
+        // the host is not a DNS name of the certificate
+        assert!(!matches("api.example.com"));
+        assert!(!matches_subject_name(&uri, SubjectName::Uri("not a uri")).unwrap());
+    }
+
+    #[test]
+    fn test_dns_name_matches() {
+        assert!(!dns_name_matches("*.com", "example.com"));
+        assert!(!dns_name_matches("*.", "example"));
+        assert!(!dns_name_matches("", ""));
+        assert!(!dns_name_matches("f*.example.com", "foo.example.com"));
+        assert!(!dns_name_matches("*.example.com", ".example.com"));
+    }
+}
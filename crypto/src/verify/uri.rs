@@ -0,0 +1,249 @@
+use std::fmt;
+
+use x509_core::{
+    oids,
+    parse::{
+        error::ParseError,
+        extensions::{GeneralNameRef, GeneralNamesRef},
+        parsing::CertificateRef,
+    },
+};
+
+/// a URI as in a uniformResourceIdentifier entry, normalized so that equivalent URIs are equal:
+/// the scheme and the host are lowercased and the default port of the scheme is left out. the
+/// path, query and fragment are kept as they are, e.g. a SPIFFE ID like
+/// `spiffe://example.org/ns/default/sa/web` keeps its path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Uri {
+    scheme: String,
+    authority: Option<Authority>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Authority {
+    userinfo: Option<String>,
+    host: String,
+    port: Option<u16>,
+}
+
+impl Uri {
+    /// parses an absolute URI (RFC 3986 section 4.3). None if `uri` has no scheme, isn't ASCII
+    /// or has an invalid port.
+    pub fn parse(uri: &str) -> Option<Self> {
+        if !uri.is_ascii() {
+            return None;
+        }
+        let (scheme, rest) = uri.split_once(':')?;
+        let mut chars = scheme.chars();
+        if !chars.next()?.is_ascii_alphabetic()
+            || !chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        {
+            return None;
+        }
+        let scheme = scheme.to_ascii_lowercase();
+
+        let (rest, fragment) = match rest.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment.to_string())),
+            None => (rest, None),
+        };
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query.to_string())),
+            None => (rest, None),
+        };
+        let (authority, path) = match rest.strip_prefix("//") {
+            Some(rest) => {
+                let end = rest.find('/').unwrap_or(rest.len());
+                let authority = parse_authority(&rest[..end], &scheme)?;
+                (Some(authority), &rest[end..])
+            }
+            None => (None, rest),
+        };
+
+        Some(Self {
+            scheme,
+            authority,
+            path: path.to_string(),
+            query,
+            fragment,
+        })
+    }
+
+    /// the scheme in lowercase, e.g. `spiffe`
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// the host in lowercase, IPv6 addresses in brackets. None if there is no authority.
+    pub fn host(&self) -> Option<&str> {
+        self.authority.as_ref().map(|a| a.host.as_str())
+    }
+
+    /// the port, None if there is none or it is the default port of the scheme
+    pub fn port(&self) -> Option<u16> {
+        self.authority.as_ref()?.port
+    }
+
+    pub fn userinfo(&self) -> Option<&str> {
+        self.authority.as_ref()?.userinfo.as_deref()
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+}
+
+fn parse_authority(authority: &str, scheme: &str) -> Option<Authority> {
+    let (userinfo, host_port) = match authority.rsplit_once('@') {
+        Some((userinfo, host_port)) => (Some(userinfo.to_string()), host_port),
+        None => (None, authority),
+    };
+    // the port follows the last colon, unless it is inside an IPv6 literal
+    let (host, port) = match host_port.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (host, port),
+        _ => (host_port, ""),
+    };
+    let port = match port {
+        "" => None,
+        port if port.bytes().all(|b| b.is_ascii_digit()) => Some(port.parse().ok()?),
+        _ => return None,
+    };
+
+    Some(Authority {
+        userinfo,
+        host: host.to_ascii_lowercase(),
+        port: port.filter(|port| Some(*port) != default_port(scheme)),
+    })
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        "ftp" => Some(21),
+        "ldap" => Some(389),
+        "ldaps" => Some(636),
+        _ => None,
+    }
+}
+
+impl fmt::Display for Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.scheme)?;
+        if let Some(authority) = &self.authority {
+            f.write_str("//")?;
+            if let Some(userinfo) = &authority.userinfo {
+                write!(f, "{}@", userinfo)?;
+            }
+            f.write_str(&authority.host)?;
+            if let Some(port) = authority.port {
+                write!(f, ":{}", port)?;
+            }
+        }
+        f.write_str(&self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// the uniformResourceIdentifier entries of the subjectAltName extension of `cert`, normalized.
+/// entries that are not absolute URIs are left out, certificates without the extension have
+/// none.
+pub fn subject_uris(cert: &CertificateRef) -> Result<Vec<Uri>, ParseError> {
+    let ext = match cert.tbs_cert().extensions() {
+        Some(extensions) => extensions.find(oids::SUBJECT_ALT_NAME)?,
+        None => None,
+    };
+    let ext = match ext {
+        Some(ext) => ext,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut uris = Vec::new();
+    for entry in GeneralNamesRef::from_slice(ext.value().as_bytes())?.iter() {
+        if let GeneralNameRef::UniformResourceIdentifier(uri) = entry? {
+            uris.extend(Uri::parse(&uri.to_str()?));
+        }
+    }
+
+    Ok(uris)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let uri = Uri::parse("HTTPS://alice@API.Example.com:443/V1/a?x=Y#Top").unwrap();
+        assert_eq!(uri.scheme(), "https");
+        assert_eq!(uri.userinfo(), Some("alice"));
+        assert_eq!(uri.host(), Some("api.example.com"));
+        assert_eq!(uri.port(), None);
+        assert_eq!(uri.path(), "/V1/a");
+        assert_eq!(uri.query(), Some("x=Y"));
+        assert_eq!(uri.fragment(), Some("Top"));
+        assert_eq!(
+            uri.to_string(),
+            "https://alice@api.example.com/V1/a?x=Y#Top"
+        );
+
+        let uri = Uri::parse("http://[2001:DB8::1]:8080").unwrap();
+        assert_eq!(uri.host(), Some("[2001:db8::1]"));
+        assert_eq!(uri.port(), Some(8080));
+        assert_eq!(uri.path(), "");
+        assert_eq!(
+            Uri::parse("http://[2001:db8::1]").unwrap().host(),
+            Some("[2001:db8::1]")
+        );
+
+        let urn = Uri::parse("urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6").unwrap();
+        assert_eq!(urn.host(), None);
+        assert_eq!(urn.path(), "uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6");
+
+        assert_eq!(Uri::parse("/relative/path"), None);
+        assert_eq!(Uri::parse("1http://example.com"), None);
+        assert_eq!(Uri::parse("http://example.com:http"), None);
+        assert_eq!(Uri::parse("http://example.com:65536"), None);
+        assert_eq!(Uri::parse("http://bücher.example"), None);
+    }
+
+    #[test]
+    fn test_subject_uris() {
+        // URI:spiffe://Prod.Example/ns/default/sa/web, URI:HTTPS://API.Example.com:443/v1?x=1
+        let cert =
+            CertificateRef::from_slice(include_bytes!("../../../certs/names/uri.der")).unwrap();
+        let uris: Vec<String> = subject_uris(&cert)
+            .unwrap()
+            .iter()
+            .map(|uri| uri.to_string())
+            .collect();
+        assert_eq!(
+            uris,
+            vec![
+                "spiffe://prod.example/ns/default/sa/web",
+                "https://api.example.com/v1?x=1",
+            ]
+        );
+
+        let names =
+            CertificateRef::from_slice(include_bytes!("../../../certs/names/names.der")).unwrap();
+        assert!(subject_uris(&names).unwrap().is_empty());
+    }
+}